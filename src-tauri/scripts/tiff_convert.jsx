@@ -3,6 +3,13 @@
 
 #target photoshop
 
+// Must match TIFF_SCRIPT_PROTOCOL_VERSION in src/commands/tiff.rs
+var SCRIPT_PROTOCOL_VERSION = 1;
+
+// Per-job suffix for the temp files, filled in by the app when it copies this script
+// (keeps concurrent conversions from reading each other's settings and results)
+var JOB_ID = "";
+
 var originalDialogs = app.displayDialogs;
 app.displayDialogs = DialogModes.NO;
 app.preferences.rulerUnits = Units.PIXELS;
@@ -12,7 +19,8 @@ app.preferences.rulerUnits = Units.PIXELS;
  ----------------------------------------------------- */
 function main() {
     var tempFolder = Folder.temp;
-    var settingsFile = new File(tempFolder + "/daidori_tiff_settings.json");
+    var suffix = JOB_ID ? "_" + JOB_ID : "";
+    var settingsFile = new File(tempFolder + "/daidori_tiff_settings" + suffix + ".json");
 
     if (!settingsFile.exists) {
         alert("Settings file not found: " + settingsFile.fsName);
@@ -37,12 +45,29 @@ function main() {
         return;
     }
 
+    var resultFile = new File(tempFolder + "/daidori_tiff_results" + suffix + ".json");
+
+    // Protocol handshake: refuse to run against a backend expecting another format
+    if (config.protocolVersion !== SCRIPT_PROTOCOL_VERSION) {
+        resultFile.open("w");
+        resultFile.encoding = "UTF-8";
+        resultFile.write(valueToJSON({
+            protocolVersion: SCRIPT_PROTOCOL_VERSION,
+            error: "Protocol version mismatch (script: " + SCRIPT_PROTOCOL_VERSION +
+                ", app: " + config.protocolVersion + ")",
+            results: []
+        }));
+        resultFile.close();
+        app.displayDialogs = originalDialogs;
+        return;
+    }
+
     var globalSettings = config.globalSettings;
     var results = [];
 
     // Initial heartbeat
     try {
-        var pf = new File(tempFolder + "/daidori_tiff_progress.txt");
+        var pf = new File(tempFolder + "/daidori_tiff_progress" + suffix + ".txt");
         pf.open("w"); pf.write("0/" + String(config.files.length)); pf.close();
     } catch (e_hb0) {}
 
@@ -53,7 +78,7 @@ function main() {
 
         // Heartbeat progress
         try {
-            var progressFile = new File(tempFolder + "/daidori_tiff_progress.txt");
+            var progressFile = new File(tempFolder + "/daidori_tiff_progress" + suffix + ".txt");
             progressFile.open("w");
             progressFile.write(String(i + 1) + "/" + String(config.files.length));
             progressFile.close();
@@ -61,10 +86,9 @@ function main() {
     }
 
     // Write results
-    var resultFile = new File(tempFolder + "/daidori_tiff_results.json");
     resultFile.open("w");
     resultFile.encoding = "UTF-8";
-    resultFile.write(valueToJSON({ results: results }));
+    resultFile.write(valueToJSON({ protocolVersion: SCRIPT_PROTOCOL_VERSION, results: results }));
     resultFile.close();
 
     app.displayDialogs = originalDialogs;
@@ -120,6 +144,12 @@ function processFile(fileConfig, globalSettings) {
             doc.resizeImage(targetW, targetH, targetDPI, ResampleMethod.AUTOMATIC);
         }
 
+        // 5.5. Snap near-black to pure black（統合済みの場合のみ）
+        if (globalSettings.snapBlackThreshold && doc.artLayers.length === 1 && doc.layerSets.length === 0) {
+            // 閾値以下の画素だけを純黒にする（それより明るい階調は変えない）
+            snapNearBlack(doc, globalSettings.snapBlackThreshold);
+        }
+
         // 6. Remove alpha channels
         while (doc.channels.length > getExpectedChannelCount(doc)) {
             doc.channels[doc.channels.length - 1].remove();
@@ -203,6 +233,57 @@ function unlockRecursive(container) {
 /* -----------------------------------------------------
   Helpers
  ----------------------------------------------------- */
+// 輝度が threshold 以下の画素を選択し、純黒（CMYKではK100）で塗りつぶす
+// 複製したレイヤーに2階調化を掛けて選択範囲を作り、元のレイヤーのそれ以外の画素には触れない
+// 合成チャンネルの明るさから選択範囲を作成（チャンネルのサムネイルを Ctrl+クリックしたのと同じ）
+// CMYKの channels[0] はシアン版のため、グレースケール以外は合成チャンネルを使う
+function loadCompositeSelection(doc) {
+    if (doc.mode === DocumentMode.GRAYSCALE) {
+        doc.selection.load(doc.channels[0]);
+        return;
+    }
+    var composite;
+    switch (doc.mode) {
+        case DocumentMode.CMYK: composite = "CMYK"; break;
+        case DocumentMode.LAB: composite = "Lab "; break;
+        default: composite = "RGB ";
+    }
+    var target = new ActionReference();
+    target.putProperty(charIDToTypeID("Chnl"), charIDToTypeID("fsel"));
+    var source = new ActionReference();
+    source.putEnumerated(charIDToTypeID("Chnl"), charIDToTypeID("Chnl"), charIDToTypeID(composite));
+    var desc = new ActionDescriptor();
+    desc.putReference(charIDToTypeID("null"), target);
+    desc.putReference(charIDToTypeID("T   "), source);
+    executeAction(charIDToTypeID("setd"), desc, DialogModes.NO);
+}
+
+function snapNearBlack(doc, threshold) {
+    var layer = doc.artLayers[0];
+    var mask = layer.duplicate();
+    // 2階調化: level 未満が黒、level 以上が白になる
+    mask.threshold(Math.min(threshold + 1, 255));
+    // 白（閾値より明るい部分）を選択して反転し、閾値以下の部分だけを選択する
+    loadCompositeSelection(doc);
+    doc.selection.invert();
+    mask.remove();
+
+    doc.activeLayer = layer;
+    var black = new SolidColor();
+    if (doc.mode === DocumentMode.CMYK) {
+        black.cmyk.cyan = 0;
+        black.cmyk.magenta = 0;
+        black.cmyk.yellow = 0;
+        black.cmyk.black = 100;
+    } else {
+        black.rgb.red = 0;
+        black.rgb.green = 0;
+        black.rgb.blue = 0;
+    }
+    doc.selection.fill(black);
+    doc.selection.deselect();
+}
+
 function getExpectedChannelCount(doc) {
     switch (doc.mode) {
         case DocumentMode.RGB: return 3;
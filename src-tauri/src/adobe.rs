@@ -0,0 +1,169 @@
+use std::fs;
+use std::path::Path;
+use crate::settings::load_settings;
+use crate::types::DetectedAdobeApp;
+
+// レジストリに登録がない環境向けの既定インストール先
+const FALLBACK_PHOTOSHOP_PATHS: &[&str] = &[
+    r"C:\Program Files\Adobe\Adobe Photoshop 2025\Photoshop.exe",
+    r"C:\Program Files\Adobe\Adobe Photoshop 2024\Photoshop.exe",
+    r"C:\Program Files\Adobe\Adobe Photoshop 2023\Photoshop.exe",
+    r"C:\Program Files\Adobe\Adobe Photoshop 2022\Photoshop.exe",
+    r"C:\Program Files\Adobe\Adobe Photoshop 2021\Photoshop.exe",
+    r"C:\Program Files\Adobe\Adobe Photoshop 2020\Photoshop.exe",
+    r"C:\Program Files\Adobe\Adobe Photoshop CC 2019\Photoshop.exe",
+    r"C:\Program Files\Adobe\Adobe Photoshop CC 2018\Photoshop.exe",
+    r"C:\Program Files\Adobe\Adobe Photoshop CC\Photoshop.exe",
+    r"C:\Program Files (x86)\Adobe\Adobe Photoshop 2025\Photoshop.exe",
+    r"C:\Program Files (x86)\Adobe\Adobe Photoshop 2024\Photoshop.exe",
+    r"C:\Program Files (x86)\Adobe\Adobe Photoshop 2023\Photoshop.exe",
+];
+
+fn app_name(exe: &Path) -> String {
+    exe.parent()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| exe.to_string_lossy().to_string())
+}
+
+// レジストリ（HKLM\SOFTWARE\Adobe\<product>\<version>、32bit版は WOW6432Node）から検出
+#[cfg(windows)]
+fn detect_from_registry(product: &str, exe_name: &str) -> Vec<DetectedAdobeApp> {
+    use winreg::enums::HKEY_LOCAL_MACHINE;
+    use winreg::RegKey;
+    use std::path::PathBuf;
+
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let mut apps = Vec::new();
+    for root in [r"SOFTWARE\Adobe", r"SOFTWARE\WOW6432Node\Adobe"] {
+        let Ok(product_key) = hklm.open_subkey(format!(r"{}\{}", root, product)) else {
+            continue;
+        };
+        for version in product_key.enum_keys().filter_map(|k| k.ok()) {
+            let Ok(version_key) = product_key.open_subkey(&version) else {
+                continue;
+            };
+            let install_dir: Option<String> = ["ApplicationPath", "InstallPath"]
+                .iter()
+                .find_map(|name| version_key.get_value(name).ok());
+            let Some(install_dir) = install_dir else {
+                continue;
+            };
+            let exe = PathBuf::from(install_dir.trim_end_matches('\\')).join(exe_name);
+            if exe.exists() {
+                apps.push(DetectedAdobeApp {
+                    name: app_name(&exe),
+                    version: Some(version),
+                    path: exe.to_string_lossy().to_string(),
+                });
+            }
+        }
+    }
+    apps
+}
+
+#[cfg(not(windows))]
+fn detect_from_registry(_product: &str, _exe_name: &str) -> Vec<DetectedAdobeApp> {
+    Vec::new()
+}
+
+// バージョン番号を数値の列に（"26.0.1" → [26, 0, 1]、数字以外は0、不明なら空で最も古い扱い）
+fn version_parts(version: Option<&str>) -> Vec<u32> {
+    version
+        .map(|v| v.split('.').map(|part| part.trim().parse().unwrap_or(0)).collect())
+        .unwrap_or_default()
+}
+
+// バージョン番号の大小で並べ替え（新しい順）、同じ実行ファイルは1件にまとめる
+// 小数として比べると 10.10 が 10.9 より古くなるため、"." で区切った数値の列として比べる
+fn sort_newest_first(mut apps: Vec<DetectedAdobeApp>) -> Vec<DetectedAdobeApp> {
+    apps.sort_by(|a, b| {
+        version_parts(b.version.as_deref())
+            .cmp(&version_parts(a.version.as_deref()))
+            .then_with(|| b.name.cmp(&a.name))
+    });
+    let mut seen = std::collections::HashSet::new();
+    apps.retain(|app| seen.insert(app.path.to_lowercase()));
+    apps
+}
+
+// インストール済みのPhotoshopを新しい順に列挙
+pub fn detect_photoshop_versions() -> Vec<DetectedAdobeApp> {
+    let mut apps = detect_from_registry("Photoshop", "Photoshop.exe");
+    for path in FALLBACK_PHOTOSHOP_PATHS {
+        let exe = Path::new(path);
+        if exe.exists() {
+            apps.push(DetectedAdobeApp {
+                name: app_name(exe),
+                version: None,
+                path: path.to_string(),
+            });
+        }
+    }
+    sort_newest_first(apps)
+}
+
+// インストール済みのBridgeを新しい順に列挙
+pub fn detect_bridge_versions() -> Vec<DetectedAdobeApp> {
+    sort_newest_first(detect_from_registry("Adobe Bridge", "Adobe Bridge.exe"))
+}
+
+// TIFF変換に使うPhotoshop（設定で選択したもの、なければ最新版）
+pub fn preferred_photoshop_path() -> Option<String> {
+    if let Some(path) = load_settings().photoshop_path.filter(|p| Path::new(p).exists()) {
+        return Some(path);
+    }
+    detect_photoshop_versions().into_iter().next().map(|app| app.path)
+}
+
+// プロジェクトで固定したPhotoshopが使えるか確認し、実行ファイルのパスを返す
+// バージョンが分かる場合は、同じバージョンが別の場所にあればそれを使う
+// 共有されたプロジェクトに書かれたパスをそのまま起動しないよう、検出したインストールに一致するものだけ使う
+pub fn resolve_pinned_photoshop(pinned: &DetectedAdobeApp) -> Result<String, String> {
+    let detected = detect_photoshop_versions();
+
+    if let Some(ref version) = pinned.version {
+        if let Some(app) = detected.iter().find(|app| app.version.as_deref() == Some(version.as_str())) {
+            return Ok(app.path.clone());
+        }
+        return Err(format!(
+            "プロジェクトで指定されたPhotoshop（{} / バージョン {}）が見つかりません。\
+             バージョンによって色変換の結果が変わるため、同じバージョンをインストールするか指定を変更してください",
+            pinned.name, version
+        ));
+    }
+
+    let pinned_path = fs::canonicalize(&pinned.path).ok();
+    let installed = pinned_path.as_ref().and_then(|pinned_path| {
+        detected
+            .iter()
+            .find(|app| fs::canonicalize(&app.path).ok().as_ref() == Some(pinned_path))
+    });
+    match installed {
+        Some(app) => Ok(app.path.clone()),
+        None => Err(format!(
+            "プロジェクトで指定されたPhotoshop（{}）はインストール済みのPhotoshopとして検出されていないため使用しません: {}",
+            pinned.name, pinned.path
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(version: &str) -> DetectedAdobeApp {
+        DetectedAdobeApp {
+            name: "Adobe Photoshop".to_string(),
+            version: Some(version.to_string()),
+            path: format!("ps-{}", version),
+        }
+    }
+
+    #[test]
+    fn sorts_versions_numerically() {
+        let sorted = sort_newest_first(vec![app("10.9"), app("10.10"), app("9.0"), app("10.9.1")]);
+        let versions: Vec<_> = sorted.iter().filter_map(|a| a.version.as_deref()).collect();
+        assert_eq!(versions, ["10.10", "10.9.1", "10.9", "9.0"]);
+    }
+}
@@ -0,0 +1,249 @@
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+use serde::Deserialize;
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+use crate::app_mode::is_safe_mode;
+use crate::commands::export::run_export;
+use crate::commands::history::rerun_export;
+use crate::commands::preflight::run_preflight;
+use crate::commands::project::read_project_file;
+use crate::jobs::JobRegistry;
+use crate::plugins::{run_plugins, ANALYZER_PROTOCOL_VERSION};
+use crate::settings::save_settings;
+use crate::token::{generate_token, tokens_match};
+use crate::types::{AnalyzerPage, AnalyzerRequest, AppSettings, ExportPage, ExportRequest, PreflightOptions};
+
+// 起動中のローカルHTTP APIサーバー（アプリの管理状態）
+pub struct ApiServer {
+    running: Mutex<Option<RunningApi>>,
+}
+
+struct RunningApi {
+    server: Arc<Server>,
+    port: u16,
+    // 設定変更で差し替える（ポートが同じなら待ち受けはそのまま）
+    token: Arc<RwLock<String>>,
+}
+
+impl ApiServer {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+        }
+    }
+}
+
+// 解析要求（POST /api/analyze）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnalyzeBody {
+    pages: Vec<AnalyzerPage>,
+    #[serde(default)]
+    plugin_ids: Option<Vec<String>>,
+    #[serde(default)]
+    options: Option<serde_json::Value>,
+}
+
+// プロジェクト読み込み要求（POST /api/project/load）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoadProjectBody {
+    path: String,
+}
+
+// プリフライト要求（POST /api/preflight）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PreflightBody {
+    project_path: String,
+    // 未指定ならプロジェクトファイルのあるフォルダ
+    #[serde(default)]
+    base_path: Option<String>,
+    #[serde(default)]
+    options: Option<PreflightOptions>,
+}
+
+// プリセット（過去のエクスポートの設定）を指定したエクスポート要求（POST /api/export/preset）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PresetExportBody {
+    // エクスポート履歴のID
+    preset_id: String,
+    #[serde(default)]
+    pages: Option<Vec<ExportPage>>,
+    #[serde(default)]
+    overrides: Option<serde_json::Value>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+// 設定に合わせてサーバーを起動・停止（設定変更時にも呼ぶ）
+pub fn apply_api_server_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+    let state = app.state::<ApiServer>();
+    let mut running = state.running.lock().map_err(|e| e.to_string())?;
+
+    if !settings.api_server_enabled || is_safe_mode() {
+        if let Some(previous) = running.take() {
+            previous.server.unblock();
+        }
+        return Ok(());
+    }
+
+    let token = ensure_api_token(settings)?;
+
+    // ポートが同じなら待ち受けを続けたままトークンだけ差し替える
+    if let Some(current) = running.as_ref().filter(|r| r.port == settings.api_server_port) {
+        *current.token.write().map_err(|e| e.to_string())? = token;
+        return Ok(());
+    }
+
+    // 新しいポートで待ち受けてから古いサーバーを止める（間に他のプロセスにポートを取られない）
+    // 外部からの接続を受けないようループバックのみで待ち受け
+    let server = Server::http(("127.0.0.1", settings.api_server_port))
+        .map_err(|e| format!("ローカルAPIの起動に失敗（ポート {}）: {}", settings.api_server_port, e))?;
+    let server = Arc::new(server);
+    let token = Arc::new(RwLock::new(token));
+
+    let thread_server = server.clone();
+    let thread_token = token.clone();
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for request in thread_server.incoming_requests() {
+            // エクスポートなど時間のかかる処理があるため1リクエスト1スレッドで処理
+            let app = app.clone();
+            let token = thread_token.clone();
+            std::thread::spawn(move || handle_request(&app, request, &token));
+        }
+    });
+
+    if let Some(previous) = running.replace(RunningApi {
+        server,
+        port: settings.api_server_port,
+        token,
+    }) {
+        previous.server.unblock();
+    }
+    Ok(())
+}
+
+// 認証トークン（未設定なら生成して設定に保存し、設定画面から確認できるようにする）
+fn ensure_api_token(settings: &AppSettings) -> Result<String, String> {
+    if let Some(token) = settings.api_token.clone().filter(|t| !t.is_empty()) {
+        return Ok(token);
+    }
+    let token = generate_token()?;
+    save_settings(&AppSettings {
+        api_token: Some(token.clone()),
+        ..settings.clone()
+    })?;
+    Ok(token)
+}
+
+fn handle_request(app: &AppHandle, mut request: Request, token: &RwLock<String>) {
+    let (status, body) = if let Err(reason) = check_origin(&request) {
+        (403, json!({ "error": reason }))
+    } else if !is_authorized(&request, token) {
+        (401, json!({ "error": "認証トークンが正しくありません" }))
+    } else {
+        let mut content = String::new();
+        match request.as_reader().read_to_string(&mut content) {
+            Ok(_) => route(app, request.method(), request.url(), &content),
+            Err(e) => (400, json!({ "error": format!("リクエストの読み込みに失敗: {}", e) })),
+        }
+    };
+
+    let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json; charset=utf-8"[..])
+        .expect("静的なヘッダーは常に有効");
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(e) = request.respond(response) {
+        eprintln!("ローカルAPIの応答に失敗: {}", e);
+    }
+}
+
+fn header_value<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv(name))
+        .map(|h| h.value.as_str())
+}
+
+// ブラウザ経由の要求を拒否（Webページからの CSRF・DNSリバインディング対策）
+// 外部ツールは Origin を送らず、Host はループバックのアドレスになる
+fn check_origin(request: &Request) -> Result<(), &'static str> {
+    if header_value(request, "Origin").is_some() {
+        return Err("ブラウザからの要求は受け付けません");
+    }
+    let host = header_value(request, "Host").ok_or("Host ヘッダーがありません")?;
+    let name = match host.rsplit_once(':') {
+        // IPv6 は "[::1]:47321" の形式
+        Some((name, port)) if !name.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    if matches!(name.to_ascii_lowercase().as_str(), "127.0.0.1" | "localhost" | "[::1]") {
+        Ok(())
+    } else {
+        Err("ループバック以外のホスト名では受け付けません")
+    }
+}
+
+fn is_authorized(request: &Request, token: &RwLock<String>) -> bool {
+    let Ok(token) = token.read() else {
+        return false;
+    };
+    header_value(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| tokens_match(given.trim(), &token))
+}
+
+// パスごとに処理を振り分け（ステータスコードとJSONを返す）
+fn route(app: &AppHandle, method: &Method, url: &str, body: &str) -> (u16, serde_json::Value) {
+    let path = url.split('?').next().unwrap_or(url);
+    let result = match (method, path) {
+        (Method::Get, "/api/status") => Ok(json!({
+            "version": app.package_info().version.to_string(),
+            "jobs": app.state::<JobRegistry>().list(),
+        })),
+        (Method::Post, "/api/project/load") => parse_body::<LoadProjectBody>(body)
+            .and_then(|b| read_project_file(Path::new(&b.path)))
+            .and_then(|project| serde_json::to_value(project).map_err(|e| e.to_string())),
+        (Method::Post, "/api/analyze") => parse_body::<AnalyzeBody>(body).map(|b| {
+            let request = AnalyzerRequest {
+                protocol_version: ANALYZER_PROTOCOL_VERSION,
+                pages: b.pages,
+                options: b.options.unwrap_or(serde_json::Value::Null),
+            };
+            json!({ "results": run_plugins(&request, b.plugin_ids.as_deref()) })
+        }),
+        (Method::Post, "/api/preflight") => parse_body::<PreflightBody>(body).and_then(|b| {
+            let project_path = Path::new(&b.project_path);
+            let project = read_project_file(project_path)?;
+            let base_path = b.base_path.unwrap_or_else(|| {
+                project_path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default()
+            });
+            let report = run_preflight(&project, &base_path, &b.options.unwrap_or_default())?;
+            serde_json::to_value(report).map_err(|e| e.to_string())
+        }),
+        (Method::Post, "/api/export") => parse_body::<ExportRequest>(body)
+            .and_then(|request| run_export(app, request))
+            .map(|exported| json!({ "exported": exported })),
+        (Method::Post, "/api/export/preset") => parse_body::<PresetExportBody>(body)
+            .and_then(|b| rerun_export(app, &b.preset_id, b.pages, b.overrides, b.dry_run))
+            .and_then(|result| serde_json::to_value(result).map_err(|e| e.to_string())),
+        _ => return (404, json!({ "error": format!("不明なエンドポイント: {} {}", method, path) })),
+    };
+
+    match result {
+        Ok(value) => (200, value),
+        Err(e) => (400, json!({ "error": e })),
+    }
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T, String> {
+    serde_json::from_str(body).map_err(|e| format!("JSON解析エラー: {}", e))
+}
@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+// ポータブルモードのデータフォルダ名（実行ファイルと同じ場所に置く）
+const PORTABLE_DATA_DIR: &str = "portable-data";
+
+// セーフモード（キャッシュ・監視・最近使ったファイル・設定を読み込まずに起動）
+// 壊れたキャッシュや設定で起動時にクラッシュする場合の復旧用
+// 起動引数 --safe-mode または環境変数 DAIDORI_SAFE_MODE=1 で有効
+pub fn is_safe_mode() -> bool {
+    static SAFE_MODE: OnceLock<bool> = OnceLock::new();
+    *SAFE_MODE.get_or_init(|| {
+        std::env::args().any(|arg| arg == "--safe-mode")
+            || std::env::var("DAIDORI_SAFE_MODE").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+    })
+}
+
+// ポータブルモードのデータフォルダ（設定・キャッシュ・最近使ったファイルを保存）
+// 実行ファイルの横に portable-data フォルダがあるか、環境変数 DAIDORI_PORTABLE=1 で有効
+// USBメモリから印刷所の確認用端末で起動する場合など、OSのユーザーフォルダを汚さない
+pub fn portable_data_dir() -> Option<&'static PathBuf> {
+    static PORTABLE_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    PORTABLE_DIR
+        .get_or_init(|| {
+            let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+            let data_dir = exe_dir.join(PORTABLE_DATA_DIR);
+            let requested = std::env::var("DAIDORI_PORTABLE").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"));
+            if requested && !data_dir.exists() {
+                if let Err(e) = std::fs::create_dir_all(&data_dir) {
+                    eprintln!("ポータブルデータフォルダ作成失敗: {} - {}", data_dir.display(), e);
+                    return None;
+                }
+            }
+            data_dir.is_dir().then_some(data_dir)
+        })
+        .as_ref()
+}
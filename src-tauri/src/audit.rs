@@ -0,0 +1,83 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use crate::file_utils::get_config_path;
+use crate::settings::load_settings;
+use crate::state::AppState;
+use crate::types::AuditEntry;
+
+fn audit_log_path() -> Result<PathBuf, String> {
+    Ok(get_config_path()?.join("audit.log"))
+}
+
+pub fn machine_name() -> Option<String> {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .filter(|s| !s.is_empty())
+}
+
+// 破壊的な操作の前に操作者名を確認（設定で必須にしている場合）
+pub fn require_operator(app: &AppHandle) -> Result<Option<String>, String> {
+    let operator = app.state::<AppState>().operator();
+    if operator.is_none() && load_settings().require_operator_name {
+        return Err("操作者名が未入力です。操作者名を入力してから実行してください".to_string());
+    }
+    Ok(operator)
+}
+
+// 監査ログに1行追記（JSON Lines 形式）
+pub fn record_audit(
+    operator: Option<String>,
+    action: &str,
+    target: &str,
+    details: String,
+    result: Result<(), &str>,
+) {
+    let entry = AuditEntry {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        operator,
+        machine: machine_name(),
+        action: action.to_string(),
+        target: target.to_string(),
+        details,
+        success: result.is_ok(),
+        error: result.err().map(|e| e.to_string()),
+    };
+
+    let written = (|| -> Result<(), String> {
+        let path = audit_log_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| e.to_string())?;
+        writeln!(file, "{}", line).map_err(|e| e.to_string())
+    })();
+
+    if let Err(e) = written {
+        eprintln!("監査ログの書き込みに失敗: {}", e);
+    }
+}
+
+// 監査ログを新しい順に読み込み
+pub fn read_audit_log(limit: usize) -> Result<Vec<AuditEntry>, String> {
+    let path = audit_log_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = fs::File::open(&path).map_err(|e| format!("読み込みエラー: {}", e))?;
+    let mut entries: Vec<AuditEntry> = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect();
+    entries.reverse();
+    entries.truncate(limit);
+    Ok(entries)
+}
@@ -0,0 +1,207 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use crate::file_utils::{get_config_path, modified_millis, write_replacing};
+use crate::types::{ProjectFile, RecoveryCandidate, RecoverySnapshot};
+
+// 停止指示を確認する間隔
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// 自動保存の共有状態
+struct AutosaveShared {
+    recovery_path: PathBuf,
+    project_path: Option<String>,
+    /// 未保存の最新スナップショット（書き込むと消える）
+    pending: Mutex<Option<ProjectFile>>,
+    stop: AtomicBool,
+}
+
+// プロジェクトの自動保存（破棄されるとスレッドを停止）
+pub struct Autosave {
+    shared: Arc<AutosaveShared>,
+}
+
+impl Autosave {
+    // フロントエンドから届いた最新のプロジェクト状態を次回の自動保存で書き込む
+    pub fn update(&self, project: ProjectFile) {
+        *self.shared.pending.lock().unwrap_or_else(|e| e.into_inner()) = Some(project);
+    }
+
+    // 最新の状態をすぐに書き込む
+    pub fn flush(&self) -> Result<(), String> {
+        write_pending(&self.shared)
+    }
+
+    pub fn recovery_path(&self) -> &Path {
+        &self.shared.recovery_path
+    }
+
+    // 未保存（パスのない）プロジェクトの自動保存か
+    pub fn is_untitled(&self) -> bool {
+        self.shared.project_path.is_none()
+    }
+}
+
+impl Drop for Autosave {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// 復旧用スナップショットの保存先
+fn recovery_dir() -> Result<PathBuf, String> {
+    let dir = get_config_path()?.join("recovery");
+    fs::create_dir_all(&dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+    Ok(dir)
+}
+
+// 未保存のプロジェクトのスナップショットの接頭辞
+const UNTITLED_PREFIX: &str = "untitled-";
+// 正常終了したセッションの印の拡張子
+const CLEAN_EXIT_EXTENSION: &str = "clean";
+
+// このプロセスのセッションID（起動時刻）。未保存のプロジェクトのスナップショットを前回以前のものと区別する
+fn session_id() -> &'static str {
+    static SESSION: OnceLock<String> = OnceLock::new();
+    SESSION.get_or_init(|| chrono::Local::now().format("%Y%m%d%H%M%S%3f").to_string())
+}
+
+// プロジェクトごとのスナップショットのパス（未保存のプロジェクトはセッション・ウィンドウごと）
+// 次の起動で同じウィンドウラベルを使っても、異常終了時に残ったスナップショットを上書きしない
+pub fn recovery_path_for(project_path: Option<&str>, window_label: &str) -> Result<PathBuf, String> {
+    let key = match project_path {
+        Some(path) => format!("{:x}", md5::compute(path)),
+        None => format!("{}{}-{}", UNTITLED_PREFIX, session_id(), window_label),
+    };
+    Ok(recovery_dir()?.join(format!("{}.json", key)))
+}
+
+// 正常終了したことを記録（次回起動時にこのセッションの未保存スナップショットを復旧候補にしない）
+pub fn mark_clean_exit() {
+    let marked = recovery_dir()
+        .and_then(|dir| {
+            fs::write(dir.join(format!("{}.{}", session_id(), CLEAN_EXIT_EXTENSION)), b"")
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = marked {
+        eprintln!("正常終了の記録に失敗: {}", e);
+    }
+}
+
+// 未保存のプロジェクトのスナップショットを作成したセッション（"untitled-<セッション>-<ウィンドウ>.json"）
+fn untitled_session(path: &Path) -> Option<&str> {
+    let stem = path.file_stem()?.to_str()?;
+    stem.strip_prefix(UNTITLED_PREFIX)?.split('-').next()
+}
+
+fn write_pending(shared: &AutosaveShared) -> Result<(), String> {
+    let Some(project) = shared.pending.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return Ok(());
+    };
+    let snapshot = RecoverySnapshot {
+        project_path: shared.project_path.clone(),
+        saved_at: chrono::Local::now().to_rfc3339(),
+        project,
+    };
+    let json = serde_json::to_string(&snapshot).map_err(|e| format!("JSONシリアライズエラー: {}", e))?;
+    write_replacing(&shared.recovery_path, |file| file.write_all(json.as_bytes()).map_err(|e| e.to_string()))
+}
+
+// 一定間隔で最新のプロジェクト状態を復旧用フォルダに書き込むスレッドを開始
+pub fn start_autosave(project_path: Option<String>, window_label: &str, interval: Duration) -> Result<Autosave, String> {
+    let shared = Arc::new(AutosaveShared {
+        recovery_path: recovery_path_for(project_path.as_deref(), window_label)?,
+        project_path,
+        pending: Mutex::new(None),
+        stop: AtomicBool::new(false),
+    });
+
+    let worker = shared.clone();
+    std::thread::spawn(move || {
+        let mut last_write = Instant::now();
+        while !worker.stop.load(Ordering::Relaxed) {
+            std::thread::sleep(POLL_INTERVAL);
+            if last_write.elapsed() < interval {
+                continue;
+            }
+            last_write = Instant::now();
+            if let Err(e) = write_pending(&worker) {
+                eprintln!("自動保存に失敗: {}", e);
+            }
+        }
+    });
+
+    Ok(Autosave { shared })
+}
+
+// 保存済みのプロジェクトの復旧用スナップショットを削除（通常の保存が完了したとき）
+pub fn clear_recovery(project_path: &str) {
+    if let Ok(path) = recovery_path_for(Some(project_path), "") {
+        let _ = fs::remove_file(path);
+    }
+}
+
+// 復旧用スナップショットを読み込み
+pub fn read_snapshot(path: &Path) -> Result<RecoverySnapshot, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("ファイル読み込みエラー: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("JSON解析エラー: {}", e))
+}
+
+// 前回異常終了したときに残った復旧候補（保存済みのプロジェクトより古いものは削除）
+// 正常終了したセッションの未保存スナップショットは異常終了の名残ではないため削除
+pub fn recovery_candidates() -> Result<Vec<RecoveryCandidate>, String> {
+    let dir = recovery_dir()?;
+    let entries: Vec<_> = fs::read_dir(&dir).map_err(|e| e.to_string())?.filter_map(|e| e.ok()).collect();
+    let clean_markers: Vec<PathBuf> = entries
+        .iter()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some(CLEAN_EXIT_EXTENSION))
+        .collect();
+    let clean_sessions: Vec<&str> = clean_markers
+        .iter()
+        .filter_map(|p| p.file_stem().and_then(|s| s.to_str()))
+        .collect();
+
+    let mut candidates = Vec::new();
+    for entry in entries {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        if untitled_session(&path).is_some_and(|session| clean_sessions.contains(&session)) {
+            let _ = fs::remove_file(&path);
+            continue;
+        }
+        let (Ok(snapshot), Ok(metadata)) = (read_snapshot(&path), entry.metadata()) else {
+            continue;
+        };
+        let snapshot_modified = modified_millis(&metadata);
+
+        // プロジェクトファイルの方が新しければ、スナップショット以降に保存されている
+        let project_modified = snapshot
+            .project_path
+            .as_deref()
+            .and_then(|p| fs::metadata(p).ok())
+            .map(|m| modified_millis(&m));
+        if project_modified.is_some_and(|m| m >= snapshot_modified) {
+            let _ = fs::remove_file(&path);
+            continue;
+        }
+
+        candidates.push(RecoveryCandidate {
+            recovery_path: path.to_string_lossy().to_string(),
+            project_path: snapshot.project_path,
+            project_name: snapshot.project.name,
+            saved_at: snapshot.saved_at,
+            project_exists: project_modified.is_some(),
+        });
+    }
+    for marker in &clean_markers {
+        let _ = fs::remove_file(marker);
+    }
+    candidates.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+    Ok(candidates)
+}
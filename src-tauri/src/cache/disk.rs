@@ -1,5 +1,6 @@
 use std::fs;
 use std::path::PathBuf;
+use crate::app_mode::{is_safe_mode, portable_data_dir};
 
 // サムネイルキャッシュディレクトリ
 pub struct ThumbnailCache {
@@ -8,10 +9,20 @@ pub struct ThumbnailCache {
 
 impl ThumbnailCache {
     pub fn new() -> Self {
-        let cache_dir = dirs::cache_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("daidori-manager")
-            .join("thumbnails");
+        let cache_dir = if is_safe_mode() {
+            // セーフモードでは既存のキャッシュを使わず、毎回空の一時キャッシュで起動
+            // 同時に起動した他のインスタンスのキャッシュを消さないよう、プロセスごとのフォルダにする
+            let dir = std::env::temp_dir().join(format!("daidori-manager-safe-mode-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            dir.join("thumbnails")
+        } else if let Some(data_dir) = portable_data_dir() {
+            data_dir.join("cache").join("thumbnails")
+        } else {
+            dirs::cache_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("daidori-manager")
+                .join("thumbnails")
+        };
 
         // キャッシュディレクトリ作成（エラー時はログ出力）
         if let Err(e) = fs::create_dir_all(&cache_dir) {
@@ -20,4 +31,40 @@ impl ThumbnailCache {
 
         Self { cache_dir }
     }
+
+    // ディスクキャッシュ内のファイルをすべて削除し、解放したバイト数を返す
+    // フォルダ自体は残す（生成中の書き込み先がなくならないように）
+    pub fn clear(&self) -> Result<u64, String> {
+        let entries = match fs::read_dir(&self.cache_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(format!("キャッシュディレクトリ読み込みエラー: {}", e)),
+        };
+
+        let mut freed = 0;
+        for entry in entries.filter_map(|e| e.ok()) {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            // 使用中で削除できないファイルは次回に回す
+            if fs::remove_file(entry.path()).is_ok() {
+                freed += metadata.len();
+            }
+        }
+        Ok(freed)
+    }
+
+    // サムネイル以外のキャッシュ（プレビュー等）の保存先を取得・作成
+    pub fn sibling_dir(&self, name: &str) -> Result<PathBuf, String> {
+        let dir = self
+            .cache_dir
+            .parent()
+            .map(|p| p.join(name))
+            .unwrap_or_else(|| self.cache_dir.join(name));
+        fs::create_dir_all(&dir).map_err(|e| format!("キャッシュディレクトリ作成失敗: {} - {}", dir.display(), e))?;
+        Ok(dir)
+    }
 }
@@ -26,6 +26,11 @@ impl ThumbnailMemoryCache {
         }
     }
 
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.order.clear();
+    }
+
     pub fn insert(&mut self, key: String, value: String) {
         // 既存のキーがあれば更新
         if self.cache.contains_key(&key) {
@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use crate::types::CleanupReport;
+
+// 前回の掃除結果（起動時の結果を設定画面で表示するため保持）
+static LAST_REPORT: Mutex<Option<CleanupReport>> = Mutex::new(None);
+
+// 一時フォルダに残るTIFF変換の受け渡しファイル
+const TIFF_TEMP_PREFIX: &str = "daidori_tiff_";
+
+// 掃除対象のファイル名か
+fn is_orphan_name(name: &str, in_temp_dir: bool) -> bool {
+    if in_temp_dir {
+        return name.starts_with(TIFF_TEMP_PREFIX);
+    }
+    // 書き込み途中のキャッシュ（"{key}.{pid}.tmp"）、保存途中の設定（"*.json.tmp"）、
+    // 排他書き込みの途中で残った出力（".{name}.partial"）
+    name.ends_with(".tmp") || (name.starts_with('.') && name.ends_with(".partial"))
+}
+
+// 最終更新が max_age より古いか（取得できなければ対象外）
+fn is_stale(metadata: &fs::Metadata, max_age: Duration) -> bool {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|m| SystemTime::now().duration_since(m).ok())
+        .is_some_and(|age| age >= max_age)
+}
+
+// フォルダ以下の合計サイズ
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some(if metadata.is_dir() { dir_size(&entry.path()) } else { metadata.len() })
+        })
+        .sum()
+}
+
+// 残骸を削除（生成途中で残ったタイルの一時フォルダ "{key}.{pid}_{n}.tmp" はフォルダごと）
+fn remove_orphan(path: &Path, metadata: &fs::Metadata) -> std::io::Result<u64> {
+    if metadata.is_dir() {
+        let size = dir_size(path);
+        fs::remove_dir_all(path)?;
+        Ok(size)
+    } else {
+        fs::remove_file(path)?;
+        Ok(metadata.len())
+    }
+}
+
+// フォルダ直下の残骸ファイル・一時フォルダを削除
+fn sweep_dir(dir: &Path, in_temp_dir: bool, max_age: Duration, report: &mut CleanupReport) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !is_orphan_name(&name, in_temp_dir) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        // 一時フォルダ内のフォルダは他のアプリのものなので対象外
+        let is_staging_dir = metadata.is_dir() && !in_temp_dir;
+        if !(metadata.is_file() || is_staging_dir) || !is_stale(&metadata, max_age) {
+            continue;
+        }
+        match remove_orphan(&entry.path(), &metadata) {
+            Ok(bytes) => {
+                report.files_removed += 1;
+                report.bytes_reclaimed += bytes;
+            }
+            Err(e) => report.errors.push(format!("{}: {}", entry.path().display(), e)),
+        }
+    }
+}
+
+// クラッシュ等で残った一時ファイルを掃除
+// cache_dirs: サムネイル・プレビュー等のキャッシュ、extra_dirs: 出力先など任意のフォルダ
+// max_age が0だと処理中の一時ファイルまで消してしまうため受け付けない
+pub fn cleanup_orphans(cache_dirs: &[&Path], extra_dirs: &[&Path], max_age: Duration) -> Result<CleanupReport, String> {
+    if max_age.is_zero() {
+        return Err("一時ファイルを削除するまでの時間は0より大きくしてください".to_string());
+    }
+    let mut report = CleanupReport::default();
+    sweep_dir(&std::env::temp_dir(), true, max_age, &mut report);
+    for dir in cache_dirs.iter().chain(extra_dirs) {
+        sweep_dir(dir, false, max_age, &mut report);
+    }
+
+    *LAST_REPORT.lock().unwrap_or_else(|e| e.into_inner()) = Some(report.clone());
+    Ok(report)
+}
+
+pub fn last_cleanup_report() -> Option<CleanupReport> {
+    LAST_REPORT.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
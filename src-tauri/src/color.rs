@@ -0,0 +1,147 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use image::DynamicImage;
+use moxcms::{ColorProfile, DataColorSpace, Layout, ToneReprCurve, TransformExecutor, TransformOptions, Xyzd};
+use rayon::prelude::*;
+
+// 埋め込みプロファイルでsRGBに変換するか（起動時・設定保存時に反映）
+static COLOR_MANAGEMENT: AtomicBool = AtomicBool::new(true);
+
+pub fn set_color_management(enabled: bool) {
+    COLOR_MANAGEMENT.store(enabled, Ordering::Relaxed);
+}
+
+pub fn color_management_enabled() -> bool {
+    COLOR_MANAGEMENT.load(Ordering::Relaxed)
+}
+
+// 原色の色度の許容差（プロファイルの固定小数点の丸めや、作成ソフトによる色順応の違いを吸収する）
+const COLORANT_TOLERANCE: f64 = 0.002;
+
+fn same_colorant(a: &Xyzd, b: &Xyzd) -> bool {
+    (a.x - b.x).abs() < COLORANT_TOLERANCE && (a.y - b.y).abs() < COLORANT_TOLERANCE && (a.z - b.z).abs() < COLORANT_TOLERANCE
+}
+
+// トーンカーブが8bitの各階調で半階調未満の差に収まるか
+fn same_trc(source: &ColorProfile, trc: &Option<ToneReprCurve>, reference: &Option<ToneReprCurve>) -> bool {
+    let (Ok(a), Ok(b)) = (source.build_8bit_lin_table(trc), source.build_8bit_lin_table(reference)) else {
+        return false;
+    };
+    a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < 0.5 / 255.0)
+}
+
+// sRGBと同じ原色・トーンカーブのプロファイルか（変換しても結果が変わらないため省略する）
+// 説明の文字列ではなく中身で判定する（"sRGBより広い" のような説明のプロファイルも変換する）
+fn is_srgb_profile(source: &ColorProfile) -> bool {
+    let srgb = ColorProfile::new_srgb();
+    same_colorant(&source.red_colorant, &srgb.red_colorant)
+        && same_colorant(&source.green_colorant, &srgb.green_colorant)
+        && same_colorant(&source.blue_colorant, &srgb.blue_colorant)
+        && same_trc(source, &source.red_trc, &srgb.red_trc)
+        && same_trc(source, &source.green_trc, &srgb.green_trc)
+        && same_trc(source, &source.blue_trc, &srgb.blue_trc)
+}
+
+// 画素データを行ごとに並列で変換（変換元を行単位で複製して同じバッファに書き戻す）
+fn transform_rows<T: Copy + Default + Send + Sync>(
+    data: &mut [T],
+    row_len: usize,
+    transform: &(dyn TransformExecutor<T> + Send + Sync),
+) -> bool {
+    data.par_chunks_mut(row_len.max(1))
+        .try_for_each(|row| {
+            let input = row.to_vec();
+            transform.transform(&input, row).map_err(|_| ())
+        })
+        .is_ok()
+}
+
+fn transform_8bit(data: &mut [u8], row_len: usize, source: &ColorProfile, layout: Layout) -> bool {
+    let target = ColorProfile::new_srgb();
+    source
+        .create_transform_8bit(layout, &target, layout, TransformOptions::default())
+        .is_ok_and(|transform| transform_rows(data, row_len, transform.as_ref()))
+}
+
+fn transform_16bit(data: &mut [u16], row_len: usize, source: &ColorProfile, layout: Layout) -> bool {
+    let target = ColorProfile::new_srgb();
+    source
+        .create_transform_16bit(layout, &target, layout, TransformOptions::default())
+        .is_ok_and(|transform| transform_rows(data, row_len, transform.as_ref()))
+}
+
+// 埋め込みプロファイル（AdobeRGBなど）のRGB画像をsRGBに変換
+// プロファイルを無視して表示すると彩度が落ちて見えるため。変換しない・できない場合はそのまま返す
+// グレースケール・32bit浮動小数点の画像は対象外（16bitの画像は16bitのまま変換する）
+pub fn convert_to_srgb(img: DynamicImage, icc_profile: &[u8]) -> DynamicImage {
+    if !color_management_enabled() {
+        return img;
+    }
+    let Ok(source) = ColorProfile::new_from_slice(icc_profile) else {
+        return img;
+    };
+    if source.color_space != DataColorSpace::Rgb || is_srgb_profile(&source) {
+        return img;
+    }
+
+    let width = img.width() as usize;
+    let (img, converted) = match img {
+        DynamicImage::ImageRgb8(mut buffer) => {
+            let converted = transform_8bit(&mut buffer, width * 3, &source, Layout::Rgb);
+            (DynamicImage::ImageRgb8(buffer), converted)
+        }
+        DynamicImage::ImageRgba8(mut buffer) => {
+            let converted = transform_8bit(&mut buffer, width * 4, &source, Layout::Rgba);
+            (DynamicImage::ImageRgba8(buffer), converted)
+        }
+        DynamicImage::ImageRgb16(mut buffer) => {
+            let converted = transform_16bit(&mut buffer, width * 3, &source, Layout::Rgb);
+            (DynamicImage::ImageRgb16(buffer), converted)
+        }
+        DynamicImage::ImageRgba16(mut buffer) => {
+            let converted = transform_16bit(&mut buffer, width * 4, &source, Layout::Rgba);
+            (DynamicImage::ImageRgba16(buffer), converted)
+        }
+        other => (other, true),
+    };
+    if !converted {
+        eprintln!("ICCプロファイルによる色変換に失敗しました");
+    }
+    img
+}
+
+// CMYK（インク量 0〜255、0 = インクなし）をsRGBに変換
+// 埋め込みのICCプロファイルがあればそれで変換し、なければ単純な式で近似する（色味は印刷結果と異なる）
+pub fn cmyk_to_rgb(cmyk: &[u8], icc_profile: Option<&[u8]>) -> Vec<u8> {
+    let icc_profile = icc_profile.filter(|_| color_management_enabled());
+    if let Some(rgb) = icc_profile.and_then(|profile| cmyk_to_rgb_icc(cmyk, profile)) {
+        return rgb;
+    }
+
+    let mut rgb = vec![0u8; cmyk.len() / 4 * 3];
+    rgb.par_chunks_exact_mut(3)
+        .zip(cmyk.par_chunks_exact(4))
+        .for_each(|(out, ink)| {
+            let white = 255 - ink[3] as u32;
+            for (channel, value) in out.iter_mut().zip(&ink[..3]) {
+                *channel = ((255 - *value as u32) * white / 255) as u8;
+            }
+        });
+    rgb
+}
+
+// ICCプロファイルでCMYKからsRGBに変換（CMYKのプロファイルでない・変換できない場合は None）
+fn cmyk_to_rgb_icc(cmyk: &[u8], icc_profile: &[u8]) -> Option<Vec<u8>> {
+    let source = ColorProfile::new_from_slice(icc_profile).ok()?;
+    if source.color_space != DataColorSpace::Cmyk {
+        return None;
+    }
+    let target = ColorProfile::new_srgb();
+    // 4チャンネルのデータは Rgba のレイアウトとして渡す
+    let transform = source
+        .create_transform_8bit(Layout::Rgba, &target, Layout::Rgb, TransformOptions::default())
+        .ok()?;
+
+    let mut rgb = vec![0u8; cmyk.len() / 4 * 3];
+    transform.transform(cmyk, &mut rgb).ok()?;
+    Some(rgb)
+}
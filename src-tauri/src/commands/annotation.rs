@@ -0,0 +1,439 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use rayon::prelude::*;
+use crate::commands::project::validate_file_reference;
+use crate::history::new_history_id;
+use crate::image_utils::{open_image, save_image};
+use crate::processing::draw_annotation;
+use crate::types::{
+    AnnotationExchange, AnnotationImportResult, AnnotationRecord, AnnotationRenderOptions, AnnotationRenderResult,
+    AnnotationSummary, PageAnnotation, ProjectFile, SavedPage,
+};
+
+// 交換用ファイルの形式の識別子
+const EXCHANGE_FORMAT: &str = "daidori-annotations";
+const EXCHANGE_VERSION: u32 = 1;
+
+// CSVの列（順序固定で書き出し、取り込み時は見出しで対応付け）
+const CSV_COLUMNS: [&str; 13] = [
+    "id", "pageId", "pageNumber", "fileName", "pageLabel", "x", "y", "width", "height", "comment", "author",
+    "createdAt", "resolved",
+];
+
+// 読み順のページ（チャプター名・表示名付き）
+struct OrderedPage<'a> {
+    page: &'a SavedPage,
+    chapter_name: &'a str,
+    label: String,
+}
+
+fn ordered_pages(project: &ProjectFile) -> Vec<OrderedPage<'_>> {
+    project
+        .chapters
+        .iter()
+        .flat_map(|chapter| chapter.pages.iter().map(move |page| (chapter, page)))
+        .enumerate()
+        .map(|(i, (chapter, page))| OrderedPage {
+            page,
+            chapter_name: &chapter.name,
+            label: page.label.clone().unwrap_or_else(|| format!("{} - {}", chapter.name, i + 1)),
+        })
+        .collect()
+}
+
+// ページごとの校正指示（作成順。番号はこの順で振る）
+fn annotations_by_page(project: &ProjectFile, include_resolved: bool) -> HashMap<&str, Vec<&PageAnnotation>> {
+    let mut by_page: HashMap<&str, Vec<&PageAnnotation>> = HashMap::new();
+    for annotation in project.annotations.iter().filter(|a| include_resolved || !a.resolved) {
+        by_page.entry(annotation.page_id.as_str()).or_default().push(annotation);
+    }
+    by_page
+}
+
+/// 校正指示の一覧を読み順で取得（既定では未対応のもののみ）
+#[tauri::command]
+pub fn summarize_annotations(project: ProjectFile, include_resolved: Option<bool>) -> Vec<AnnotationSummary> {
+    let by_page = annotations_by_page(&project, include_resolved.unwrap_or(false));
+    let mut summaries = Vec::new();
+    for ordered in ordered_pages(&project) {
+        let Some(annotations) = by_page.get(ordered.page.id.as_str()) else {
+            continue;
+        };
+        for (i, annotation) in annotations.iter().enumerate() {
+            summaries.push(AnnotationSummary {
+                annotation_id: annotation.id.clone(),
+                page_id: annotation.page_id.clone(),
+                page_label: ordered.label.clone(),
+                chapter_name: ordered.chapter_name.to_string(),
+                number: i + 1,
+                comment: annotation.comment.clone(),
+                author: annotation.author.clone(),
+                created_at: annotation.created_at.clone(),
+                resolved: annotation.resolved,
+            });
+        }
+    }
+    summaries
+}
+
+/// 校正指示の枠と番号を書き込んだ確認用画像を書き出し、番号とコメントの一覧を comments.txt に保存
+#[tauri::command]
+pub async fn render_annotated_pages(
+    project: ProjectFile,
+    base_path: String,
+    output_dir: String,
+    options: Option<AnnotationRenderOptions>,
+) -> Result<AnnotationRenderResult, String> {
+    let options = options.unwrap_or_default();
+
+    tokio::task::spawn_blocking(move || {
+        let out_dir = Path::new(&output_dir);
+        fs::create_dir_all(out_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+        let base = Path::new(&base_path);
+        let by_page = annotations_by_page(&project, options.include_resolved);
+
+        let targets: Vec<(usize, OrderedPage, Vec<&PageAnnotation>)> = ordered_pages(&project)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, ordered)| {
+                let annotations = by_page.get(ordered.page.id.as_str()).cloned().unwrap_or_default();
+                if annotations.is_empty() && !options.include_unannotated {
+                    return None;
+                }
+                Some((i, ordered, annotations))
+            })
+            .collect();
+        if targets.is_empty() {
+            return Err("書き出す校正指示がありません".to_string());
+        }
+
+        let rendered: Vec<Result<String, String>> = targets
+            .par_iter()
+            .map(|(i, ordered, annotations)| {
+                let page_id = ordered.page.id.clone();
+                let file_ref = ordered.page.file.as_ref().ok_or_else(|| page_id.clone())?;
+                let resolved = validate_file_reference(&page_id, file_ref, base)
+                    .resolved_path
+                    .ok_or_else(|| page_id.clone())?;
+                let img = open_image(Path::new(&resolved)).map_err(|_| page_id.clone())?;
+                let img = if img.width().max(img.height()) > options.max_size {
+                    img.resize(options.max_size, options.max_size, FilterType::Triangle)
+                } else {
+                    img
+                };
+
+                let mut canvas = img.to_rgb8();
+                for (n, annotation) in annotations.iter().enumerate() {
+                    draw_annotation(&mut canvas, annotation.x, annotation.y, annotation.width, annotation.height, n + 1);
+                }
+                let output = out_dir.join(format!("{:04}.jpg", i + 1));
+                save_image(&DynamicImage::ImageRgb8(canvas), &output, options.jpg_quality).map_err(|_| page_id)?;
+                Ok(output.to_string_lossy().to_string())
+            })
+            .collect();
+
+        // 番号とコメントの一覧（画像と並べて確認する用）
+        let mut comments = String::new();
+        let mut files = Vec::new();
+        let mut missing_page_ids = Vec::new();
+        for ((_, ordered, annotations), result) in targets.iter().zip(rendered) {
+            match result {
+                Ok(file) => {
+                    let file_name = Path::new(&file).file_name().unwrap_or_default().to_string_lossy().to_string();
+                    if !annotations.is_empty() {
+                        comments.push_str(&format!("■ {}（{}）\n", ordered.label, file_name));
+                        for (n, annotation) in annotations.iter().enumerate() {
+                            let author = annotation.author.as_deref().map(|a| format!(" - {}", a)).unwrap_or_default();
+                            let status = if annotation.resolved { "［対応済］" } else { "" };
+                            comments.push_str(&format!("  [{}] {}{}{}\n", n + 1, status, annotation.comment, author));
+                        }
+                        comments.push('\n');
+                    }
+                    files.push(file);
+                }
+                Err(page_id) => missing_page_ids.push(page_id),
+            }
+        }
+        let comments_path = out_dir.join("comments.txt");
+        fs::write(&comments_path, comments).map_err(|e| format!("ファイル書き込みエラー: {}", e))?;
+
+        Ok(AnnotationRenderResult {
+            output_dir,
+            files,
+            comments_path: comments_path.to_string_lossy().to_string(),
+            missing_page_ids,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// CSVの1項目（区切り・引用符・改行を含む場合は引用符で囲む）
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// CSVを行・項目に分解（引用符内の改行・区切りに対応）
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|f| !f.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                } else {
+                    row.clear();
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+fn records_to_csv(records: &[AnnotationRecord]) -> String {
+    // Excelで文字化けしないようBOMを付ける
+    let mut csv = String::from("\u{feff}");
+    csv.push_str(&CSV_COLUMNS.join(","));
+    csv.push_str("\r\n");
+    for record in records {
+        let fields = [
+            record.id.clone(),
+            record.page_id.clone(),
+            record.page_number.map(|n| n.to_string()).unwrap_or_default(),
+            record.file_name.clone(),
+            record.page_label.clone(),
+            record.x.to_string(),
+            record.y.to_string(),
+            record.width.to_string(),
+            record.height.to_string(),
+            record.comment.clone(),
+            record.author.clone().unwrap_or_default(),
+            record.created_at.clone(),
+            record.resolved.to_string(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&line.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn records_from_csv(text: &str) -> Result<Vec<AnnotationRecord>, String> {
+    let mut rows = parse_csv(text).into_iter();
+    let header = rows.next().ok_or_else(|| "CSVが空です".to_string())?;
+    let column = |name: &str| header.iter().position(|h| h.trim() == name);
+    let indexes: HashMap<&str, usize> = CSV_COLUMNS.iter().filter_map(|c| column(c).map(|i| (*c, i))).collect();
+    for required in ["x", "y", "width", "height", "comment"] {
+        if !indexes.contains_key(required) {
+            return Err(format!("CSVに {} 列がありません", required));
+        }
+    }
+
+    let mut records = Vec::new();
+    for (line, row) in rows.enumerate() {
+        let get = |name: &str| {
+            indexes
+                .get(name)
+                .and_then(|i| row.get(*i))
+                .map(|v| v.trim().to_string())
+                .unwrap_or_default()
+        };
+        let number = |name: &str| {
+            get(name)
+                .parse::<f64>()
+                .map_err(|_| format!("CSVの{}行目: {} が数値ではありません", line + 2, name))
+        };
+        records.push(AnnotationRecord {
+            id: get("id"),
+            page_id: get("pageId"),
+            page_number: get("pageNumber").parse().ok(),
+            file_name: get("fileName"),
+            page_label: get("pageLabel"),
+            x: number("x")?,
+            y: number("y")?,
+            width: number("width")?,
+            height: number("height")?,
+            comment: get("comment"),
+            author: Some(get("author")).filter(|a| !a.is_empty()),
+            created_at: get("createdAt"),
+            resolved: matches!(get("resolved").to_lowercase().as_str(), "true" | "1" | "yes" | "済"),
+        });
+    }
+    Ok(records)
+}
+
+// 出力形式（未指定なら拡張子で判定）
+fn exchange_format(path: &Path, format: Option<&str>) -> String {
+    format
+        .map(str::to_lowercase)
+        .or_else(|| path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()))
+        .filter(|f| f == "csv")
+        .unwrap_or_else(|| "json".to_string())
+}
+
+/// 校正指示を他のツールとやり取りできるJSON/CSVに書き出し（format 未指定なら拡張子で判定）
+/// 戻り値: 書き出した指示の数
+#[tauri::command]
+pub async fn export_annotations(
+    project: ProjectFile,
+    output_path: String,
+    format: Option<String>,
+    include_resolved: Option<bool>,
+) -> Result<usize, String> {
+    tokio::task::spawn_blocking(move || {
+        let by_page = annotations_by_page(&project, include_resolved.unwrap_or(true));
+        let mut records = Vec::new();
+        for (i, ordered) in ordered_pages(&project).into_iter().enumerate() {
+            for annotation in by_page.get(ordered.page.id.as_str()).into_iter().flatten() {
+                records.push(AnnotationRecord {
+                    id: annotation.id.clone(),
+                    page_id: annotation.page_id.clone(),
+                    page_number: Some(i + 1),
+                    file_name: ordered.page.file.as_ref().map(|f| f.file_name.clone()).unwrap_or_default(),
+                    page_label: ordered.label.clone(),
+                    x: annotation.x,
+                    y: annotation.y,
+                    width: annotation.width,
+                    height: annotation.height,
+                    comment: annotation.comment.clone(),
+                    author: annotation.author.clone(),
+                    created_at: annotation.created_at.clone(),
+                    resolved: annotation.resolved,
+                });
+            }
+        }
+
+        let output = Path::new(&output_path);
+        let content = if exchange_format(output, format.as_deref()) == "csv" {
+            records_to_csv(&records)
+        } else {
+            let exchange = AnnotationExchange {
+                format: EXCHANGE_FORMAT.to_string(),
+                version: EXCHANGE_VERSION,
+                project_name: Some(project.name.clone()),
+                exported_at: chrono::Local::now().to_rfc3339(),
+                annotations: records.clone(),
+            };
+            serde_json::to_string_pretty(&exchange).map_err(|e| format!("JSONシリアライズエラー: {}", e))?
+        };
+        fs::write(output, content).map_err(|e| format!("ファイル書き込みエラー: {}", e))?;
+        Ok(records.len())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 他のツールから戻ってきた校正指示（JSON/CSV）を取り込み、対応するページに割り当てる
+/// ページはID → ファイル名 → 通しページ番号の順で照合し、同じIDの指示は上書きする
+/// 結果の annotations をプロジェクトに反映して保存するのはフロントエンド側
+#[tauri::command]
+pub async fn import_annotations(project: ProjectFile, input_path: String) -> Result<AnnotationImportResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let input = Path::new(&input_path);
+        let text = fs::read_to_string(input).map_err(|e| format!("ファイル読み込みエラー: {}", e))?;
+        let json = text.trim_start_matches('\u{feff}').trim_start();
+        let records = if json.starts_with('{') {
+            let exchange: AnnotationExchange =
+                serde_json::from_str(json).map_err(|e| format!("JSON解析エラー: {}", e))?;
+            if exchange.format != EXCHANGE_FORMAT {
+                return Err(format!("校正指示のファイルではありません（{}）", exchange.format));
+            }
+            exchange.annotations
+        } else if json.starts_with('[') {
+            // 指示の配列だけのJSONも受け付ける
+            serde_json::from_str(json).map_err(|e| format!("JSON解析エラー: {}", e))?
+        } else {
+            records_from_csv(&text)?
+        };
+
+        // 照合用の索引
+        let pages = ordered_pages(&project);
+        let by_file_name: HashMap<String, &str> = pages
+            .iter()
+            .filter_map(|o| o.page.file.as_ref().map(|f| (f.file_name.to_lowercase(), o.page.id.as_str())))
+            .collect();
+        let find_page = |record: &AnnotationRecord| -> Option<String> {
+            if let Some(o) = pages.iter().find(|o| !record.page_id.is_empty() && o.page.id == record.page_id) {
+                return Some(o.page.id.clone());
+            }
+            if !record.file_name.is_empty() {
+                if let Some(id) = by_file_name.get(&record.file_name.to_lowercase()) {
+                    return Some(id.to_string());
+                }
+            }
+            let number = record.page_number?.checked_sub(1)?;
+            pages.get(number).map(|o| o.page.id.clone())
+        };
+
+        let mut annotations = project.annotations.clone();
+        let (mut added, mut updated) = (0, 0);
+        let mut unmatched = Vec::new();
+        let now = chrono::Local::now().to_rfc3339();
+        for (i, record) in records.into_iter().enumerate() {
+            let Some(page_id) = find_page(&record) else {
+                unmatched.push(record.comment);
+                continue;
+            };
+            let annotation = PageAnnotation {
+                id: if record.id.is_empty() {
+                    format!("import-{}-{}", new_history_id(), i + 1)
+                } else {
+                    record.id
+                },
+                page_id,
+                x: record.x,
+                y: record.y,
+                width: record.width,
+                height: record.height,
+                comment: record.comment,
+                author: record.author,
+                created_at: if record.created_at.is_empty() { now.clone() } else { record.created_at },
+                resolved: record.resolved,
+            };
+            match annotations.iter_mut().find(|a| a.id == annotation.id) {
+                Some(existing) => {
+                    *existing = annotation;
+                    updated += 1;
+                }
+                None => {
+                    annotations.push(annotation);
+                    added += 1;
+                }
+            }
+        }
+
+        Ok(AnnotationImportResult {
+            annotations,
+            added,
+            updated,
+            unmatched,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
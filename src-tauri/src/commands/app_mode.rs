@@ -0,0 +1,11 @@
+use crate::app_mode::{is_safe_mode, portable_data_dir};
+use crate::types::AppModeInfo;
+
+/// 起動モードを取得（セーフモード時はフロントエンドで案内を表示）
+#[tauri::command]
+pub fn get_app_mode() -> AppModeInfo {
+    AppModeInfo {
+        safe_mode: is_safe_mode(),
+        portable_data_dir: portable_data_dir().map(|p| p.to_string_lossy().to_string()),
+    }
+}
@@ -0,0 +1,23 @@
+use tauri::State;
+use crate::audit::read_audit_log;
+use crate::state::AppState;
+use crate::types::AuditEntry;
+
+/// 操作者名を設定（共有端末で誰が操作したかを監査ログに残す。空文字でクリア）
+#[tauri::command]
+pub fn set_operator_name(state: State<'_, AppState>, name: String) {
+    let name = name.trim();
+    state.set_operator((!name.is_empty()).then(|| name.to_string()));
+}
+
+/// 現在の操作者名を取得
+#[tauri::command]
+pub fn get_operator_name(state: State<'_, AppState>) -> Option<String> {
+    state.operator()
+}
+
+/// 監査ログを新しい順に取得
+#[tauri::command]
+pub async fn get_audit_log(limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+    read_audit_log(limit.unwrap_or(200))
+}
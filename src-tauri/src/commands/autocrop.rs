@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::Path;
+use rayon::prelude::*;
+use tauri::AppHandle;
+use crate::jobs::run_batch_job;
+use crate::types::{AutoCropAnalysis, AutoCropOptions, BatchFileResult, CropRect, CropRequest};
+use crate::image_utils::{open_image_budgeted, processed_file_name, save_image, ImageOperation};
+use crate::processing::detect_crop;
+
+/// スキャン原稿の余白・トンボを検出し、切り抜き候補を返す（ファイルは変更しない）
+#[tauri::command]
+pub async fn analyze_autocrop(
+    paths: Vec<String>,
+    options: AutoCropOptions,
+) -> Result<Vec<AutoCropAnalysis>, String> {
+    tokio::task::spawn_blocking(move || {
+        paths
+            .par_iter()
+            .map(|path| match open_image_budgeted(Path::new(path), ImageOperation::General) {
+                Ok((img, _permit)) => {
+                    let (content, crop) = detect_crop(&img, &options);
+                    AutoCropAnalysis {
+                        path: path.clone(),
+                        width: img.width(),
+                        height: img.height(),
+                        content,
+                        crop,
+                        error: None,
+                    }
+                }
+                Err(e) => {
+                    let empty = CropRect { x: 0, y: 0, width: 0, height: 0 };
+                    AutoCropAnalysis {
+                        path: path.clone(),
+                        width: 0,
+                        height: 0,
+                        content: empty,
+                        crop: empty,
+                        error: Some(e),
+                    }
+                }
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// 1ファイルを切り抜いて出力
+fn crop_single_file(request: &CropRequest, output_dir: &Path, quality: u8) -> Result<String, String> {
+    let source = Path::new(&request.path);
+    let (img, _permit) = open_image_budgeted(source, ImageOperation::General)?;
+    let rect = request.crop;
+
+    if rect.width == 0
+        || rect.height == 0
+        || rect.x + rect.width > img.width()
+        || rect.y + rect.height > img.height()
+    {
+        return Err("切り抜き範囲が画像の外にはみ出しています".to_string());
+    }
+
+    let cropped = img.crop_imm(rect.x, rect.y, rect.width, rect.height);
+    let output_file = output_dir.join(processed_file_name(source));
+
+    save_image(&cropped, &output_file, quality)?;
+    Ok(output_file.to_string_lossy().to_string())
+}
+
+/// 確認済みの切り抜き範囲を適用して出力
+#[tauri::command]
+pub async fn apply_autocrop(
+    app_handle: AppHandle,
+    requests: Vec<CropRequest>,
+    output_dir: String,
+    jpg_quality: Option<u8>,
+) -> Result<Vec<BatchFileResult>, String> {
+    let quality = jpg_quality.unwrap_or(95);
+
+    tokio::task::spawn_blocking(move || {
+        let out_dir = Path::new(&output_dir);
+        fs::create_dir_all(out_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+        Ok(run_batch_job(
+            &app_handle,
+            "autocrop",
+            &format!("切り抜き: {}", output_dir),
+            out_dir,
+            &requests,
+            |request| request.path.as_str(),
+            |request| crop_single_file(request, out_dir, quality),
+        ))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
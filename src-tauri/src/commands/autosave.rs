@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tauri::State;
+use crate::autosave::{read_snapshot, recovery_candidates, recovery_path_for};
+use crate::state::AppState;
+use crate::types::{ProjectFile, RecoveryCandidate};
+
+// 自動保存の既定間隔（秒）
+const DEFAULT_AUTOSAVE_INTERVAL_SECS: u64 = 60;
+
+/// ウィンドウのプロジェクトの自動保存を開始（既に実行中なら置き換え）
+/// file_path は未保存のプロジェクトなら None、interval_secs の既定は60秒
+/// 未保存のプロジェクトに名前を付けて保存した後に呼ぶと、未保存時のスナップショットを削除する
+#[tauri::command]
+pub fn start_autosave(
+    window: tauri::WebviewWindow,
+    state: State<'_, AppState>,
+    file_path: Option<String>,
+    interval_secs: Option<u64>,
+) -> Result<(), String> {
+    let interval = Duration::from_secs(interval_secs.unwrap_or(DEFAULT_AUTOSAVE_INTERVAL_SECS).max(5));
+    let has_path = file_path.is_some();
+    let autosave = crate::autosave::start_autosave(file_path, window.label(), interval)?;
+    let previous = state.with_window(window.label(), |w| w.autosave.replace(autosave));
+    if let Some(previous) = previous.filter(|p| has_path && p.is_untitled()) {
+        let _ = fs::remove_file(previous.recovery_path());
+    }
+    Ok(())
+}
+
+/// 自動保存する最新のプロジェクト状態を渡す（編集のたびに呼ぶ。書き込みは一定間隔）
+#[tauri::command]
+pub fn update_autosave_state(
+    window: tauri::WebviewWindow,
+    state: State<'_, AppState>,
+    project: ProjectFile,
+) -> Result<(), String> {
+    state.with_window(window.label(), |w| match w.autosave {
+        Some(ref autosave) => {
+            autosave.update(project);
+            Ok(())
+        }
+        None => Err("自動保存が開始されていません".to_string()),
+    })
+}
+
+/// 自動保存を停止
+/// discard: 復旧用スナップショットも削除する（保存せずに閉じることを選んだ場合など）
+#[tauri::command]
+pub fn stop_autosave(window: tauri::WebviewWindow, state: State<'_, AppState>, discard: Option<bool>) {
+    let autosave = state.with_window(window.label(), |w| w.autosave.take());
+    if let Some(autosave) = autosave {
+        if discard.unwrap_or(false) {
+            let _ = fs::remove_file(autosave.recovery_path());
+        } else if let Err(e) = autosave.flush() {
+            eprintln!("自動保存に失敗: {}", e);
+        }
+    }
+}
+
+/// 前回異常終了した際に残った復旧候補を取得（起動時に確認）
+#[tauri::command]
+pub async fn get_recovery_candidates() -> Result<Vec<RecoveryCandidate>, String> {
+    tokio::task::spawn_blocking(recovery_candidates)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 復旧候補のプロジェクトを読み込み
+#[tauri::command]
+pub async fn load_recovery(recovery_path: String) -> Result<ProjectFile, String> {
+    tokio::task::spawn_blocking(move || {
+        ensure_recovery_path(&recovery_path)?;
+        read_snapshot(Path::new(&recovery_path)).map(|snapshot| snapshot.project)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 復旧候補を破棄
+#[tauri::command]
+pub async fn discard_recovery(recovery_path: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        ensure_recovery_path(&recovery_path)?;
+        fs::remove_file(&recovery_path).map_err(|e| format!("ファイル削除エラー: {}", e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// 復旧用フォルダ外のファイルを操作しないよう確認
+fn ensure_recovery_path(recovery_path: &str) -> Result<(), String> {
+    let dir = recovery_path_for(None, "")?
+        .parent()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| "復旧用フォルダを特定できません".to_string())?;
+    if Path::new(recovery_path).parent() != Some(dir.as_path()) {
+        return Err("復旧用のファイルではありません".to_string());
+    }
+    Ok(())
+}
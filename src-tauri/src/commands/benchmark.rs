@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use rayon::prelude::*;
+use crate::constants::{SUPPORTED_EXTENSIONS, THUMBNAIL_SIZE};
+use crate::image_utils::{create_thumbnail, encode_image, open_image};
+use crate::types::{BenchmarkFormatResult, BenchmarkParallelResult, BenchmarkReport, BenchmarkThumbnailResult};
+
+// 形式ごとに計測するファイル数
+const SAMPLES_PER_FORMAT: usize = 5;
+// 並列数の計測に使うファイル数の上限
+const PARALLEL_SAMPLES: usize = 16;
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+fn average(total: f64, count: usize) -> f64 {
+    if count == 0 { 0.0 } else { total / count as f64 }
+}
+
+// 計測用のファイルを形式ごとに選ぶ（jpeg → jpg、tiff → tif にまとめる）
+fn collect_samples(folder: &Path) -> Result<BTreeMap<String, Vec<PathBuf>>, String> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(folder)
+        .map_err(|e| format!("フォルダの読み込みに失敗: {}", e))?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect();
+    paths.sort_by(|a, b| natord::compare(&a.to_string_lossy(), &b.to_string_lossy()));
+
+    let mut samples: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for path in paths {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+        if !SUPPORTED_EXTENSIONS.contains(&ext.as_str()) {
+            continue;
+        }
+        let format = match ext.as_str() {
+            "jpeg" => "jpg".to_string(),
+            "tiff" => "tif".to_string(),
+            _ => ext,
+        };
+        let files = samples.entry(format).or_default();
+        if files.len() < SAMPLES_PER_FORMAT {
+            files.push(path);
+        }
+    }
+    Ok(samples)
+}
+
+fn encode_size_and_time(img: &DynamicImage, ext: &str, quality: u8) -> Option<(f64, u64)> {
+    let mut buffer = Cursor::new(Vec::new());
+    let start = Instant::now();
+    encode_image(img, &mut buffer, ext, quality).ok()?;
+    Some((elapsed_ms(start), buffer.into_inner().len() as u64))
+}
+
+// 指定の並列数でサムネイル生成（読み込み〜PNG化）の速度を計測
+fn measure_parallel(files: &[PathBuf], workers: usize) -> Option<f64> {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(workers).build().ok()?;
+    let start = Instant::now();
+    let done: usize = pool.install(|| {
+        files
+            .par_iter()
+            .map(|path| open_image(path).and_then(create_thumbnail).is_ok() as usize)
+            .sum()
+    });
+    let secs = start.elapsed().as_secs_f64();
+    (done > 0 && secs > 0.0).then(|| done as f64 / secs)
+}
+
+fn run_benchmark_blocking(sample_folder: &str) -> Result<BenchmarkReport, String> {
+    let folder = Path::new(sample_folder);
+    if !folder.is_dir() {
+        return Err("無効なフォルダパス".to_string());
+    }
+    let samples = collect_samples(folder)?;
+    if samples.is_empty() {
+        return Err("計測できる画像ファイルがありません".to_string());
+    }
+
+    let cpu_threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let mut notes = Vec::new();
+
+    // 1. 形式ごとの読み込み・縮小・エンコード時間
+    let mut formats = Vec::new();
+    let mut thumbnail_totals: BTreeMap<&str, (f64, u64, usize)> = BTreeMap::new();
+    for (format, paths) in &samples {
+        let (mut decode, mut resize, mut encode, mut pixels) = (0.0, 0.0, 0.0, 0.0);
+        let (mut files, mut failed) = (0, 0);
+
+        for path in paths {
+            let start = Instant::now();
+            let img = match open_image(path) {
+                Ok(img) => img,
+                Err(e) => {
+                    eprintln!("ベンチマーク: 読み込み失敗 {}: {}", path.display(), e);
+                    failed += 1;
+                    continue;
+                }
+            };
+            decode += elapsed_ms(start);
+            pixels += (img.width() as f64 * img.height() as f64) / 1_000_000.0;
+
+            let start = Instant::now();
+            let thumbnail = img.resize(THUMBNAIL_SIZE, THUMBNAIL_SIZE * 14 / 10, FilterType::Triangle);
+            resize += elapsed_ms(start);
+
+            if let Some((ms, _)) = encode_size_and_time(&img, "jpg", 90) {
+                encode += ms;
+            }
+            for thumb_format in ["png", "jpg"] {
+                if let Some((ms, bytes)) = encode_size_and_time(&thumbnail, thumb_format, 90) {
+                    let total = thumbnail_totals.entry(thumb_format).or_insert((0.0, 0, 0));
+                    total.0 += ms;
+                    total.1 += bytes;
+                    total.2 += 1;
+                }
+            }
+            files += 1;
+        }
+
+        let result = BenchmarkFormatResult {
+            format: format.clone(),
+            files,
+            failed,
+            megapixels: average(pixels, files),
+            decode_ms: average(decode, files),
+            resize_ms: average(resize, files),
+            encode_ms: average(encode, files),
+        };
+        if format == "psd" && result.decode_ms > 1000.0 {
+            notes.push(format!(
+                "PSDの読み込みに平均{:.0}ミリ秒かかっています。Photoshopで「最大互換」を有効にして保存すると埋め込みサムネイルで高速に表示できます",
+                result.decode_ms
+            ));
+        }
+        formats.push(result);
+    }
+
+    let thumbnails: Vec<BenchmarkThumbnailResult> = thumbnail_totals
+        .iter()
+        .map(|(format, (ms, bytes, count))| BenchmarkThumbnailResult {
+            format: format.to_string(),
+            encode_ms: average(*ms, *count),
+            bytes: if *count == 0 { 0 } else { bytes / *count as u64 },
+        })
+        .collect();
+
+    // 2. サムネイル形式: 既定は劣化のないPNG。PNGが大幅に大きいか遅い場合のみJPGを勧める
+    let png = thumbnails.iter().find(|t| t.format == "png");
+    let jpg = thumbnails.iter().find(|t| t.format == "jpg");
+    let recommended_thumbnail_format = match (png, jpg) {
+        (Some(png), Some(jpg)) if png.bytes > jpg.bytes * 3 || png.encode_ms > jpg.encode_ms * 2.0 + 10.0 => {
+            notes.push(format!(
+                "PNGのサムネイルはJPGより大きく（{}KB / {}KB）、保存に時間がかかります（{:.1}ms / {:.1}ms）",
+                png.bytes / 1024,
+                jpg.bytes / 1024,
+                png.encode_ms,
+                jpg.encode_ms
+            ));
+            "jpg"
+        }
+        _ => "png",
+    }
+    .to_string();
+
+    // 3. 並列数ごとの速度（1, 2, 4, ... と論理CPU数）
+    let parallel_files: Vec<PathBuf> = samples.values().flatten().take(PARALLEL_SAMPLES).cloned().collect();
+    let mut candidates: Vec<usize> = std::iter::successors(Some(1usize), |n| Some(n * 2))
+        .take_while(|n| *n < cpu_threads)
+        .collect();
+    candidates.push(cpu_threads);
+    let parallel: Vec<BenchmarkParallelResult> = candidates
+        .into_iter()
+        .filter_map(|workers| {
+            measure_parallel(&parallel_files, workers).map(|files_per_sec| BenchmarkParallelResult {
+                workers,
+                files_per_sec,
+            })
+        })
+        .collect();
+
+    let best = parallel.iter().map(|p| p.files_per_sec).fold(0.0, f64::max);
+    let recommended_workers = parallel
+        .iter()
+        .find(|p| p.files_per_sec >= best * 0.9)
+        .map(|p| p.workers)
+        .unwrap_or(cpu_threads);
+    if recommended_workers < cpu_threads {
+        notes.push(format!(
+            "並列数を{}より増やしても速くなりません（ディスクの読み込み速度またはメモリが上限の可能性）",
+            recommended_workers
+        ));
+    }
+
+    Ok(BenchmarkReport {
+        cpu_threads,
+        formats,
+        thumbnails,
+        parallel,
+        recommended_workers,
+        recommended_thumbnail_format,
+        notes,
+    })
+}
+
+/// サンプルフォルダの画像で処理速度を計測し、並列数とサムネイル形式の推奨値を返す
+#[tauri::command]
+pub async fn run_benchmark(sample_folder: String) -> Result<BenchmarkReport, String> {
+    tokio::task::spawn_blocking(move || run_benchmark_blocking(&sample_folder))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,61 @@
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+use crate::jobs::run_batch_job;
+use crate::types::{BatchFileResult, BleedSettings};
+use crate::image_utils::{mm_to_px, open_image_budgeted, processed_file_name, save_image, ImageOperation};
+use crate::processing::apply_bleed;
+
+// 1ファイルに塗り足しを追加して出力
+fn bleed_single_file(source: &Path, output_dir: &Path, bleed: &BleedSettings, quality: u8) -> Result<String, String> {
+    let (img, _permit) = open_image_budgeted(source, ImageOperation::General)?;
+    let padded = apply_bleed(&img, mm_to_px(bleed.bleed_mm, bleed.dpi), &bleed.mode)?;
+    let output_file = output_dir.join(processed_file_name(source));
+
+    save_image(&padded, &output_file, quality)?;
+    Ok(output_file.to_string_lossy().to_string())
+}
+
+// 同じファイルを指すか（出力で元ファイルを上書きしないための確認）
+fn same_file(source: &Path, output_file: &Path) -> bool {
+    match (fs::canonicalize(source), output_file.parent().map(fs::canonicalize)) {
+        (Ok(source), Some(Ok(dir))) => Some(source.as_path()) == output_file.file_name().map(|name| dir.join(name)).as_deref(),
+        _ => false,
+    }
+}
+
+/// 仕上がりサイズのみの原稿に塗り足しを一括追加
+/// 元ファイルと同じフォルダに出力すると上書きになるため、別のフォルダを指定する（PSDはPNGで出力するため対象外）
+#[tauri::command]
+pub async fn apply_bleed_batch(
+    app_handle: AppHandle,
+    paths: Vec<String>,
+    bleed: BleedSettings,
+    output_dir: String,
+    jpg_quality: Option<u8>,
+) -> Result<Vec<BatchFileResult>, String> {
+    let quality = jpg_quality.unwrap_or(95);
+
+    tokio::task::spawn_blocking(move || {
+        let out_dir = Path::new(&output_dir);
+        fs::create_dir_all(out_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+        if let Some(path) = paths
+            .iter()
+            .find(|path| same_file(Path::new(path), &out_dir.join(processed_file_name(Path::new(path)))))
+        {
+            return Err(format!("出力先が元ファイルと同じフォルダです（元ファイルが上書きされます）: {}", path));
+        }
+
+        Ok(run_batch_job(
+            &app_handle,
+            "bleed",
+            &format!("塗り足しの追加: {}", output_dir),
+            out_dir,
+            &paths,
+            |path| path.as_str(),
+            |path| bleed_single_file(Path::new(path), out_dir, &bleed, quality),
+        ))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
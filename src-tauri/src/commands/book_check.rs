@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use crate::types::{BookOutputSpec, BookVerification, PreflightIssue, PreflightReport};
+
+// 指摘を作成
+fn issue(severity: &str, category: &str, message: impl Into<String>) -> PreflightIssue {
+    PreflightIssue {
+        severity: severity.to_string(),
+        category: category.to_string(),
+        message: message.into(),
+    }
+}
+
+// パスの拡張子を除いたファイル名（比較用に小文字化）
+fn file_stem_lower(path: &str) -> String {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    let stem = name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name);
+    stem.to_lowercase()
+}
+
+// XMLから指定タグの属性一覧を抜き出す（OPF・XHTMLの構造確認に必要な範囲のみ）
+fn find_tags(xml: &str, tag: &str) -> Vec<HashMap<String, String>> {
+    let mut tags = Vec::new();
+    let open = format!("<{}", tag);
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        // <itemref と <item のような前方一致を除外
+        if !after.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>') {
+            rest = after;
+            continue;
+        }
+        let end = after.find('>').unwrap_or(after.len());
+        tags.push(parse_attributes(&after[..end]));
+        rest = &after[end..];
+    }
+    tags
+}
+
+fn parse_attributes(source: &str) -> HashMap<String, String> {
+    let mut attrs = HashMap::new();
+    let mut rest = source;
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim().rsplit(char::is_whitespace).next().unwrap_or("").to_string();
+        let value_part = rest[eq + 1..].trim_start();
+        let Some(quote) = value_part.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(close) = value_part[1..].find(quote) else {
+            break;
+        };
+        attrs.insert(name, value_part[1..close + 1].to_string());
+        rest = &value_part[close + 2..];
+    }
+    attrs
+}
+
+// EPUB内のファイルを文字列で読み込み
+fn read_zip_text(archive: &mut zip::ZipArchive<fs::File>, name: &str) -> Result<String, String> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|_| format!("EPUB内に {} がありません", name))?;
+    let mut text = String::new();
+    entry.read_to_string(&mut text).map_err(|e| e.to_string())?;
+    Ok(text)
+}
+
+// OPFからの相対パスをEPUB内のパスに解決
+fn resolve_href(base_dir: &str, href: &str) -> String {
+    let mut parts: Vec<&str> = base_dir.split('/').filter(|p| !p.is_empty()).collect();
+    for part in href.split('#').next().unwrap_or(href).split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            other => parts.push(other),
+        }
+    }
+    parts.join("/")
+}
+
+// 期待する並びと実際の並びを比較
+fn check_page_order(actual: &[String], spec: &BookOutputSpec, issues: &mut Vec<PreflightIssue>) {
+    if spec.page_order.is_empty() {
+        return;
+    }
+    let expected: Vec<String> = spec.page_order.iter().map(|p| file_stem_lower(p)).collect();
+    if actual.len() != expected.len() {
+        issues.push(issue(
+            "error",
+            "pageOrder",
+            format!("ページ数が一致しません（想定 {}、出力 {}）", expected.len(), actual.len()),
+        ));
+    }
+    if let Some(i) = actual.iter().zip(&expected).position(|(a, e)| a != e) {
+        issues.push(issue(
+            "error",
+            "pageOrder",
+            format!("{}ページ目の並びが違います（想定 {}、出力 {}）", i + 1, expected[i], actual[i]),
+        ));
+    }
+}
+
+// 表紙として想定する画像
+fn expected_cover(spec: &BookOutputSpec) -> Option<String> {
+    spec.cover
+        .as_deref()
+        .or(spec.page_order.first().map(String::as_str))
+        .map(file_stem_lower)
+}
+
+// EPUBの綴じ方向・ページ順・表紙を検証
+fn verify_epub(path: &Path, spec: &BookOutputSpec) -> Result<Vec<PreflightIssue>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("ファイル読み込みエラー: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("EPUB読み込みエラー: {}", e))?;
+    let mut issues = Vec::new();
+
+    let container = read_zip_text(&mut archive, "META-INF/container.xml")?;
+    let opf_path = find_tags(&container, "rootfile")
+        .into_iter()
+        .find_map(|attrs| attrs.get("full-path").cloned())
+        .ok_or_else(|| "container.xml にパッケージ文書の指定がありません".to_string())?;
+    let opf = read_zip_text(&mut archive, &opf_path)?;
+    let opf_dir = opf_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+
+    // 綴じ方向: 右綴じは rtl、左綴じは ltr
+    let expected_direction = if spec.binding == "left" { "ltr" } else { "rtl" };
+    let direction = find_tags(&opf, "spine")
+        .first()
+        .and_then(|attrs| attrs.get("page-progression-direction").cloned());
+    match direction.as_deref() {
+        Some(d) if d == expected_direction => {}
+        Some(d) => issues.push(issue(
+            "error",
+            "binding",
+            format!("ページ送りの方向が {} です（{}綴じは {}）", d, binding_label(spec), expected_direction),
+        )),
+        None => issues.push(issue(
+            "error",
+            "binding",
+            format!("spine に page-progression-direction がありません（{}綴じは {}）", binding_label(spec), expected_direction),
+        )),
+    }
+
+    // マニフェスト: id → (パス, properties)
+    let manifest: HashMap<String, (String, String)> = find_tags(&opf, "item")
+        .into_iter()
+        .filter_map(|attrs| {
+            let id = attrs.get("id")?.clone();
+            let href = resolve_href(opf_dir, attrs.get("href")?);
+            Some((id, (href, attrs.get("properties").cloned().unwrap_or_default())))
+        })
+        .collect();
+
+    // spine の順に各ページが表示する画像を取得
+    let mut page_images = Vec::new();
+    for itemref in find_tags(&opf, "itemref") {
+        let Some((href, _)) = itemref.get("idref").and_then(|id| manifest.get(id)) else {
+            continue;
+        };
+        if href.ends_with(".jpg") || href.ends_with(".jpeg") || href.ends_with(".png") {
+            page_images.push(file_stem_lower(href));
+            continue;
+        }
+        let xhtml = read_zip_text(&mut archive, href)?;
+        let page_dir = href.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+        let image = find_tags(&xhtml, "img")
+            .into_iter()
+            .find_map(|attrs| attrs.get("src").cloned())
+            .or_else(|| {
+                find_tags(&xhtml, "image")
+                    .into_iter()
+                    .find_map(|attrs| attrs.get("xlink:href").or(attrs.get("href")).cloned())
+            });
+        match image {
+            Some(src) => page_images.push(file_stem_lower(&resolve_href(page_dir, &src))),
+            None => issues.push(issue("warning", "pageOrder", format!("画像のないページがあります: {}", href))),
+        }
+    }
+    check_page_order(&page_images, spec, &mut issues);
+
+    // 表紙: cover-image の画像が想定どおりで、最初のページに置かれているか
+    let cover_image = manifest
+        .values()
+        .find(|(_, properties)| properties.split_whitespace().any(|p| p == "cover-image"))
+        .map(|(href, _)| file_stem_lower(href));
+    match (cover_image, expected_cover(spec)) {
+        (None, _) => issues.push(issue("error", "cover", "cover-image が指定されていません")),
+        (Some(actual), Some(expected)) if actual != expected => issues.push(issue(
+            "error",
+            "cover",
+            format!("表紙画像が違います（想定 {}、出力 {}）", expected, actual),
+        )),
+        (Some(actual), _) => {
+            if page_images.first() != Some(&actual) {
+                issues.push(issue("error", "cover", "表紙が最初のページに配置されていません"));
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+// PDFの綴じ方向・ページ数を検証（PDFは画像名を持たないため並びはページ数で確認）
+fn verify_pdf(path: &Path, spec: &BookOutputSpec) -> Result<Vec<PreflightIssue>, String> {
+    let document = lopdf::Document::load(path).map_err(|e| format!("PDF読み込みエラー: {}", e))?;
+    let mut issues = Vec::new();
+
+    let catalog = document.catalog().map_err(|e| format!("PDF読み込みエラー: {}", e))?;
+    let preferences = catalog
+        .get(b"ViewerPreferences")
+        .ok()
+        .and_then(|obj| document.dereference(obj).ok())
+        .and_then(|(_, obj)| obj.as_dict().ok());
+    let direction = preferences
+        .and_then(|prefs| prefs.get(b"Direction").ok())
+        .and_then(|obj| obj.as_name().ok())
+        .map(|name| String::from_utf8_lossy(name).to_string());
+
+    // 綴じ方向: 右綴じは R2L、左綴じは L2R（未指定は L2R 扱い）
+    let expected_direction = if spec.binding == "left" { "L2R" } else { "R2L" };
+    let actual_direction = direction.as_deref().unwrap_or("L2R");
+    if actual_direction != expected_direction {
+        issues.push(issue(
+            "error",
+            "binding",
+            format!(
+                "ViewerPreferences の Direction が {} です（{}綴じは {}）",
+                actual_direction,
+                binding_label(spec),
+                expected_direction
+            ),
+        ));
+    }
+
+    // 見開き表示で表紙が単独になる設定か（右綴じは TwoPageRight が一般的）
+    let layout = catalog
+        .get(b"PageLayout")
+        .ok()
+        .and_then(|obj| obj.as_name().ok())
+        .map(|name| String::from_utf8_lossy(name).to_string());
+    if let Some(layout) = layout.filter(|l| l == "TwoPageLeft" || l == "TwoColumnLeft") {
+        issues.push(issue(
+            "warning",
+            "cover",
+            format!("PageLayout が {} のため、見開き表示で表紙が単独ページになりません", layout),
+        ));
+    }
+
+    let page_count = document.get_pages().len();
+    if !spec.page_order.is_empty() && page_count != spec.page_order.len() {
+        issues.push(issue(
+            "error",
+            "pageOrder",
+            format!("ページ数が一致しません（想定 {}、出力 {}）", spec.page_order.len(), page_count),
+        ));
+    }
+
+    Ok(issues)
+}
+
+fn binding_label(spec: &BookOutputSpec) -> &'static str {
+    if spec.binding == "left" {
+        "左"
+    } else {
+        "右"
+    }
+}
+
+/// 出力したEPUB/PDFの綴じ方向・ページ順・表紙の位置を台割の仕様と照合
+/// 結果はプリフライトと同じ形式のレポートで返し、指摘があれば passed = false
+#[tauri::command]
+pub async fn verify_book_output(path: String, spec: BookOutputSpec) -> Result<BookVerification, String> {
+    tokio::task::spawn_blocking(move || {
+        let file = Path::new(&path);
+        let ext = file
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        let issues = match ext.as_str() {
+            "epub" => verify_epub(file, &spec)?,
+            "pdf" => verify_pdf(file, &spec)?,
+            other => return Err(format!("検証に対応していない形式: {}", other)),
+        };
+
+        let passed = !issues.iter().any(|i| i.severity == "error");
+        Ok(BookVerification {
+            passed,
+            report: PreflightReport {
+                title: format!("綴じ方向・ページ順の検証: {}", file.file_name().unwrap_or_default().to_string_lossy()),
+                project_name: None,
+                issues,
+                pages: Vec::new(),
+            },
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,103 @@
+use std::path::Path;
+use rayon::prelude::*;
+use crate::image_utils::read_image_dimensions;
+use crate::types::{ChapterSplitOptions, SuggestedChapter};
+
+// ファイル名を「末尾の番号より前の部分」と「末尾の番号」に分解（"ch02_015" → ("ch02_", Some(15))）
+fn split_name(path: &str) -> (String, Option<u32>) {
+    let stem = Path::new(path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let chars: Vec<char> = stem.chars().collect();
+    let Some(end) = chars.iter().rposition(|c| c.is_ascii_digit()) else {
+        return (stem, None);
+    };
+    let start = chars[..=end]
+        .iter()
+        .rposition(|c| !c.is_ascii_digit())
+        .map_or(0, |i| i + 1);
+    let prefix: String = chars[..start].iter().collect();
+    let number = chars[start..=end].iter().collect::<String>().parse().ok();
+    (prefix, number)
+}
+
+// 接頭辞からチャプター名を作る（区切り文字を除去、なければ連番）
+fn chapter_name(prefix: &str, index: usize) -> String {
+    let name = prefix.trim_matches(|c: char| c == '_' || c == '-' || c == ' ' || c == '.');
+    if name.is_empty() {
+        format!("第{}話", index + 1)
+    } else {
+        name.to_string()
+    }
+}
+
+fn size_changed(prev: Option<(u32, u32)>, cur: Option<(u32, u32)>, tolerance: f64) -> bool {
+    let (Some((pw, ph)), Some((cw, ch))) = (prev, cur) else {
+        return false;
+    };
+    let differs = |a: u32, b: u32| (a as f64 - b as f64).abs() / a.max(1) as f64 > tolerance;
+    // 高さが同じで幅が倍・半分の場合は見開きページなので区切りとしない
+    let spread = !differs(ph, ch) && (!differs(pw, cw * 2) || !differs(pw * 2, cw));
+    !spread && (differs(pw, cw) || differs(ph, ch))
+}
+
+/// フォルダを一括で読み込んだファイル列から、チャプターの区切りを提案
+/// ファイル名の接頭辞・番号のリセットや飛び・画像サイズの変化から判定する（paths は並び順どおり）
+#[tauri::command]
+pub async fn suggest_chapter_splits(
+    paths: Vec<String>,
+    options: Option<ChapterSplitOptions>,
+) -> Result<Vec<SuggestedChapter>, String> {
+    let options = options.unwrap_or_default();
+
+    tokio::task::spawn_blocking(move || {
+        let sizes: Vec<Option<(u32, u32)>> = paths
+            .par_iter()
+            .map(|path| read_image_dimensions(Path::new(path)).ok())
+            .collect();
+        let names: Vec<(String, Option<u32>)> = paths.iter().map(|p| split_name(p)).collect();
+
+        let mut chapters: Vec<SuggestedChapter> = Vec::new();
+        for (i, path) in paths.iter().enumerate() {
+            let mut reasons = Vec::new();
+            let mut score = 0;
+
+            if i > 0 {
+                let (prev_prefix, prev_number) = &names[i - 1];
+                let (prefix, number) = &names[i];
+                if prefix != prev_prefix {
+                    reasons.push(format!("ファイル名の接頭辞が変化 ({} → {})", prev_prefix, prefix));
+                    score += 2;
+                }
+                if let (Some(prev), Some(cur)) = (prev_number, number) {
+                    if cur <= prev {
+                        reasons.push(format!("番号がリセット ({} → {})", prev, cur));
+                        score += 2;
+                    } else if cur - prev > options.max_gap {
+                        reasons.push(format!("番号が飛んでいる ({} → {})", prev, cur));
+                        score += 1;
+                    }
+                }
+                if size_changed(sizes[i - 1], sizes[i], options.size_tolerance) {
+                    reasons.push("画像サイズが変化".to_string());
+                    score += 1;
+                }
+            }
+
+            match chapters.last_mut() {
+                Some(current) if score < options.min_score => current.paths.push(path.clone()),
+                _ => chapters.push(SuggestedChapter {
+                    name: chapter_name(&names[i].0, chapters.len()),
+                    paths: vec![path.clone()],
+                    reasons,
+                    score,
+                }),
+            }
+        }
+
+        Ok(chapters)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
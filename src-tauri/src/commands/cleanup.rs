@@ -0,0 +1,94 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use crate::audit::record_audit;
+use crate::cache::ThumbnailCache;
+use crate::cleanup::{cleanup_orphans, last_cleanup_report};
+use crate::file_utils::get_config_path;
+use crate::settings::load_settings;
+use crate::state::AppState;
+use crate::types::CleanupReport;
+
+// 掃除対象のキャッシュ・設定フォルダ
+fn cache_dirs(cache: &ThumbnailCache) -> Vec<PathBuf> {
+    let mut dirs = vec![cache.cache_dir.clone()];
+    for name in ["proofs", "similarity", "tiles", "night", "spreads", "tablet"] {
+        if let Ok(dir) = cache.sibling_dir(name) {
+            dirs.push(dir);
+        }
+    }
+    if let Ok(config) = get_config_path() {
+        dirs.push(config);
+    }
+    dirs
+}
+
+// 削除したファイルがあれば監査ログに記録
+fn audit_cleanup(operator: Option<String>, dirs: &[PathBuf], report: &CleanupReport) {
+    if report.files_removed == 0 && report.errors.is_empty() {
+        return;
+    }
+    let target = dirs.iter().map(|d| d.to_string_lossy()).collect::<Vec<_>>().join(", ");
+    let details = format!(
+        "{}件を削除 ({} bytes)、削除できなかったファイル {}件",
+        report.files_removed,
+        report.bytes_reclaimed,
+        report.errors.len()
+    );
+    record_audit(operator, "orphan-cleanup", &target, details, Ok(()));
+}
+
+// 起動時にバックグラウンドで掃除し、結果を通知
+pub fn spawn_startup_cleanup(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let max_age = Duration::from_secs(load_settings().cleanup_max_age_hours * 3600);
+        let dirs = cache_dirs(&app.state::<ThumbnailCache>());
+        let dir_refs: Vec<&Path> = dirs.iter().map(|d| d.as_path()).collect();
+        let report = match cleanup_orphans(&dir_refs, &[], max_age) {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("一時ファイルの掃除をスキップしました: {}", e);
+                return;
+            }
+        };
+        audit_cleanup(None, &dirs, &report);
+        if report.files_removed > 0 {
+            eprintln!(
+                "残っていた一時ファイルを削除しました: {}件 ({} bytes)",
+                report.files_removed, report.bytes_reclaimed
+            );
+        }
+        let _ = app.emit("orphan-cleanup-finished", report);
+    });
+}
+
+/// 残った一時ファイル・一時フォルダを掃除（extra_dirs に最近の出力先を渡すと書き込み途中のファイルも削除）
+#[tauri::command]
+pub async fn run_orphan_cleanup(
+    extra_dirs: Option<Vec<String>>,
+    cache: State<'_, ThumbnailCache>,
+    app_state: State<'_, AppState>,
+) -> Result<CleanupReport, String> {
+    let dirs = cache_dirs(&cache);
+    let extra_dirs: Vec<PathBuf> = extra_dirs.unwrap_or_default().into_iter().map(PathBuf::from).collect();
+    let max_age = Duration::from_secs(load_settings().cleanup_max_age_hours * 3600);
+    let operator = app_state.operator();
+
+    tokio::task::spawn_blocking(move || {
+        let dir_refs: Vec<&Path> = dirs.iter().map(|d| d.as_path()).collect();
+        let extra_refs: Vec<&Path> = extra_dirs.iter().map(|d| d.as_path()).collect();
+        let report = cleanup_orphans(&dir_refs, &extra_refs, max_age)?;
+        let audited: Vec<PathBuf> = dirs.iter().chain(extra_dirs.iter()).cloned().collect();
+        audit_cleanup(operator, &audited, &report);
+        Ok(report)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 前回（起動時など）の掃除結果を取得
+#[tauri::command]
+pub fn get_last_cleanup_report() -> Option<CleanupReport> {
+    last_cleanup_report()
+}
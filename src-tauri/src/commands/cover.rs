@@ -0,0 +1,217 @@
+use std::fs;
+use std::path::Path;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use crate::types::{CoverDimensions, CoverLayoutInfo, CoverSpec, CoverSplitResult};
+use crate::image_utils::{
+    is_photoshop_ext, mm_to_px, open_image_for, read_image_dimensions, save_image, validate_dimensions_for, ImageOperation,
+};
+
+// アスペクト比の判定許容誤差
+const LAYOUT_RATIO_TOLERANCE: f64 = 0.03;
+
+// 表紙仕様をピクセル寸法に変換
+pub fn cover_dimensions(spec: &CoverSpec) -> CoverDimensions {
+    let trim_width = mm_to_px(spec.trim_width_mm, spec.dpi);
+    let trim_height = mm_to_px(spec.trim_height_mm, spec.dpi);
+    let spine_width = mm_to_px(spec.spine_width_mm, spec.dpi);
+    let bleed = mm_to_px(spec.bleed_mm, spec.dpi);
+
+    CoverDimensions {
+        trim_width,
+        trim_height,
+        spine_width,
+        bleed,
+        full_width: trim_width * 2 + spine_width + bleed * 2,
+        full_height: trim_height + bleed * 2,
+    }
+}
+
+// 展開図上で表1が左側に来るか（右綴じは 表1 | 背 | 表4 の並び）
+fn front_on_left(spec: &CoverSpec) -> bool {
+    spec.binding != "left"
+}
+
+fn ratio_matches(actual: f64, expected: f64) -> bool {
+    expected > 0.0 && ((actual - expected) / expected).abs() <= LAYOUT_RATIO_TOLERANCE
+}
+
+/// 表紙画像が単ページか展開図（表1+背+表4）かを判定
+#[tauri::command]
+pub async fn detect_cover_layout(path: String, spec: CoverSpec) -> Result<CoverLayoutInfo, String> {
+    let (width, height) = read_image_dimensions(Path::new(&path))?;
+    let expected = cover_dimensions(&spec);
+
+    let actual = width as f64 / height.max(1) as f64;
+    let d = &expected;
+
+    // (レイアウト, 塗り足し込み, 想定アスペクト比)
+    let candidates = [
+        ("wraparound", true, d.full_width as f64 / d.full_height.max(1) as f64),
+        ("wraparound", false, (d.trim_width * 2 + d.spine_width) as f64 / d.trim_height.max(1) as f64),
+        ("single", true, (d.trim_width + d.bleed * 2) as f64 / d.full_height.max(1) as f64),
+        ("single", false, d.trim_width as f64 / d.trim_height.max(1) as f64),
+    ];
+
+    // 最も近い候補を採用（許容誤差外なら unknown）
+    let best = candidates
+        .iter()
+        .filter(|(_, _, ratio)| ratio_matches(actual, *ratio))
+        .min_by(|a, b| {
+            (actual - a.2).abs().partial_cmp(&(actual - b.2).abs()).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+    let (layout, includes_bleed) = match best {
+        Some((layout, bleed, _)) => (layout.to_string(), *bleed),
+        None => ("unknown".to_string(), false),
+    };
+
+    Ok(CoverLayoutInfo {
+        layout,
+        includes_bleed,
+        width,
+        height,
+        expected,
+    })
+}
+
+// 表1・表4（・背）の画像から塗り足し付きの展開図を合成
+pub fn compose_wraparound(
+    front: &DynamicImage,
+    back: &DynamicImage,
+    spine: Option<&DynamicImage>,
+    spec: &CoverSpec,
+) -> Result<DynamicImage, String> {
+    let dims = cover_dimensions(spec);
+    validate_dimensions_for(ImageOperation::Cover, dims.full_width, dims.full_height)?;
+
+    // 表1・表4は外側の塗り足しを含めた幅で配置
+    let panel_width = dims.trim_width + dims.bleed;
+
+    let front = front.resize_to_fill(panel_width, dims.full_height, FilterType::Lanczos3);
+    let back = back.resize_to_fill(panel_width, dims.full_height, FilterType::Lanczos3);
+
+    let mut canvas = image::RgbaImage::from_pixel(
+        dims.full_width,
+        dims.full_height,
+        image::Rgba([255, 255, 255, 255]),
+    );
+
+    let (left, right) = if front_on_left(spec) { (&front, &back) } else { (&back, &front) };
+    image::imageops::overlay(&mut canvas, left, 0, 0);
+    image::imageops::overlay(&mut canvas, right, (panel_width + dims.spine_width) as i64, 0);
+
+    // 背画像が指定されていれば背幅に合わせて配置（未指定なら白）
+    if let Some(spine) = spine {
+        if dims.spine_width > 0 {
+            let spine_img = spine.resize_to_fill(dims.spine_width, dims.full_height, FilterType::Lanczos3);
+            image::imageops::overlay(&mut canvas, &spine_img, panel_width as i64, 0);
+        }
+    }
+
+    Ok(DynamicImage::ImageRgba8(canvas))
+}
+
+/// 表1・表4（・背）から塗り足し付きの展開図を合成
+#[tauri::command]
+pub async fn compose_wraparound_cover(
+    front_path: String,
+    back_path: String,
+    spine_path: Option<String>,
+    spec: CoverSpec,
+    output_path: String,
+    jpg_quality: Option<u8>,
+) -> Result<CoverDimensions, String> {
+    tokio::task::spawn_blocking(move || {
+        let front = open_image_for(Path::new(&front_path), ImageOperation::Cover)?;
+        let back = open_image_for(Path::new(&back_path), ImageOperation::Cover)?;
+        let spine = match spine_path {
+            Some(ref spine) => Some(open_image_for(Path::new(spine), ImageOperation::Cover)?),
+            None => None,
+        };
+
+        let composed = compose_wraparound(&front, &back, spine.as_ref(), &spec)?;
+
+        let output = Path::new(&output_path);
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+        }
+        save_image(&composed, output, jpg_quality.unwrap_or(95))?;
+
+        Ok(cover_dimensions(&spec))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 展開図を表1・背・表4に分割
+#[tauri::command]
+pub async fn split_wraparound_cover(
+    source_path: String,
+    spec: CoverSpec,
+    output_dir: String,
+    jpg_quality: Option<u8>,
+) -> Result<CoverSplitResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let source = Path::new(&source_path);
+        let img = open_image_for(source, ImageOperation::Cover)?;
+        let dims = cover_dimensions(&spec);
+
+        // 実画像と仕様の差は横方向の比率で吸収
+        let scale = img.width() as f64 / dims.full_width.max(1) as f64;
+        let panel_width = ((dims.trim_width + dims.bleed) as f64 * scale).round() as u32;
+        let spine_width = (dims.spine_width as f64 * scale).round() as u32;
+
+        if panel_width * 2 + spine_width > img.width() || panel_width == 0 {
+            return Err(format!(
+                "展開図のサイズが仕様と一致しません: {}x{}",
+                img.width(),
+                img.height()
+            ));
+        }
+
+        let right_x = img.width() - panel_width;
+        let left_img = img.crop_imm(0, 0, panel_width, img.height());
+        // 背幅が0（背のない冊子）なら背は切り出さない
+        let spine_img = (spine_width > 0 && right_x > panel_width)
+            .then(|| img.crop_imm(panel_width, 0, right_x - panel_width, img.height()));
+        let right_img = img.crop_imm(right_x, 0, panel_width, img.height());
+
+        let (front, back) = if front_on_left(&spec) { (left_img, right_img) } else { (right_img, left_img) };
+
+        let out_dir = Path::new(&output_dir);
+        fs::create_dir_all(out_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+        let stem = source
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("cover");
+        let ext = match source.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()) {
+            Some(e) if !is_photoshop_ext(&e) => e,
+            _ => "png".to_string(),
+        };
+        let quality = jpg_quality.unwrap_or(95);
+
+        let front_path = out_dir.join(format!("{}_front.{}", stem, ext));
+        let back_path = out_dir.join(format!("{}_back.{}", stem, ext));
+
+        save_image(&front, &front_path, quality)?;
+        save_image(&back, &back_path, quality)?;
+        let spine_path = match spine_img {
+            Some(spine_img) => {
+                let spine_path = out_dir.join(format!("{}_spine.{}", stem, ext));
+                save_image(&spine_img, &spine_path, quality)?;
+                Some(spine_path.to_string_lossy().to_string())
+            }
+            None => None,
+        };
+
+        Ok(CoverSplitResult {
+            front_path: front_path.to_string_lossy().to_string(),
+            spine_path,
+            back_path: back_path.to_string_lossy().to_string(),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
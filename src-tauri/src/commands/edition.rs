@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use rayon::prelude::*;
+use crate::file_utils::{full_content_hash, zip_directory};
+use crate::history::new_history_id;
+use crate::types::{
+    Edition, EditionComparison, EditionFile, EditionFreezeResult, EditionRename, ProjectFile, ReprintPackageResult,
+};
+
+// アーカイブ内の保管場所: objects/{ハッシュ先頭2文字}/{ハッシュ}.{拡張子}
+fn object_path(archive: &Path, hash: &str, source: &Path) -> PathBuf {
+    let ext = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_else(|| "bin".to_string());
+    archive.join("objects").join(&hash[..2]).join(format!("{}.{}", hash, ext))
+}
+
+// 版の記録は保存済みのプロジェクトから届くため、ハッシュと相対パスを使う前に形式を確かめる
+fn validate_hash(hash: &str) -> Result<(), String> {
+    if hash.len() < 2 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("ハッシュの形式が不正です: {}", hash));
+    }
+    Ok(())
+}
+
+// 出力先の外に書き出さないよう、通常の名前以外（..、ルート、ドライブ指定）を含む相対パスは拒否する
+fn validate_relative_path(relative: &str) -> Result<(), String> {
+    let path = Path::new(relative);
+    if relative.is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(format!("ファイルのパスが不正です: {}", relative));
+    }
+    Ok(())
+}
+
+// 読み取り専用にして誤って上書きされないようにする
+fn set_readonly(path: &Path) {
+    if let Ok(metadata) = fs::metadata(path) {
+        let mut permissions = metadata.permissions();
+        permissions.set_readonly(true);
+        let _ = fs::set_permissions(path, permissions);
+    }
+}
+
+// ファイルをアーカイブに保管（同じ内容が保管済みならコピーしない）。戻り値: 新しく保管したか
+fn store_object(archive: &Path, source: &Path, hash: &str) -> Result<bool, String> {
+    let object = object_path(archive, hash, source);
+    if object.exists() {
+        return Ok(false);
+    }
+    let dir = object.parent().ok_or_else(|| "保管先を特定できません".to_string())?;
+    fs::create_dir_all(dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+    // 途中で中断しても不完全なファイルが残らないよう一時ファイルから置き換える
+    let temp = object.with_extension(format!("{}.tmp", std::process::id()));
+    fs::copy(source, &temp).map_err(|e| format!("コピーエラー: {} - {}", source.display(), e))?;
+    if let Err(e) = fs::rename(&temp, &object) {
+        let _ = fs::remove_file(&temp);
+        if !object.exists() {
+            return Err(format!("保管エラー: {}", e));
+        }
+        return Ok(false);
+    }
+    set_readonly(&object);
+    Ok(true)
+}
+
+fn find_edition<'a>(project: &'a ProjectFile, key: &str) -> Result<&'a Edition, String> {
+    project
+        .editions
+        .iter()
+        .find(|e| e.id == key || e.name == key)
+        .ok_or_else(|| format!("版が見つかりません: {}", key))
+}
+
+/// 出力したデータ一式を版（初版・二刷など）として凍結
+/// ファイルは内容ハッシュで名前を付けてアーカイブに保管し（版をまたいで同じ内容は1つだけ）、
+/// 構成を editions/{id}.json に記録する。結果のプロジェクトを保存するのはフロントエンド側
+#[tauri::command]
+pub async fn freeze_edition(
+    project: ProjectFile,
+    export_dir: String,
+    archive_dir: String,
+    name: String,
+    note: Option<String>,
+    history_id: Option<String>,
+) -> Result<EditionFreezeResult, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("版の名前を入力してください".to_string());
+    }
+    if project.editions.iter().any(|e| e.name == name) {
+        return Err(format!("同じ名前の版がすでにあります: {}", name));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let source_dir = Path::new(&export_dir);
+        if !source_dir.is_dir() {
+            return Err("出力フォルダが存在しません".to_string());
+        }
+        let archive = Path::new(&archive_dir);
+
+        let mut sources = Vec::new();
+        for entry in walkdir::WalkDir::new(source_dir).sort_by_file_name() {
+            let entry = entry.map_err(|e| e.to_string())?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(source_dir).map_err(|e| e.to_string())?;
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            sources.push((relative, entry.path().to_path_buf()));
+        }
+        if sources.is_empty() {
+            return Err("出力フォルダにファイルがありません".to_string());
+        }
+
+        let stored: Vec<(EditionFile, bool)> = sources
+            .par_iter()
+            .map(|(relative, path)| {
+                let hash = full_content_hash(path).map_err(|e| format!("{}: {}", relative, e))?;
+                let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+                let is_new = store_object(archive, path, &hash)?;
+                Ok((
+                    EditionFile {
+                        path: relative.clone(),
+                        hash,
+                        size,
+                    },
+                    is_new,
+                ))
+            })
+            .collect::<Result<_, String>>()?;
+
+        let new_count = stored.iter().filter(|(_, is_new)| *is_new).count();
+        let edition = Edition {
+            id: new_history_id(),
+            name,
+            frozen_at: chrono::Local::now().to_rfc3339(),
+            source_dir: export_dir.clone(),
+            archive_dir: archive_dir.clone(),
+            history_id,
+            note,
+            files: stored.into_iter().map(|(file, _)| file).collect(),
+        };
+
+        // 版の構成（プロジェクトを失っても復元できるようアーカイブ側にも残す）
+        let editions_dir = archive.join("editions");
+        fs::create_dir_all(&editions_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+        let manifest_path = editions_dir.join(format!("{}.json", edition.id));
+        let manifest = serde_json::to_string_pretty(&edition).map_err(|e| format!("JSONシリアライズエラー: {}", e))?;
+        fs::write(&manifest_path, manifest).map_err(|e| format!("ファイル書き込みエラー: {}", e))?;
+        set_readonly(&manifest_path);
+
+        let total = edition.files.len();
+        let mut project = project;
+        project.editions.push(edition.clone());
+        Ok(EditionFreezeResult {
+            project,
+            edition,
+            stored: new_count,
+            reused: total - new_count,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// 2つの版の差分。同じパスで内容が異なるものを差し替え、内容が同じでパスが異なるものを名前の変更として扱う
+fn diff_editions(from: &Edition, to: &Edition) -> EditionComparison {
+    let from_files: HashMap<&str, &str> = from.files.iter().map(|f| (f.path.as_str(), f.hash.as_str())).collect();
+    let to_files: HashMap<&str, &str> = to.files.iter().map(|f| (f.path.as_str(), f.hash.as_str())).collect();
+
+    let mut changed = Vec::new();
+    let mut unchanged = 0;
+    let mut added: Vec<&EditionFile> = Vec::new();
+    for file in &to.files {
+        match from_files.get(file.path.as_str()) {
+            Some(hash) if *hash == file.hash => unchanged += 1,
+            Some(_) => changed.push(file.path.clone()),
+            None => added.push(file),
+        }
+    }
+    let mut removed: Vec<&EditionFile> = from
+        .files
+        .iter()
+        .filter(|f| !to_files.contains_key(f.path.as_str()))
+        .collect();
+
+    // 削除されたファイルと同じ内容の追加ファイルは名前の変更とみなす
+    let mut renamed = Vec::new();
+    added.retain(|file| match removed.iter().position(|r| r.hash == file.hash) {
+        Some(i) => {
+            let old = removed.remove(i);
+            renamed.push(EditionRename {
+                from: old.path.clone(),
+                to: file.path.clone(),
+            });
+            false
+        }
+        None => true,
+    });
+
+    EditionComparison {
+        from_edition: from.name.clone(),
+        to_edition: to.name.clone(),
+        added: added.into_iter().map(|f| f.path.clone()).collect(),
+        removed: removed.into_iter().map(|f| f.path.clone()).collect(),
+        changed,
+        renamed,
+        unchanged,
+    }
+}
+
+/// 2つの版を比較（ID または名前で指定）
+#[tauri::command]
+pub fn compare_editions(project: ProjectFile, from_edition: String, to_edition: String) -> Result<EditionComparison, String> {
+    let from = find_edition(&project, &from_edition)?;
+    let to = find_edition(&project, &to_edition)?;
+    Ok(diff_editions(from, to))
+}
+
+// 変更一覧（印刷所への連絡用のテキスト）
+fn change_list_text(project_name: &str, from: &Edition, to: &Edition, comparison: &EditionComparison) -> String {
+    let mut text = String::new();
+    text.push_str(&format!("{} 重版用差し替えデータ\r\n", project_name));
+    text.push_str(&format!("前の版: {}（{}）\r\n", from.name, from.frozen_at));
+    text.push_str(&format!("今回の版: {}（{}）\r\n", to.name, to.frozen_at));
+    text.push_str(&format!("作成日時: {}\r\n", chrono::Local::now().format("%Y-%m-%d %H:%M")));
+
+    let sections: [(&str, Vec<String>); 4] = [
+        ("差し替え", comparison.changed.clone()),
+        ("追加", comparison.added.clone()),
+        ("削除", comparison.removed.clone()),
+        (
+            "ファイル名の変更（内容は同じ、データは同梱しない）",
+            comparison.renamed.iter().map(|r| format!("{} → {}", r.from, r.to)).collect(),
+        ),
+    ];
+    for (title, files) in sections {
+        if files.is_empty() {
+            continue;
+        }
+        text.push_str(&format!("\r\n■ {}（{}件）\r\n", title, files.len()));
+        for file in files {
+            text.push_str(&format!("  {}\r\n", file));
+        }
+    }
+    text.push_str(&format!("\r\n変更なし: {}件\r\n", comparison.unchanged));
+    text
+}
+
+/// 2つの版から重版用の差し替えデータ一式を作成
+/// 新しい版で内容が変わった・追加されたファイルだけをアーカイブから書き出し、変更一覧を添える
+#[tauri::command]
+pub async fn build_reprint_package(
+    project: ProjectFile,
+    from_edition: String,
+    to_edition: String,
+    output_dir: String,
+    zip: Option<bool>,
+) -> Result<ReprintPackageResult, String> {
+    tokio::task::spawn_blocking(move || {
+        let from = find_edition(&project, &from_edition)?;
+        let to = find_edition(&project, &to_edition)?;
+        let comparison = diff_editions(from, to);
+        if comparison.changed.is_empty() && comparison.added.is_empty() && comparison.removed.is_empty() {
+            return Err("2つの版に差分がありません".to_string());
+        }
+
+        let out_dir = Path::new(&output_dir);
+        fs::create_dir_all(out_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+        let archive = Path::new(&to.archive_dir);
+        let hashes: HashMap<&str, &str> = to.files.iter().map(|f| (f.path.as_str(), f.hash.as_str())).collect();
+
+        let mut files = Vec::new();
+        for relative in comparison.changed.iter().chain(&comparison.added) {
+            validate_relative_path(relative)?;
+            let hash = hashes
+                .get(relative.as_str())
+                .ok_or_else(|| format!("版の記録にファイルがありません: {}", relative))?;
+            validate_hash(hash)?;
+            let object = object_path(archive, hash, Path::new(relative));
+            if !object.exists() {
+                return Err(format!("アーカイブにファイルがありません: {}", relative));
+            }
+            let dest = out_dir.join(relative);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+            }
+            // fs::copy だとアーカイブの読み取り専用属性を引き継ぐため、新規ファイルに書き込む
+            let copied = fs::File::open(&object).and_then(|mut reader| {
+                let mut writer = fs::File::create(&dest)?;
+                std::io::copy(&mut reader, &mut writer)
+            });
+            copied.map_err(|e| format!("コピーエラー: {} - {}", relative, e))?;
+            files.push(dest.to_string_lossy().to_string());
+        }
+
+        let change_list_path = out_dir.join("変更一覧.txt");
+        fs::write(&change_list_path, change_list_text(&project.name, from, to, &comparison))
+            .map_err(|e| format!("ファイル書き込みエラー: {}", e))?;
+
+        let zip_path = if zip.unwrap_or(false) {
+            let zip_path = out_dir.with_extension("zip");
+            zip_directory(out_dir, &zip_path)?;
+            Some(zip_path.to_string_lossy().to_string())
+        } else {
+            None
+        };
+
+        Ok(ReprintPackageResult {
+            output_dir,
+            files,
+            change_list_path: change_list_path.to_string_lossy().to_string(),
+            zip_path,
+            comparison,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
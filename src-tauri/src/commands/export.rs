@@ -1,83 +1,1003 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::{Cursor, Write};
 use std::path::{Path, PathBuf};
-use image::codecs::jpeg::JpegEncoder;
+use image::metadata::Orientation;
 use image::DynamicImage;
-use crate::types::ExportPage;
-use crate::image_utils::validate_dimensions;
+use tauri::{AppHandle, Manager, State};
+use crate::state::AppState;
+use crate::types::{
+    BleedSettings, CoverExportSettings, ExportCollision, ExportHistoryEntry, ExportPage, ExportPreview, ExportRequest,
+    PageList, PlannedExportFile, PreflightReport, ReexportResult, SkippedExportPage, UpscaledExportPage,
+};
+use crate::commands::cover::compose_wraparound;
+use crate::file_utils::{
+    cloud_placeholder_reason, copy_exclusive, mark_folder_scan_deferred, retry_on_lock, write_exclusive, zip_directory,
+};
+use crate::metadata::{stamp_dpi, strip_metadata_lossless};
+use crate::audit::{record_audit, require_operator};
+use crate::jobs::{JobHandle, JobRegistry};
+use crate::usage::record_export;
+use crate::history::{new_history_id, record_history, snapshot_pages};
+use crate::desktop_notify::{notify_if_background, NotifyAction};
+use crate::memory_budget::MemoryPermit;
+use crate::image_utils::{
+    encode_image_with, flatten_on_white, image_limits, is_photoshop_ext, mm_to_px, open_image_for, open_image_page_budgeted,
+    orientation_swaps_axes, parse_chroma_subsampling, read_image_dimensions, read_orientation, validate_dimensions_for,
+    validate_dimensions_within, EncodeOptions, ImageOperation,
+};
+use crate::image_info::read_image_info;
+use crate::processing::{
+    apply_bleed, is_high_bit_depth, reduce_to_8bit, resize_scale, resize_to_target, split_spread, target_output_size, ResizeMode,
+    SpreadHalf,
+};
+use crate::naming::{render_name_template, sanitize_folder_name, NameTokens};
+use crate::scripting::{check_output_component, ExportScript, PageDecision};
 
-// 画像のサイズを取得
+// 入稿サイズへのリサンプリング設定
+#[derive(Clone, Copy)]
+enum ResizeTarget {
+    // 指定ピクセル数に合わせる（見開き原稿は幅2ページ分）
+    Size { width: u32, height: u32, mode: ResizeMode },
+    // 原稿に記録された解像度から指定解像度に合わせる
+    Dpi(u32),
+}
+
+// エクスポート実行時のオプション（出力サイズの見積もりでも同じ設定を使う）
+#[derive(Clone)]
+pub(crate) struct ExportRunOptions {
+    should_move: bool,
+    should_convert: bool,
+    // 変換時の出力拡張子 ("jpg" | "jxl")
+    convert_ext: &'static str,
+    quality: u8,
+    // JPEGのクロマサブサンプリング
+    chroma_subsampling: String,
+    bleed: Option<BleedSettings>,
+    // 排他作成＋リトライで書き込む（ウイルス対策ソフトのロック対策）
+    safe_write: bool,
+    // EXIF/XMP等のメタデータを除去する（デジタル配信用）
+    strip_metadata: bool,
+    // 見開き原稿を分割して片側だけ出力する
+    spread_half: Option<SpreadHalf>,
+    // 見開きの分割時にノド側で重複させる幅 (px)
+    spread_overlap_px: u32,
+    // 16bit原稿を8bitの形式に変換するときディザを掛ける
+    dither: bool,
+    // ページ種別ごとのJPG品質（未指定の種別は quality）
+    page_type_quality: HashMap<String, u8>,
+    // 入稿サイズへのリサンプリング
+    resize: Option<ResizeTarget>,
+    // 見開き原稿（分割しない場合は入稿サイズの幅を2ページ分にする）
+    spread: bool,
+    // 出力ファイルに記録する解像度（指定または入稿解像度。なければ原稿の解像度を使う）
+    output_dpi: Option<u32>,
+    // PDF素材からレンダリングするページ（0始まり）
+    pdf_page: usize,
+}
+
+impl ExportRunOptions {
+    fn encode_options(&self, quality: u8) -> EncodeOptions {
+        EncodeOptions {
+            jpg_quality: quality,
+            chroma_subsampling: self.chroma_subsampling.clone(),
+        }
+    }
+
+    // スクリプトによるページ単位の変換設定を反映
+    fn for_page(&self, decision: &PageDecision) -> Self {
+        let mut opts = self.clone();
+        if let Some(quality) = self.page_type_quality.get(&decision.page.page_type) {
+            opts.quality = *quality;
+        }
+        if let Some(format) = decision.format {
+            opts.should_convert = format.is_some();
+            if let Some(ext) = format {
+                opts.convert_ext = ext;
+            }
+        }
+        // スクリプトで指定した品質はページ種別ごとの品質より優先
+        if let Some(quality) = decision.quality {
+            opts.quality = quality;
+        }
+        opts.spread_half = decision.spread_half;
+        opts.pdf_page = decision.page.pdf_page.unwrap_or(0);
+        opts.spread = decision.page.spread;
+        opts
+    }
+
+    // 原稿をデコードして出力するか（PSDも統合画像から変換する）
+    fn needs_decode(&self) -> bool {
+        self.should_convert || self.spread_half.is_some() || self.resize.is_some()
+    }
+
+    // このページの入稿サイズ（ピクセル数で指定した場合のみ）
+    fn target_size(&self) -> Option<(u32, u32, ResizeMode)> {
+        match self.resize? {
+            ResizeTarget::Size { width, height, mode } => {
+                // 見開きの幅の上限は plan_export で確認済み
+                let width = if self.spread && self.spread_half.is_none() { width.checked_mul(2)? } else { width };
+                Some((width, height, mode))
+            }
+            ResizeTarget::Dpi(_) => None,
+        }
+    }
+
+    // 解像度指定の場合の倍率（原稿に解像度の記録がなければ None）
+    fn dpi_scale(&self, source: &Path) -> Option<f64> {
+        match self.resize? {
+            ResizeTarget::Dpi(dpi) => read_image_info(source).ok()?.dpi.map(|source_dpi| dpi as f64 / source_dpi),
+            ResizeTarget::Size { .. } => None,
+        }
+    }
+
+    fn bleed_px(&self) -> u32 {
+        self.bleed
+            .as_ref()
+            .map(|b| mm_to_px(b.bleed_mm, b.dpi))
+            .unwrap_or(0)
+    }
+}
+
+// 画像のサイズを取得（ヘッダーのみ読み込み）
 fn get_image_dimensions(path: &Path) -> Result<(u32, u32), String> {
-    let ext = path
+    let (width, height) = read_image_dimensions(path)?;
+
+    // 画像サイズ検証（DoS防止）
+    validate_dimensions_for(ImageOperation::Export, width, height)?;
+
+    Ok((width, height))
+}
+
+// 原稿に記録された解像度 (ppi)
+fn source_dpi(source: &Path) -> Option<f64> {
+    read_image_info(source).ok()?.dpi
+}
+
+// エンコード済みのデータに解像度を記録（JPG・PNG・TIFF以外はそのまま）
+fn stamp_output_dpi(buffer: &mut Cursor<Vec<u8>>, ext: &str, dpi: Option<u32>) -> Result<(), String> {
+    let Some(dpi) = dpi else {
+        return Ok(());
+    };
+    if let Some(stamped) = stamp_dpi(ext, buffer.get_ref(), dpi) {
+        *buffer = Cursor::new(stamped?);
+    }
+    Ok(())
+}
+
+// 出力ファイルを書き込み（safe_write時は排他作成＋リトライ）
+fn write_output(
+    output_file: &Path,
+    opts: &ExportRunOptions,
+    encode: impl FnOnce(&mut Cursor<Vec<u8>>) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut buffer = Cursor::new(Vec::new());
+    encode(&mut buffer)?;
+    let data = buffer.into_inner();
+
+    if opts.safe_write {
+        write_exclusive(output_file, |file| file.write_all(&data).map_err(|e| e.to_string()))
+    } else {
+        fs::write(output_file, &data).map_err(|e| e.to_string())
+    }
+}
+
+// 参照ページがない場合の白紙サイズ（A5 350dpi）
+const DEFAULT_BLANK_SIZE: (u32, u32) = (1654, 2339);
+
+// 画像の拡張子を取得（PSDは出力形式として使わないため None、PDFはPNGで出力するため "png"）
+fn non_photoshop_ext(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .filter(|e| !is_photoshop_ext(e))
+        .map(|e| if e == "pdf" { "png".to_string() } else { e })
+}
+
+// 最初のファイルがあるページからサイズと拡張子を取得
+fn reference_page_format(pages: &[ExportPage]) -> (Option<(u32, u32)>, String) {
+    let mut reference_size: Option<(u32, u32)> = None;
+    let mut reference_ext = "png".to_string();
+
+    for page in pages {
+        if let Some(ref source_path) = page.source_path {
+            let source = Path::new(source_path);
+            if source.exists() {
+                if let Ok(dims) = get_image_dimensions(source) {
+                    reference_size = Some(dims);
+                }
+                if let Some(ext) = non_photoshop_ext(source) {
+                    reference_ext = ext;
+                }
+                break;
+            }
+        }
+    }
+
+    (reference_size, reference_ext)
+}
+
+// 白紙ページのサイズと拡張子を前後のページから決定
+fn blank_page_format(
+    pages: &[ExportPage],
+    index: usize,
+    default_size: (u32, u32),
+    reference_ext: &str,
+) -> ((u32, u32), String) {
+    let mut size = default_size;
+    let mut ext = reference_ext.to_string();
+
+    let existing_source = |page: &ExportPage| {
+        page.source_path
+            .as_deref()
+            .map(Path::new)
+            .filter(|source| source.exists())
+            .map(Path::to_path_buf)
+    };
+
+    // 前のページからサイズを取得し、なければ後のページからも確認
+    if let Some(prev_source) = pages[..index].iter().rev().find_map(existing_source) {
+        if let Ok(dims) = get_image_dimensions(&prev_source) {
+            size = dims;
+        }
+        if let Some(e) = non_photoshop_ext(&prev_source) {
+            ext = e;
+        }
+    }
+    if size == default_size {
+        if let Some(next_source) = pages[index + 1..].iter().find_map(existing_source) {
+            if let Ok(dims) = get_image_dimensions(&next_source) {
+                size = dims;
+            }
+            if let Some(e) = non_photoshop_ext(&next_source) {
+                ext = e;
+            }
+        }
+    }
+
+    (size, ext)
+}
+
+// 白紙ページの出力サイズ・拡張子・品質（前後のページのサイズに塗り足しを加え、入稿サイズの指定があれば原稿のページと同じ合わせ方をする）
+// fit では前後のページと同じく入稿サイズに収まる大きさになる。変換モードの場合は変換先の形式
+fn blank_output(
+    pages: &[ExportPage],
+    index: usize,
+    default_size: (u32, u32),
+    reference_ext: &str,
+    opts: &ExportRunOptions,
+) -> ((u32, u32), String, u8) {
+    let (size, ext) = blank_page_format(pages, index, default_size, reference_ext);
+    let bleed_px = opts.bleed_px();
+    let size = (size.0 + bleed_px * 2, size.1 + bleed_px * 2);
+    let size = match opts.target_size() {
+        Some((width, height, mode)) => target_output_size(size.0, size.1, width, height, mode),
+        None => size,
+    };
+    if opts.should_convert {
+        (size, opts.convert_ext.to_string(), opts.quality)
+    } else {
+        (size, ext, 95)
+    }
+}
+
+fn blank_image((width, height): (u32, u32)) -> DynamicImage {
+    DynamicImage::ImageRgb8(image::RgbImage::from_pixel(width, height, image::Rgb([255, 255, 255])))
+}
+
+// ソースファイルの出力先パス（PSBを変換モードで出力する場合など、出力できなければ None）
+// PSDは psd クレートで統合画像を読めるため変換できるが、PSBは読み込めない
+fn source_output_file(source: &Path, page_output_dir: &Path, output_name: &str, opts: &ExportRunOptions) -> Option<PathBuf> {
+    let source_ext = source
         .extension()
         .and_then(|e| e.to_str())
-        .unwrap_or("")
+        .unwrap_or("png")
         .to_lowercase();
-
-    let (width, height) = if ext == "psd" {
-        let data = fs::read(path).map_err(|e| e.to_string())?;
-        let psd = psd::Psd::from_bytes(&data)
-            .map_err(|e| format!("PSD読み込みエラー: {:?}", e))?;
-        (psd.width(), psd.height())
+    // 見開きの分割・リサンプリングもデコードが必要なため、PSBは出力できない
+    if opts.needs_decode() && source_ext == "psb" {
+        return None;
+    }
+    // PDFは選択したページをPNGにレンダリングして出力、分割・リサンプリングしたPSDはPNGで出力
+    let output_ext = if opts.should_convert {
+        opts.convert_ext
+    } else if source_ext == "pdf" || (source_ext == "psd" && opts.needs_decode()) {
+        "png"
     } else {
-        let img = image::open(path).map_err(|e| e.to_string())?;
-        (img.width(), img.height())
+        source_ext.as_str()
     };
+    Some(page_output_dir.join(format!("{}.{}", output_name, output_ext)))
+}
 
-    // 画像サイズ検証（DoS防止）
-    validate_dimensions(width, height)?;
+// リサンプリング前の画像サイズをヘッダーの寸法から計算（向き・見開きの分割・塗り足しを反映）
+fn planned_source_size(source: &Path, opts: &ExportRunOptions) -> Option<(u32, u32)> {
+    let (mut width, mut height) = read_image_dimensions(source).ok()?;
+    if orientation_swaps_axes(read_orientation(source)) {
+        (width, height) = (height, width);
+    }
+    if opts.spread_half.is_some() {
+        width = (width / 2 + opts.spread_overlap_px).min(width);
+    }
+    if opts.bleed.is_some() {
+        width += opts.bleed_px() * 2;
+        height += opts.bleed_px() * 2;
+    }
+    Some((width, height))
+}
 
-    Ok((width, height))
+// 入稿サイズへのリサンプリングの倍率をヘッダーの寸法から計算（リサンプリングしない場合は None）
+fn planned_resize_scale(source: &Path, opts: &ExportRunOptions) -> Option<f64> {
+    let Some((target_width, target_height, mode)) = opts.target_size() else {
+        return opts.dpi_scale(source);
+    };
+    let (width, height) = planned_source_size(source, opts)?;
+    Some(resize_scale(width, height, target_width, target_height, mode))
+}
+
+// 変換して出力する画像のサイズをヘッダーの寸法から計算（resample_for_output と同じ合わせ方）
+fn planned_output_size(source: &Path, opts: &ExportRunOptions) -> Option<(u32, u32)> {
+    let (width, height) = planned_source_size(source, opts)?;
+    if let Some((target_width, target_height, mode)) = opts.target_size() {
+        return Some(target_output_size(width, height, target_width, target_height, mode));
+    }
+    match opts.dpi_scale(source).filter(|s| (s - 1.0).abs() > 0.001) {
+        Some(scale) => Some((
+            ((width as f64 * scale).round() as u32).max(1),
+            ((height as f64 * scale).round() as u32).max(1),
+        )),
+        None => Some((width, height)),
+    }
+}
+
+// 入稿サイズ・入稿解像度へのリサンプリング（塗り足しを含めた最終サイズに対して行う）
+// 原稿の解像度の記録が不正な場合に巨大な画像を作らないよう、リサンプリング後のサイズを上限と照合する
+fn resample_for_output(img: DynamicImage, source: &Path, opts: &ExportRunOptions) -> Result<DynamicImage, String> {
+    let limits = image_limits(ImageOperation::Export);
+    if let Some((width, height, mode)) = opts.target_size() {
+        let (output_width, output_height) = target_output_size(img.width(), img.height(), width, height, mode);
+        validate_dimensions_within(output_width, output_height, &limits)?;
+        return Ok(resize_to_target(&img, width, height, mode));
+    }
+    match opts.dpi_scale(source).filter(|s| (s - 1.0).abs() > 0.001) {
+        Some(scale) => {
+            let width = ((img.width() as f64 * scale).round() as u32).max(1);
+            let height = ((img.height() as f64 * scale).round() as u32).max(1);
+            validate_dimensions_within(width, height, &limits)
+                .map_err(|e| format!("入稿解像度に合わせると{}（原稿の解像度の記録を確認してください）", e))?;
+            Ok(img.resize_exact(width, height, image::imageops::FilterType::Lanczos3))
+        }
+        None => Ok(img),
+    }
+}
+
+// 入稿解像度を指定したのに、原稿に解像度の記録がなく合わせられないページ
+fn unknown_dpi_page(page: &ExportPage, source: &Path, opts: &ExportRunOptions) -> Option<SkippedExportPage> {
+    let is_dpi_target = matches!(opts.resize, Some(ResizeTarget::Dpi(_)));
+    (is_dpi_target && opts.dpi_scale(source).is_none())
+        .then(|| skipped_page(page, "解像度の記録がないため入稿解像度に合わせていません"))
+}
+
+// 拡大されるページの記録（わずかな誤差は除く）
+fn upscaled_page(page: &ExportPage, source: &Path, opts: &ExportRunOptions) -> Option<UpscaledExportPage> {
+    let scale = planned_resize_scale(source, opts).filter(|s| *s > 1.001)?;
+    Some(UpscaledExportPage {
+        page_id: page.page_id.clone(),
+        source_path: page.source_path.clone(),
+        output_name: page.output_name.clone(),
+        scale,
+    })
+}
+
+// 塗り足しを付けるか（PSDはPhotoshop側で処理するため対象外。JPG等に変換する場合は統合画像に付ける）
+fn applies_bleed(source_ext: &str, opts: &ExportRunOptions) -> bool {
+    opts.bleed.is_some() && (!is_photoshop_ext(source_ext) || opts.needs_decode())
+}
+
+// 元ファイルを読み込んで変換するか（コピー・移動で済まないか）。プレビューと実際の出力で同じ判定を使う
+// メタデータ除去: JPEG/PNGはそのまま除去、それ以外は再エンコードで除去（PSDは対象外）
+// 向きの指定があると除去で横倒しになるため、再エンコードで画素に反映する
+fn decodes_source(source_ext: &str, orientation: Orientation, opts: &ExportRunOptions) -> bool {
+    let strip_by_reencode = opts.strip_metadata
+        && (orientation != Orientation::NoTransforms
+            || !matches!(source_ext, "jpg" | "jpeg" | "png" | "psd" | "psb"));
+    opts.needs_decode() || applies_bleed(source_ext, opts) || strip_by_reencode || source_ext == "pdf"
+}
+
+// JPG・WebPは8bitで書き出す（16bit原稿は出力前に明示的に8bitにする。PNG・TIFF・JXLは16bitのまま）
+fn writes_8bit(ext: &str) -> bool {
+    matches!(ext, "jpg" | "jpeg" | "webp")
+}
+
+// 原稿を読み込み、向き・見開きの分割・塗り足し・入稿サイズへのリサンプリングを反映した出力画像にする
+// 出力サイズの見積もりのサンプルも同じ処理で作る
+fn render_source_page(source: &Path, opts: &ExportRunOptions) -> Result<(DynamicImage, MemoryPermit), String> {
+    let source_ext = source
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("png")
+        .to_lowercase();
+
+    // PSDは psd クレートの統合画像（コンポジット）を原寸で使用し、透明部分は白で合成
+    let (mut img, permit) = open_image_page_budgeted(source, opts.pdf_page, ImageOperation::Export)?;
+    if is_photoshop_ext(&source_ext) {
+        img = flatten_on_white(&img);
+    }
+    // 変換後はEXIFが残らないため、向きを画素に反映してから分割・塗り足しを行う
+    let orientation = read_orientation(source);
+    if orientation != Orientation::NoTransforms {
+        img.apply_orientation(orientation);
+    }
+    if let Some(half) = opts.spread_half {
+        img = split_spread(&img, half, opts.spread_overlap_px);
+    }
+    if applies_bleed(&source_ext, opts) {
+        if let Some(ref bleed) = opts.bleed {
+            img = apply_bleed(&img, opts.bleed_px(), &bleed.mode)?;
+        }
+    }
+    // 入稿サイズへのリサンプリングは塗り足しを含めた最終サイズに対して行う
+    img = resample_for_output(img, source, opts)?;
+    Ok((img, permit))
 }
 
-// 白紙画像を生成
-fn create_blank_image(width: u32, height: u32, output_path: &Path) -> Result<(), String> {
-    let ext = output_path
+// ソースファイルのあるページを出力（コピー/移動、または変換）
+// 出力しなかった場合（変換できない形式など）は false を返す
+fn export_source_file(
+    source: &Path,
+    page_output_dir: &Path,
+    output_name: &str,
+    opts: &ExportRunOptions,
+) -> Result<bool, String> {
+    // クラウド上のみのファイルは読み込み時のIOエラーより先に分かりやすく報告
+    if let Some(reason) = cloud_placeholder_reason(source) {
+        return Err(reason);
+    }
+
+    let source_ext = source
         .extension()
         .and_then(|e| e.to_str())
         .unwrap_or("png")
         .to_lowercase();
 
-    // 白い画像を生成
-    let img = image::RgbImage::from_pixel(width, height, image::Rgb([255, 255, 255]));
-    let dynamic_img = DynamicImage::ImageRgb8(img);
+    let is_photoshop = is_photoshop_ext(&source_ext);
+    let is_pdf = source_ext == "pdf";
+
+    // EXIFの向き（撮影した資料など）。デコードする場合は画素を回転して反映する
+    let orientation = read_orientation(source);
+
+    // コピーする原稿も、解像度の指定があれば記録し直す（指定がなければ原稿の記録がそのまま残る）
+    let stamp_on_copy = opts.output_dpi.is_some() && matches!(source_ext.as_str(), "jpg" | "jpeg" | "png" | "tif" | "tiff");
+
+    // PSBファイルは変換できないのでスキップ
+    let Some(output_file) = source_output_file(source, page_output_dir, output_name, opts) else {
+        return Ok(false);
+    };
+
+    if decodes_source(&source_ext, orientation, opts) {
+        // 画像を読み込んで変換（JPG/JXL変換・塗り足し・メタデータ除去、WebPはロスレスで再エンコード）
+        // 画像クレートのエンコーダーはメタデータを書き込まないため、変換時は常に除去される
+        let output_ext = output_file.extension().and_then(|e| e.to_str()).unwrap_or("png");
 
-    match ext.as_str() {
-        "jpg" | "jpeg" => {
-            let mut file = fs::File::create(output_path).map_err(|e| e.to_string())?;
-            let encoder = JpegEncoder::new_with_quality(&mut file, 95);
-            dynamic_img.write_with_encoder(encoder).map_err(|e| e.to_string())?;
+        let (mut img, _permit) = render_source_page(source, opts)?;
+        if is_high_bit_depth(&img) && writes_8bit(output_ext) {
+            img = reduce_to_8bit(img, opts.dither);
         }
-        "png" => {
-            dynamic_img.save(output_path).map_err(|e| e.to_string())?;
+        // エンコーダーは解像度を書き込まないため、原稿の解像度（リサンプリングした場合は倍率を反映）を記録
+        let dpi = opts.output_dpi.or_else(|| {
+            let scale = planned_resize_scale(source, opts).unwrap_or(1.0);
+            source_dpi(source).map(|dpi| (dpi * scale).round() as u32)
+        });
+        let encode_options = opts.encode_options(opts.quality);
+        write_output(&output_file, opts, |buffer| {
+            encode_image_with(&img, buffer, output_ext, &encode_options)?;
+            stamp_output_dpi(buffer, output_ext, dpi)
+        })?;
+
+        // 移動モードの場合は元ファイルを削除（PSDはレイヤー情報、PDFは2ページ目以降が失われるため元ファイルを残す）
+        // 分割した見開きは片側ずつ出力し、リサンプリングした原稿は元の解像度が失われるため元ファイルを残す
+        if opts.should_move && !is_photoshop && !is_pdf && opts.spread_half.is_none() && opts.resize.is_none() {
+            retry_on_lock(|| fs::remove_file(source)).map_err(|e| e.to_string())?;
         }
-        "tif" | "tiff" => {
-            dynamic_img.save(output_path).map_err(|e| e.to_string())?;
+    } else if (opts.strip_metadata || stamp_on_copy) && !is_photoshop_ext(&source_ext) {
+        // 再圧縮せずにメタデータの除去・解像度の記録のみ行って出力
+        let data = fs::read(source).map_err(|e| e.to_string())?;
+        let data = if opts.strip_metadata {
+            strip_metadata_lossless(&source_ext, &data).unwrap_or(Ok(data))?
+        } else {
+            data
+        };
+        let data = match opts.output_dpi {
+            Some(dpi) => stamp_dpi(&source_ext, &data, dpi).unwrap_or(Ok(data))?,
+            None => data,
+        };
+        write_output(&output_file, opts, |buffer| buffer.write_all(&data).map_err(|e| e.to_string()))?;
+
+        if opts.should_move {
+            retry_on_lock(|| fs::remove_file(source)).map_err(|e| e.to_string())?;
         }
-        _ => {
-            // デフォルトはPNG
-            dynamic_img.save(output_path).map_err(|e| e.to_string())?;
+    } else {
+        // そのままコピーまたは移動
+        if opts.should_move {
+            if opts.safe_write {
+                retry_on_lock(|| fs::rename(source, &output_file)).map_err(|e| e.to_string())?;
+            } else {
+                fs::rename(source, &output_file).map_err(|e| e.to_string())?;
+            }
+        } else if opts.safe_write {
+            copy_exclusive(source, &output_file)?;
+        } else {
+            fs::copy(source, &output_file).map_err(|e| e.to_string())?;
         }
     }
 
-    Ok(())
+    Ok(true)
 }
 
-#[tauri::command]
-pub async fn export_pages(
-    output_path: String,
-    pages: Vec<ExportPage>,
-    move_files: Option<bool>,
-    convert_to_jpg: Option<bool>,
-    jpg_quality: Option<u8>,
+// 表紙のファイル名テンプレートを展開（{index} は既定で2桁）
+fn render_cover_name(template: &str, page: &ExportPage, index: usize) -> String {
+    let tokens = NameTokens {
+        name: &page.output_name,
+        chapter: page.subfolder.as_deref().unwrap_or(""),
+        index,
+        digits: 2,
+        ..Default::default()
+    };
+    match page.source_path {
+        Some(ref source) => render_name_template(template, &tokens.with_source(Path::new(source))),
+        None => render_name_template(template, &tokens),
+    }
+}
+
+// 表紙の出力拡張子
+fn cover_output_ext(settings: &CoverExportSettings) -> Result<&'static str, String> {
+    match settings.output_format.to_lowercase().as_str() {
+        "jpg" | "jpeg" => Ok("jpg"),
+        "png" => Ok("png"),
+        "tif" | "tiff" => Ok("tif"),
+        other => Err(format!("サポートされていない表紙の出力形式: {}", other)),
+    }
+}
+
+// 元ファイルが存在する表紙ページ
+fn existing_cover_sources<'a>(covers: &[&'a ExportPage]) -> Vec<(&'a ExportPage, &'a Path)> {
+    covers
+        .iter()
+        .filter_map(|page| page.source_path.as_deref().map(|s| (*page, Path::new(s))))
+        .filter(|(_, source)| source.exists())
+        .collect()
+}
+
+fn cover_output_file(cover_dir: &Path, settings: &CoverExportSettings, page: &ExportPage, index: usize, ext: &str) -> PathBuf {
+    let name = render_cover_name(&settings.name_template, page, index);
+    cover_dir.join(format!("{}.{}", name, ext))
+}
+
+// 表紙ページを専用設定で出力（RGB化・別フォルダ・展開図合成）
+// 合成・変換を伴うため、移動モードでも表紙の元ファイルは残す
+fn export_cover_pages(
+    covers: &[&ExportPage],
+    output_dir: &Path,
+    settings: &CoverExportSettings,
+    opts: &ExportRunOptions,
+    notes: &mut ExportNotes,
 ) -> Result<usize, String> {
-    let should_move = move_files.unwrap_or(false);
-    let should_convert = convert_to_jpg.unwrap_or(false);
-    let quality = jpg_quality.unwrap_or(95);
-    let output_dir = Path::new(&output_path);
+    let ext = cover_output_ext(settings)?;
+    let sources = existing_cover_sources(covers);
+    if sources.is_empty() {
+        return Ok(0);
+    }
+    for (_, source) in &sources {
+        if let Some(reason) = cloud_placeholder_reason(source) {
+            return Err(reason);
+        }
+    }
+
+    let cover_dir = output_dir.join(&settings.subfolder);
+    fs::create_dir_all(&cover_dir).map_err(|e| e.to_string())?;
+
+    let quality = settings
+        .jpg_quality
+        .or_else(|| opts.page_type_quality.get("cover").copied())
+        .unwrap_or(opts.quality);
+    let encode_options = opts.encode_options(quality);
+    let prepare = |img: DynamicImage| {
+        if settings.force_rgb {
+            DynamicImage::ImageRgb8(img.to_rgb8())
+        } else {
+            img
+        }
+    };
+
+    // 展開図: 最初の表紙を表1、最後を表4、3ページ以上あれば2ページ目を背として合成
+    if let Some(ref spec) = settings.wraparound {
+        if sources.len() < 2 {
+            return Err("展開図の合成には表1と表4の2ページ以上が必要です".to_string());
+        }
+        let front = open_image_for(sources[0].1, ImageOperation::Cover)?;
+        let back = open_image_for(sources[sources.len() - 1].1, ImageOperation::Cover)?;
+        let spine = if sources.len() >= 3 { Some(open_image_for(sources[1].1, ImageOperation::Cover)?) } else { None };
+
+        let composed = prepare(compose_wraparound(&front, &back, spine.as_ref(), spec)?);
+        let output_file = cover_output_file(&cover_dir, settings, sources[0].0, 1, ext);
+        let dpi = opts.output_dpi.unwrap_or(spec.dpi);
+        write_output(&output_file, opts, |buffer| {
+            encode_image_with(&composed, buffer, ext, &encode_options)?;
+            stamp_output_dpi(buffer, ext, Some(dpi))
+        })?;
+        return Ok(1);
+    }
+
+    // 表1・表4などは本文と同じく入稿サイズ・入稿解像度に合わせる（展開図は判型が異なるため対象外）
+    // 表紙専用の出力では塗り足しを付けないため、倍率の計算にも含めない
+    for (i, (page, source)) in sources.iter().enumerate() {
+        let page_opts = ExportRunOptions { bleed: None, spread: page.spread, ..opts.clone() };
+        let img = resample_for_output(open_image_for(source, ImageOperation::Cover)?, source, &page_opts)?;
+        let img = prepare(img);
+        notes.upscaled.extend(upscaled_page(page, source, &page_opts));
+        notes.unknown_dpi.extend(unknown_dpi_page(page, source, &page_opts));
+        let output_file = cover_output_file(&cover_dir, settings, page, i + 1, ext);
+        let scale = planned_resize_scale(source, &page_opts).unwrap_or(1.0);
+        let dpi = opts.output_dpi.or_else(|| source_dpi(source).map(|dpi| (dpi * scale).round() as u32));
+        write_output(&output_file, opts, |buffer| {
+            encode_image_with(&img, buffer, ext, &encode_options)?;
+            stamp_output_dpi(buffer, ext, dpi)
+        })?;
+    }
+
+    Ok(sources.len())
+}
+
+// エクスポート結果の補足（履歴の報告・完了通知に含める）
+#[derive(Default)]
+struct ExportNotes {
+    // 入稿サイズに合わせるために拡大したページ
+    upscaled: Vec<UpscaledExportPage>,
+    // 入稿解像度を指定したが、解像度の記録がなく合わせられなかったページ
+    unknown_dpi: Vec<SkippedExportPage>,
+}
+
+// エクスポートをバックグラウンドジョブとして実行（ブロッキング処理）
+// トレイからの一時停止・キャンセル、空き容量不足時の自動一時停止に対応
+pub fn run_export(app: &AppHandle, request: ExportRequest) -> Result<usize, String> {
+    let output_path = request.output_path.clone();
+
+    // 元ファイルを移動するエクスポートは操作者とともに監査ログに記録
+    let moves_sources = request.move_files.unwrap_or(false);
+    let operator = if moves_sources { require_operator(app)? } else { None };
+    let page_count = request.pages.len();
+
+    let job = app
+        .state::<JobRegistry>()
+        .start(app, "export", &format!("エクスポート: {}", output_path), request.pages.len())
+        .with_disk_watch(&[Path::new(&output_path)]);
+
+    // 履歴用に設定と元ファイルの状態を控える（移動モードでは出力後に元ファイルがなくなるため先に記録）
+    let history_id = new_history_id();
+    let started_at = chrono::Local::now().to_rfc3339();
+    let history_pages = snapshot_pages(&request.pages);
+    let history_request = request.clone();
+
+    let started = std::time::Instant::now();
+    let mut notes = ExportNotes::default();
+    let result = export_with_job(&job, request, &mut notes);
+    let ExportNotes { upscaled, unknown_dpi } = notes;
+    record_history(&ExportHistoryEntry {
+        id: history_id,
+        kind: "export".to_string(),
+        started_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+        output_path: output_path.clone(),
+        status: if result.is_ok() { "completed" } else { "failed" }.to_string(),
+        error: result.as_ref().err().cloned(),
+        exported: *result.as_ref().unwrap_or(&0),
+        operator: app.state::<AppState>().operator(),
+        request: Some(history_request),
+        tiff_config: None,
+        report: (!upscaled.is_empty() || !unknown_dpi.is_empty())
+            .then(|| serde_json::json!({ "upscaledPages": upscaled, "unknownDpiPages": unknown_dpi })),
+        pages: history_pages,
+    });
+    match result {
+        Ok(exported) => {
+            record_export(exported, started.elapsed().as_millis() as u64);
+            let mut upscale_note = String::new();
+            if !upscaled.is_empty() {
+                upscale_note += &format!("（{}ページを入稿サイズに拡大）", upscaled.len());
+            }
+            if !unknown_dpi.is_empty() {
+                upscale_note += &format!("（{}ページは解像度の記録がなく入稿解像度に未対応）", unknown_dpi.len());
+            }
+            notify_if_background(
+                app,
+                "エクスポートが完了しました",
+                &format!("{}件を出力しました{}: {}", exported, upscale_note, output_path),
+                &output_path,
+                &[NotifyAction::OpenFolder],
+            );
+        }
+        // トレイからのキャンセルは失敗ではないため、キャンセルしたことだけを伝える
+        Err(ref e) if job.is_cancelled() => {
+            notify_if_background(app, "エクスポートをキャンセルしました", e, &output_path, &[NotifyAction::OpenFolder])
+        }
+        Err(ref e) => notify_if_background(app, "エクスポートに失敗しました", e, &output_path, &[]),
+    }
+    job.finish(result.as_ref().map(|_| ()).map_err(|e| e.clone()));
+
+    if moves_sources {
+        let details = match result {
+            Ok(exported) => format!("{}ページ中 {}件を移動", page_count, exported),
+            Err(_) => format!("{}ページ", page_count),
+        };
+        record_audit(
+            operator,
+            "export-move",
+            &output_path,
+            details,
+            result.as_ref().map(|_| ()).map_err(|e| e.as_str()),
+        );
+    }
+    result
+}
+
+// エクスポートの実行計画（オプション解決とスクリプトによるページ選別の結果）
+struct ExportPlan {
+    output_dir: PathBuf,
+    opts: ExportRunOptions,
+    decisions: Vec<PageDecision>,
+    // スクリプトで除外されたページ
+    excluded: Vec<ExportPage>,
+    cover_export: Option<CoverExportSettings>,
+    chapter_zips: bool,
+}
+
+// 数字だけの出力名（"001" など）の番号
+fn numeric_name(name: &str) -> Option<u64> {
+    if name.is_empty() || !name.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    name.parse().ok()
+}
+
+// 見開き原稿を読み順に左右2ページへ展開
+// naming が "side" なら "{name}_R" "{name}_L" とし、他のページの出力名は変えない
+// それ以外では、数字だけの出力名は分割で増えた分だけ以降のページを繰り下げ、番号が戻ったら（チャプターごとの連番）繰り下げをやめる
+// 数字以外の出力名は "-1" "-2" を付ける
+fn split_spread_decisions(decisions: Vec<PageDecision>, right_to_left: bool, naming: &str) -> Vec<PageDecision> {
+    let by_side = naming == "side";
+    let mut result = Vec::with_capacity(decisions.len());
+    let mut shift = 0;
+    let mut previous = None;
+
+    for mut decision in decisions {
+        let name = decision.page.output_name.clone();
+        let number = numeric_name(&name).filter(|_| !by_side);
+        if let Some(n) = number {
+            if previous.is_some_and(|prev| n <= prev) {
+                shift = 0;
+            }
+            previous = Some(n);
+        }
+        let renumber = |offset: u64| match number {
+            Some(n) => format!("{:0width$}", n + shift + offset, width = name.len()),
+            None => format!("{}-{}", name, offset + 1),
+        };
+
+        // 表紙の見開き（展開図）は分割しない
+        let split = decision.page.spread && decision.page.source_path.is_some() && decision.page.page_type != "cover";
+        if !split {
+            if number.is_some() {
+                decision.page.output_name = renumber(0);
+            }
+            result.push(decision);
+            continue;
+        }
+
+        for (offset, half) in SpreadHalf::reading_order(right_to_left).into_iter().enumerate() {
+            let mut page = decision.page.clone();
+            page.output_name = if by_side {
+                format!("{}_{}", name, half.suffix())
+            } else {
+                renumber(offset as u64)
+            };
+            result.push(PageDecision {
+                page,
+                format: decision.format,
+                quality: decision.quality,
+                spread_half: Some(half),
+            });
+        }
+        shift += 1;
+    }
+
+    result
+}
+
+// チャプターのサブフォルダ名を入稿システム向けに整える
+// 別のチャプターが同じ名前になる場合は "_2" などを付け、名前が残らなければ出現順の連番にする
+fn sanitize_subfolders(decisions: &mut [PageDecision], mode: &str) -> Result<(), String> {
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    let mut used = HashSet::new();
+    for decision in decisions.iter_mut() {
+        let Some(subfolder) = decision.page.subfolder.clone().filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        let name = match renamed.get(&subfolder) {
+            Some(name) => name.clone(),
+            None => {
+                let base = sanitize_folder_name(&subfolder, mode)?;
+                let base = if base.is_empty() { format!("chapter{:02}", renamed.len() + 1) } else { base };
+                // 大文字小文字を区別しないファイルシステムでも衝突しないよう小文字で比較
+                let mut name = base.clone();
+                let mut suffix = 2;
+                while !used.insert(name.to_lowercase()) {
+                    name = format!("{}_{}", base, suffix);
+                    suffix += 1;
+                }
+                renamed.insert(subfolder, name.clone());
+                name
+            }
+        };
+        decision.page.subfolder = Some(name);
+    }
+    Ok(())
+}
+
+// ページ種別ごとの品質を検証（本文は "body" でも指定できる）
+fn page_type_quality_map(qualities: HashMap<String, u8>) -> Result<HashMap<String, u8>, String> {
+    qualities
+        .into_iter()
+        .map(|(page_type, quality)| {
+            if !(1..=100).contains(&quality) {
+                return Err(format!("JPG品質は1〜100で指定してください（{}: {}）", page_type, quality));
+            }
+            let page_type = if page_type == "body" { "file".to_string() } else { page_type };
+            Ok((page_type, quality))
+        })
+        .collect()
+}
+
+// 要求からオプションと出力対象ページを決定（ファイルシステムには書き込まない）
+fn plan_export(request: ExportRequest) -> Result<ExportPlan, String> {
+    let ExportRequest {
+        output_path,
+        pages,
+        move_files,
+        convert_to_jpg,
+        jpg_quality,
+        bleed,
+        safe_write,
+        output_format,
+        strip_metadata,
+        chroma_subsampling,
+        cover_export,
+        script,
+        chapter_zips,
+        spread_split,
+        dither,
+        page_type_quality,
+        target_width,
+        target_height,
+        target_dpi,
+        resize_mode,
+        output_dpi,
+        subfolder_naming,
+    } = request;
+    let split_spreads = spread_split.is_some();
+    let spread_split = spread_split.unwrap_or_default();
+
+    // 4:4:4（サブサンプリングなし）を既定とし、トーンや細線の劣化を防ぐ
+    let chroma_subsampling = chroma_subsampling.unwrap_or_else(|| "444".to_string());
+    parse_chroma_subsampling(&chroma_subsampling)?;
+
+    // output_format: "jpg" | "jxl"（convert_to_jpg は従来互換）
+    let convert_ext = match output_format.as_deref() {
+        Some("jxl") => Some("jxl"),
+        Some("jpg") | Some("jpeg") => Some("jpg"),
+        Some(other) => return Err(format!("サポートされていない出力形式: {}", other)),
+        None if convert_to_jpg.unwrap_or(false) => Some("jpg"),
+        None => None,
+    };
+    let resize = match (target_width, target_height, target_dpi) {
+        (Some(width), Some(height), _) if width > 0 && height > 0 => Some(ResizeTarget::Size {
+            width,
+            height,
+            mode: ResizeMode::parse(resize_mode.as_deref().unwrap_or("pad"))?,
+        }),
+        (Some(_), _, _) | (_, Some(_), _) => return Err("入稿サイズは幅と高さの両方を指定してください".to_string()),
+        (None, None, Some(dpi)) if dpi > 0 => Some(ResizeTarget::Dpi(dpi)),
+        _ => None,
+    };
+    let opts = ExportRunOptions {
+        should_move: move_files.unwrap_or(false),
+        should_convert: convert_ext.is_some(),
+        convert_ext: convert_ext.unwrap_or("jpg"),
+        quality: jpg_quality.unwrap_or(95),
+        chroma_subsampling,
+        bleed,
+        safe_write: safe_write.unwrap_or(false),
+        strip_metadata: strip_metadata.unwrap_or(false),
+        spread_half: None,
+        spread_overlap_px: mm_to_px(spread_split.gutter_overlap_mm, spread_split.dpi),
+        dither: dither.unwrap_or(true),
+        page_type_quality: page_type_quality_map(page_type_quality.unwrap_or_default())?,
+        resize,
+        spread: false,
+        output_dpi: output_dpi.or(target_dpi).filter(|dpi| *dpi > 0),
+        pdf_page: 0,
+    };
+
+    // スクリプトで各ページの出力名・サブフォルダ・変換設定を決定（除外されたページは出力しない）
+    let mut excluded = Vec::new();
+    let decisions: Vec<PageDecision> = match script.as_deref().filter(|s| !s.trim().is_empty()) {
+        Some(source) => {
+            let script = ExportScript::compile(source)?;
+            let total = pages.len();
+            let mut decisions = Vec::with_capacity(total);
+            for (i, page) in pages.iter().enumerate() {
+                match script.decide(page, i, total)? {
+                    Some(decision) => {
+                        // 出力先に連結する前に、スクリプトが書き換えた名前を改めて確認
+                        if decision.page.output_name != page.output_name {
+                            check_output_component(&decision.page.output_name, "outputName", i)?;
+                        }
+                        if let Some(subfolder) = decision.page.subfolder.as_ref().filter(|s| page.subfolder.as_ref() != Some(*s)) {
+                            check_output_component(subfolder, "subfolder", i)?;
+                        }
+                        decisions.push(decision)
+                    }
+                    None => excluded.push(page.clone()),
+                }
+            }
+            decisions
+        }
+        None => pages
+            .into_iter()
+            .map(|page| PageDecision { page, format: None, quality: None, spread_half: None })
+            .collect(),
+    };
+    let mut decisions = if split_spreads {
+        split_spread_decisions(decisions, spread_split.binding != "left", &spread_split.naming)
+    } else {
+        decisions
+    };
+    match subfolder_naming.as_deref() {
+        None | Some("keep") => {}
+        Some(mode) => sanitize_subfolders(&mut decisions, mode)?,
+    }
+
+    // 入稿サイズが上限内か（分割しない見開きは2ページ分の幅）を出力前に確認
+    if let Some(ResizeTarget::Size { width, height, .. }) = opts.resize {
+        let limits = image_limits(ImageOperation::Export);
+        validate_dimensions_within(width, height, &limits)?;
+        if decisions.iter().any(|d| d.page.spread && d.spread_half.is_none()) {
+            let spread_width = width
+                .checked_mul(2)
+                .ok_or_else(|| format!("入稿サイズの幅が大きすぎます: {}", width))?;
+            validate_dimensions_within(spread_width, height, &limits)?;
+        }
+    }
+
+    Ok(ExportPlan {
+        output_dir: PathBuf::from(output_path),
+        opts,
+        decisions,
+        excluded,
+        cover_export,
+        chapter_zips: chapter_zips.unwrap_or(false),
+    })
+}
+
+// 入稿サイズに合わせて拡大したページ・入稿解像度に合わせられなかったページは notes に記録する
+fn export_with_job(job: &JobHandle, request: ExportRequest, notes: &mut ExportNotes) -> Result<usize, String> {
+    let ExportPlan { output_dir, opts, decisions, cover_export, chapter_zips, .. } = plan_export(request)?;
+    let output_dir = output_dir.as_path();
+    let pages: Vec<ExportPage> = decisions.iter().map(|d| d.page.clone()).collect();
 
     if !output_dir.exists() {
         fs::create_dir_all(output_dir).map_err(|e| e.to_string())?;
     }
+    if opts.safe_write {
+        mark_folder_scan_deferred(output_dir);
+    }
 
     // サブフォルダを事前に作成
     let mut created_subfolders = std::collections::HashSet::new();
@@ -103,184 +1023,534 @@ pub async fn export_pages(
     };
 
     // まず、ファイルがあるページからサイズと拡張子を取得
-    let mut reference_size: Option<(u32, u32)> = None;
-    let mut reference_ext = "png".to_string();
+    let (reference_size, reference_ext) = reference_page_format(&pages);
 
-    for page in &pages {
-        if let Some(ref source_path) = page.source_path {
-            let source = Path::new(source_path);
-            if source.exists() {
-                if reference_size.is_none() {
-                    if let Ok(dims) = get_image_dimensions(source) {
-                        reference_size = Some(dims);
-                    }
-                }
-                if let Some(ext) = source.extension().and_then(|e| e.to_str()) {
-                    let ext_lower = ext.to_lowercase();
-                    // PSDは出力形式として使わない（PNG or JPEGに変換）
-                    if ext_lower != "psd" {
-                        reference_ext = ext_lower;
+    // デフォルトサイズ（参照ページがない場合）
+    let default_size = reference_size.unwrap_or(DEFAULT_BLANK_SIZE);
+
+    // 白紙ページに記録する解像度（指定がなければ最初の原稿に合わせる）
+    let blank_dpi = opts.output_dpi.or_else(|| {
+        pages
+            .iter()
+            .filter_map(|page| page.source_path.as_deref().map(Path::new))
+            .find(|source| source.exists())
+            .and_then(source_dpi)
+            .map(|dpi| dpi.round() as u32)
+    });
+
+    let mut exported = 0;
+
+    for (i, page) in pages.iter().enumerate() {
+        if !job.wait_if_paused() {
+            return Err(format!("エクスポートがキャンセルされました（{}件出力済み）", exported));
+        }
+        job.set_progress(i + 1);
+
+        // 表紙専用設定がある場合、表紙は後でまとめて出力
+        if cover_export.is_some() && page.page_type == "cover" {
+            continue;
+        }
+
+        let page_output_dir = get_output_dir(page);
+        let opts = opts.for_page(&decisions[i]);
+
+        match page.page_type.as_str() {
+            "file" | "cover" | "colophon" | "intermission" => {
+                // ファイルがあるページはコピーまたは移動（オプションでJPG変換・塗り足し）
+                // 幕間もファイルがあれば同様に出力
+                if let Some(ref source_path) = page.source_path {
+                    let source = Path::new(source_path);
+                    if source.exists() && export_source_file(source, &page_output_dir, &page.output_name, &opts)? {
+                        exported += 1;
+                        notes.upscaled.extend(upscaled_page(page, source, &opts));
+                        notes.unknown_dpi.extend(unknown_dpi_page(page, source, &opts));
                     }
                 }
-                break;
             }
+            "blank" => {
+                // 白紙ページ: 前後のページからサイズと拡張子を取得
+                let (size, final_ext, blank_quality) = blank_output(&pages, i, default_size, &reference_ext, &opts);
+                let output_file = page_output_dir.join(format!("{}.{}", page.output_name, final_ext));
+                let img = blank_image(size);
+                let encode_options = opts.encode_options(blank_quality);
+                write_output(&output_file, &opts, |buffer| {
+                    encode_image_with(&img, buffer, &final_ext, &encode_options)?;
+                    stamp_output_dpi(buffer, &final_ext, blank_dpi)
+                })?;
+                exported += 1;
+            }
+            _ => {}
         }
     }
 
-    // デフォルトサイズ（参照ページがない場合）
-    let default_size = reference_size.unwrap_or((1654, 2339)); // A5 350dpi
+    if let Some(ref settings) = cover_export {
+        let covers: Vec<&ExportPage> = pages.iter().filter(|p| p.page_type == "cover").collect();
+        exported += export_cover_pages(&covers, output_dir, settings, &opts, notes)?;
+    }
 
-    let mut exported = 0;
+    // チャプターごとにZIPにまとめる（Web連載サービスはチャプター単位のZIPで入稿する）
+    if chapter_zips {
+        if !job.wait_if_paused() {
+            return Err(format!("エクスポートがキャンセルされました（{}件出力済み）", exported));
+        }
+        for zip_path in chapter_zip_paths(output_dir, &pages) {
+            let chapter_dir = zip_path.with_extension("");
+            if chapter_dir.is_dir() {
+                zip_directory(&chapter_dir, &zip_path)?;
+            }
+        }
+    }
+
+    Ok(exported)
+}
+
+// チャプターごとのZIPの出力先（サブフォルダの出現順、サブフォルダのないページは対象外）
+fn chapter_zip_paths(output_dir: &Path, pages: &[ExportPage]) -> Vec<PathBuf> {
+    let mut subfolders: Vec<&str> = Vec::new();
+    for page in pages {
+        if let Some(subfolder) = page.subfolder.as_deref().filter(|s| !s.is_empty()) {
+            if !subfolders.contains(&subfolder) {
+                subfolders.push(subfolder);
+            }
+        }
+    }
+    subfolders
+        .into_iter()
+        .map(|subfolder| output_dir.join(format!("{}.zip", subfolder)))
+        .collect()
+}
+
+// スキップ・欠落ページの記録
+fn skipped_page(page: &ExportPage, reason: &str) -> SkippedExportPage {
+    SkippedExportPage {
+        page_id: page.page_id.clone(),
+        source_path: page.source_path.clone(),
+        output_name: page.output_name.clone(),
+        reason: reason.to_string(),
+    }
+}
+
+// エクスポートを実行せずに出力予定を洗い出す（ファイルシステムには書き込まない）
+fn build_export_preview(request: ExportRequest) -> Result<ExportPreview, String> {
+    let ExportPlan { output_dir, opts, decisions, excluded, cover_export, chapter_zips } = plan_export(request)?;
+    let pages: Vec<ExportPage> = decisions.iter().map(|d| d.page.clone()).collect();
+
+    let mut planned: Vec<(PlannedExportFile, &ExportPage)> = Vec::new();
+    let mut missing_sources = Vec::new();
+    let mut upscaled_pages = Vec::new();
+    let mut unknown_dpi_pages = Vec::new();
+    let mut bleed_skipped = Vec::new();
+    let mut skipped: Vec<SkippedExportPage> = excluded
+        .iter()
+        .map(|page| skipped_page(page, "スクリプトで除外"))
+        .collect();
+
+    let (reference_size, reference_ext) = reference_page_format(&pages);
+    let default_size = reference_size.unwrap_or(DEFAULT_BLANK_SIZE);
+    let plan_file = |page: &ExportPage, output_file: PathBuf, action: &str| PlannedExportFile {
+        page_id: page.page_id.clone(),
+        source_path: page.source_path.clone(),
+        output_path: output_file.to_string_lossy().to_string(),
+        action: action.to_string(),
+    };
 
     for (i, page) in pages.iter().enumerate() {
-        let page_output_dir = get_output_dir(page);
+        if cover_export.is_some() && page.page_type == "cover" {
+            continue;
+        }
+
+        let page_output_dir = match page.subfolder {
+            Some(ref subfolder) => output_dir.join(subfolder),
+            None => output_dir.clone(),
+        };
+        let opts = opts.for_page(&decisions[i]);
 
         match page.page_type.as_str() {
-            "file" | "cover" | "colophon" => {
-                // ファイルがあるページはコピーまたは移動（オプションでJPG変換）
-                if let Some(ref source_path) = page.source_path {
-                    let source = Path::new(source_path);
-                    if source.exists() {
+            "file" | "cover" | "colophon" | "intermission" => {
+                let Some(ref source_path) = page.source_path else {
+                    // 幕間は画像なしでも出力しないのが正常
+                    if page.page_type != "intermission" {
+                        missing_sources.push(skipped_page(page, "元ファイルが指定されていません"));
+                    }
+                    continue;
+                };
+                let source = Path::new(source_path);
+                if !source.exists() {
+                    missing_sources.push(skipped_page(page, "元ファイルが見つかりません"));
+                    continue;
+                }
+                match source_output_file(source, &page_output_dir, &page.output_name, &opts) {
+                    Some(output_file) => {
                         let source_ext = source
                             .extension()
                             .and_then(|e| e.to_str())
                             .unwrap_or("png")
                             .to_lowercase();
-
-                        if should_convert {
-                            // JPGに変換して出力
-                            let output_file = page_output_dir.join(format!("{}.jpg", page.output_name));
-
-                            // PSDファイルは変換できないのでスキップ
-                            if source_ext == "psd" {
-                                continue;
-                            }
-
-                            // 画像を読み込んで変換
-                            let img = image::open(source).map_err(|e| e.to_string())?;
-                            let mut file = fs::File::create(&output_file).map_err(|e| e.to_string())?;
-                            let encoder = JpegEncoder::new_with_quality(&mut file, quality);
-                            img.write_with_encoder(encoder).map_err(|e| e.to_string())?;
-
-                            // 移動モードの場合は元ファイルを削除
-                            if should_move {
-                                fs::remove_file(source).map_err(|e| e.to_string())?;
-                            }
-                        } else {
-                            // そのままコピーまたは移動
-                            let output_file = page_output_dir.join(format!("{}.{}", page.output_name, source_ext));
-                            if should_move {
-                                fs::rename(source, &output_file).map_err(|e| e.to_string())?;
-                            } else {
-                                fs::copy(source, &output_file).map_err(|e| e.to_string())?;
-                            }
+                        if opts.bleed.is_some() && !applies_bleed(&source_ext, &opts) {
+                            bleed_skipped.push(skipped_page(page, "PSDはそのまま出力されるため塗り足しは付きません（変換モードでは統合画像に付けます）"));
                         }
-                        exported += 1;
+                        let action = if decodes_source(&source_ext, read_orientation(source), &opts) {
+                            "convert"
+                        } else if opts.should_move {
+                            "move"
+                        } else {
+                            "copy"
+                        };
+                        planned.push((plan_file(page, output_file, action), page));
+                        upscaled_pages.extend(upscaled_page(page, source, &opts));
+                        unknown_dpi_pages.extend(unknown_dpi_page(page, source, &opts));
                     }
+                    None => skipped.push(skipped_page(page, "PSBは変換モード・見開きの分割では出力されません（Photoshopで変換してください）")),
                 }
             }
             "blank" => {
-                // 白紙ページ: 前後のページからサイズと拡張子を取得
-                let mut size = default_size;
-                let mut ext = reference_ext.clone();
-
-                // 前のページからサイズを取得
-                for j in (0..i).rev() {
-                    if let Some(ref prev_path) = pages[j].source_path {
-                        let prev_source = Path::new(prev_path);
-                        if prev_source.exists() {
-                            if let Ok(dims) = get_image_dimensions(prev_source) {
-                                size = dims;
-                            }
-                            if let Some(e) = prev_source.extension().and_then(|e| e.to_str()) {
-                                let e_lower = e.to_lowercase();
-                                if e_lower != "psd" {
-                                    ext = e_lower;
-                                }
-                            }
-                            break;
-                        }
-                    }
-                }
+                // 白紙ページは前後のページの拡張子（変換モードは変換先の形式）で出力
+                let (_, final_ext, _) = blank_output(&pages, i, default_size, &reference_ext, &opts);
+                let output_file = page_output_dir.join(format!("{}.{}", page.output_name, final_ext));
+                planned.push((plan_file(page, output_file, "blank"), page));
+            }
+            _ => skipped.push(skipped_page(page, "出力対象外のページ種別")),
+        }
+    }
 
-                // 後のページからも確認（前がなければ）
-                if size == default_size {
-                    for j in (i + 1)..pages.len() {
-                        if let Some(ref next_path) = pages[j].source_path {
-                            let next_source = Path::new(next_path);
-                            if next_source.exists() {
-                                if let Ok(dims) = get_image_dimensions(next_source) {
-                                    size = dims;
-                                }
-                                if let Some(e) = next_source.extension().and_then(|e| e.to_str()) {
-                                    let e_lower = e.to_lowercase();
-                                    if e_lower != "psd" {
-                                        ext = e_lower;
-                                    }
-                                }
-                                break;
-                            }
-                        }
-                    }
+    if let Some(ref settings) = cover_export {
+        let covers: Vec<&ExportPage> = pages.iter().filter(|p| p.page_type == "cover").collect();
+        for page in &covers {
+            if !page.source_path.as_deref().is_some_and(|s| Path::new(s).exists()) {
+                missing_sources.push(skipped_page(page, "表紙の元ファイルが見つかりません"));
+            }
+        }
+        let ext = cover_output_ext(settings)?;
+        let sources = existing_cover_sources(&covers);
+        let cover_dir = output_dir.join(&settings.subfolder);
+        if settings.wraparound.is_some() {
+            // 展開図は1ファイルに合成
+            if sources.len() >= 2 {
+                let output_file = cover_output_file(&cover_dir, settings, sources[0].0, 1, ext);
+                planned.push((plan_file(sources[0].0, output_file, "cover"), sources[0].0));
+            } else {
+                for (page, _) in &sources {
+                    skipped.push(skipped_page(page, "展開図の合成には表1と表4の2ページ以上が必要です"));
                 }
+            }
+        } else {
+            for (i, (page, source)) in sources.iter().enumerate() {
+                let output_file = cover_output_file(&cover_dir, settings, page, i + 1, ext);
+                planned.push((plan_file(page, output_file, "cover"), *page));
+                let page_opts = ExportRunOptions { bleed: None, spread: page.spread, ..opts.clone() };
+                upscaled_pages.extend(upscaled_page(page, source, &page_opts));
+                unknown_dpi_pages.extend(unknown_dpi_page(page, source, &page_opts));
+            }
+        }
+    }
 
-                // JPG変換モードの場合はJPGで白紙を生成
-                let final_ext = if should_convert { "jpg".to_string() } else { ext };
-                let output_file = page_output_dir.join(format!("{}.{}", page.output_name, final_ext));
-                if should_convert {
-                    // JPGで白紙を生成
-                    let img = image::RgbImage::from_pixel(size.0, size.1, image::Rgb([255, 255, 255]));
-                    let dynamic_img = DynamicImage::ImageRgb8(img);
-                    let mut file = fs::File::create(&output_file).map_err(|e| e.to_string())?;
-                    let encoder = JpegEncoder::new_with_quality(&mut file, quality);
-                    dynamic_img.write_with_encoder(encoder).map_err(|e| e.to_string())?;
-                } else {
-                    create_blank_image(size.0, size.1, &output_file)?;
-                }
-                exported += 1;
+    // 出力パスの衝突（Windows/macOSの既定では大文字小文字を区別しないため小文字で比較）
+    let mut by_path: std::collections::BTreeMap<String, Vec<&(PlannedExportFile, &ExportPage)>> =
+        std::collections::BTreeMap::new();
+    for entry in &planned {
+        by_path.entry(entry.0.output_path.to_lowercase()).or_default().push(entry);
+    }
+    let collisions = by_path
+        .values()
+        .filter(|entries| entries.len() > 1)
+        .map(|entries| ExportCollision {
+            output_path: entries[0].0.output_path.clone(),
+            sources: entries
+                .iter()
+                .map(|(_, page)| page.source_path.clone().unwrap_or_else(|| page.output_name.clone()))
+                .collect(),
+            page_ids: entries.iter().filter_map(|(_, page)| page.page_id.clone()).collect(),
+        })
+        .collect();
+
+    let mut existing_files: Vec<String> = by_path
+        .values()
+        .map(|entries| &entries[0].0.output_path)
+        .filter(|path| Path::new(path).exists())
+        .cloned()
+        .collect();
+
+    let mut planned: Vec<PlannedExportFile> = planned.into_iter().map(|(file, _)| file).collect();
+    if chapter_zips {
+        for zip_path in chapter_zip_paths(&output_dir, &pages) {
+            let output_path = zip_path.to_string_lossy().to_string();
+            if zip_path.exists() {
+                existing_files.push(output_path.clone());
             }
-            "intermission" => {
-                // 幕間: ファイルがあればコピーまたは移動
-                if let Some(ref source_path) = page.source_path {
-                    let source = Path::new(source_path);
-                    if source.exists() {
-                        let source_ext = source
-                            .extension()
-                            .and_then(|e| e.to_str())
-                            .unwrap_or("png")
-                            .to_lowercase();
+            planned.push(PlannedExportFile {
+                page_id: None,
+                source_path: None,
+                output_path,
+                action: "archive".to_string(),
+            });
+        }
+    }
 
-                        if should_convert {
-                            // JPGに変換して出力
-                            let output_file = page_output_dir.join(format!("{}.jpg", page.output_name));
+    Ok(ExportPreview {
+        planned,
+        collisions,
+        existing_files,
+        missing_sources,
+        skipped,
+        upscaled_pages,
+        unknown_dpi_pages,
+        bleed_skipped,
+    })
+}
 
-                            if source_ext == "psd" {
-                                continue;
-                            }
+// 出力サイズの見積もりに使う、ページごとの出力内容
+pub(crate) enum PlannedOutput {
+    // 変換せずにコピー（メタデータの除去・解像度の記録のみ）
+    Copy { source: String, bytes: u64 },
+    // 原稿を変換して出力（size は出力のピクセル数）
+    Encode { source: String, page_type: String, opts: ExportRunOptions, size: (u32, u32), ext: String, quality: u8 },
+    // 白紙ページ
+    Blank { size: (u32, u32), ext: String, quality: u8, opts: ExportRunOptions },
+    // ファイルがない・クラウド上のみ・寸法を読めないため見積もれない
+    Unknown { source: String },
+}
 
-                            let img = image::open(source).map_err(|e| e.to_string())?;
-                            let mut file = fs::File::create(&output_file).map_err(|e| e.to_string())?;
-                            let encoder = JpegEncoder::new_with_quality(&mut file, quality);
-                            img.write_with_encoder(encoder).map_err(|e| e.to_string())?;
+// エクスポートと同じ計画から各ページの出力内容を求める（convert_to 指定時は全ページをその形式に変換した場合）
+// 表紙専用設定がある場合も、表紙は通常のページとして見積もる
+pub(crate) fn plan_outputs(request: ExportRequest, convert_to: Option<&'static str>) -> Result<Vec<PlannedOutput>, String> {
+    let ExportPlan { opts, decisions, .. } = plan_export(request)?;
+    let pages: Vec<ExportPage> = decisions.iter().map(|d| d.page.clone()).collect();
+    let (reference_size, reference_ext) = reference_page_format(&pages);
+    let default_size = reference_size.unwrap_or(DEFAULT_BLANK_SIZE);
 
-                            if should_move {
-                                fs::remove_file(source).map_err(|e| e.to_string())?;
-                            }
-                        } else {
-                            let output_file = page_output_dir.join(format!("{}.{}", page.output_name, source_ext));
-                            if should_move {
-                                fs::rename(source, &output_file).map_err(|e| e.to_string())?;
-                            } else {
-                                fs::copy(source, &output_file).map_err(|e| e.to_string())?;
-                            }
-                        }
-                        exported += 1;
-                    }
+    let mut outputs = Vec::new();
+    for (i, decision) in decisions.iter().enumerate() {
+        let page = &decision.page;
+        let mut opts = opts.for_page(decision);
+        if let Some(ext) = convert_to {
+            opts.should_convert = true;
+            opts.convert_ext = ext;
+        }
+        match page.page_type.as_str() {
+            "file" | "cover" | "colophon" | "intermission" => {
+                let Some(ref source_path) = page.source_path else {
+                    continue;
+                };
+                let source = Path::new(source_path);
+                if !source.exists() || cloud_placeholder_reason(source).is_some() {
+                    outputs.push(PlannedOutput::Unknown { source: source_path.clone() });
+                    continue;
                 }
+                // PSBを変換する場合など、出力されないページは含めない
+                let Some(output_file) = source_output_file(source, Path::new(""), &page.output_name, &opts) else {
+                    continue;
+                };
+                let source_ext = source
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .unwrap_or("png")
+                    .to_lowercase();
+                let output = if decodes_source(&source_ext, read_orientation(source), &opts) {
+                    planned_output_size(source, &opts).map(|size| PlannedOutput::Encode {
+                        source: source_path.clone(),
+                        page_type: page.page_type.clone(),
+                        size,
+                        ext: output_file.extension().and_then(|e| e.to_str()).unwrap_or("png").to_string(),
+                        quality: opts.quality,
+                        opts,
+                    })
+                } else {
+                    fs::metadata(source).ok().map(|metadata| PlannedOutput::Copy {
+                        source: source_path.clone(),
+                        bytes: metadata.len(),
+                    })
+                };
+                outputs.push(output.unwrap_or_else(|| PlannedOutput::Unknown { source: source_path.clone() }));
+            }
+            "blank" => {
+                let (size, ext, quality) = blank_output(&pages, i, default_size, &reference_ext, &opts);
+                outputs.push(PlannedOutput::Blank { size, ext, quality, opts });
             }
             _ => {}
         }
     }
+    Ok(outputs)
+}
+
+fn encoded_len(img: &DynamicImage, ext: &str, quality: u8, opts: &ExportRunOptions) -> Option<u64> {
+    let mut buffer = Cursor::new(Vec::new());
+    encode_image_with(img, &mut buffer, ext, &opts.encode_options(quality)).ok()?;
+    Some(buffer.into_inner().len() as u64)
+}
+
+// 見積もりのサンプル: 出力と同じ処理で作った画像を、各形式・品質でエンコードしたサイズ
+// 戻り値: (出力のピクセル数, targets の順のバイト数)
+pub(crate) fn sample_encoded_sizes(
+    source: &Path,
+    opts: &ExportRunOptions,
+    targets: &[(String, u8)],
+) -> Result<(u64, Vec<Option<u64>>), String> {
+    let (img, _permit) = render_source_page(source, opts)?;
+    let mut reduced: Option<DynamicImage> = None;
+    let sizes = targets
+        .iter()
+        .map(|(ext, quality)| {
+            if is_high_bit_depth(&img) && writes_8bit(ext) {
+                let reduced = reduced.get_or_insert_with(|| reduce_to_8bit(img.clone(), opts.dither));
+                encoded_len(reduced, ext, *quality, opts)
+            } else {
+                encoded_len(&img, ext, *quality, opts)
+            }
+        })
+        .collect();
+    Ok((img.width() as u64 * img.height() as u64, sizes))
+}
+
+// 白紙ページを実際にエンコードしたサイズ
+pub(crate) fn blank_encoded_size(size: (u32, u32), ext: &str, quality: u8, opts: &ExportRunOptions) -> Option<u64> {
+    encoded_len(&blank_image(size), ext, quality, opts)
+}
+
+/// エクスポートのドライラン（出力予定パス・名前の衝突・欠落ファイル・スキップされるページを返す）
+/// export_pages と同じ要求を受け取り、ファイルシステムには一切書き込まない
+#[tauri::command]
+pub async fn preview_export(request: ExportRequest) -> Result<ExportPreview, String> {
+    tokio::task::spawn_blocking(move || build_export_preview(request))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// ページをエクスポート（引数一式は ExportRequest。preview_export と同じ要求を受け取る）
+#[tauri::command]
+pub async fn export_pages(
+    app_handle: AppHandle,
+    window: tauri::WebviewWindow,
+    state: State<'_, AppState>,
+    request: ExportRequest,
+) -> Result<usize, String> {
+    let job_request = request.clone();
+    let exported = tokio::task::spawn_blocking(move || run_export(&app_handle, job_request))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    // クイックエクスポート用に前回の設定を記録（移動モードは再実行できないため除外）
+    if !request.move_files.unwrap_or(false) {
+        state.with_window(window.label(), |w| w.last_export = Some(request));
+    }
 
     Ok(exported)
 }
+
+/// エクスポートスクリプトの構文を検証（保存前のチェック用）
+#[tauri::command]
+pub fn validate_export_script(script: String) -> Result<(), String> {
+    ExportScript::compile(&script).map(|_| ())
+}
+
+// ページリストの出力名の既定テンプレート（リスト内の連番3桁）
+const DEFAULT_PAGE_LIST_TEMPLATE: &str = "{index:3}";
+
+/// プロジェクトに保存したページリストを名前で指定してエクスポート
+/// request には全ページを渡し、リストの順に並べ替えてリスト内の連番で命名する（サブフォルダには分けない）
+/// name_template は {index} {name} {original} {chapter} が使える（既定 "{index:3}"）
+#[tauri::command]
+pub async fn export_page_list(
+    app_handle: AppHandle,
+    request: ExportRequest,
+    page_lists: Vec<PageList>,
+    list_name: String,
+    name_template: Option<String>,
+) -> Result<ReexportResult, String> {
+    let list = page_lists
+        .into_iter()
+        .find(|l| l.name == list_name)
+        .ok_or_else(|| format!("ページリストが見つかりません: {}", list_name))?;
+    let template = name_template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGE_LIST_TEMPLATE.to_string());
+
+    let mut request = request;
+    let all_pages = std::mem::take(&mut request.pages);
+    let mut missing_page_ids = Vec::new();
+    for page_id in &list.page_ids {
+        let Some(i) = all_pages.iter().position(|p| p.page_id.as_ref() == Some(page_id)) else {
+            missing_page_ids.push(page_id.clone());
+            continue;
+        };
+        // 同じページを複数回含むリストもあるため、元の一覧からは取り除かずに複製する
+        let mut page = all_pages[i].clone();
+        let tokens = NameTokens {
+            name: &page.output_name,
+            chapter: page.subfolder.as_deref().unwrap_or(""),
+            index: request.pages.len() + 1,
+            digits: 3,
+            ..Default::default()
+        };
+        let output_name = match page.source_path {
+            Some(ref source) => render_name_template(&template, &tokens.with_source(Path::new(source))),
+            None => render_name_template(&template, &tokens),
+        };
+        page.output_name = output_name;
+        page.subfolder = None;
+        request.pages.push(page);
+    }
+    if request.pages.is_empty() {
+        return Err(format!("ページリスト「{}」にエクスポートできるページがありません", list.name));
+    }
+    let requested = request.pages.len();
+
+    let exported = tokio::task::spawn_blocking(move || run_export(&app_handle, request))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    Ok(ReexportResult {
+        requested,
+        exported,
+        missing_page_ids,
+    })
+}
+
+/// プリフライト・エクスポートで指摘されたページのみを再エクスポート
+/// request には更新後の設定と全ページを渡し、report の指摘ページと page_ids を対象にする
+/// severities 未指定なら "error" と "warning" の指摘を対象とする
+#[tauri::command]
+pub async fn reexport_flagged_pages(
+    app_handle: AppHandle,
+    request: ExportRequest,
+    report: Option<PreflightReport>,
+    page_ids: Option<Vec<String>>,
+    severities: Option<Vec<String>>,
+) -> Result<ReexportResult, String> {
+    let severities = severities.unwrap_or_else(|| vec!["error".to_string(), "warning".to_string()]);
+
+    let mut targets: Vec<String> = page_ids.unwrap_or_default();
+    if let Some(report) = report {
+        for page in report.pages {
+            let flagged = page.issues.iter().any(|i| severities.contains(&i.severity));
+            if let (true, Some(page_id)) = (flagged, page.page_id) {
+                targets.push(page_id);
+            }
+        }
+    }
+    targets.sort();
+    targets.dedup();
+    if targets.is_empty() {
+        return Err("再エクスポートの対象ページがありません".to_string());
+    }
+
+    let mut request = request;
+    request
+        .pages
+        .retain(|p| p.page_id.as_ref().is_some_and(|id| targets.binary_search(id).is_ok()));
+    let missing_page_ids: Vec<String> = targets
+        .iter()
+        .filter(|id| !request.pages.iter().any(|p| p.page_id.as_ref() == Some(*id)))
+        .cloned()
+        .collect();
+    let requested = request.pages.len();
+
+    let exported = tokio::task::spawn_blocking(move || run_export(&app_handle, request))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    Ok(ReexportResult {
+        requested,
+        exported,
+        missing_page_ids,
+    })
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::path::Path;
+use rayon::prelude::*;
+use crate::commands::export::{blank_encoded_size, plan_outputs, sample_encoded_sizes, PlannedOutput};
+use crate::types::{ExportFormatEstimate, ExportRequest, ExportSizeEstimate, ExportSizeEstimateOptions};
+
+// 見積もる形式と、その形式に変換する場合の拡張子（"requested" は要求の設定どおりに出力した場合）
+fn convert_target(format: &str) -> Result<Option<&'static str>, String> {
+    match format {
+        "requested" => Ok(None),
+        "jpg" | "jpeg" => Ok(Some("jpg")),
+        "png" => Ok(Some("png")),
+        "tif" | "tiff" => Ok(Some("tif")),
+        "webp" => Ok(Some("webp")),
+        "jxl" => Ok(Some("jxl")),
+        other => Err(format!("不明な出力形式: {}", other)),
+    }
+}
+
+// 計測の単位（拡張子・品質）。品質はJPGのサイズにしか影響しないため、それ以外は0にまとめる
+fn encode_key(ext: &str, quality: u8) -> (String, u8) {
+    let ext = ext.to_lowercase();
+    let quality = if matches!(ext.as_str(), "jpg" | "jpeg") { quality } else { 0 };
+    (ext, quality)
+}
+
+// サンプルのエンコード結果から求めた1ピクセルあたりのバイト数
+struct FormatRatio {
+    mean: f64,
+    low: f64,
+    high: f64,
+}
+
+// 本全体から均等な間隔でサンプルを選ぶ（表紙など種別の異なるページは各種別の先頭を必ず含める）
+// 残りは選ばれていないページから均等に選ぶため、同じページが重なってサンプル数が減ることはない
+fn pick_samples(page_types: &[&str], count: usize) -> Vec<usize> {
+    let count = count.max(1).min(page_types.len());
+    let mut picked: Vec<usize> = Vec::new();
+    for (i, page_type) in page_types.iter().enumerate() {
+        if picked.len() < count && !picked.iter().any(|&p| page_types[p] == *page_type) {
+            picked.push(i);
+        }
+    }
+    let candidates: Vec<usize> = (0..page_types.len()).filter(|i| !picked.contains(i)).collect();
+    let remaining = count - picked.len();
+    for k in 0..remaining {
+        picked.push(candidates[(k * 2 + 1) * candidates.len() / (remaining * 2)]);
+    }
+    picked.sort_unstable();
+    picked
+}
+
+// サンプル全体の合計から平均を、ページごとの比率から上下限を求める
+fn format_ratio(samples: &[(u64, Option<u64>)]) -> Option<FormatRatio> {
+    let measured: Vec<(u64, u64)> = samples
+        .iter()
+        .filter_map(|(pixels, bytes)| bytes.map(|b| (*pixels, b)))
+        .filter(|(pixels, _)| *pixels > 0)
+        .collect();
+    if measured.is_empty() {
+        return None;
+    }
+    let total_pixels: u64 = measured.iter().map(|(p, _)| p).sum();
+    let total_bytes: u64 = measured.iter().map(|(_, b)| b).sum();
+    let ratios: Vec<f64> = measured.iter().map(|(p, b)| *b as f64 / *p as f64).collect();
+    Some(FormatRatio {
+        mean: total_bytes as f64 / total_pixels as f64,
+        low: ratios.iter().copied().fold(f64::INFINITY, f64::min),
+        high: ratios.iter().copied().fold(0.0, f64::max),
+    })
+}
+
+/// 代表的なページを実際にエンコードして、出力形式ごとの合計サイズを見積もる
+/// 全ページを変換する前に、入稿先のアップロード上限（既定2GB）に収まるかを確認するためのもの
+/// export_pages と同じ要求を受け取り、ページ種別ごとの品質・入稿サイズ・塗り足し・見開きの分割・スクリプトを反映する
+#[tauri::command]
+pub async fn estimate_export_size(
+    request: ExportRequest,
+    options: Option<ExportSizeEstimateOptions>,
+) -> Result<ExportSizeEstimate, String> {
+    let options = options.unwrap_or_default();
+    let formats: Vec<String> = options.formats.iter().map(|f| f.to_lowercase()).collect();
+    let targets = formats
+        .iter()
+        .map(|f| convert_target(f))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    tokio::task::spawn_blocking(move || {
+        let page_count = request.pages.len();
+        // 形式ごとの出力内容と、サンプル用に全ページを変換する場合の出力内容（寸法と変換処理は形式によらない）
+        let plans = targets
+            .iter()
+            .map(|target| plan_outputs(request.clone(), *target))
+            .collect::<Result<Vec<_>, _>>()?;
+        let sampling_plan = plan_outputs(request, Some("png"))?;
+
+        // 見積もりに必要な形式・品質の組み合わせ
+        let mut keys: Vec<(String, u8)> = Vec::new();
+        for output in plans.iter().flatten() {
+            if let PlannedOutput::Encode { ext, quality, .. } = output {
+                let key = encode_key(ext, *quality);
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        let candidates: Vec<(&str, &str, &_)> = sampling_plan
+            .iter()
+            .filter_map(|output| match output {
+                PlannedOutput::Encode { source, page_type, opts, .. } => Some((source.as_str(), page_type.as_str(), opts)),
+                _ => None,
+            })
+            .collect();
+        let page_types: Vec<&str> = candidates.iter().map(|(_, page_type, _)| *page_type).collect();
+        let sample_indices = if keys.is_empty() { Vec::new() } else { pick_samples(&page_types, options.sample_count) };
+        let measurements: Vec<(usize, Result<(u64, Vec<Option<u64>>), String>)> = sample_indices
+            .par_iter()
+            .map(|&i| {
+                let (source, _, opts) = candidates[i];
+                (i, sample_encoded_sizes(Path::new(source), opts, &keys))
+            })
+            .collect();
+
+        // デコードできなかったサンプルは書き出しでも失敗するため見積もりから外す
+        let mut failed: Vec<&str> = Vec::new();
+        let mut sampled_pages = Vec::new();
+        let mut samples: Vec<(u64, Vec<Option<u64>>)> = Vec::new();
+        for (i, measurement) in measurements {
+            match measurement {
+                Ok(sample) => {
+                    sampled_pages.push(candidates[i].0.to_string());
+                    samples.push(sample);
+                }
+                Err(_) => failed.push(candidates[i].0),
+            }
+        }
+        if !sample_indices.is_empty() && samples.is_empty() {
+            return Err("見積もりに使えるページがありません（原稿を読み込めません）".to_string());
+        }
+
+        let ratios: HashMap<&(String, u8), FormatRatio> = keys
+            .iter()
+            .enumerate()
+            .filter_map(|(index, key)| {
+                let per_sample: Vec<(u64, Option<u64>)> = samples.iter().map(|(p, sizes)| (*p, sizes[index])).collect();
+                format_ratio(&per_sample).map(|ratio| (key, ratio))
+            })
+            .collect();
+
+        // 白紙ページは前後のページと同じサイズの白い画像を実際にエンコードする（同じサイズ・形式は1回だけ）
+        let mut blank_sizes: HashMap<((u32, u32), String, u8), u64> = HashMap::new();
+
+        let mut unestimated_pages: Vec<String> = Vec::new();
+        let mut mark_unestimated = |source: &String| {
+            if !unestimated_pages.contains(source) {
+                unestimated_pages.push(source.clone());
+            }
+        };
+        let mut estimates = Vec::new();
+        for (format, outputs) in formats.iter().zip(&plans) {
+            let (mut estimated, mut low, mut high) = (0f64, 0f64, 0f64);
+            let mut estimated_pages = 0usize;
+            for output in outputs {
+                let (mean, min, max) = match output {
+                    PlannedOutput::Copy { source, .. } | PlannedOutput::Encode { source, .. }
+                        if failed.contains(&source.as_str()) =>
+                    {
+                        mark_unestimated(source);
+                        continue;
+                    }
+                    PlannedOutput::Unknown { source } => {
+                        mark_unestimated(source);
+                        continue;
+                    }
+                    PlannedOutput::Copy { bytes, .. } => {
+                        let bytes = *bytes as f64;
+                        (bytes, bytes, bytes)
+                    }
+                    PlannedOutput::Encode { source, size, ext, quality, .. } => {
+                        let Some(ratio) = ratios.get(&encode_key(ext, *quality)) else {
+                            mark_unestimated(source);
+                            continue;
+                        };
+                        let pixels = size.0 as f64 * size.1 as f64;
+                        (pixels * ratio.mean, pixels * ratio.low, pixels * ratio.high)
+                    }
+                    PlannedOutput::Blank { size, ext, quality, opts } => {
+                        let (ext, quality) = encode_key(ext, *quality);
+                        let bytes = match blank_sizes.get(&(*size, ext.clone(), quality)) {
+                            Some(bytes) => *bytes,
+                            None => {
+                                let bytes = blank_encoded_size(*size, &ext, quality.max(1), opts).unwrap_or(0);
+                                blank_sizes.insert((*size, ext, quality), bytes);
+                                bytes
+                            }
+                        };
+                        let bytes = bytes as f64;
+                        (bytes, bytes, bytes)
+                    }
+                };
+                estimated += mean;
+                low += min;
+                high += max;
+                estimated_pages += 1;
+            }
+
+            let estimated_bytes = estimated.round() as u64;
+            let high_bytes = high.round() as u64;
+            estimates.push(ExportFormatEstimate {
+                format: format.clone(),
+                estimated_bytes,
+                low_bytes: low.round() as u64,
+                high_bytes,
+                average_page_bytes: if estimated_pages > 0 { estimated_bytes / estimated_pages as u64 } else { 0 },
+                fits_limit: high_bytes <= options.size_limit_bytes,
+                estimate_fits_limit: estimated_bytes <= options.size_limit_bytes,
+            });
+        }
+
+        Ok(ExportSizeEstimate {
+            page_count,
+            sampled_pages,
+            unestimated_pages,
+            size_limit_bytes: options.size_limit_bytes,
+            formats: estimates,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
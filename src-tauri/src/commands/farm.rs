@@ -0,0 +1,88 @@
+use std::path::Path;
+use tauri::AppHandle;
+use crate::commands::tiff::apply_tiff_name_template;
+use crate::farm::{read_status, start_worker, stop_worker, submit_job};
+use crate::types::{DetectedAdobeApp, ExportRequest, FarmJob, FarmStatus, FarmTiffPayload, TiffConvertConfig};
+
+fn default_files_per_job() -> usize {
+    20
+}
+
+/// TIFF変換をファイル数ごとのジョブに分けて共有キューに投入
+/// output_dir は全ワーカーから見える共有フォルダを指定する
+#[tauri::command]
+pub async fn submit_farm_tiff_jobs(
+    queue_dir: String,
+    config: TiffConvertConfig,
+    output_dir: String,
+    pinned_photoshop: Option<DetectedAdobeApp>,
+    files_per_job: Option<usize>,
+) -> Result<Vec<FarmJob>, String> {
+    tokio::task::spawn_blocking(move || {
+        if config.files.is_empty() {
+            return Err("変換するファイルがありません".to_string());
+        }
+        std::fs::create_dir_all(&output_dir).map_err(|e| format!("出力ディレクトリの作成に失敗: {}", e))?;
+
+        // 連番がジョブごとにやり直しにならないよう、分割前に出力名を確定
+        let mut config = config;
+        apply_tiff_name_template(&mut config);
+        config.name_template = None;
+
+        let chunk_size = files_per_job.unwrap_or_else(default_files_per_job).max(1);
+        let total = config.files.len().div_ceil(chunk_size);
+        let queue = Path::new(&queue_dir);
+        config
+            .files
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(i, files)| {
+                let payload = FarmTiffPayload {
+                    config: TiffConvertConfig {
+                        files: files.to_vec(),
+                        ..config.clone()
+                    },
+                    output_dir: output_dir.clone(),
+                    pinned_photoshop: pinned_photoshop.clone(),
+                };
+                let payload = serde_json::to_value(payload).map_err(|e| e.to_string())?;
+                let label = format!("TIFF変換 {}/{}: {}", i + 1, total, output_dir);
+                submit_job(queue, "tiff-convert", label, payload, i)
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// エクスポートを共有キューに投入
+#[tauri::command]
+pub async fn submit_farm_export_job(queue_dir: String, request: ExportRequest) -> Result<FarmJob, String> {
+    tokio::task::spawn_blocking(move || {
+        let label = format!("エクスポート: {}", request.output_path);
+        let payload = serde_json::to_value(request).map_err(|e| e.to_string())?;
+        submit_job(Path::new(&queue_dir), "export", label, payload, 0)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// このマシンをワーカーとして共有キューのジョブを処理
+#[tauri::command]
+pub fn start_farm_worker(app_handle: AppHandle, queue_dir: String, poll_secs: Option<u64>) -> Result<(), String> {
+    start_worker(app_handle, queue_dir, poll_secs.unwrap_or(5))
+}
+
+/// ワーカーを停止（実行中のジョブは最後まで処理）
+#[tauri::command]
+pub fn stop_farm_worker(app_handle: AppHandle) {
+    stop_worker(&app_handle);
+}
+
+/// 共有キューのジョブとワーカーの状況
+#[tauri::command]
+pub async fn get_farm_status(queue_dir: String) -> Result<FarmStatus, String> {
+    tokio::task::spawn_blocking(move || read_status(Path::new(&queue_dir)))
+        .await
+        .map_err(|e| e.to_string())?
+}
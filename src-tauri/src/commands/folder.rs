@@ -1,12 +1,36 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
-use crate::types::FileInfo;
+use crate::types::{CloudFileStatus, FileInfo, PageTypeRule, SyncConflictStatus};
 use crate::constants::SUPPORTED_EXTENSIONS;
+use crate::file_utils::{cloud_sync_provider, find_sync_conflict_copies, is_cloud_placeholder, sync_conflict_original};
+use crate::image_info::read_bit_depth;
 use crate::image_utils::get_file_type;
+use crate::page_rules::classify_file;
 
+// rules 指定時はファイル名からページ種別・ラベルを割り当てる
+// recursive 指定時はサブフォルダも読み込む（max_depth は読み込みフォルダ直下を1とした深さ、省略時は無制限）
+// 大きなフォルダやネットワークドライブでは時間がかかるため、ブロッキング用のスレッドで読み込む
 #[tauri::command]
-pub fn get_folder_contents(folder_path: String) -> Result<Vec<FileInfo>, String> {
-    let path = Path::new(&folder_path);
+pub async fn get_folder_contents(
+    folder_path: String,
+    rules: Option<Vec<PageTypeRule>>,
+    recursive: Option<bool>,
+    max_depth: Option<usize>,
+) -> Result<Vec<FileInfo>, String> {
+    tokio::task::spawn_blocking(move || {
+        read_folder_contents(Path::new(&folder_path), &rules.unwrap_or_default(), recursive.unwrap_or(false), max_depth)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn read_folder_contents(
+    path: &Path,
+    rules: &[PageTypeRule],
+    recursive: bool,
+    max_depth: Option<usize>,
+) -> Result<Vec<FileInfo>, String> {
 
     if !path.exists() || !path.is_dir() {
         return Err("無効なフォルダパス".to_string());
@@ -14,7 +38,13 @@ pub fn get_folder_contents(folder_path: String) -> Result<Vec<FileInfo>, String>
 
     let mut files: Vec<FileInfo> = Vec::new();
 
-    let entries = fs::read_dir(path).map_err(|e| e.to_string())?;
+    let depth = if recursive { max_depth.unwrap_or(usize::MAX).max(1) } else { 1 };
+    let entries = walkdir::WalkDir::new(path)
+        .min_depth(1)
+        .max_depth(depth)
+        .into_iter()
+        // 隠しフォルダ（プロジェクト横のキャッシュなど）は読み込まない
+        .filter_entry(|e| !(e.depth() > 0 && e.file_type().is_dir() && e.file_name().to_string_lossy().starts_with('.')));
 
     for entry_result in entries {
         // ディレクトリエントリ読み込みエラーをログ出力
@@ -48,17 +78,120 @@ pub fn get_folder_contents(folder_path: String) -> Result<Vec<FileInfo>, String>
             .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as u64)
             .unwrap_or(0);
 
+        let cloud_placeholder = is_cloud_placeholder(&metadata);
+        // クラウド上のみのファイルは読むとダウンロードが始まるため調べない
+        let bit_depth = if cloud_placeholder { None } else { read_bit_depth(entry_path) };
+
+        let path_str = entry_path.to_string_lossy().to_string();
+        let classification = classify_file(&path_str, rules);
+        let relative_path = if recursive {
+            entry_path.strip_prefix(path).ok().map(|relative| {
+                relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+        } else {
+            None
+        };
+
         files.push(FileInfo {
-            path: entry_path.to_string_lossy().to_string(),
+            path: path_str,
             name: entry_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
             size: metadata.len(),
             modified_time,
             file_type: file_type.to_string(),
+            cloud_placeholder,
+            suggested_page_type: classification.page_type,
+            suggested_label: classification.label,
+            relative_path,
+            bit_depth,
+            sync_conflict_of: None,
+            sync_conflict_copies: Vec::new(),
         });
     }
 
-    // ファイル名で自然順ソート
-    files.sort_by(|a, b| natord::compare(&a.name, &b.name));
+    mark_sync_conflicts(&mut files);
+
+    // ファイル名で自然順ソート（再帰時はサブフォルダごとにまとまるよう相対パスで）
+    if recursive {
+        files.sort_by(|a, b| {
+            natord::compare(
+                a.relative_path.as_deref().unwrap_or(&a.name),
+                b.relative_path.as_deref().unwrap_or(&b.name),
+            )
+        });
+    } else {
+        files.sort_by(|a, b| natord::compare(&a.name, &b.name));
+    }
 
     Ok(files)
 }
+
+// 同期の競合ファイル（Dropbox の conflicted copy、OneDrive のPC名付きなど）と、その元のファイルに印を付ける
+// 競合した方を誤って出力しないよう、読み込み時点で両方を警告できるようにする
+fn mark_sync_conflicts(files: &mut [FileInfo]) {
+    let index: HashMap<String, usize> = files.iter().enumerate().map(|(i, f)| (f.path.to_lowercase(), i)).collect();
+    let mut conflicts = Vec::new();
+    for (i, file) in files.iter().enumerate() {
+        let dir = Path::new(&file.path).parent().unwrap_or(Path::new(""));
+        let exists = |name: &str| index.contains_key(&dir.join(name).to_string_lossy().to_lowercase());
+        if let Some(original) = sync_conflict_original(&file.name, exists) {
+            let original_path = dir.join(original).to_string_lossy().to_string();
+            conflicts.push((i, original_path));
+        }
+    }
+    for (i, original_path) in conflicts {
+        if let Some(&original) = index.get(&original_path.to_lowercase()) {
+            let copy = files[i].path.clone();
+            files[original].sync_conflict_copies.push(copy);
+        }
+        files[i].sync_conflict_of = Some(original_path);
+    }
+}
+
+/// プロジェクトのページの原稿について同期の競合ファイルを検出（競合のあるものだけ返す）
+#[tauri::command]
+pub async fn detect_sync_conflicts(paths: Vec<String>) -> Result<Vec<SyncConflictStatus>, String> {
+    tokio::task::spawn_blocking(move || {
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let p = Path::new(&path);
+                let name = p.file_name()?.to_string_lossy().to_string();
+                let conflict_of = sync_conflict_original(&name, |original| p.with_file_name(original).exists());
+                let conflict_copies = find_sync_conflict_copies(p);
+                if conflict_copies.is_empty() && conflict_of.is_none() {
+                    return None;
+                }
+                Some(SyncConflictStatus {
+                    path,
+                    conflict_copies,
+                    conflict_of,
+                })
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// サムネイル生成・エクスポート前にクラウド上のみのファイルを検出
+#[tauri::command]
+pub fn check_cloud_files(paths: Vec<String>) -> Vec<CloudFileStatus> {
+    paths
+        .into_iter()
+        .map(|path| {
+            let p = Path::new(&path);
+            let placeholder = fs::metadata(p)
+                .map(|m| m.is_file() && is_cloud_placeholder(&m))
+                .unwrap_or(false);
+            CloudFileStatus {
+                provider: cloud_sync_provider(p).map(|s| s.to_string()),
+                placeholder,
+                path,
+            }
+        })
+        .collect()
+}
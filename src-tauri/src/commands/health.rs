@@ -0,0 +1,175 @@
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use crate::cache::ThumbnailCache;
+use crate::commands::tiff::find_photoshop_path;
+use crate::file_utils::{available_space, get_config_path};
+use crate::settings::load_settings;
+use crate::types::{AppSettings, HealthCheckItem, HealthCheckReport, RecentFile, SessionState};
+
+fn item(id: &str, label: &str, status: &str, message: impl Into<String>) -> HealthCheckItem {
+    HealthCheckItem {
+        id: id.to_string(),
+        label: label.to_string(),
+        status: status.to_string(),
+        message: message.into(),
+    }
+}
+
+// キャッシュディレクトリに書き込めるか
+fn check_cache(cache_dir: &Path) -> HealthCheckItem {
+    let probe = cache_dir.join(".health_check");
+    match fs::write(&probe, b"ok").and_then(|_| fs::remove_file(&probe)) {
+        Ok(()) => item("cache", "サムネイルキャッシュ", "ok", cache_dir.to_string_lossy()),
+        Err(e) => item(
+            "cache",
+            "サムネイルキャッシュ",
+            "error",
+            format!("書き込みできません: {} - {}", cache_dir.display(), e),
+        ),
+    }
+}
+
+// 設定ファイルが読み込めるか（壊れたファイルは既定値で起動する）
+fn check_config() -> HealthCheckItem {
+    let config_path = match get_config_path() {
+        Ok(path) => path,
+        Err(e) => return item("config", "設定ファイル", "error", e),
+    };
+
+    fn parses<T: serde::de::DeserializeOwned>(path: &Path) -> bool {
+        fs::read_to_string(path)
+            .ok()
+            .is_some_and(|content| serde_json::from_str::<T>(&content).is_ok())
+    }
+
+    let files: [(&str, fn(&Path) -> bool); 3] = [
+        ("settings.json", parses::<AppSettings>),
+        ("recent_files.json", parses::<Vec<RecentFile>>),
+        ("session.json", parses::<SessionState>),
+    ];
+    let broken: Vec<&str> = files
+        .iter()
+        .filter(|(name, valid)| {
+            let path = config_path.join(name);
+            path.exists() && !valid(&path)
+        })
+        .map(|(name, _)| *name)
+        .collect();
+
+    if broken.is_empty() {
+        item("config", "設定ファイル", "ok", config_path.to_string_lossy())
+    } else {
+        item(
+            "config",
+            "設定ファイル",
+            "warning",
+            format!("読み込めないため既定値を使用します: {}", broken.join(", ")),
+        )
+    }
+}
+
+fn check_photoshop() -> HealthCheckItem {
+    match find_photoshop_path() {
+        Some(path) => item("photoshop", "Photoshop", "ok", path),
+        None => item("photoshop", "Photoshop", "warning", "見つかりません（TIFF変換は利用できません）"),
+    }
+}
+
+// フォルダ監視が使えるか（Linux は inotify の監視数上限を確認）
+fn check_watcher() -> HealthCheckItem {
+    if cfg!(target_os = "linux") {
+        let limit = fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok());
+        return match limit {
+            Some(n) if n >= 8192 => item("watcher", "フォルダ監視", "ok", format!("inotify 監視上限: {}", n)),
+            Some(n) => item(
+                "watcher",
+                "フォルダ監視",
+                "warning",
+                format!("inotify の監視上限が少ないため大きなフォルダを監視できません: {}", n),
+            ),
+            None => item("watcher", "フォルダ監視", "warning", "inotify の設定を確認できません"),
+        };
+    }
+    item("watcher", "フォルダ監視", "ok", "OS標準の監視APIを使用")
+}
+
+// Windows の長いパス（260文字超）のサポート
+fn check_long_paths() -> HealthCheckItem {
+    if !cfg!(windows) {
+        return item("longPath", "長いパス", "ok", "制限なし");
+    }
+    let mut command = std::process::Command::new("reg");
+    command.args([
+        "query",
+        r"HKLM\SYSTEM\CurrentControlSet\Control\FileSystem",
+        "/v",
+        "LongPathsEnabled",
+    ]);
+    // コンソールウィンドウを表示しない
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = command.output();
+    match output {
+        Ok(out) if String::from_utf8_lossy(&out.stdout).contains("0x1") => {
+            item("longPath", "長いパス", "ok", "有効")
+        }
+        Ok(_) => item(
+            "longPath",
+            "長いパス",
+            "warning",
+            "無効です。深いフォルダ階層への出力が失敗する場合があります",
+        ),
+        Err(e) => item("longPath", "長いパス", "warning", format!("確認できません: {}", e)),
+    }
+}
+
+fn check_disk_space(cache_dir: &Path) -> HealthCheckItem {
+    let settings = load_settings();
+    match available_space(cache_dir) {
+        Some(bytes) => {
+            let mb = bytes / 1024 / 1024;
+            let status = if mb < settings.disk_pause_mb {
+                "error"
+            } else if mb < settings.disk_warning_mb {
+                "warning"
+            } else {
+                "ok"
+            };
+            item("diskSpace", "空き容量", status, format!("キャッシュドライブの空き: {} MB", mb))
+        }
+        None => item("diskSpace", "空き容量", "warning", "空き容量を取得できません"),
+    }
+}
+
+/// 起動時・トラブルシューティング用に動作環境を診断
+#[tauri::command]
+pub async fn run_health_check(cache: State<'_, ThumbnailCache>) -> Result<HealthCheckReport, String> {
+    let cache_dir = cache.cache_dir.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let items = vec![
+            check_cache(&cache_dir),
+            check_config(),
+            check_photoshop(),
+            check_watcher(),
+            check_long_paths(),
+            check_disk_space(&cache_dir),
+        ];
+        let healthy = items.iter().all(|i| i.status != "error");
+
+        HealthCheckReport {
+            items,
+            healthy,
+            checked_at: chrono::Utc::now().to_rfc3339(),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,78 @@
+use tauri::AppHandle;
+use crate::commands::export::run_export;
+use crate::history::{diff_pages, find_history, read_history, snapshot_pages};
+use crate::types::{ExportHistoryEntry, ExportHistorySummary, ExportPage, ExportRequest, RerunExportResult};
+
+/// エクスポート・変換の履歴一覧を新しい順に取得
+#[tauri::command]
+pub async fn list_export_history(limit: Option<usize>) -> Result<Vec<ExportHistorySummary>, String> {
+    tokio::task::spawn_blocking(move || {
+        let entries = read_history()?;
+        Ok(entries
+            .iter()
+            .take(limit.unwrap_or(200))
+            .map(ExportHistorySummary::from)
+            .collect())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 履歴の詳細（設定一式・結果）を取得
+#[tauri::command]
+pub async fn get_export_job(id: String) -> Result<ExportHistoryEntry, String> {
+    tokio::task::spawn_blocking(move || find_history(&id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 過去のエクスポートと同じ設定で、現在のページ構成に対して再エクスポート
+/// pages: 現在のページ（未指定なら履歴時点のページ）、overrides: 上書きする設定（ExportRequest の一部のキー）
+/// dry_run 指定時は出力せず、履歴時点からのページの変化のみ返す
+#[tauri::command]
+pub async fn rerun_export_job(
+    app_handle: AppHandle,
+    id: String,
+    pages: Option<Vec<ExportPage>>,
+    overrides: Option<serde_json::Value>,
+    dry_run: Option<bool>,
+) -> Result<RerunExportResult, String> {
+    tokio::task::spawn_blocking(move || rerun_export(&app_handle, &id, pages, overrides, dry_run.unwrap_or(false)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// 履歴の設定で再エクスポート（ローカルAPIのプリセット指定のエクスポートからも呼ぶ）
+pub fn rerun_export(
+    app_handle: &AppHandle,
+    id: &str,
+    pages: Option<Vec<ExportPage>>,
+    overrides: Option<serde_json::Value>,
+    dry_run: bool,
+) -> Result<RerunExportResult, String> {
+    let entry = find_history(id)?;
+    let previous = entry
+        .request
+        .ok_or_else(|| "この履歴は再実行できません（エクスポート以外の処理です）".to_string())?;
+
+    // 保存済みの設定に上書き分を重ねる
+    let mut merged = serde_json::to_value(&previous).map_err(|e| format!("JSON変換に失敗: {}", e))?;
+    if let (Some(target), Some(serde_json::Value::Object(overrides))) = (merged.as_object_mut(), overrides) {
+        for (key, value) in overrides {
+            target.insert(key, value);
+        }
+    }
+    let mut request: ExportRequest =
+        serde_json::from_value(merged).map_err(|e| format!("上書き設定が不正です: {}", e))?;
+    if let Some(pages) = pages {
+        request.pages = pages;
+    }
+
+    let diff = diff_pages(&entry.pages, &snapshot_pages(&request.pages));
+    if dry_run {
+        return Ok(RerunExportResult { exported: 0, diff });
+    }
+
+    let exported = run_export(app_handle, request)?;
+    Ok(RerunExportResult { exported, diff })
+}
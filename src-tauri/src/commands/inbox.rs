@@ -0,0 +1,29 @@
+use tauri::{AppHandle, State};
+use crate::app_mode::is_safe_mode;
+use crate::inbox::start_inbox_watch;
+use crate::state::AppState;
+use crate::types::InboxConfig;
+
+/// 受け取りフォルダの監視を開始（同じウィンドウの既存の監視は置き換え）
+/// セーフモードでは監視を開始しない
+#[tauri::command]
+pub fn start_inbox(
+    app_handle: AppHandle,
+    window: tauri::WebviewWindow,
+    state: State<'_, AppState>,
+    config: InboxConfig,
+) -> Result<(), String> {
+    if is_safe_mode() {
+        eprintln!("セーフモードのため受け取りフォルダを監視しません: {}", config.inbox_path);
+        return Ok(());
+    }
+    let watch = start_inbox_watch(app_handle, window.label().to_string(), config)?;
+    state.with_window(window.label(), |w| w.inbox = Some(watch));
+    Ok(())
+}
+
+/// 受け取りフォルダの監視を停止
+#[tauri::command]
+pub fn stop_inbox(window: tauri::WebviewWindow, state: State<'_, AppState>) {
+    state.with_window(window.label(), |w| w.inbox = None);
+}
@@ -0,0 +1,45 @@
+use std::sync::atomic::AtomicBool;
+use tauri::AppHandle;
+use crate::integrity::{scan_project, start_scanner, stop_scanner};
+use crate::types::{IntegrityScanReport, ProjectFile};
+
+fn default_interval_hours() -> u64 {
+    24
+}
+
+/// 原稿の整合性チェックを今すぐ1回実行
+/// 参照している原稿を再ハッシュし、更新日時が変わらないまま内容が変わった・読めなくなった・同期の競合ファイルがあるものを報告する
+#[tauri::command]
+pub async fn run_integrity_scan(
+    app: AppHandle,
+    project: ProjectFile,
+    project_path: String,
+) -> Result<IntegrityScanReport, String> {
+    tokio::task::spawn_blocking(move || scan_project(&app, &project, &project_path, &AtomicBool::new(false)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 原稿の整合性チェックを定期的にバックグラウンドで実行（既定は24時間ごと、ジョブの実行中は待機）
+/// 結果は "integrity-scan-finished" イベントで通知する。プロジェクトを開き直したら再度呼ぶ
+#[tauri::command]
+pub async fn start_integrity_scan(
+    app: AppHandle,
+    project: ProjectFile,
+    project_path: String,
+    interval_hours: Option<u64>,
+) -> Result<(), String> {
+    // 前回のチェックの終了を待つため、ブロッキング用のスレッドで行う
+    let interval_hours = interval_hours.unwrap_or_else(default_interval_hours);
+    tokio::task::spawn_blocking(move || start_scanner(app, project, project_path, interval_hours))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 定期的な整合性チェックを停止
+#[tauri::command]
+pub async fn stop_integrity_scan(app: AppHandle) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || stop_scanner(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,41 @@
+use std::path::Path;
+use tauri::{AppHandle, State};
+use crate::file_utils::available_space;
+use crate::jobs::JobRegistry;
+use crate::types::{BackgroundJobInfo, DiskSpaceInfo};
+
+/// バックグラウンドジョブの一覧を取得
+#[tauri::command]
+pub fn list_background_jobs(registry: State<'_, JobRegistry>) -> Vec<BackgroundJobInfo> {
+    registry.list()
+}
+
+/// バックグラウンドジョブを一時停止（id 未指定なら全ジョブ）
+#[tauri::command]
+pub fn pause_background_job(app_handle: AppHandle, registry: State<'_, JobRegistry>, id: Option<u64>) {
+    registry.set_paused(&app_handle, id, true);
+}
+
+/// バックグラウンドジョブを再開（id 未指定なら全ジョブ）
+#[tauri::command]
+pub fn resume_background_job(app_handle: AppHandle, registry: State<'_, JobRegistry>, id: Option<u64>) {
+    registry.set_paused(&app_handle, id, false);
+}
+
+/// バックグラウンドジョブをキャンセル（id 未指定なら全ジョブ）
+#[tauri::command]
+pub fn cancel_background_job(app_handle: AppHandle, registry: State<'_, JobRegistry>, id: Option<u64>) {
+    registry.cancel(&app_handle, id);
+}
+
+/// 指定パスのドライブの空き容量を取得（キャッシュ・出力先の事前確認用）
+#[tauri::command]
+pub fn get_disk_space(paths: Vec<String>) -> Vec<DiskSpaceInfo> {
+    paths
+        .into_iter()
+        .map(|path| DiskSpaceInfo {
+            available_bytes: available_space(Path::new(&path)),
+            path,
+        })
+        .collect()
+}
@@ -0,0 +1,11 @@
+use std::path::Path;
+use crate::metadata::read_metadata;
+use crate::types::ImageMetadata;
+
+/// ソースファイルのEXIF・XMPを読み取り
+#[tauri::command]
+pub async fn read_image_metadata(path: String) -> Result<ImageMetadata, String> {
+    tokio::task::spawn_blocking(move || read_metadata(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -4,3 +4,49 @@ pub mod project;
 pub mod recent;
 pub mod open_file;
 pub mod tiff;
+pub mod cover;
+pub mod bleed;
+pub mod autocrop;
+pub mod tone;
+pub mod strip;
+pub mod viewer;
+pub mod metadata;
+pub mod proof;
+pub mod screen;
+pub mod purity;
+pub mod trim;
+pub mod similar;
+pub mod session;
+pub mod window;
+pub mod quick_export;
+pub mod settings;
+pub mod jobs;
+pub mod health;
+pub mod app_mode;
+pub mod audit;
+pub mod usage;
+pub mod plugin;
+pub mod inbox;
+pub mod notification;
+pub mod report;
+pub mod sequence;
+pub mod rules;
+pub mod chapter_split;
+pub mod cleanup;
+pub mod history;
+pub mod watch;
+pub mod farm;
+pub mod benchmark;
+pub mod tiles;
+pub mod pdf;
+pub mod scan;
+pub mod autosave;
+pub mod book_check;
+pub mod preflight;
+pub mod storefront;
+pub mod spread;
+pub mod annotation;
+pub mod edition;
+pub mod integrity;
+pub mod preview_server;
+pub mod export_size;
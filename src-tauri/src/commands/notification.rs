@@ -0,0 +1,13 @@
+use crate::notifications::post_webhook;
+use crate::types::{OperationSummary, WebhookConfig};
+
+/// Webhookにテスト通知を送信
+#[tauri::command]
+pub async fn test_webhook(webhook: WebhookConfig) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let summary = OperationSummary::new("test", "台割マネージャーからのテスト通知", Ok(None));
+        post_webhook(&webhook, &summary)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
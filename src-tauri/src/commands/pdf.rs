@@ -0,0 +1,44 @@
+use std::fs;
+use std::path::Path;
+use crate::image_utils::{image_limits, save_image, ImageOperation};
+use crate::pdf::{pdf_page_count, render_pdf_page, PDF_RENDER_DPI};
+
+/// PDFのページ数を取得（取り込むページの選択用）
+#[tauri::command]
+pub async fn get_pdf_page_count(path: String) -> Result<usize, String> {
+    tokio::task::spawn_blocking(move || pdf_page_count(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// PDFの指定ページを画像ファイルとして書き出し（台割のページ素材として使用）
+/// page_index は0始まり、dpi 未指定なら入稿解像度（350dpi）。出力先が既にあればエラー
+#[tauri::command]
+pub async fn extract_pdf_page(
+    path: String,
+    page_index: usize,
+    output_path: String,
+    dpi: Option<u32>,
+    jpg_quality: Option<u8>,
+) -> Result<String, String> {
+    let dpi = dpi.unwrap_or(PDF_RENDER_DPI).clamp(72, 1200);
+
+    tokio::task::spawn_blocking(move || {
+        let source = Path::new(&path);
+        let output = Path::new(&output_path);
+        // 取り込み済みの素材を上書きしない
+        if output.exists() {
+            return Err(format!("出力先のファイルが既に存在します: {}", output_path));
+        }
+
+        let img = render_pdf_page(source, page_index, dpi, None, Some(&image_limits(ImageOperation::Export)))?;
+
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+        }
+        save_image(&img, output, jpg_quality.unwrap_or(95))?;
+        Ok(output_path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,30 @@
+use crate::plugins::{discover_plugins, run_plugins, ANALYZER_PROTOCOL_VERSION};
+use crate::types::{AnalyzerPage, AnalyzerPluginInfo, AnalyzerPluginResult, AnalyzerRequest};
+
+/// インストール済みの解析プラグイン一覧を取得
+#[tauri::command]
+pub async fn list_analyzer_plugins() -> Result<Vec<AnalyzerPluginInfo>, String> {
+    tokio::task::spawn_blocking(discover_plugins)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 解析プラグインを実行（スタジオ独自の命名規則チェックなど）
+/// plugin_ids 未指定なら全プラグインを実行
+#[tauri::command]
+pub async fn run_analyzer_plugins(
+    pages: Vec<AnalyzerPage>,
+    plugin_ids: Option<Vec<String>>,
+    options: Option<serde_json::Value>,
+) -> Result<Vec<AnalyzerPluginResult>, String> {
+    tokio::task::spawn_blocking(move || {
+        let request = AnalyzerRequest {
+            protocol_version: ANALYZER_PROTOCOL_VERSION,
+            pages,
+            options: options.unwrap_or(serde_json::Value::Null),
+        };
+        Ok(run_plugins(&request, plugin_ids.as_deref()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
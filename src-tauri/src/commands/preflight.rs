@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::path::Path;
+use rayon::prelude::*;
+use crate::commands::project::validate_file_reference;
+use crate::image_info::{read_image_info, ImageInfo};
+use crate::image_utils::mm_to_px;
+use crate::types::{PreflightIssue, PreflightOptions, PreflightPageSize, PreflightReport, PreflightReportPage, ProjectFile};
+
+// 指摘を作成
+fn issue(severity: &str, category: &str, message: impl Into<String>) -> PreflightIssue {
+    PreflightIssue {
+        severity: severity.to_string(),
+        category: category.to_string(),
+        message: message.into(),
+    }
+}
+
+fn color_mode_label(mode: &str) -> &str {
+    match mode {
+        "bitmap" => "モノクロ2階調",
+        "grayscale" => "グレースケール",
+        "indexed" => "インデックスカラー",
+        "rgb" => "RGB",
+        "cmyk" => "CMYK",
+        "lab" => "Lab",
+        other => other,
+    }
+}
+
+// 原稿の寸法が仕上がり寸法（塗り足し込み）と一致するか。見開き原稿は幅2ページ分として扱う
+fn check_page_size(info: &ImageInfo, dpi: f64, size: &PreflightPageSize, issues: &mut Vec<PreflightIssue>) {
+    let bleed = if size.includes_bleed { size.bleed_mm * 2.0 } else { 0.0 };
+    let expected_width_mm = size.trim_width_mm + bleed;
+    let spread_width_mm = size.trim_width_mm * 2.0 + bleed;
+    let expected_height_mm = size.trim_height_mm + bleed;
+
+    let dpi_u32 = dpi.round() as u32;
+    let tolerance = mm_to_px(size.tolerance_mm, dpi_u32).max(1) as i64;
+    let matches = |px: u32, mm: f64| (px as i64 - mm_to_px(mm, dpi_u32) as i64).abs() <= tolerance;
+
+    let width_ok = matches(info.width, expected_width_mm) || matches(info.width, spread_width_mm);
+    if !width_ok || !matches(info.height, expected_height_mm) {
+        let to_mm = |px: u32| px as f64 / dpi * 25.4;
+        issues.push(issue(
+            "warning",
+            "dimensions",
+            format!(
+                "原稿の寸法が仕上がりと一致しません（{:.1}×{:.1}mm、想定 {:.1}×{:.1}mm @{}ppi）",
+                to_mm(info.width),
+                to_mm(info.height),
+                expected_width_mm,
+                expected_height_mm,
+                dpi_u32
+            ),
+        ));
+    }
+}
+
+// 1ページ分の解像度・寸法のチェック
+fn check_page_image(info: &ImageInfo, options: &PreflightOptions) -> Vec<PreflightIssue> {
+    let mut issues = Vec::new();
+    let dpi = match info.dpi {
+        Some(dpi) => dpi,
+        None => {
+            issues.push(issue(
+                "info",
+                "resolution",
+                format!("解像度が記録されていないため {}ppi として扱いました", options.default_dpi),
+            ));
+            options.default_dpi as f64
+        }
+    };
+
+    if let Some(min_dpi) = options.min_dpi.filter(|min| dpi + 0.5 < *min) {
+        issues.push(issue(
+            "error",
+            "resolution",
+            format!("解像度が不足しています（{:.0}ppi、必要 {:.0}ppi）", dpi, min_dpi),
+        ));
+    }
+    if let Some(ref size) = options.page_size {
+        check_page_size(info, dpi, size, &mut issues);
+    }
+    issues
+}
+
+/// プロジェクトの全ページを一括チェック（刷版前の確認）
+/// 解像度不足・カラーモードの混在・仕上がり寸法との不一致・ファイルの欠落・総ページ数の折の単位を調べる
+#[tauri::command]
+pub async fn preflight_project(
+    project: ProjectFile,
+    base_path: String,
+    options: Option<PreflightOptions>,
+) -> Result<PreflightReport, String> {
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || run_preflight(&project, &base_path, &options))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// 全ページのチェック本体（ローカルAPIからも呼ぶ）
+pub fn run_preflight(project: &ProjectFile, base_path: &str, options: &PreflightOptions) -> Result<PreflightReport, String> {
+    let base = Path::new(base_path);
+    let pages: Vec<_> = project
+        .chapters
+        .iter()
+        .flat_map(|chapter| chapter.pages.iter().map(move |page| (chapter, page)))
+        .collect();
+
+    // ファイルの所在を確認してから、見つかった原稿の情報を並列に読み取る
+    let checked: Vec<(PreflightReportPage, Option<String>)> = pages
+        .par_iter()
+        .enumerate()
+        .filter_map(|(i, (chapter, page))| {
+            let file_ref = page.file.as_ref()?;
+            let label = page
+                .label
+                .clone()
+                .unwrap_or_else(|| format!("{} - {}", chapter.name, i + 1));
+            let validation = validate_file_reference(&page.id, file_ref, base);
+            let mut issues = Vec::new();
+            let mut color_mode = None;
+
+            match validation.resolved_path {
+                None => issues.push(issue("error", "missingFile", "ファイルが見つかりません")),
+                Some(ref resolved) => {
+                    if validation.status == "moved" {
+                        issues.push(issue("info", "missingFile", format!("ファイルの場所が変わっています: {}", resolved)));
+                    }
+                    match read_image_info(Path::new(resolved)) {
+                        Ok(info) => {
+                            issues.extend(check_page_image(&info, options));
+                            color_mode = info.color_mode;
+                        }
+                        Err(e) => issues.push(issue("error", "file", format!("原稿を読み込めません: {}", e))),
+                    }
+                }
+            }
+
+            let report_page = PreflightReportPage {
+                page_id: Some(page.id.clone()),
+                label,
+                path: Some(validation.resolved_path.unwrap_or(validation.original_path)),
+                issues,
+            };
+            Some((report_page, color_mode))
+        })
+        .collect();
+
+    let mut report_issues = Vec::new();
+
+    // カラーモード: 指定がなければ最も多いモードを基準にする
+    let expected_mode = options.color_mode.clone().or_else(|| {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for mode in checked.iter().filter_map(|(_, mode)| mode.as_deref()) {
+            *counts.entry(mode).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .max_by_key(|(mode, count)| (*count, std::cmp::Reverse(*mode)))
+            .map(|(mode, _)| mode.to_string())
+    });
+    let mut report_pages = Vec::with_capacity(checked.len());
+    let mut mismatched = 0;
+    for (mut page, mode) in checked {
+        let check = options.check_color_modes || options.color_mode.is_some();
+        if let (true, Some(mode), Some(expected)) = (check, mode.as_deref(), expected_mode.as_deref()) {
+            if mode != expected {
+                mismatched += 1;
+                let (severity, basis) = if options.color_mode.is_some() {
+                    ("error", "指定")
+                } else {
+                    ("warning", "他のページ")
+                };
+                page.issues.push(issue(
+                    severity,
+                    "colorMode",
+                    format!(
+                        "カラーモードが{}です（{}は{}）",
+                        color_mode_label(mode),
+                        basis,
+                        color_mode_label(expected)
+                    ),
+                ));
+            }
+        }
+        report_pages.push(page);
+    }
+    if mismatched > 0 {
+        report_issues.push(issue(
+            "warning",
+            "colorMode",
+            format!("カラーモードが異なるページが{}ページあります", mismatched),
+        ));
+    }
+
+    // 総ページ数（白紙・扉なども含む）が折の単位で割り切れるか
+    if let Some(multiple) = options.page_multiple.filter(|m| *m > 1) {
+        let total = pages.len();
+        if total % multiple != 0 {
+            report_issues.push(issue(
+                "error",
+                "pageCount",
+                format!(
+                    "総ページ数 {} が{}の倍数ではありません（あと{}ページ必要です）",
+                    total,
+                    multiple,
+                    multiple - total % multiple
+                ),
+            ));
+        }
+    }
+
+    Ok(PreflightReport {
+        title: format!("プリフライト: {}", project.name),
+        project_name: Some(project.name.clone()),
+        issues: report_issues,
+        pages: report_pages,
+    })
+}
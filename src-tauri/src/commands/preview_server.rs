@@ -0,0 +1,61 @@
+use tauri::State;
+use crate::cache::ThumbnailCache;
+use crate::preview_server::{
+    preview_server_info, start_preview_server, stop_preview_server, update_preview_book, PreviewBook, PreviewServer,
+};
+use crate::types::{PreviewServerInfo, ViewerExportOptions, ViewerPage};
+
+fn default_port() -> u16 {
+    47322
+}
+
+fn preview_book(pages: Vec<ViewerPage>, title: Option<String>, options: Option<ViewerExportOptions>) -> PreviewBook {
+    PreviewBook {
+        title: title.unwrap_or_else(|| "台割プレビュー".to_string()),
+        pages,
+        options: options.unwrap_or_default(),
+    }
+}
+
+/// 現在のページ順をLAN内のタブレットからめくって確認できる閲覧専用サーバーを起動
+/// 返されたURL（認証トークン付き）をタブレットのブラウザで開く。ページ画像は要求されたときに縮小して生成する
+#[tauri::command]
+pub fn start_tablet_preview(
+    pages: Vec<ViewerPage>,
+    title: Option<String>,
+    options: Option<ViewerExportOptions>,
+    port: Option<u16>,
+    server: State<'_, PreviewServer>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<PreviewServerInfo, String> {
+    let cache_dir = cache.sibling_dir("tablet")?;
+    start_preview_server(
+        &server,
+        preview_book(pages, title, options),
+        port.unwrap_or_else(default_port),
+        cache_dir,
+    )
+}
+
+/// 並べ替え・差し替え後のページ順をプレビューサーバーに反映（タブレット側は再読み込みで反映）
+#[tauri::command]
+pub fn update_tablet_preview(
+    pages: Vec<ViewerPage>,
+    title: Option<String>,
+    options: Option<ViewerExportOptions>,
+    server: State<'_, PreviewServer>,
+) -> Result<PreviewServerInfo, String> {
+    update_preview_book(&server, preview_book(pages, title, options))
+}
+
+/// プレビューサーバーを停止
+#[tauri::command]
+pub fn stop_tablet_preview(server: State<'_, PreviewServer>) -> Result<(), String> {
+    stop_preview_server(&server)
+}
+
+/// プレビューサーバーの接続情報（停止中は None）
+#[tauri::command]
+pub fn get_tablet_preview_info(server: State<'_, PreviewServer>) -> Option<PreviewServerInfo> {
+    preview_server_info(&server)
+}
@@ -1,44 +1,298 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
-use crate::types::{ProjectFile, SavedFileReference, FileValidationResult};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+use crate::app_mode::is_safe_mode;
+use crate::autosave::clear_recovery;
+use crate::constants::SUPPORTED_EXTENSIONS;
+use crate::file_utils::{content_hash, mark_hidden, write_replacing};
+use crate::settings::load_settings;
+use crate::state::{AppState, ProjectCache};
+use crate::types::{
+    LoadedProject, ProjectBackup, ProjectFile, RelinkBaseResult, RelinkSuggestion, SavedFileReference, FileValidationResult,
+};
+use serde_json::{Map, Value};
+
+// プロジェクトのバックアップの拡張子
+const BACKUP_EXTENSION: &str = "bak";
 
 // プロジェクトを保存
+// 内容ハッシュの計算でページのファイルを読むため、ブロッキング用のスレッドで行う
 #[tauri::command]
 pub async fn save_project(file_path: String, project: ProjectFile) -> Result<(), String> {
-    let path = Path::new(&file_path);
+    tokio::task::spawn_blocking(move || {
+        let path = Path::new(&file_path);
+        // 現在のスキーマで書き出すため、古いバージョンは現在のものにする
+        // （同じメジャーの新しいアプリで保存されたファイルはそのバージョンを残し、古いアプリでの上書きで下がらないようにする）
+        let mut project = project;
+        if parse_schema_version(&project.version) < parse_schema_version(PROJECT_SCHEMA_VERSION) {
+            project.version = PROJECT_SCHEMA_VERSION.to_string();
+        }
+        fill_content_hashes(&file_path, &mut project);
 
-    // 親ディレクトリが存在することを確認
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
-    }
+        // 親ディレクトリが存在することを確認
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+        }
 
-    // JSONとしてシリアライズして書き込み
-    let json = serde_json::to_string_pretty(&project)
-        .map_err(|e| format!("JSONシリアライズエラー: {}", e))?;
+        // JSONとしてシリアライズして書き込み
+        let json = serde_json::to_string_pretty(&project)
+            .map_err(|e| format!("JSONシリアライズエラー: {}", e))?;
 
-    fs::write(path, json).map_err(|e| format!("ファイル書き込みエラー: {}", e))?;
+        write_project_atomic(path, &json, load_settings().project_backup_count)?;
+
+        // 保存できたので異常終了時の復旧用スナップショットは不要
+        clear_recovery(&file_path);
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// 上書き前の内容をバックアップしてから、一時ファイル経由でプロジェクトを書き込む
+// 書き込み途中でクラッシュしても元のファイルは壊れない
+fn write_project_atomic(path: &Path, json: &str, backup_count: usize) -> Result<(), String> {
+    if backup_count > 0 && path.exists() {
+        backup_project_file(path)?;
+    }
 
+    write_replacing(path, |file| file.write_all(json.as_bytes()).map_err(|e| e.to_string()))
+        .map_err(|e| format!("ファイル書き込みエラー: {}", e))?;
+
+    if backup_count > 0 {
+        prune_project_backups(path, backup_count);
+    }
     Ok(())
 }
 
-// プロジェクトを読み込み
+// バックアップファイル名の接頭辞（"<プロジェクトファイル名>."）
+fn backup_prefix(path: &Path) -> Result<String, String> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| "プロジェクトのファイル名が不正です".to_string())?;
+    Ok(format!("{}.", name))
+}
+
+// 現在のプロジェクトファイルを "<ファイル名>.<日時>.bak" としてコピー
+fn backup_project_file(path: &Path) -> Result<PathBuf, String> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.3f");
+    let backup = path.with_file_name(format!("{}{}.{}", backup_prefix(path)?, stamp, BACKUP_EXTENSION));
+    fs::copy(path, &backup).map_err(|e| format!("バックアップ作成エラー: {}", e))?;
+    Ok(backup)
+}
+
+// プロジェクト横のバックアップ一覧（新しい順）
+fn project_backups(path: &Path) -> Result<Vec<ProjectBackup>, String> {
+    let prefix = backup_prefix(path)?;
+    let suffix = format!(".{}", BACKUP_EXTENSION);
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("フォルダ読み込みエラー: {}", e)),
+    };
+
+    let mut backups: Vec<ProjectBackup> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let stamp = name.strip_prefix(&prefix)?.strip_suffix(&suffix)?.to_string();
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            Some(ProjectBackup {
+                path: entry.path().to_string_lossy().to_string(),
+                created_at: chrono::NaiveDateTime::parse_from_str(&stamp, "%Y%m%d-%H%M%S%.3f")
+                    .map(|t| t.format("%Y-%m-%dT%H:%M:%S").to_string())
+                    .unwrap_or(stamp),
+                size: metadata.len(),
+            })
+        })
+        .collect();
+    // 日時はファイル名に固定桁で入っているため、パスの降順で新しい順になる
+    backups.sort_by(|a, b| b.path.cmp(&a.path));
+    Ok(backups)
+}
+
+// 保持数を超えた古いバックアップを削除
+fn prune_project_backups(path: &Path, keep: usize) {
+    if let Ok(backups) = project_backups(path) {
+        for backup in backups.iter().skip(keep) {
+            let _ = fs::remove_file(&backup.path);
+        }
+    }
+}
+
+/// プロジェクトの自動バックアップ一覧を取得（新しい順）
+#[tauri::command]
+pub async fn list_project_backups(file_path: String) -> Result<Vec<ProjectBackup>, String> {
+    tokio::task::spawn_blocking(move || project_backups(Path::new(&file_path)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// バックアップからプロジェクトを復元し、復元した内容を返す
+/// 復元前の状態もバックアップに残すため、復元自体を取り消せる
+#[tauri::command]
+pub async fn restore_project_backup(file_path: String, backup_path: String) -> Result<ProjectFile, String> {
+    tokio::task::spawn_blocking(move || {
+        let path = Path::new(&file_path);
+        let backup = Path::new(&backup_path);
+
+        // 別のプロジェクトのバックアップを誤って復元しないよう確認
+        let is_own_backup = project_backups(path)?
+            .iter()
+            .any(|b| Path::new(&b.path) == backup);
+        if !is_own_backup {
+            return Err("このプロジェクトのバックアップではありません".to_string());
+        }
+
+        let project = read_project_file(backup)?;
+        let json = fs::read_to_string(backup).map_err(|e| format!("ファイル読み込みエラー: {}", e))?;
+        let backup_count = load_settings().project_backup_count.max(1);
+        write_project_atomic(path, &json, backup_count)?;
+
+        Ok(project)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// プロジェクトを読み込み（古い形式は現在の形式に移行し、実行した移行を返す）
 #[tauri::command]
-pub async fn load_project(file_path: String) -> Result<ProjectFile, String> {
-    let path = Path::new(&file_path);
+pub async fn load_project(file_path: String) -> Result<LoadedProject, String> {
+    let (project, migrations) = read_project_file_migrated(Path::new(&file_path))?;
+    Ok(LoadedProject { project, migrations })
+}
+
+// プロジェクトファイルを読み込んで解析（ローカルAPIからも使用）
+pub fn read_project_file(path: &Path) -> Result<ProjectFile, String> {
+    read_project_file_migrated(path).map(|(project, _)| project)
+}
 
+// プロジェクトファイルを読み込み、必要ならスキーマを移行して解析
+fn read_project_file_migrated(path: &Path) -> Result<(ProjectFile, Vec<String>), String> {
     if !path.exists() {
         return Err("ファイルが見つかりません".to_string());
     }
 
     let content = fs::read_to_string(path).map_err(|e| format!("ファイル読み込みエラー: {}", e))?;
-    let project: ProjectFile = serde_json::from_str(&content)
+    let mut value: Value = serde_json::from_str(&content)
         .map_err(|e| format!("JSON解析エラー: {}", e))?;
+    let migrations = migrate_project(&mut value, path)?;
+    let project: ProjectFile = serde_json::from_value(value)
+        .map_err(|e| format!("JSON解析エラー: {}", e))?;
+
+    Ok((project, migrations))
+}
+
+// ========== スキーマの移行 ==========
+
+// 現在のプロジェクトファイルのスキーマバージョン
+pub const PROJECT_SCHEMA_VERSION: &str = "1.0";
+
+// 1段階分の移行（from のファイルを to の形式に更新）
+struct ProjectMigration {
+    from: &'static str,
+    to: &'static str,
+    description: &'static str,
+    apply: fn(&mut Value, &Path),
+}
+
+// 古い順に並べる（version のないファイルは "0" として扱う）
+const PROJECT_MIGRATIONS: &[ProjectMigration] = &[ProjectMigration {
+    from: "0",
+    to: "1.0",
+    description: "バージョン情報のない初期形式のプロジェクトに必須項目を補完",
+    apply: fill_required_fields,
+}];
+
+// "1.2" → (1, 2, 0)、"1.2.3" → (1, 2, 3)（省略した部分は 0）
+fn parse_schema_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.trim().split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+    let patch = parts.next().map_or(Some(0), |p| p.parse().ok())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
 
-    Ok(project)
+// 古いスキーマのプロジェクトを現在の形式に移行し、実行した移行の説明を返す
+fn migrate_project(value: &mut Value, path: &Path) -> Result<Vec<String>, String> {
+    if !value.is_object() {
+        return Err("プロジェクトファイルの形式が不正です".to_string());
+    }
+    let version = value
+        .get("version")
+        .and_then(|v| v.as_str())
+        .unwrap_or("0")
+        .to_string();
+    let mut parsed = parse_schema_version(&version)
+        .ok_or_else(|| format!("プロジェクトファイルのバージョンが不正です: {}", version))?;
+
+    // 新しいメジャーバージョンのアプリで保存されたファイルは読み込まない（項目の意味が変わっている可能性がある）
+    let current = parse_schema_version(PROJECT_SCHEMA_VERSION);
+    if current.is_some_and(|current| parsed.0 > current.0) {
+        return Err(format!(
+            "新しいバージョンのアプリで作成されたプロジェクトです（形式 {}、対応 {}）",
+            version, PROJECT_SCHEMA_VERSION
+        ));
+    }
+
+    let mut applied = Vec::new();
+    while let Some(migration) = PROJECT_MIGRATIONS
+        .iter()
+        .find(|m| parse_schema_version(m.from) == Some(parsed))
+    {
+        (migration.apply)(value, path);
+        parsed = parse_schema_version(migration.to).unwrap_or(parsed);
+        value["version"] = Value::String(migration.to.to_string());
+        applied.push(format!("{} → {}: {}", migration.from, migration.to, migration.description));
+    }
+    Ok(applied)
+}
+
+// オブジェクトに項目がなければ既定値を設定
+fn set_default(object: &mut Map<String, Value>, key: &str, default: impl FnOnce() -> Value) {
+    if !object.get(key).is_some_and(|v| !v.is_null()) {
+        object.insert(key.to_string(), default());
+    }
+}
+
+// 0 → 1.0: 初期形式で省略されていた必須項目を補完
+fn fill_required_fields(value: &mut Value, path: &Path) {
+    let Some(project) = value.as_object_mut() else {
+        return;
+    };
+    let now = chrono::Local::now().to_rfc3339();
+    let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+    let base_path = path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+
+    set_default(project, "name", || Value::String(name));
+    set_default(project, "created_at", || Value::String(now.clone()));
+    set_default(project, "modified_at", || Value::String(now));
+    set_default(project, "base_path", || Value::String(base_path));
+    set_default(project, "chapters", || Value::Array(Vec::new()));
+
+    for chapter in project.get_mut("chapters").and_then(Value::as_array_mut).into_iter().flatten() {
+        let Some(chapter) = chapter.as_object_mut() else {
+            continue;
+        };
+        set_default(chapter, "type", || Value::String("chapter".to_string()));
+        set_default(chapter, "pages", || Value::Array(Vec::new()));
+        for page in chapter.get_mut("pages").and_then(Value::as_array_mut).into_iter().flatten() {
+            if let Some(page) = page.as_object_mut() {
+                set_default(page, "page_type", || Value::String("file".to_string()));
+            }
+        }
+    }
 }
 
 // ファイル参照を検証
-fn validate_file_reference(
+pub(crate) fn validate_file_reference(
     page_id: &str,
     file_ref: &SavedFileReference,
     base_path: &Path,
@@ -116,3 +370,259 @@ pub async fn validate_project_files(
 
     Ok(results)
 }
+
+// パスの先頭が old_prefix のフォルダ以下なら new_prefix に置き換える
+// 区切り文字の違い（\ と /）は同一視し、置き換え後は new_prefix の区切り文字に揃える
+fn replace_path_prefix(path: &str, old_prefix: &str, new_prefix: &str) -> Option<String> {
+    let normalize = |p: &str| p.replace('\\', "/");
+    let path_norm = normalize(path);
+    let old_norm = normalize(old_prefix);
+    let old_norm = old_norm.trim_end_matches('/');
+    let rest = path_norm.strip_prefix(old_norm)?;
+    if !(rest.is_empty() || rest.starts_with('/')) {
+        return None;
+    }
+
+    let separator = if new_prefix.contains('\\') && !new_prefix.contains('/') { '\\' } else { '/' };
+    let rest = rest.trim_start_matches('/');
+    let new_prefix = new_prefix.trim_end_matches(['/', '\\']);
+    if rest.is_empty() {
+        return Some(new_prefix.to_string());
+    }
+    Some(format!("{}{}{}", new_prefix, separator, rest.replace('/', &separator.to_string())))
+}
+
+/// 移動したフォルダ以下を参照しているページのパスを一括で書き換えて再検証
+/// ローカルからNASへ移したチャプターなど、old_prefix 以下のファイル参照とチャプターのフォルダを new_prefix 以下に置き換える
+#[tauri::command]
+pub async fn relink_project_base(
+    project: ProjectFile,
+    base_path: String,
+    old_prefix: String,
+    new_prefix: String,
+) -> Result<RelinkBaseResult, String> {
+    if old_prefix.trim().is_empty() || new_prefix.trim().is_empty() {
+        return Err("置き換え前と置き換え後のフォルダを指定してください".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let mut project = project;
+        let base = Path::new(&base_path);
+        let mut matched = 0;
+        let mut missing_page_ids = Vec::new();
+
+        for chapter in &mut project.chapters {
+            if let Some(folder) = chapter
+                .folder_path
+                .as_deref()
+                .and_then(|f| replace_path_prefix(f, &old_prefix, &new_prefix))
+            {
+                chapter.folder_path = Some(folder);
+            }
+
+            for page in &mut chapter.pages {
+                let Some(file_ref) = page.file.as_mut() else {
+                    continue;
+                };
+                let Some(new_path) = replace_path_prefix(&file_ref.absolute_path, &old_prefix, &new_prefix) else {
+                    continue;
+                };
+                matched += 1;
+                file_ref.absolute_path = new_path;
+                // プロジェクトのフォルダ以下に移った場合は相対パスも更新
+                if let Ok(relative) = Path::new(&file_ref.absolute_path).strip_prefix(base) {
+                    file_ref.relative_path = relative.to_string_lossy().replace('\\', "/");
+                }
+
+                let result = validate_file_reference(&page.id, file_ref, base);
+                if result.status == "missing" {
+                    missing_page_ids.push(page.id.clone());
+                }
+            }
+        }
+
+        Ok(RelinkBaseResult {
+            project,
+            matched,
+            fixed: matched - missing_page_ids.len(),
+            still_missing: missing_page_ids.len(),
+            missing_page_ids,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// 内容ハッシュのキャッシュ（パス・更新日時・サイズが同じなら再計算しない）
+// プロジェクトごとに直近の保存で参照したファイルだけを残し、保持するプロジェクト数も制限する
+type ContentHashCache = HashMap<(String, u64, u64), String>;
+static CONTENT_HASHES: Mutex<Option<HashMap<String, ContentHashCache>>> = Mutex::new(None);
+const MAX_CACHED_PROJECTS: usize = 8;
+
+// ハッシュのないファイル参照に内容ハッシュを付与（ファイルが読めなければ付与しない）
+// ファイルの読み込み中は他のプロジェクトの保存を待たせないよう、キャッシュのロックを外しておく
+fn fill_content_hashes(project_path: &str, project: &mut ProjectFile) {
+    let previous = CONTENT_HASHES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_mut()
+        .and_then(|caches| caches.remove(project_path))
+        .unwrap_or_default();
+    let mut current = ContentHashCache::new();
+    for chapter in &mut project.chapters {
+        for file_ref in chapter.pages.iter_mut().filter_map(|p| p.file.as_mut()) {
+            if file_ref.content_hash.is_some() {
+                continue;
+            }
+            let key = (file_ref.absolute_path.clone(), file_ref.modified_time, file_ref.file_size);
+            let hash = match previous.get(&key) {
+                Some(hash) => Some(hash.clone()),
+                None => content_hash(Path::new(&file_ref.absolute_path)).ok(),
+            };
+            if let Some(hash) = hash {
+                current.insert(key, hash.clone());
+                file_ref.content_hash = Some(hash);
+            }
+        }
+    }
+
+    let mut caches = CONTENT_HASHES.lock().unwrap_or_else(|e| e.into_inner());
+    let caches = caches.get_or_insert_with(HashMap::new);
+    if caches.len() >= MAX_CACHED_PROJECTS {
+        caches.clear();
+    }
+    caches.insert(project_path.to_string(), current);
+}
+
+/// 見つからないページのファイルをフォルダ内から探して再リンク候補を返す
+/// 内容ハッシュが一致するファイルを優先し、ハッシュのない古いプロジェクトはファイル名とサイズで照合
+#[tauri::command]
+pub async fn search_missing_files(
+    project: ProjectFile,
+    base_path: String,
+    root_dir: String,
+) -> Result<Vec<RelinkSuggestion>, String> {
+    tokio::task::spawn_blocking(move || {
+        let base = Path::new(&base_path);
+        let missing: Vec<(&str, &SavedFileReference)> = project
+            .chapters
+            .iter()
+            .flat_map(|chapter| chapter.pages.iter())
+            .filter_map(|page| page.file.as_ref().map(|f| (page.id.as_str(), f)))
+            .filter(|(id, f)| validate_file_reference(id, f, base).status == "missing")
+            .collect();
+        if missing.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // サイズが一致するファイルだけハッシュを計算する
+        let sizes: HashSet<u64> = missing.iter().map(|(_, f)| f.file_size).collect();
+        let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+        for entry in walkdir::WalkDir::new(&root_dir).into_iter().filter_map(|e| e.ok()) {
+            let is_image = entry
+                .path()
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| SUPPORTED_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+            if !entry.file_type().is_file() || !is_image {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if sizes.contains(&metadata.len()) {
+                by_size.entry(metadata.len()).or_default().push(entry.into_path());
+            }
+        }
+
+        let mut hashes: HashMap<PathBuf, Option<String>> = HashMap::new();
+        let mut suggestions = Vec::new();
+        for (page_id, file_ref) in missing {
+            let Some(candidates) = by_size.get(&file_ref.file_size) else {
+                continue;
+            };
+
+            let (match_kind, matched): (&str, Vec<String>) = match file_ref.content_hash {
+                Some(ref expected) => {
+                    let matched = candidates
+                        .iter()
+                        .filter(|path| {
+                            hashes
+                                .entry(path.to_path_buf())
+                                .or_insert_with(|| content_hash(path).ok())
+                                .as_deref()
+                                == Some(expected.as_str())
+                        })
+                        .map(|path| path.to_string_lossy().to_string())
+                        .collect();
+                    ("hash", matched)
+                }
+                None => {
+                    let matched = candidates
+                        .iter()
+                        .filter(|path| {
+                            path.file_name()
+                                .and_then(|n| n.to_str())
+                                .is_some_and(|n| n.eq_ignore_ascii_case(&file_ref.file_name))
+                        })
+                        .map(|path| path.to_string_lossy().to_string())
+                        .collect();
+                    ("name", matched)
+                }
+            };
+
+            if let Some(first) = matched.first() {
+                suggestions.push(RelinkSuggestion {
+                    page_id: page_id.to_string(),
+                    original_path: file_ref.absolute_path.clone(),
+                    suggested_path: first.clone(),
+                    match_kind: match_kind.to_string(),
+                    candidates: matched,
+                });
+            }
+        }
+
+        Ok(suggestions)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// プロジェクト横のサムネイルキャッシュのフォルダ名
+const PROJECT_CACHE_DIR: &str = ".cache";
+
+/// プロジェクト横の .cache フォルダをサムネイルキャッシュとして使用（共有NASのチーム作業向け）
+/// 戻り値: 使用するキャッシュフォルダ（無効化した場合は None）
+#[tauri::command]
+pub async fn set_project_cache(
+    window: tauri::WebviewWindow,
+    state: State<'_, AppState>,
+    project_path: String,
+    enabled: bool,
+) -> Result<Option<String>, String> {
+    // セーフモードでは既存のキャッシュを使わない
+    if !enabled || is_safe_mode() {
+        state.with_window(window.label(), |w| w.project_cache = None);
+        return Ok(None);
+    }
+
+    let project_dir = Path::new(&project_path)
+        .parent()
+        .ok_or_else(|| "プロジェクトのフォルダを特定できません".to_string())?
+        .to_path_buf();
+    let root = project_dir.join(PROJECT_CACHE_DIR);
+    let cache_dir = root.join("thumbnails");
+
+    let created = !root.exists();
+    fs::create_dir_all(&cache_dir).map_err(|e| format!("キャッシュフォルダ作成エラー: {}", e))?;
+    if created {
+        mark_hidden(&root);
+    }
+
+    let cache_dir_str = cache_dir.to_string_lossy().to_string();
+    state.with_window(window.label(), |w| {
+        w.project_cache = Some(ProjectCache { project_dir, cache_dir })
+    });
+
+    Ok(Some(cache_dir_str))
+}
@@ -0,0 +1,262 @@
+use std::path::Path;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use serde::Serialize;
+use tauri::State;
+use crate::cache::ThumbnailCache;
+use crate::file_utils::{cloud_placeholder_reason, path_modified_millis};
+use crate::image_utils::open_image;
+use crate::processing::{apply_night_filter, render_print_proof, simulate_color_vision};
+use crate::types::{
+    ColorVisionPreviewOptions, NightPreviewOptions, PreviewImageResult, PrintProofOptions, SpreadPreviewOptions,
+};
+
+// 設定ごとにキャッシュを分けるキーを生成
+fn preview_cache_key<T: Serialize>(path: &str, modified_time: u64, options: &T, kind: &str) -> String {
+    let settings = serde_json::to_string(options).unwrap_or_default();
+    let input = format!("{}:{}:{}:{}", path, modified_time, settings, kind);
+    format!("{:x}", md5::compute(&input))
+}
+
+// 長辺が max_size を超える場合のみ縮小
+fn fit_preview(img: DynamicImage, max_size: u32) -> DynamicImage {
+    let max_size = max_size.max(64);
+    if img.width().max(img.height()) > max_size {
+        img.resize(max_size, max_size, FilterType::Triangle)
+    } else {
+        img
+    }
+}
+
+// キャッシュ済みのプレビューを返すか、render で生成して保存
+fn cached_preview(
+    preview_dir: &Path,
+    path: &str,
+    cache_key: String,
+    render: impl FnOnce(DynamicImage) -> Result<DynamicImage, String>,
+) -> Result<PreviewImageResult, String> {
+    let source = Path::new(path);
+    let cached_path = preview_dir.join(format!("{}.png", cache_key));
+
+    if cached_path.exists() {
+        let (width, height) = image::image_dimensions(&cached_path)
+            .map_err(|e| format!("プレビュー読み込みエラー: {}", e))?;
+        return Ok(PreviewImageResult {
+            cache_path: cached_path.to_string_lossy().to_string(),
+            width,
+            height,
+            status: "cached".to_string(),
+        });
+    }
+
+    if let Some(reason) = cloud_placeholder_reason(source) {
+        return Err(reason);
+    }
+
+    let preview = render(open_image(source)?)?;
+    preview
+        .save_with_format(&cached_path, image::ImageFormat::Png)
+        .map_err(|e| format!("プレビュー保存エラー: {}", e))?;
+
+    Ok(PreviewImageResult {
+        cache_path: cached_path.to_string_lossy().to_string(),
+        width: preview.width(),
+        height: preview.height(),
+        status: "generated".to_string(),
+    })
+}
+
+/// 印刷時の見え方（グレースケール・ドットゲイン・網点）をシミュレーションしたプレビューを生成
+/// 網点は原稿解像度で生成してから縮小するため、モアレも確認できる
+#[tauri::command]
+pub async fn render_print_preview(
+    path: String,
+    options: Option<PrintProofOptions>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<PreviewImageResult, String> {
+    let options = options.unwrap_or_default();
+    let proof_dir = cache.sibling_dir("proofs")?;
+
+    tokio::task::spawn_blocking(move || {
+        let source = Path::new(&path);
+        if !source.exists() {
+            return Err("ファイルが存在しません".to_string());
+        }
+
+        let cache_key = preview_cache_key(&path, path_modified_millis(source), &options, "proof");
+        // 網点の濃度が平均化されるよう面積補間系のフィルタで縮小
+        cached_preview(&proof_dir, &path, cache_key, |img| {
+            let proof = DynamicImage::ImageLuma8(render_print_proof(&img, &options));
+            Ok(fit_preview(proof, options.max_size))
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 夜間の校正用に反転・減光したプレビューを生成（通常のプレビューとは別にキャッシュ）
+#[tauri::command]
+pub async fn render_night_preview(
+    path: String,
+    options: Option<NightPreviewOptions>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<PreviewImageResult, String> {
+    let options = options.unwrap_or_default();
+    let night_dir = cache.sibling_dir("night")?;
+
+    tokio::task::spawn_blocking(move || {
+        let source = Path::new(&path);
+        if !source.exists() {
+            return Err("ファイルが存在しません".to_string());
+        }
+
+        let cache_key = preview_cache_key(&path, path_modified_millis(source), &options, "night");
+        // 縮小してからフィルタを掛ける（画素ごとの処理のため結果は変わらない）
+        cached_preview(&night_dir, &path, cache_key, |img| {
+            let preview = fit_preview(img, options.max_size);
+            Ok(DynamicImage::ImageRgb8(apply_night_filter(&preview, &options)))
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 表紙などを1型・2型・3型色覚での見え方に変換したプレビューを生成
+/// タイトルや価格表示の文字が背景と区別できるかの確認用
+#[tauri::command]
+pub async fn render_color_vision_preview(
+    path: String,
+    options: ColorVisionPreviewOptions,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<PreviewImageResult, String> {
+    let proof_dir = cache.sibling_dir("proofs")?;
+
+    tokio::task::spawn_blocking(move || {
+        let source = Path::new(&path);
+        if !source.exists() {
+            return Err("ファイルが存在しません".to_string());
+        }
+
+        let cache_key = preview_cache_key(&path, path_modified_millis(source), &options, "color-vision");
+        cached_preview(&proof_dir, &path, cache_key, |img| {
+            let preview = fit_preview(img, options.max_size);
+            simulate_color_vision(&preview, &options.kind).map(DynamicImage::ImageRgb8)
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// 見開きの片側を読み込んで縮小（ページがなければ None）
+fn load_spread_page(path: Option<&str>, max_size: u32) -> Result<Option<DynamicImage>, String> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let source = Path::new(path);
+    if !source.exists() {
+        return Err(format!("ファイルが存在しません: {}", path));
+    }
+    if let Some(reason) = cloud_placeholder_reason(source) {
+        return Err(reason);
+    }
+    Ok(Some(fit_preview(open_image(source)?, max_size)))
+}
+
+/// 向かい合う2ページを横に並べた見開きプレビューを生成（巨大な原稿をフロントエンドで合成しなくて済むように）
+/// 片側だけ指定した場合は反対側を空白にする（表紙・最終ページなど）
+#[tauri::command]
+pub async fn compose_spread_preview(
+    left_path: Option<String>,
+    right_path: Option<String>,
+    options: Option<SpreadPreviewOptions>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<PreviewImageResult, String> {
+    let options = options.unwrap_or_default();
+    let spread_dir = cache.sibling_dir("spreads")?;
+
+    tokio::task::spawn_blocking(move || {
+        // 読み順で渡された場合、右綴じなら前のページを右側に置く
+        let (left, right) = if options.reading_order && options.right_to_left {
+            (right_path, left_path)
+        } else {
+            (left_path, right_path)
+        };
+        if left.is_none() && right.is_none() {
+            return Err("ページが指定されていません".to_string());
+        }
+
+        let side_key = |path: &Option<String>| match path {
+            Some(p) => format!("{}:{}", p, path_modified_millis(Path::new(p))),
+            None => String::new(),
+        };
+        let key_path = format!("{}|{}", side_key(&left), side_key(&right));
+        let cache_key = preview_cache_key(&key_path, 0, &options, "spread");
+        let cached_path = spread_dir.join(format!("{}.png", cache_key));
+
+        if cached_path.exists() {
+            let (width, height) = image::image_dimensions(&cached_path)
+                .map_err(|e| format!("プレビュー読み込みエラー: {}", e))?;
+            return Ok(PreviewImageResult {
+                cache_path: cached_path.to_string_lossy().to_string(),
+                width,
+                height,
+                status: "cached".to_string(),
+            });
+        }
+
+        let left_img = load_spread_page(left.as_deref(), options.max_size)?;
+        let right_img = load_spread_page(right.as_deref(), options.max_size)?;
+
+        // 高さを低い方に揃える。ページがない側は反対側と同じ大きさの空白にする
+        let height = [&left_img, &right_img]
+            .iter()
+            .filter_map(|img| img.as_ref().map(|i| i.height()))
+            .min()
+            .unwrap_or(1)
+            .max(1);
+        let fit_height = |img: Option<DynamicImage>| {
+            img.map(|i| {
+                if i.height() == height {
+                    i
+                } else {
+                    i.resize(u32::MAX, height, FilterType::Triangle)
+                }
+            })
+        };
+        let left_img = fit_height(left_img);
+        let right_img = fit_height(right_img);
+        let page_width = |img: &Option<DynamicImage>, other: &Option<DynamicImage>| {
+            img.as_ref().or(other.as_ref()).map(|i| i.width()).unwrap_or(1)
+        };
+        let left_width = page_width(&left_img, &right_img);
+        let right_width = page_width(&right_img, &left_img);
+
+        // ノドの余白はページ幅までに抑え、巨大なキャンバスや幅の桁あふれを防ぐ
+        let gap = options.gap.min(left_width.max(right_width));
+        let canvas_width = left_width
+            .checked_add(gap)
+            .and_then(|w| w.checked_add(right_width))
+            .ok_or_else(|| "見開きプレビューの幅が大きすぎます".to_string())?;
+        let mut canvas = image::RgbImage::from_pixel(canvas_width, height, image::Rgb(options.background));
+        if let Some(ref img) = left_img {
+            image::imageops::overlay(&mut canvas, &img.to_rgb8(), 0, 0);
+        }
+        if let Some(ref img) = right_img {
+            image::imageops::overlay(&mut canvas, &img.to_rgb8(), (left_width + gap) as i64, 0);
+        }
+
+        let preview = fit_preview(DynamicImage::ImageRgb8(canvas), options.max_size);
+        preview
+            .save_with_format(&cached_path, image::ImageFormat::Png)
+            .map_err(|e| format!("プレビュー保存エラー: {}", e))?;
+
+        Ok(PreviewImageResult {
+            cache_path: cached_path.to_string_lossy().to_string(),
+            width: preview.width(),
+            height: preview.height(),
+            status: "generated".to_string(),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
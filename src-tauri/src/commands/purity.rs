@@ -0,0 +1,40 @@
+use std::path::Path;
+use rayon::prelude::*;
+use crate::image_utils::{open_image_budgeted, ImageOperation};
+use crate::processing::analyze_black_purity;
+use crate::types::{BlackPurityAnalysis, BlackPurityOptions};
+
+/// 墨一色の線画に純黒でない黒（リッチブラック・浮いた黒）が含まれていないか一括チェック
+/// 該当ページはTIFF書き出し時の黒点補正（snapBlackThreshold）で純黒に揃えられる
+#[tauri::command]
+pub async fn check_black_purity(
+    paths: Vec<String>,
+    options: Option<BlackPurityOptions>,
+) -> Result<Vec<BlackPurityAnalysis>, String> {
+    let options = options.unwrap_or_default();
+
+    tokio::task::spawn_blocking(move || {
+        paths
+            .par_iter()
+            .map(|path| match open_image_budgeted(Path::new(path), ImageOperation::General) {
+                Ok((img, _permit)) => {
+                    let stats = analyze_black_purity(&img, options.threshold);
+                    BlackPurityAnalysis {
+                        path: path.clone(),
+                        flagged: stats.off_black_ratio > options.max_off_black_ratio,
+                        stats: Some(stats),
+                        error: None,
+                    }
+                }
+                Err(e) => BlackPurityAnalysis {
+                    path: path.clone(),
+                    stats: None,
+                    flagged: false,
+                    error: Some(e),
+                },
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,67 @@
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::GlobalShortcutExt;
+use crate::commands::export::run_export;
+use crate::state::AppState;
+use crate::types::QuickExportResult;
+
+// クイックエクスポートのショートカットを登録し直す（None なら解除のみ）
+pub fn register_quick_export_shortcut(app: &AppHandle, shortcut: Option<&str>) -> Result<(), String> {
+    let manager = app.global_shortcut();
+    manager
+        .unregister_all()
+        .map_err(|e| format!("ショートカットの解除に失敗: {}", e))?;
+
+    if let Some(shortcut) = shortcut.filter(|s| !s.trim().is_empty()) {
+        manager
+            .register(shortcut)
+            .map_err(|e| format!("ショートカットの登録に失敗: {} - {}", shortcut, e))?;
+    }
+    Ok(())
+}
+
+// 最後に操作していたウィンドウの前回エクスポートを再実行し、結果をそのウィンドウに通知
+pub fn trigger_quick_export(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let Some(label) = state.active_window() else {
+        return;
+    };
+    let request = state.with_window(&label, |w| w.last_export.clone());
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = match request {
+            Some(request) => {
+                let output_path = request.output_path.clone();
+                let job_app = app.clone();
+                let outcome = tokio::task::spawn_blocking(move || run_export(&job_app, request))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|r| r);
+                match outcome {
+                    Ok(exported) => QuickExportResult {
+                        success: true,
+                        output_path: Some(output_path),
+                        exported,
+                        error: None,
+                    },
+                    Err(e) => QuickExportResult {
+                        success: false,
+                        output_path: Some(output_path),
+                        exported: 0,
+                        error: Some(e),
+                    },
+                }
+            }
+            None => QuickExportResult {
+                success: false,
+                output_path: None,
+                exported: 0,
+                error: Some("前回のエクスポート設定がありません。一度エクスポートを実行してください".to_string()),
+            },
+        };
+
+        if let Err(e) = app.emit_to(label.as_str(), "quick-export-finished", result) {
+            eprintln!("クイックエクスポート結果の通知に失敗: {}", e);
+        }
+    });
+}
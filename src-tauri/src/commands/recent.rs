@@ -1,17 +1,17 @@
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::Path;
+use crate::app_mode::is_safe_mode;
+use crate::file_utils::get_config_path;
 use crate::types::RecentFile;
 
-// 設定ディレクトリを取得
-fn get_config_path() -> Result<PathBuf, String> {
-    dirs::config_dir()
-        .map(|p| p.join("daidori-manager"))
-        .ok_or_else(|| "設定ディレクトリを特定できません".to_string())
-}
-
 // 最近使ったファイル一覧を取得
 #[tauri::command]
 pub async fn get_recent_files() -> Result<Vec<RecentFile>, String> {
+    // セーフモードでは読み込まない
+    if is_safe_mode() {
+        return Ok(Vec::new());
+    }
+
     let config_path = get_config_path()?;
     let recent_path = config_path.join("recent_files.json");
 
@@ -34,6 +34,11 @@ pub async fn get_recent_files() -> Result<Vec<RecentFile>, String> {
 // 最近使ったファイルに追加
 #[tauri::command]
 pub async fn add_recent_file(path: String, name: String) -> Result<(), String> {
+    // セーフモードでは既存の一覧を読み込んでいないため上書きしない
+    if is_safe_mode() {
+        return Ok(());
+    }
+
     let config_path = get_config_path()?;
     let recent_path = config_path.join("recent_files.json");
 
@@ -0,0 +1,63 @@
+use std::fs;
+use std::path::Path;
+use tauri::State;
+use crate::cache::ThumbnailCache;
+use crate::file_utils::path_modified_millis;
+use crate::report::{render_html_report, render_pdf_report};
+use crate::thumbnail::ensure_thumbnail;
+use crate::types::{PreflightReport, ReportExportOptions};
+
+/// プリフライト結果をHTML/PDFのレポートとして書き出し（編集者・印刷所への提出用）
+#[tauri::command]
+pub async fn export_preflight_report(
+    report: PreflightReport,
+    output_path: String,
+    options: Option<ReportExportOptions>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let cache_dir = cache.cache_dir.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let output = Path::new(&output_path);
+        let format = options
+            .format
+            .clone()
+            .or_else(|| output.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()))
+            .unwrap_or_else(|| "html".to_string());
+
+        let mut report = report;
+        if options.flagged_only {
+            report.pages.retain(|p| !p.issues.is_empty());
+        }
+
+        // サムネイルはキャッシュから取得（生成できないページは省略）
+        let thumbnails: Vec<Option<Vec<u8>>> = report
+            .pages
+            .iter()
+            .map(|page| {
+                if !options.include_thumbnails {
+                    return None;
+                }
+                let path = page.path.as_deref()?;
+                let thumbnail = ensure_thumbnail(&cache_dir, path, path_modified_millis(Path::new(path))).ok()?;
+                fs::read(thumbnail.cache_path).ok()
+            })
+            .collect();
+
+        let generated_at = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+        let data = match format.as_str() {
+            "html" | "htm" => render_html_report(&report, &thumbnails, &generated_at).into_bytes(),
+            "pdf" => render_pdf_report(&report, &thumbnails, &generated_at),
+            other => return Err(format!("サポートされていないレポート形式: {}", other)),
+        };
+
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+        }
+        fs::write(output, data).map_err(|e| format!("ファイル書き込みエラー: {}", e))?;
+        Ok(output_path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
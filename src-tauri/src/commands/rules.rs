@@ -0,0 +1,8 @@
+use crate::page_rules::classify_file;
+use crate::types::{PageClassification, PageTypeRule};
+
+/// ページ種別ルールをファイルに適用した結果を取得（ルール編集時のプレビュー用）
+#[tauri::command]
+pub fn classify_page_files(paths: Vec<String>, rules: Vec<PageTypeRule>) -> Vec<PageClassification> {
+    paths.iter().map(|path| classify_file(path, &rules)).collect()
+}
@@ -0,0 +1,238 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::image_utils::{open_image, save_image};
+use crate::naming::{render_name_template, NameTokens};
+
+// 取り込みファイル名の既定テンプレート（連番3桁）
+const DEFAULT_SCAN_TEMPLATE: &str = "{index:3}";
+
+// フォルダ内で未使用の次の連番のパスを決定
+fn next_scan_path(folder: &Path, template: &str, chapter: &str, start: usize, ext: &str) -> PathBuf {
+    let mut index = start.max(1);
+    loop {
+        let tokens = NameTokens { chapter, index, digits: 3, ..Default::default() };
+        let path = folder.join(format!("{}.{}", render_name_template(template, &tokens), ext));
+        if !path.exists() {
+            return path;
+        }
+        index += 1;
+    }
+}
+
+// WIAの標準スキャンダイアログで1枚取り込み、PNGで保存（キャンセル時は false）
+#[cfg(windows)]
+fn acquire_scan(temp_path: &Path) -> Result<bool, String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    // 取り込み形式がPNG以外（BMP等）のスキャナーもあるため、WIAの変換フィルターでPNGに揃える
+    let script = r#"
+$ErrorActionPreference = 'Stop'
+$png = '{B96B3CAF-0728-11D3-9D7B-0000F81EF32E}'
+$dialog = New-Object -ComObject WIA.CommonDialog
+$img = $dialog.ShowAcquireImage(1, 0, 0, $png, $false, $true, $false)
+if ($img -eq $null) { Write-Output 'CANCELLED'; exit 0 }
+if ($img.FormatID -ne $png) {
+    $process = New-Object -ComObject WIA.ImageProcess
+    $process.Filters.Add($process.FilterInfos.Item('Convert').FilterID)
+    $process.Filters.Item(1).Properties.Item('FormatID').Value = $png
+    $img = $process.Apply($img)
+}
+$img.SaveFile($env:DAIDORI_SCAN_OUTPUT)
+Write-Output 'OK'
+"#;
+
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-STA", "-ExecutionPolicy", "Bypass", "-Command", script])
+        .env("DAIDORI_SCAN_OUTPUT", temp_path)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("スキャナーの呼び出しに失敗しました: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // スキャナー未接続時は WIA が例外を投げる
+        return Err(format!("スキャンに失敗しました: {}", stderr.trim()));
+    }
+    Ok(!stdout.contains("CANCELLED"))
+}
+
+// ImageCaptureCore で最初に見つかったスキャナーから1枚取り込み、PNGで保存
+// macOSには標準の取り込みダイアログがないため、フラットベッドの全面を既定の解像度で読み取る
+#[cfg(target_os = "macos")]
+fn acquire_scan(temp_path: &Path) -> Result<bool, String> {
+    // JXA（osascript の JavaScript）から ICDeviceBrowser / ICScannerDevice を操作する
+    // 定数は ICDeviceTypeMaskScanner = 0x2、ICDeviceLocationTypeMask* = 0xFF00、
+    // ICScannerFunctionalUnitTypeFlatbed = 0、ICScannerTransferModeFileBased = 0、ICScannerPixelDataTypeRGB = 2
+    let script = r#"
+ObjC.import('Foundation');
+ObjC.import('ImageCaptureCore');
+const output = ObjC.unwrap($.NSProcessInfo.processInfo.environment.objectForKey('DAIDORI_SCAN_OUTPUT'));
+let scanner = null;
+let scannedPath = null;
+let failure = null;
+let done = false;
+
+function fail(message, err) {
+    if (!failure) {
+        failure = err && !err.isNil() ? message + ': ' + ObjC.unwrap(err.localizedDescription) : message;
+    }
+    done = true;
+}
+
+ObjC.registerSubclass({
+    name: 'DaidoriScanDelegate',
+    protocols: ['ICDeviceBrowserDelegate', 'ICScannerDeviceDelegate'],
+    methods: {
+        'deviceBrowser:didAddDevice:moreComing:': {
+            types: ['void', ['id', 'id', 'bool']],
+            implementation: function (browser, device, moreComing) {
+                if (scanner || (device.type & 0x2) === 0) { return; }
+                scanner = device;
+                device.delegate = delegate;
+                device.requestOpenSession;
+            },
+        },
+        'deviceBrowser:didRemoveDevice:moreGoing:': {
+            types: ['void', ['id', 'id', 'bool']],
+            implementation: function (browser, device, moreGoing) {},
+        },
+        'didRemoveDevice:': {
+            types: ['void', ['id']],
+            implementation: function (device) { fail('スキャナーが切断されました'); },
+        },
+        'device:didOpenSessionWithError:': {
+            types: ['void', ['id', 'id']],
+            implementation: function (device, err) {
+                if (!err.isNil()) { fail('スキャナーに接続できません', err); return; }
+                device.requestSelectFunctionalUnit(0);
+            },
+        },
+        'device:didCloseSessionWithError:': {
+            types: ['void', ['id', 'id']],
+            implementation: function (device, err) {},
+        },
+        'scannerDevice:didSelectFunctionalUnit:error:': {
+            types: ['void', ['id', 'id', 'id']],
+            implementation: function (device, unit, err) {
+                if (!err.isNil()) { fail('フラットベッドを選択できません', err); return; }
+                const size = unit.physicalSize;
+                unit.measurementUnit = 0;
+                unit.scanArea = $.NSMakeRect(0, 0, size.width, size.height);
+                if (unit.supportedResolutions.containsIndex(600)) { unit.resolution = 600; }
+                unit.pixelDataType = 2;
+                unit.bitDepth = 8;
+                device.transferMode = 0;
+                device.downloadsDirectory = $.NSURL.fileURLWithPath($.NSTemporaryDirectory());
+                device.documentName = 'daidori-scan-' + $.NSProcessInfo.processInfo.processIdentifier;
+                device.documentUTI = 'public.png';
+                device.requestScan;
+            },
+        },
+        'scannerDevice:didScanToURL:': {
+            types: ['void', ['id', 'id']],
+            implementation: function (device, url) { scannedPath = ObjC.unwrap(url.path); },
+        },
+        'scannerDevice:didCompleteScanWithError:': {
+            types: ['void', ['id', 'id']],
+            implementation: function (device, err) {
+                if (!err.isNil()) { fail('スキャンに失敗しました', err); return; }
+                done = true;
+            },
+        },
+    },
+});
+
+const delegate = $.DaidoriScanDelegate.alloc.init;
+const browser = $.ICDeviceBrowser.alloc.init;
+browser.delegate = delegate;
+browser.browsedDeviceTypeMask = 0x2 | 0xFF00;
+browser.start;
+
+// スキャナーの検出は10秒、読み取りは5分まで待つ
+const started = Date.now();
+while (!done) {
+    $.NSRunLoop.currentRunLoop.runUntilDate($.NSDate.dateWithTimeIntervalSinceNow(0.2));
+    if (!scanner && Date.now() - started > 10000) { fail('スキャナーが見つかりません'); }
+    if (Date.now() - started > 300000) { fail('スキャンがタイムアウトしました'); }
+}
+browser.stop;
+if (scanner) { scanner.requestCloseSession; }
+
+if (failure) { throw new Error(failure); }
+if (!scannedPath) { throw new Error('スキャン結果のファイルがありません'); }
+const manager = $.NSFileManager.defaultManager;
+manager.removeItemAtPathError(output, null);
+if (!manager.moveItemAtPathToPathError(scannedPath, output, null)) {
+    throw new Error('スキャン結果を保存できません: ' + scannedPath);
+}
+'OK';
+"#;
+
+    let output = std::process::Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", script])
+        .env("DAIDORI_SCAN_OUTPUT", temp_path)
+        .output()
+        .map_err(|e| format!("スキャナーの呼び出しに失敗しました: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("スキャンに失敗しました: {}", stderr.trim()));
+    }
+    Ok(true)
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn acquire_scan(_temp_path: &Path) -> Result<bool, String> {
+    Err("このOSではスキャナーからの取り込みに対応していません（未対応）。Windows（WIA）とmacOS（ImageCaptureCore）のみ対応しています".to_string())
+}
+
+/// スキャナーから1枚取り込み、チャプターのフォルダに連番で保存
+/// Windowsはスキャンダイアログ（WIA）、macOSは ImageCaptureCore で最初のスキャナーから読み取る（他のOSは未対応のエラー）
+/// name_template は {index} {chapter} が使える（{index} は必須、既定 "{index:3}"）、format は "png" | "jpg" | "tif"
+/// 戻り値: 保存したファイルのパス（キャンセル時は None）
+#[tauri::command]
+pub async fn scan_to_folder(
+    folder_path: String,
+    name_template: Option<String>,
+    start_index: Option<usize>,
+    format: Option<String>,
+    jpg_quality: Option<u8>,
+) -> Result<Option<String>, String> {
+    let ext = match format.as_deref().unwrap_or("png").to_lowercase().as_str() {
+        "png" => "png",
+        "jpg" | "jpeg" => "jpg",
+        "tif" | "tiff" => "tif",
+        other => return Err(format!("サポートされていない保存形式: {}", other)),
+    };
+    let template = name_template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_SCAN_TEMPLATE.to_string());
+    // 連番がないと毎回同じ名前になり、空いている名前が見つからない
+    if !template.contains("{index}") && !template.contains("{index:") {
+        return Err(format!("ファイル名のテンプレートに {{index}} を含めてください: {}", template));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let folder = Path::new(&folder_path);
+        fs::create_dir_all(folder).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+        // 取り込み中の画像をフォルダ監視に拾われないよう、一時ファイルに保存してから変換する
+        let temp_path = std::env::temp_dir().join(format!("daidori-scan-{}.png", std::process::id()));
+        let _ = fs::remove_file(&temp_path);
+        if !acquire_scan(&temp_path)? {
+            return Ok(None);
+        }
+
+        let chapter = folder.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let output = next_scan_path(folder, &template, chapter, start_index.unwrap_or(1), ext);
+        let result = open_image(&temp_path).and_then(|img| save_image(&img, &output, jpg_quality.unwrap_or(95)));
+        let _ = fs::remove_file(&temp_path);
+        result?;
+
+        Ok(Some(output.to_string_lossy().to_string()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+use rayon::prelude::*;
+use crate::image_utils::open_image;
+use crate::processing::detect_screen_lpi;
+use crate::types::{ScreenToneAnalysis, ScreenToneOptions};
+
+// 誤検出を除くため、線数違いと判定するのに必要なブロック数
+const MIN_MISMATCH_BLOCKS: usize = 2;
+
+fn analyze_page(path: &str, options: &ScreenToneOptions) -> Result<ScreenToneAnalysis, String> {
+    let img = open_image(Path::new(path))?;
+    let mut lpis = detect_screen_lpi(&img.to_luma8(), options.dpi);
+    lpis.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let target = options.target_lpi.max(1.0);
+    let mismatched = lpis
+        .iter()
+        .filter(|&&lpi| ((lpi - target) / target).abs() > options.tolerance)
+        .count();
+
+    Ok(ScreenToneAnalysis {
+        path: path.to_string(),
+        estimated_lpi: lpis.get(lpis.len() / 2).copied(),
+        min_lpi: lpis.first().copied(),
+        max_lpi: lpis.last().copied(),
+        toned_blocks: lpis.len(),
+        mismatch: mismatched >= MIN_MISMATCH_BLOCKS,
+        error: None,
+    })
+}
+
+/// ページ内のスクリーントーンの線数を推定し、印刷線数と合わないページを検出
+/// （例: 85線印刷に60線トーン → 仕上がりでモアレになる）
+#[tauri::command]
+pub async fn analyze_screen_tones(
+    paths: Vec<String>,
+    options: Option<ScreenToneOptions>,
+) -> Result<Vec<ScreenToneAnalysis>, String> {
+    let options = options.unwrap_or_default();
+
+    tokio::task::spawn_blocking(move || {
+        paths
+            .par_iter()
+            .map(|path| {
+                analyze_page(path, &options).unwrap_or_else(|e| ScreenToneAnalysis {
+                    path: path.clone(),
+                    estimated_lpi: None,
+                    min_lpi: None,
+                    max_lpi: None,
+                    toned_blocks: 0,
+                    mismatch: false,
+                    error: Some(e),
+                })
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
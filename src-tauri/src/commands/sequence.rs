@@ -0,0 +1,14 @@
+use crate::sequence::{apply_sequence_move, compute_sequence};
+use crate::types::{SequenceInput, SequenceMove, SequenceResult, SequencedPage};
+
+/// 台割の並びからノンブル・左右・出力名を計算
+#[tauri::command]
+pub fn get_page_sequence(input: SequenceInput) -> Result<Vec<SequencedPage>, String> {
+    compute_sequence(&input)
+}
+
+/// ドラッグ＆ドロップの移動を適用し、並びと番号を再計算
+#[tauri::command]
+pub fn move_in_sequence(input: SequenceInput, movement: SequenceMove) -> Result<SequenceResult, String> {
+    apply_sequence_move(&input, &movement)
+}
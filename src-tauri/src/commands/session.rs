@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::app_mode::is_safe_mode;
+use crate::file_utils::get_config_path;
+use crate::types::SessionState;
+
+fn session_path() -> Result<PathBuf, String> {
+    Ok(get_config_path()?.join("session.json"))
+}
+
+/// 前回終了時のセッションを取得（なければ None）
+#[tauri::command]
+pub async fn get_last_session() -> Result<Option<SessionState>, String> {
+    if is_safe_mode() {
+        return Ok(None);
+    }
+
+    let path = session_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| format!("読み込みエラー: {}", e))?;
+    let Ok(mut session) = serde_json::from_str::<SessionState>(&content) else {
+        eprintln!("セッションファイルが壊れています: {}", path.display());
+        return Ok(None);
+    };
+
+    // 移動・削除されたプロジェクトはパスのみ破棄（未保存の内容は残す）
+    if session.project_path.as_deref().is_some_and(|p| !Path::new(p).exists()) {
+        session.project_path = None;
+    }
+
+    Ok(Some(session))
+}
+
+/// 現在のセッションを保存（プロジェクト未保存でも再起動時に復元できるように）
+#[tauri::command]
+pub async fn save_session(mut session: SessionState) -> Result<(), String> {
+    // セーフモードでは前回のセッションを残しておく
+    if is_safe_mode() {
+        return Ok(());
+    }
+
+    let config_path = get_config_path()?;
+    fs::create_dir_all(&config_path).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+    session.saved_at = chrono::Utc::now().to_rfc3339();
+
+    let json = serde_json::to_string(&session).map_err(|e| format!("JSONシリアライズエラー: {}", e))?;
+
+    // 書き込み途中で終了しても前回のセッションが壊れないよう一時ファイル経由で置き換え
+    let path = session_path()?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json).map_err(|e| format!("ファイル書き込みエラー: {}", e))?;
+    fs::rename(&temp_path, &path).map_err(|e| format!("ファイル書き込みエラー: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use tauri::AppHandle;
+use crate::api_server::apply_api_server_settings;
+use crate::color::set_color_management;
+use crate::commands::quick_export::register_quick_export_shortcut;
+use crate::image_utils::set_image_limits;
+use crate::memory_budget::set_memory_budget_mb;
+use crate::settings::{load_settings, save_settings};
+use crate::types::AppSettings;
+
+/// アプリ設定を取得
+#[tauri::command]
+pub async fn get_app_settings() -> Result<AppSettings, String> {
+    Ok(load_settings())
+}
+
+/// アプリ設定を保存し、ショートカット・ローカルAPI等を反映
+/// 設定は先に保存し、ショートカットの登録やポートの待ち受けに失敗しても他の変更は失わない
+#[tauri::command]
+pub async fn save_app_settings(app_handle: AppHandle, mut settings: AppSettings) -> Result<(), String> {
+    // ローカルAPIのトークンは必須のため、空で保存されたら生成済みのものを残す
+    if settings.api_token.as_deref().is_none_or(str::is_empty) {
+        settings.api_token = load_settings().api_token;
+    }
+    // 0時間だと起動時の掃除で処理中の一時ファイルまで削除してしまう
+    if settings.cleanup_max_age_hours == 0 {
+        return Err("一時ファイルを削除するまでの時間は1時間以上にしてください".to_string());
+    }
+    save_settings(&settings)?;
+    set_memory_budget_mb(settings.decode_memory_budget_mb);
+    set_image_limits(settings.image_limits, settings.image_limit_overrides.clone());
+    set_color_management(settings.color_management);
+
+    // 反映に失敗した項目はまとめて報告（設定は保存済み）
+    let errors: Vec<String> = [
+        register_quick_export_shortcut(&app_handle, settings.quick_export_shortcut.as_deref()),
+        apply_api_server_settings(&app_handle, &settings),
+    ]
+    .into_iter()
+    .filter_map(Result::err)
+    .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("設定は保存しましたが、反映に失敗しました: {}", errors.join(" / ")))
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::{AppHandle, Manager, State};
+use crate::cache::ThumbnailCache;
+use crate::constants::SUPPORTED_EXTENSIONS;
+use crate::file_utils::{is_cloud_placeholder, path_modified_millis};
+use crate::image_utils::read_image_dimensions;
+use crate::jobs::JobRegistry;
+use crate::processing::{hash_distance, perceptual_hash};
+use crate::thumbnail::ensure_thumbnail;
+use crate::types::{
+    DuplicatePagePair, SimilarFileMatch, SimilarityIndex, SimilarityIndexEntry, SimilarityIndexResult,
+};
+
+// 重複とみなす既定のハッシュ差（64bit中）
+const DEFAULT_DUPLICATE_DISTANCE: u32 = 4;
+// 類似ファイル検索の既定のハッシュ差
+const DEFAULT_SIMILAR_DISTANCE: u32 = 10;
+// 類似ファイル検索の既定の件数
+const DEFAULT_SIMILAR_LIMIT: usize = 20;
+
+// サムネイルキャッシュ経由で知覚ハッシュを計算（PSD等の再デコードを避ける）
+fn hash_file(cache_dir: &Path, path: &str) -> Result<u64, String> {
+    let modified_time = path_modified_millis(Path::new(path));
+    let thumb = ensure_thumbnail(cache_dir, path, modified_time)?;
+    let img = image::open(&thumb.cache_path).map_err(|e| format!("サムネイル読み込みエラー: {}", e))?;
+    Ok(perceptual_hash(&img))
+}
+
+/// 連続するページで同一・ほぼ同一の画像を検出（リネーム時の同じページの二重コピーなど）
+/// pages: ページ順のソースパス（白紙などファイルのないページは None）
+#[tauri::command]
+pub async fn detect_duplicate_pages(
+    pages: Vec<Option<String>>,
+    max_distance: Option<u32>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<Vec<DuplicatePagePair>, String> {
+    let max_distance = max_distance.unwrap_or(DEFAULT_DUPLICATE_DISTANCE);
+    let cache_dir = cache.cache_dir.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let hashes: Vec<Option<u64>> = pages
+            .par_iter()
+            .map(|page| {
+                page.as_deref().and_then(|path| match hash_file(&cache_dir, path) {
+                    Ok(hash) => Some(hash),
+                    Err(e) => {
+                        eprintln!("ハッシュ計算失敗: {} - {}", path, e);
+                        None
+                    }
+                })
+            })
+            .collect();
+
+        let pairs = (1..pages.len())
+            .filter_map(|i| {
+                let (a, b) = (hashes[i - 1]?, hashes[i]?);
+                let distance = hash_distance(a, b);
+                (distance <= max_distance).then(|| DuplicatePagePair {
+                    first_index: i - 1,
+                    second_index: i,
+                    first_path: pages[i - 1].clone().unwrap_or_default(),
+                    second_path: pages[i].clone().unwrap_or_default(),
+                    distance,
+                })
+            })
+            .collect();
+
+        Ok(pairs)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn index_path(cache: &ThumbnailCache) -> Result<PathBuf, String> {
+    Ok(cache.sibling_dir("similarity")?.join("index.json"))
+}
+
+fn load_index(path: &Path) -> SimilarityIndex {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// フォルダ以下の対応画像を再帰的に列挙（クラウド上のみのファイルは除外）
+fn collect_image_files(folders: &[String]) -> Vec<(String, u64)> {
+    let mut files = Vec::new();
+    for folder in folders {
+        for entry in walkdir::WalkDir::new(folder).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let ext = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("")
+                .to_lowercase();
+            if !SUPPORTED_EXTENSIONS.contains(&ext.as_str()) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if is_cloud_placeholder(&metadata) {
+                continue;
+            }
+            files.push((path.to_string_lossy().to_string(), path_modified_millis(path)));
+        }
+    }
+    files
+}
+
+/// 原稿フォルダの知覚ハッシュインデックスを構築（更新のないファイルは前回の結果を再利用）
+#[tauri::command]
+pub async fn build_similarity_index(
+    app_handle: AppHandle,
+    folders: Vec<String>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<SimilarityIndexResult, String> {
+    let cache_dir = cache.cache_dir.clone();
+    let index_file = index_path(&cache)?;
+
+    tokio::task::spawn_blocking(move || {
+        let previous: HashMap<String, SimilarityIndexEntry> = load_index(&index_file)
+            .entries
+            .into_iter()
+            .map(|entry| (entry.path.clone(), entry))
+            .collect();
+
+        let files = collect_image_files(&folders);
+
+        // ウィンドウを閉じても継続し、トレイから一時停止・キャンセルできるようジョブとして登録
+        let job = app_handle
+            .state::<JobRegistry>()
+            .start(&app_handle, "index", "類似画像インデックスの作成", files.len());
+        let done = AtomicUsize::new(0);
+
+        let hashed: Vec<Result<(SimilarityIndexEntry, bool), String>> = files
+            .par_iter()
+            .filter(|_| job.wait_if_paused())
+            .map(|(path, modified_time)| {
+                job.set_progress(done.fetch_add(1, Ordering::Relaxed) + 1);
+                if let Some(entry) = previous.get(path).filter(|e| e.modified_time == *modified_time) {
+                    return Ok((entry.clone(), true));
+                }
+                let (width, height) = read_image_dimensions(Path::new(path)).map_err(|_| path.clone())?;
+                let hash = hash_file(&cache_dir, path).map_err(|_| path.clone())?;
+                Ok((
+                    SimilarityIndexEntry {
+                        path: path.clone(),
+                        modified_time: *modified_time,
+                        hash,
+                        width,
+                        height,
+                    },
+                    false,
+                ))
+            })
+            .collect();
+
+        let cancelled = job.is_cancelled();
+        job.finish(Ok(()));
+        if cancelled {
+            return Err("インデックスの作成がキャンセルされました".to_string());
+        }
+
+        let mut entries = Vec::new();
+        let mut reused_files = 0;
+        let mut failed_files = Vec::new();
+        for result in hashed {
+            match result {
+                Ok((entry, reused)) => {
+                    if reused {
+                        reused_files += 1;
+                    }
+                    entries.push(entry);
+                }
+                Err(path) => failed_files.push(path),
+            }
+        }
+
+        let index = SimilarityIndex { folders, entries };
+        let json = serde_json::to_string(&index).map_err(|e| format!("JSON変換に失敗: {}", e))?;
+        fs::write(&index_file, json).map_err(|e| format!("インデックス保存エラー: {}", e))?;
+
+        Ok(SimilarityIndexResult {
+            indexed_files: index.entries.len(),
+            reused_files,
+            failed_files,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// インデックスから指定画像に似たファイルを検索（統合JPGの元PSD、Web用縮小版の高解像度原稿など）
+#[tauri::command]
+pub async fn find_similar_files(
+    path: String,
+    max_distance: Option<u32>,
+    limit: Option<usize>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<Vec<SimilarFileMatch>, String> {
+    let max_distance = max_distance.unwrap_or(DEFAULT_SIMILAR_DISTANCE);
+    let limit = limit.unwrap_or(DEFAULT_SIMILAR_LIMIT);
+    let cache_dir = cache.cache_dir.clone();
+    let index_file = index_path(&cache)?;
+
+    tokio::task::spawn_blocking(move || {
+        let index = load_index(&index_file);
+        if index.entries.is_empty() {
+            return Err("類似画像インデックスがありません。先にインデックスを作成してください".to_string());
+        }
+
+        let query = hash_file(&cache_dir, &path)?;
+        let mut matches: Vec<SimilarFileMatch> = index
+            .entries
+            .iter()
+            .filter(|entry| entry.path != path)
+            .map(|entry| (entry, hash_distance(query, entry.hash)))
+            .filter(|(_, distance)| *distance <= max_distance)
+            .map(|(entry, distance)| SimilarFileMatch {
+                path: entry.path.clone(),
+                distance,
+                width: entry.width,
+                height: entry.height,
+            })
+            .collect();
+
+        // 差が小さい順、同程度なら高解像度を優先
+        matches.sort_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then((b.width as u64 * b.height as u64).cmp(&(a.width as u64 * a.height as u64)))
+        });
+        matches.truncate(limit);
+
+        Ok(matches)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,72 @@
+use std::path::Path;
+use rayon::prelude::*;
+use crate::image_utils::read_image_dimensions;
+use crate::types::SpreadAnalysis;
+
+// 単ページの縦横比が求められない場合の基準（B5/A5 ≒ 1:1.42）
+const DEFAULT_PAGE_ASPECT: f64 = 182.0 / 257.0;
+
+// 既定の許容誤差（幅の倍率 2.0 に対する割合）
+const DEFAULT_SPREAD_TOLERANCE: f64 = 0.12;
+
+// 縦長の原稿の縦横比の中央値を単ページの基準とする
+fn single_page_aspect(dimensions: &[Option<(u32, u32)>]) -> f64 {
+    let mut aspects: Vec<f64> = dimensions
+        .iter()
+        .flatten()
+        .filter(|(w, h)| *h > 0 && w < h)
+        .map(|(w, h)| *w as f64 / *h as f64)
+        .collect();
+    if aspects.is_empty() {
+        return DEFAULT_PAGE_ASPECT;
+    }
+    aspects.sort_by(|a, b| a.total_cmp(b));
+    aspects[aspects.len() / 2]
+}
+
+/// 見開き原稿（単ページの約2倍の幅）を判定（フォルダ読み込み時に使用）
+/// 単ページの縦横比は同時に渡した縦長の原稿から求め、tolerance は倍率 2.0 に対する許容誤差（既定 0.12）
+#[tauri::command]
+pub async fn analyze_spreads(paths: Vec<String>, tolerance: Option<f64>) -> Result<Vec<SpreadAnalysis>, String> {
+    let tolerance = tolerance.unwrap_or(DEFAULT_SPREAD_TOLERANCE).clamp(0.0, 0.5);
+
+    tokio::task::spawn_blocking(move || {
+        let dimensions: Vec<Result<(u32, u32), String>> = paths
+            .par_iter()
+            .map(|path| read_image_dimensions(Path::new(path)))
+            .collect();
+        let page_aspect = single_page_aspect(&dimensions.iter().map(|d| d.as_ref().ok().copied()).collect::<Vec<_>>());
+
+        paths
+            .into_iter()
+            .zip(dimensions)
+            .map(|(path, dims)| match dims {
+                Ok((width, height)) => {
+                    let width_ratio = if height > 0 {
+                        width as f64 / height as f64 / page_aspect
+                    } else {
+                        0.0
+                    };
+                    SpreadAnalysis {
+                        path,
+                        width,
+                        height,
+                        is_spread: (width_ratio - 2.0).abs() <= 2.0 * tolerance,
+                        width_ratio,
+                        error: None,
+                    }
+                }
+                Err(e) => SpreadAnalysis {
+                    path,
+                    width: 0,
+                    height: 0,
+                    is_spread: false,
+                    width_ratio: 0.0,
+                    error: Some(e),
+                },
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,198 @@
+use std::fs;
+use std::path::Path;
+use image::imageops::FilterType;
+use image::{DynamicImage, RgbaImage};
+use rayon::prelude::*;
+use crate::image_utils::{open_image, save_image};
+use crate::types::{StorefrontSampleOptions, StorefrontSampleResult};
+
+// 販売サイトごとの既定値
+struct StorefrontPreset {
+    max_size: u32,
+    max_pages: usize,
+    jpg_quality: u8,
+    preview_height: u32,
+}
+
+fn storefront_preset(name: &str) -> Result<StorefrontPreset, String> {
+    match name {
+        // BOOTH: 商品画像は最大10枚（表紙 + 9ページ）
+        "booth" => Ok(StorefrontPreset {
+            max_size: 1200,
+            max_pages: 9,
+            jpg_quality: 85,
+            preview_height: 800,
+        }),
+        // メロンブックス: サンプルは数ページ分を小さめに
+        "melonbooks" => Ok(StorefrontPreset {
+            max_size: 1000,
+            max_pages: 8,
+            jpg_quality: 85,
+            preview_height: 700,
+        }),
+        "custom" => Ok(StorefrontPreset {
+            max_size: 1200,
+            max_pages: 10,
+            jpg_quality: 85,
+            preview_height: 800,
+        }),
+        other => Err(format!("不明なプリセット: {}", other)),
+    }
+}
+
+// 透かし画像の不透明度を下げる
+fn fade_watermark(watermark: &DynamicImage, width: u32, opacity: f32) -> RgbaImage {
+    let scaled = watermark.resize(width.max(1), u32::MAX, FilterType::Triangle);
+    let mut rgba = scaled.to_rgba8();
+    let opacity = opacity.clamp(0.0, 1.0);
+    for pixel in rgba.pixels_mut() {
+        pixel.0[3] = (pixel.0[3] as f32 * opacity).round() as u8;
+    }
+    rgba
+}
+
+// サンプル画像に透かしを合成
+fn apply_watermark(img: &DynamicImage, watermark: &DynamicImage, options: &StorefrontSampleOptions) -> DynamicImage {
+    let mut canvas = img.to_rgba8();
+    let (width, height) = canvas.dimensions();
+
+    match options.watermark_position.as_str() {
+        "tile" => {
+            let mark = fade_watermark(watermark, width / 4, options.watermark_opacity);
+            let (step_x, step_y) = (mark.width() * 3 / 2, mark.height().max(1) * 3);
+            // 行ごとに半分ずらして斜めに並べる
+            for (row, y) in (0..height).step_by(step_y.max(1) as usize).enumerate() {
+                let offset = if row % 2 == 1 { step_x as i64 / 2 } else { 0 };
+                for x in (0..width as i64 + step_x as i64).step_by(step_x.max(1) as usize) {
+                    image::imageops::overlay(&mut canvas, &mark, x - offset, y as i64);
+                }
+            }
+        }
+        "bottomRight" => {
+            let mark = fade_watermark(watermark, width / 4, options.watermark_opacity);
+            let margin = (width / 40) as i64;
+            let x = width as i64 - mark.width() as i64 - margin;
+            let y = height as i64 - mark.height() as i64 - margin;
+            image::imageops::overlay(&mut canvas, &mark, x, y);
+        }
+        _ => {
+            let mark = fade_watermark(watermark, width * 3 / 5, options.watermark_opacity);
+            let x = (width as i64 - mark.width() as i64) / 2;
+            let y = (height as i64 - mark.height() as i64) / 2;
+            image::imageops::overlay(&mut canvas, &mark, x, y);
+        }
+    }
+
+    DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(canvas).to_rgb8())
+}
+
+// サンプル画像を同じ高さに揃えて横一列に並べる
+fn compose_preview(images: &[DynamicImage], height: u32, right_to_left: bool) -> DynamicImage {
+    let gap = (height / 50).max(4);
+    let resized: Vec<DynamicImage> = images
+        .iter()
+        .map(|img| img.resize(u32::MAX, height, FilterType::Triangle))
+        .collect();
+    let width = resized.iter().map(|img| img.width()).sum::<u32>() + gap * (resized.len() as u32 + 1);
+
+    let mut canvas = image::RgbImage::from_pixel(width, height + gap * 2, image::Rgb([255, 255, 255]));
+    let mut x = gap;
+    let ordered: Vec<&DynamicImage> = if right_to_left {
+        resized.iter().rev().collect()
+    } else {
+        resized.iter().collect()
+    };
+    for img in ordered {
+        image::imageops::overlay(&mut canvas, &img.to_rgb8(), x as i64, gap as i64);
+        x += img.width() + gap;
+    }
+    DynamicImage::ImageRgb8(canvas)
+}
+
+/// 販売サイト（BOOTH・メロンブックス等）向けのサンプル画像一式を生成
+/// 表紙と選択したページを縮小・透かし入れして連番で保存し、横に並べた一覧画像も作成する
+#[tauri::command]
+pub async fn generate_storefront_samples(
+    cover_path: Option<String>,
+    page_paths: Vec<String>,
+    output_dir: String,
+    options: Option<StorefrontSampleOptions>,
+) -> Result<StorefrontSampleResult, String> {
+    let options = options.unwrap_or_default();
+    let preset = storefront_preset(&options.preset)?;
+    let max_size = options.max_size.unwrap_or(preset.max_size).max(64);
+    let max_pages = options.max_pages.unwrap_or(preset.max_pages);
+    let jpg_quality = options.jpg_quality.unwrap_or(preset.jpg_quality);
+    let preview_height = options.preview_height.unwrap_or(preset.preview_height).max(64);
+
+    if cover_path.is_none() && page_paths.is_empty() {
+        return Err("サンプルにするページがありません".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let out_dir = Path::new(&output_dir);
+        fs::create_dir_all(out_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+        let watermark = match options.watermark_path {
+            Some(ref path) => Some(open_image(Path::new(path)).map_err(|e| format!("透かし画像の読み込みに失敗: {}", e))?),
+            None => None,
+        };
+
+        // 表紙 → 選択ページの順に、出力名を付けて並べる
+        let mut sources: Vec<(String, String)> = Vec::new();
+        if let Some(ref cover) = cover_path {
+            sources.push((cover.clone(), "cover.jpg".to_string()));
+        }
+        for (i, page) in page_paths.iter().take(max_pages).enumerate() {
+            sources.push((page.clone(), format!("sample_{:02}.jpg", i + 1)));
+        }
+
+        let rendered: Vec<Result<(String, DynamicImage), String>> = sources
+            .par_iter()
+            .map(|(source, name)| {
+                let img = open_image(Path::new(source)).map_err(|_| source.clone())?;
+                let img = if img.width().max(img.height()) > max_size {
+                    img.resize(max_size, max_size, FilterType::Lanczos3)
+                } else {
+                    img
+                };
+                let img = match watermark {
+                    Some(ref mark) => apply_watermark(&img, mark, &options),
+                    None => DynamicImage::ImageRgb8(img.to_rgb8()),
+                };
+                let output = out_dir.join(name);
+                save_image(&img, &output, jpg_quality).map_err(|_| source.clone())?;
+                Ok((output.to_string_lossy().to_string(), img))
+            })
+            .collect();
+
+        let mut files = Vec::new();
+        let mut images = Vec::new();
+        let mut failed_pages = Vec::new();
+        for result in rendered {
+            match result {
+                Ok((file, img)) => {
+                    files.push(file);
+                    images.push(img);
+                }
+                Err(source) => failed_pages.push(source),
+            }
+        }
+        if images.is_empty() {
+            return Err("サンプル画像を作成できませんでした".to_string());
+        }
+
+        let preview = compose_preview(&images, preview_height, options.right_to_left);
+        let preview_path = out_dir.join("preview.jpg");
+        save_image(&preview, &preview_path, jpg_quality)?;
+
+        Ok(StorefrontSampleResult {
+            output_dir,
+            files,
+            preview_path: preview_path.to_string_lossy().to_string(),
+            failed_pages,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,125 @@
+use std::fs;
+use std::path::Path;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use rayon::prelude::*;
+use tauri::State;
+use crate::cache::ThumbnailCache;
+use crate::file_utils::path_modified_millis;
+use crate::image_utils::{image_limits, save_image, validate_dimensions_for, ImageOperation};
+use crate::thumbnail::ensure_thumbnail;
+use crate::types::{ThumbnailStripOptions, ThumbnailStripResult};
+
+// セルの縦横比（B5/A5 ≒ 1:1.42）
+const CELL_ASPECT: f64 = 1.42;
+
+// ページのサムネイルをキャッシュ経由で読み込み、セルに収まるよう縮小
+fn load_cell_image(cache_dir: &Path, source_path: &str, cell_w: u32, cell_h: u32) -> Option<DynamicImage> {
+    let modified_time = path_modified_millis(Path::new(source_path));
+    let thumb = ensure_thumbnail(cache_dir, source_path, modified_time).ok()?;
+    let img = image::open(&thumb.cache_path).ok()?;
+    Some(img.resize(cell_w, cell_h, FilterType::Triangle))
+}
+
+/// チャプターのサムネイルを読み順に並べた1枚画像を出力（編集者との共有用）
+/// pages: ページ順のソースパス（白紙などファイルのないページは None）
+#[tauri::command]
+pub async fn export_thumbnail_strip(
+    pages: Vec<Option<String>>,
+    output_path: String,
+    options: Option<ThumbnailStripOptions>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<ThumbnailStripResult, String> {
+    let options = options.unwrap_or_default();
+    let cache_dir = cache.cache_dir.clone();
+
+    if pages.is_empty() {
+        return Err("ページがありません".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let columns = options.columns.clamp(1, pages.len() as u32);
+        let rows = (pages.len() as u32).div_ceil(columns);
+        let gap = options.gap;
+
+        // 画像の最大辺（設定の書き出しの上限）を超える場合はセルを縮小（余白だけで最大辺を超える場合は出力できない）
+        let max_dimension = image_limits(ImageOperation::Export).max_dimension as u64;
+        let mut cell_w = options.cell_width.max(16);
+        let mut cell_h = (cell_w as f64 * CELL_ASPECT).round() as u32;
+        let gaps_h = (rows as u64 + 1) * gap as u64;
+        let total_h = rows as u64 * cell_h as u64 + gaps_h;
+        if total_h > max_dimension {
+            let available = max_dimension
+                .checked_sub(gaps_h)
+                .filter(|&available| available >= rows as u64 * 16)
+                .ok_or_else(|| format!("余白が大きすぎます（{}px）", gap))?;
+            cell_h = (available / rows as u64) as u32;
+            cell_w = (cell_h as f64 / CELL_ASPECT).round() as u32;
+        }
+
+        let width = columns as u64 * cell_w as u64 + (columns as u64 + 1) * gap as u64;
+        let height = rows as u64 * cell_h as u64 + gaps_h;
+        let too_large = || format!("一覧画像が大きすぎます（{}x{}px）。列数・セル幅・余白を減らしてください", width, height);
+        if width > max_dimension || height > max_dimension {
+            return Err(too_large());
+        }
+        let (width, height) = (width as u32, height as u32);
+        validate_dimensions_for(ImageOperation::Export, width, height).map_err(|e| format!("{}: {}", too_large(), e))?;
+
+        let cells: Vec<Option<DynamicImage>> = pages
+            .par_iter()
+            .map(|page| {
+                page.as_deref()
+                    .and_then(|path| load_cell_image(&cache_dir, path, cell_w, cell_h))
+            })
+            .collect();
+
+        let mut canvas = image::RgbImage::from_pixel(width, height, image::Rgb([255, 255, 255]));
+        let frame = image::Rgb([200, 200, 200]);
+
+        for (i, cell) in cells.iter().enumerate() {
+            let row = i as u32 / columns;
+            let mut col = i as u32 % columns;
+            if options.right_to_left {
+                col = columns - 1 - col;
+            }
+            let cell_x = gap + col * (cell_w + gap);
+            let cell_y = gap + row * (cell_h + gap);
+
+            match cell {
+                Some(img) => {
+                    // セル中央に配置
+                    let x = cell_x + (cell_w - img.width().min(cell_w)) / 2;
+                    let y = cell_y + (cell_h - img.height().min(cell_h)) / 2;
+                    image::imageops::overlay(&mut canvas, &img.to_rgb8(), x as i64, y as i64);
+                }
+                None => {
+                    // 白紙・読み込み失敗ページは枠線のみ
+                    for x in cell_x..cell_x + cell_w {
+                        canvas.put_pixel(x, cell_y, frame);
+                        canvas.put_pixel(x, cell_y + cell_h - 1, frame);
+                    }
+                    for y in cell_y..cell_y + cell_h {
+                        canvas.put_pixel(cell_x, y, frame);
+                        canvas.put_pixel(cell_x + cell_w - 1, y, frame);
+                    }
+                }
+            }
+        }
+
+        let output = Path::new(&output_path);
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+        }
+        save_image(&DynamicImage::ImageRgb8(canvas), output, options.jpg_quality)?;
+
+        Ok(ThumbnailStripResult {
+            output_path,
+            width,
+            height,
+            page_count: pages.len(),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -1,43 +1,50 @@
 use std::fs;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use tauri::Manager;
-use crate::types::{TiffConvertConfig, TiffConvertResponse, TiffResultsWrapper};
-
-/// Photoshopのインストールパスを検索
-fn find_photoshop_path() -> Option<String> {
-    let possible_paths = [
-        // Adobe Photoshop 2025
-        r"C:\Program Files\Adobe\Adobe Photoshop 2025\Photoshop.exe",
-        // Adobe Photoshop 2024
-        r"C:\Program Files\Adobe\Adobe Photoshop 2024\Photoshop.exe",
-        // Adobe Photoshop 2023
-        r"C:\Program Files\Adobe\Adobe Photoshop 2023\Photoshop.exe",
-        // Adobe Photoshop CC 2022
-        r"C:\Program Files\Adobe\Adobe Photoshop 2022\Photoshop.exe",
-        // Adobe Photoshop CC 2021
-        r"C:\Program Files\Adobe\Adobe Photoshop 2021\Photoshop.exe",
-        // Adobe Photoshop CC 2020
-        r"C:\Program Files\Adobe\Adobe Photoshop 2020\Photoshop.exe",
-        // Adobe Photoshop CC 2019
-        r"C:\Program Files\Adobe\Adobe Photoshop CC 2019\Photoshop.exe",
-        // Adobe Photoshop CC 2018
-        r"C:\Program Files\Adobe\Adobe Photoshop CC 2018\Photoshop.exe",
-        // Adobe Photoshop CC
-        r"C:\Program Files\Adobe\Adobe Photoshop CC\Photoshop.exe",
-        // 32bit versions
-        r"C:\Program Files (x86)\Adobe\Adobe Photoshop 2025\Photoshop.exe",
-        r"C:\Program Files (x86)\Adobe\Adobe Photoshop 2024\Photoshop.exe",
-        r"C:\Program Files (x86)\Adobe\Adobe Photoshop 2023\Photoshop.exe",
-    ];
-
-    for path in &possible_paths {
-        if Path::new(path).exists() {
-            return Some(path.to_string());
-        }
-    }
-    None
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{Emitter, Manager};
+use crate::audit::record_audit;
+use crate::jobs::{JobHandle, JobRegistry};
+use crate::desktop_notify::{notify_if_background, NotifyAction};
+use crate::types::ExportHistoryEntry;
+use crate::history::{new_history_id, record_history};
+use crate::naming::{render_name_template, NameTokens};
+use crate::state::AppState;
+use crate::types::{
+    DetectedAdobeApp, OutputConflictPolicy, TiffConvertConfig, TiffConvertResponse, TiffConvertResult, TiffConvertStatus, TiffResultsWrapper, TiffStallAction,
+};
+use crate::adobe::{detect_bridge_versions, detect_photoshop_versions, preferred_photoshop_path, resolve_pinned_photoshop};
+
+// TIFF変換スクリプト（tiff_convert.jsx）とのプロトコルバージョン
+// 設定JSON・結果JSONの形式を変えたらJSX側の SCRIPT_PROTOCOL_VERSION と揃えて上げる
+pub const TIFF_SCRIPT_PROTOCOL_VERSION: u32 = 1;
+
+// スクリプト内のジョブIDの行（コピー時に実際のIDに置き換える）
+const JSX_JOB_ID_LINE: &str = "var JOB_ID = \"\";";
+
+// 一時ファイル名の連番
+static TEMP_JOB_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Photoshopのインストールパスを検索（設定で選択したもの、なければレジストリから検出した最新版）
+pub fn find_photoshop_path() -> Option<String> {
+    preferred_photoshop_path()
+}
+
+/// インストール済みのPhotoshopを新しい順に取得（複数バージョンがある環境での選択用）
+#[tauri::command]
+pub async fn list_detected_photoshop_versions() -> Result<Vec<DetectedAdobeApp>, String> {
+    tokio::task::spawn_blocking(detect_photoshop_versions)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// インストール済みのAdobe Bridgeを新しい順に取得
+#[tauri::command]
+pub async fn list_detected_bridge_versions() -> Result<Vec<DetectedAdobeApp>, String> {
+    tokio::task::spawn_blocking(detect_bridge_versions)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// Photoshopがインストールされているかチェック
@@ -46,15 +53,69 @@ pub async fn check_photoshop_installed() -> Result<bool, String> {
     Ok(find_photoshop_path().is_some())
 }
 
+/// プロジェクトで固定したPhotoshopが使えるか確認し、使用する実行ファイルのパスを返す
+#[tauri::command]
+pub async fn validate_pinned_photoshop(pinned: DetectedAdobeApp) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || resolve_pinned_photoshop(&pinned))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
 /// Photoshopを使用してPSDをTIFFに変換
+/// pinned_photoshop 指定時はそのバージョンで変換（見つからなければ変換しない）
 #[tauri::command]
 pub async fn run_photoshop_tiff_convert(
     app_handle: tauri::AppHandle,
+    window: tauri::WebviewWindow,
+    config: TiffConvertConfig,
+    output_dir: String,
+    pinned_photoshop: Option<DetectedAdobeApp>,
+    output_conflict: Option<OutputConflictPolicy>,
+) -> Result<TiffConvertResponse, String> {
+    convert_tiff(&app_handle, window.label(), config, output_dir, pinned_photoshop, output_conflict)
+}
+
+// 呼び出し元ウィンドウを前面に復帰（ウィンドウのない処理では何もしない）
+fn focus_window(app_handle: &tauri::AppHandle, window_label: &str) {
+    if let Some(window) = app_handle.get_webview_window(window_label) {
+        let _ = window.set_focus();
+    }
+}
+
+// TIFF変換の本体（進行状況は window_label のウィンドウに通知）
+// レンダーファームのワーカーからも呼ぶため、ウィンドウがなくても動作する
+// トレイから確認・キャンセルできるようバックグラウンドジョブとして登録（変換中はスリープを抑止）
+pub fn convert_tiff(
+    app_handle: &tauri::AppHandle,
+    window_label: &str,
+    config: TiffConvertConfig,
+    output_dir: String,
+    pinned_photoshop: Option<DetectedAdobeApp>,
+    output_conflict: Option<OutputConflictPolicy>,
+) -> Result<TiffConvertResponse, String> {
+    let job = app_handle
+        .state::<JobRegistry>()
+        .start(app_handle, "tiff-convert", &format!("TIFF変換: {}", output_dir), config.files.len())
+        .with_disk_watch(&[Path::new(&output_dir)]);
+    let result = convert_tiff_with_job(app_handle, &job, window_label, config, output_dir, pinned_photoshop, output_conflict);
+    job.finish(result.as_ref().map(|_| ()).map_err(|e| e.clone()));
+    result
+}
+
+fn convert_tiff_with_job(
+    app_handle: &tauri::AppHandle,
+    job: &JobHandle,
+    window_label: &str,
     config: TiffConvertConfig,
     output_dir: String,
+    pinned_photoshop: Option<DetectedAdobeApp>,
+    output_conflict: Option<OutputConflictPolicy>,
 ) -> Result<TiffConvertResponse, String> {
-    let ps_path = find_photoshop_path()
-        .ok_or_else(|| "Photoshopが見つかりません。Adobe Photoshopをインストールしてください。".to_string())?;
+    let ps_path = match pinned_photoshop {
+        Some(ref pinned) => resolve_pinned_photoshop(pinned)?,
+        None => find_photoshop_path()
+            .ok_or_else(|| "Photoshopが見つかりません。Adobe Photoshopをインストールしてください。".to_string())?,
+    };
 
     // スクリプトパスを取得
     let resource_path = app_handle
@@ -78,32 +139,48 @@ pub async fn run_photoshop_tiff_convert(
         }
     };
 
+    // 一時ファイルはジョブごとに分ける（ファームのワーカーや複数ウィンドウで同時に変換しても衝突しない）
     let temp_dir = std::env::temp_dir();
-    let settings_path = temp_dir.join("daidori_tiff_settings.json");
-    let output_path = temp_dir.join("daidori_tiff_results.json");
+    let job_id = format!("{}_{}", std::process::id(), TEMP_JOB_COUNTER.fetch_add(1, Ordering::Relaxed));
+    let settings_path = temp_dir.join(format!("daidori_tiff_settings_{}.json", job_id));
+    let output_path = temp_dir.join(format!("daidori_tiff_results_{}.json", job_id));
 
     // 既存の結果ファイルを削除
     let _ = fs::remove_file(&output_path);
 
-    // 出力ディレクトリ: 既存の場合は連番で新規作成
+    // 出力ディレクトリ: 既存の場合は指定に従う（既定は連番で新規作成）
+    let mut stale_tiffs = Vec::new();
     let final_output_dir = {
         let base_path = Path::new(&output_dir);
-        if base_path.exists() {
-            let base = output_dir.clone();
-            let mut counter = 1;
-            loop {
-                let candidate = format!("{} ({})", base, counter);
-                if !Path::new(&candidate).exists() {
-                    fs::create_dir_all(&candidate)
-                        .map_err(|e| format!("出力ディレクトリの作成に失敗: {}", e))?;
-                    break candidate;
-                }
-                counter += 1;
-            }
-        } else {
+        if !base_path.exists() {
             fs::create_dir_all(&output_dir)
                 .map_err(|e| format!("出力ディレクトリの作成に失敗: {}", e))?;
             output_dir.clone()
+        } else {
+            match output_conflict.unwrap_or_default() {
+                OutputConflictPolicy::Error => {
+                    return Err(format!("出力フォルダが既に存在します: {}", output_dir));
+                }
+                OutputConflictPolicy::Reuse => output_dir.clone(),
+                OutputConflictPolicy::Clean => {
+                    // 変換に失敗しても既存の納品物が残るよう、削除は変換の成功後に行う
+                    stale_tiffs = list_tiff_files(base_path)?;
+                    output_dir.clone()
+                }
+                OutputConflictPolicy::Suffix => {
+                    let base = output_dir.clone();
+                    let mut counter = 1;
+                    loop {
+                        let candidate = format!("{} ({})", base, counter);
+                        if !Path::new(&candidate).exists() {
+                            fs::create_dir_all(&candidate)
+                                .map_err(|e| format!("出力ディレクトリの作成に失敗: {}", e))?;
+                            break candidate;
+                        }
+                        counter += 1;
+                    }
+                }
+            }
         }
     };
 
@@ -111,6 +188,8 @@ pub async fn run_photoshop_tiff_convert(
 
     // 設定JSONを作成（outputPathを最終出力ディレクトリに書き換え）
     let mut config_with_output = config;
+    config_with_output.protocol_version = TIFF_SCRIPT_PROTOCOL_VERSION;
+    apply_tiff_name_template(&mut config_with_output);
     for file_config in &mut config_with_output.files {
         file_config.output_path = final_output_dir.clone();
     }
@@ -127,32 +206,81 @@ pub async fn run_photoshop_tiff_convert(
         .map_err(|e| format!("設定の書き込みに失敗: {}", e))?;
     drop(settings_file);
 
-    // スクリプトをtempにコピー（日本語パス問題回避）
-    let temp_script = temp_dir.join("daidori_tiff_convert_temp.jsx");
-    fs::copy(&script_path_str, &temp_script)
+    // スクリプトをtempにコピー（日本語パス問題回避）。一時ファイル名の区別にジョブIDを埋め込む
+    let temp_script = temp_dir.join(format!("daidori_tiff_convert_{}.jsx", job_id));
+    let script_source = fs::read_to_string(&script_path_str)
+        .map_err(|e| format!("スクリプトの読み込みに失敗: {}", e))?;
+    if !script_source.contains(JSX_JOB_ID_LINE) {
+        let _ = fs::remove_file(&settings_path);
+        return Err("TIFF変換スクリプトが古いためジョブIDを設定できません。アプリを再インストールしてください".to_string());
+    }
+    let script_source = script_source.replacen(JSX_JOB_ID_LINE, &format!("var JOB_ID = \"{}\";", job_id), 1);
+    fs::write(&temp_script, script_source)
         .map_err(|e| format!("スクリプトのコピーに失敗: {}", e))?;
     let script_to_run = temp_script.to_string_lossy().to_string();
 
     eprintln!("TIFF Convert - Photoshop: {}", ps_path);
     eprintln!("TIFF Convert - Script: {}", script_to_run);
 
+    // 履歴用の設定（出力先は書き換え後）
+    let history_config = serde_json::to_value(&config_with_output).ok();
+    let history_id = new_history_id();
+    let started_at = chrono::Local::now().to_rfc3339();
+    let started = std::time::Instant::now();
+    let record_tiff_history = |exported: usize, report: Option<serde_json::Value>, error: Option<String>| {
+        record_history(&ExportHistoryEntry {
+            id: history_id.clone(),
+            kind: "tiff-convert".to_string(),
+            started_at: started_at.clone(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            output_path: final_output_dir.clone(),
+            status: if error.is_none() { "completed" } else { "failed" }.to_string(),
+            error,
+            exported,
+            operator: None,
+            request: None,
+            tiff_config: history_config.clone(),
+            report,
+            pages: Vec::new(),
+        });
+    };
+
     // Photoshopを起動（非ブロッキング）
-    let _child = Command::new(&ps_path)
+    let mut child = Command::new(&ps_path)
         .arg("-r")
         .arg(&script_to_run)
         .spawn()
         .map_err(|e| format!("Photoshopの起動に失敗: {}", e))?;
 
+    // 以前の変換で残った指示を破棄
+    let app_state = app_handle.state::<AppState>();
+    let label = window_label.to_string();
+    app_state.with_window(&label, |w| w.tiff_stall_action = None);
+
     // 結果をポーリング
     let file_count = config_with_output.files.len().max(1);
     let poll_interval_ms: u64 = 500;
     let initial_timeout_secs: u64 = 600;  // 10分（PS起動 + 最初のファイル）
     let final_timeout_secs: u64 = 120;    // 2分（最後のファイル後）
-    let progress_path = temp_dir.join("daidori_tiff_progress.txt");
+    let initial_stall_secs: u64 = 180;    // 起動後この時間応答がなければ停止とみなす
+    let stall_secs: u64 = 90;             // 処理中にこの時間進捗がなければ停止とみなす
+    let progress_path = temp_dir.join(format!("daidori_tiff_progress_{}.txt", job_id));
     let _ = fs::remove_file(&progress_path);
     let mut last_progress = String::new();
     let mut polls_since_progress: u64 = 0;
     let mut all_done = false;
+    let mut stalled = false;
+    let mut aborted = false;
+
+    let emit_status = |status: &str, progress: &str, polls: u64, process_alive: bool, message: Option<String>| {
+        let _ = app_handle.emit_to(window_label, "tiff-convert-status", TiffConvertStatus {
+            status: status.to_string(),
+            progress: (!progress.is_empty()).then(|| progress.to_string()),
+            seconds_since_progress: polls * poll_interval_ms / 1000,
+            process_alive,
+            message,
+        });
+    };
 
     eprintln!("TIFF Convert - Heartbeat: {}s initial, {} files", initial_timeout_secs, file_count);
 
@@ -167,6 +295,8 @@ pub async fn run_photoshop_tiff_convert(
             }
         }
 
+        let process_alive = matches!(child.try_wait(), Ok(None));
+
         // 進捗ファイルをチェック（"X/N"形式）
         if let Ok(content) = fs::read_to_string(&progress_path) {
             let trimmed = content.trim().to_string();
@@ -174,17 +304,55 @@ pub async fn run_photoshop_tiff_convert(
                 eprintln!("TIFF Convert progress: {}", trimmed);
                 last_progress = trimmed.clone();
                 polls_since_progress = 0;
+                stalled = false;
+                emit_status("converting", &last_progress, 0, process_alive, None);
                 // "X/N"をパースして完了チェック
                 if let Some((current, total)) = trimmed.split_once('/') {
                     if let (Ok(c), Ok(t)) = (current.parse::<u64>(), total.parse::<u64>()) {
                         all_done = c >= t && t > 0;
+                        job.set_progress(c as usize);
                     }
                 }
             }
         }
 
+        // 停止中の変換への指示を確認（トレイからのキャンセルは中断として扱う）
+        let action = app_state.with_window(&label, |w| w.tiff_stall_action.take());
+        match action.or_else(|| job.is_cancelled().then_some(TiffStallAction::Abort)) {
+            Some(TiffStallAction::Abort) => {
+                eprintln!("TIFF Convert aborted by user");
+                if process_alive {
+                    let _ = child.kill();
+                }
+                aborted = true;
+                break;
+            }
+            Some(TiffStallAction::ForceContinue) => {
+                eprintln!("TIFF Convert: continue waiting");
+                polls_since_progress = 0;
+                stalled = false;
+                emit_status("converting", &last_progress, 0, process_alive, None);
+            }
+            None => {}
+        }
+
         polls_since_progress += 1;
 
+        // 進捗がないまま一定時間経過したら停止として通知（Photoshopでダイアログが開いている可能性）
+        let stall_secs_now = if last_progress.is_empty() { initial_stall_secs } else { stall_secs };
+        let stall_polls = stall_secs_now * 1000 / poll_interval_ms;
+        if !stalled && !all_done && polls_since_progress >= stall_polls {
+            stalled = true;
+            let message = if process_alive || last_progress.is_empty() {
+                "Photoshopでダイアログ（カラープロファイルの不一致、フォントの置き換えなど）が開いている可能性があります。\
+                 Photoshopを確認してダイアログを閉じるか、変換を中断してください。"
+            } else {
+                "Photoshopからの応答がありません。Photoshopでダイアログが開いていないか確認するか、変換を中断してください。"
+            };
+            eprintln!("TIFF Convert stalled ({}s since last progress)", polls_since_progress * poll_interval_ms / 1000);
+            emit_status("stalled", &last_progress, polls_since_progress, process_alive, Some(message.to_string()));
+        }
+
         // タイムアウト計算
         let timeout_polls = if all_done {
             (final_timeout_secs * 1000) / poll_interval_ms
@@ -214,23 +382,56 @@ pub async fn run_photoshop_tiff_convert(
 
     let _ = fs::remove_file(&progress_path);
 
+    if aborted {
+        let _ = fs::remove_file(&settings_path);
+        let _ = fs::remove_file(&temp_script);
+        focus_window(app_handle, window_label);
+        let error = "TIFF変換を中断しました".to_string();
+        record_tiff_history(0, None, Some(error.clone()));
+        return Err(error);
+    }
+
     // 結果を読み取り
     if output_path.exists() {
         let results_json = fs::read_to_string(&output_path)
             .map_err(|e| format!("結果の読み取りに失敗: {}", e))?;
 
-        let wrapper: TiffResultsWrapper = serde_json::from_str(&results_json)
-            .map_err(|e| format!("結果のパースに失敗: {}. JSON: {}", e, results_json))?;
+        let wrapper = match parse_tiff_results(&results_json) {
+            Ok(wrapper) => wrapper,
+            Err(error) => {
+                let _ = fs::remove_file(&settings_path);
+                let _ = fs::remove_file(&output_path);
+                let _ = fs::remove_file(&temp_script);
+                focus_window(app_handle, window_label);
+                record_tiff_history(0, None, Some(error.clone()));
+                return Err(error);
+            }
+        };
 
         // 一時ファイルを削除
         let _ = fs::remove_file(&settings_path);
         let _ = fs::remove_file(&output_path);
         let _ = fs::remove_file(&temp_script);
 
-        // ウィンドウを前面に復帰
-        if let Some(window) = app_handle.get_webview_window("main") {
-            let _ = window.set_focus();
+        // 呼び出し元ウィンドウを前面に復帰
+        focus_window(app_handle, window_label);
+
+        let succeeded = wrapper.results.iter().filter(|r| r.success).count();
+        if !stale_tiffs.is_empty() {
+            if succeeded == wrapper.results.len() {
+                remove_stale_tiffs(app_handle, &final_output_dir, stale_tiffs, &wrapper.results);
+            } else {
+                eprintln!("変換に失敗したファイルがあるため、既存のTIFFを残しました: {}", final_output_dir);
+            }
         }
+        record_tiff_history(succeeded, serde_json::to_value(&wrapper.results).ok(), None);
+        notify_if_background(
+            app_handle,
+            "TIFF変換が完了しました",
+            &format!("{}/{}件を変換しました", succeeded, wrapper.results.len()),
+            &final_output_dir,
+            &[NotifyAction::OpenFolder, NotifyAction::ViewReport],
+        );
 
         Ok(TiffConvertResponse {
             results: wrapper.results,
@@ -238,9 +439,117 @@ pub async fn run_photoshop_tiff_convert(
         })
     } else {
         let _ = fs::remove_file(&temp_script);
-        if let Some(window) = app_handle.get_webview_window("main") {
-            let _ = window.set_focus();
+        focus_window(app_handle, window_label);
+        let error = "Photoshopが出力ファイルを生成しませんでした。スクリプトが失敗した可能性があります。".to_string();
+        record_tiff_history(0, None, Some(error.clone()));
+        Err(error)
+    }
+}
+
+// 出力フォルダ直下のTIFF（納品フォルダに置かれた他のファイルは対象外）
+fn list_tiff_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("出力フォルダの読み込みに失敗: {}", e))?;
+    Ok(entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| e.eq_ignore_ascii_case("tif") || e.eq_ignore_ascii_case("tiff"))
+        })
+        .collect())
+}
+
+// 変換前からあったTIFFのうち、今回の出力で上書きされなかったものを削除して監査ログに記録
+fn remove_stale_tiffs(app_handle: &tauri::AppHandle, output_dir: &str, stale: Vec<PathBuf>, results: &[TiffConvertResult]) {
+    let written: Vec<String> = results
+        .iter()
+        .filter_map(|r| r.output_path.as_deref())
+        .filter_map(|p| Path::new(p).file_name())
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .collect();
+    let mut removed = 0;
+    let mut errors = Vec::new();
+    for path in stale {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+        if written.contains(&name) {
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => removed += 1,
+            Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    let error = errors.join(", ");
+    record_audit(
+        app_handle.state::<AppState>().operator(),
+        "tiff-clean",
+        output_dir,
+        format!("既存のTIFF {}件を削除", removed),
+        if errors.is_empty() { Ok(()) } else { Err(&error) },
+    );
+}
+
+// 出力名をテンプレートから決定（テンプレートなしで出力名が空のファイルは元ファイル名）
+pub fn apply_tiff_name_template(config: &mut TiffConvertConfig) {
+    let template = config.name_template.clone();
+    for (i, file) in config.files.iter_mut().enumerate() {
+        let source = Path::new(&file.path);
+        let original = source.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string();
+        let name = if file.output_name.is_empty() { original.clone() } else { file.output_name.clone() };
+        file.output_name = match template {
+            Some(ref template) if !template.is_empty() => render_name_template(template, &NameTokens {
+                name: &name,
+                original: &original,
+                chapter: file.chapter.as_deref().unwrap_or(""),
+                page: file.page_number,
+                index: i + 1,
+                digits: 3,
+            }),
+            _ => name,
+        };
+    }
+}
+
+// 結果JSONを読み取り、スクリプトのバージョンが一致するか確認
+fn parse_tiff_results(results_json: &str) -> Result<TiffResultsWrapper, String> {
+    // 形式が変わっていても先にバージョンだけは読めるよう、まず汎用のJSONとして解析
+    let value: serde_json::Value = serde_json::from_str(results_json)
+        .map_err(|e| format!("結果のパースに失敗: {}. JSON: {}", e, results_json))?;
+    match value.get("protocolVersion").and_then(|v| v.as_u64()) {
+        None => {
+            return Err(format!(
+                "TIFF変換スクリプトが古いため結果を読み取れません（必要なバージョン: {}）。\
+                 アプリを再インストールするか、変更したスクリプトを元に戻してください",
+                TIFF_SCRIPT_PROTOCOL_VERSION
+            ));
+        }
+        Some(version) if version != TIFF_SCRIPT_PROTOCOL_VERSION as u64 => {
+            return Err(format!(
+                "TIFF変換スクリプトのバージョンが一致しません（スクリプト: {}、アプリ: {}）。\
+                 アプリを再インストールするか、変更したスクリプトを元に戻してください",
+                version, TIFF_SCRIPT_PROTOCOL_VERSION
+            ));
         }
-        Err("Photoshopが出力ファイルを生成しませんでした。スクリプトが失敗した可能性があります。".to_string())
+        Some(_) => {}
     }
+
+    let wrapper: TiffResultsWrapper = serde_json::from_value(value)
+        .map_err(|e| format!("結果のパースに失敗: {}. JSON: {}", e, results_json))?;
+    if let Some(ref error) = wrapper.error {
+        return Err(format!("TIFF変換スクリプトでエラー: {}", error));
+    }
+    Ok(wrapper)
+}
+
+/// 停止したTIFF変換を中断するか、そのまま待ち続けるかを指示
+#[tauri::command]
+pub fn resolve_tiff_stall(
+    window: tauri::WebviewWindow,
+    app_state: tauri::State<'_, AppState>,
+    action: TiffStallAction,
+) {
+    app_state.with_window(window.label(), |w| w.tiff_stall_action = Some(action));
 }
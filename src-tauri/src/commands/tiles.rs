@@ -0,0 +1,281 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use image::imageops::FilterType;
+use image::DynamicImage;
+use rayon::prelude::*;
+use tauri::State;
+use crate::cache::ThumbnailCache;
+use crate::file_utils::{cloud_placeholder_reason, path_modified_millis};
+use crate::image_utils::{open_image_budgeted, save_image, ImageOperation};
+use crate::types::{PreviewRegion, TileLevel, TilePyramid};
+
+// タイルの既定サイズ (px)
+const DEFAULT_TILE_SIZE: u32 = 512;
+// タイルのJPEG品質（細線が潰れない程度）
+const TILE_JPG_QUALITY: u8 = 90;
+// タイル構成を記録するファイル（最後に書き込み、存在すれば生成済みとみなす）
+const MANIFEST_FILE: &str = "manifest.json";
+// 生成途中の一時フォルダを生成ごとに分ける連番（同じページを複数ウィンドウで同時に開いても衝突しない）
+static TEMP_DIR_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// ファイルとタイルサイズごとにキャッシュを分けるキーを生成
+fn tile_cache_key(path: &str, modified_time: u64, tile_size: u32) -> String {
+    let input = format!("{}:{}:{}:tiles", path, modified_time, tile_size);
+    format!("{:x}", md5::compute(&input))
+}
+
+// 原寸から1枚のタイルに収まるまで半分ずつ縮小した段階を計算
+fn tile_levels(width: u32, height: u32, tile_size: u32) -> Vec<TileLevel> {
+    let mut levels = Vec::new();
+    let (mut w, mut h) = (width, height);
+    let mut level = 0;
+    loop {
+        levels.push(TileLevel {
+            level,
+            scale: w as f64 / width.max(1) as f64,
+            width: w,
+            height: h,
+            columns: w.div_ceil(tile_size),
+            rows: h.div_ceil(tile_size),
+        });
+        if w <= tile_size && h <= tile_size {
+            break;
+        }
+        w = w.div_ceil(2).max(1);
+        h = h.div_ceil(2).max(1);
+        level += 1;
+    }
+    levels
+}
+
+// 1段階分のタイルを書き出し（タイル単位で並列）
+fn write_level_tiles(img: &DynamicImage, level: &TileLevel, tile_size: u32, level_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(level_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+    let tiles: Vec<(u32, u32)> = (0..level.rows)
+        .flat_map(|y| (0..level.columns).map(move |x| (x, y)))
+        .collect();
+    tiles.par_iter().try_for_each(|&(x, y)| {
+        let left = x * tile_size;
+        let top = y * tile_size;
+        let tile = img.crop_imm(left, top, tile_size.min(level.width - left), tile_size.min(level.height - top));
+        save_image(&tile, &level_dir.join(format!("{}_{}.jpg", x, y)), TILE_JPG_QUALITY)
+    })
+}
+
+// 全段階のタイルを生成（原寸を1回だけデコードし、前の段階から順に縮小）
+fn generate_tiles(source: &Path, tile_dir: &Path, tile_size: u32) -> Result<TilePyramid, String> {
+    let (img, _permit) = open_image_budgeted(source, ImageOperation::General)?;
+    let (width, height) = (img.width(), img.height());
+    let levels = tile_levels(width, height, tile_size);
+
+    let mut current = DynamicImage::ImageRgb8(img.to_rgb8());
+    drop(img);
+    for level in &levels {
+        if current.width() != level.width || current.height() != level.height {
+            current = current.resize_exact(level.width, level.height, FilterType::Triangle);
+        }
+        write_level_tiles(&current, level, tile_size, &tile_dir.join(level.level.to_string()))?;
+    }
+
+    Ok(TilePyramid {
+        tile_dir: String::new(),
+        width,
+        height,
+        tile_size,
+        format: "jpg".to_string(),
+        levels,
+        status: "generated".to_string(),
+    })
+}
+
+// 生成済みのタイル構成を読み込み
+fn read_manifest(tile_dir: &Path) -> Option<TilePyramid> {
+    let data = fs::read_to_string(tile_dir.join(MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+// タイルを生成済みなら読み込み、なければ生成（一時フォルダに書き出してから置き換える）
+fn ensure_tiles(path: &str, tiles_root: &Path, tile_size: u32) -> Result<TilePyramid, String> {
+    let source = Path::new(path);
+    if !source.exists() {
+        return Err("ファイルが存在しません".to_string());
+    }
+
+    let cache_key = tile_cache_key(path, path_modified_millis(source), tile_size);
+    let tile_dir = tiles_root.join(&cache_key);
+    let tile_dir_str = tile_dir.to_string_lossy().to_string();
+
+    if let Some(mut pyramid) = read_manifest(&tile_dir) {
+        pyramid.tile_dir = tile_dir_str;
+        pyramid.status = "cached".to_string();
+        return Ok(pyramid);
+    }
+
+    if let Some(reason) = cloud_placeholder_reason(source) {
+        return Err(reason);
+    }
+
+    // 生成途中のタイルを読まれないよう一時フォルダに書き出してから置き換える
+    let temp_dir: PathBuf = tiles_root.join(format!(
+        "{}.{}_{}.tmp",
+        cache_key,
+        std::process::id(),
+        TEMP_DIR_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = fs::remove_dir_all(&temp_dir);
+    let result = generate_tiles(source, &temp_dir, tile_size).and_then(|pyramid| {
+        let manifest = serde_json::to_string(&pyramid).map_err(|e| e.to_string())?;
+        fs::write(temp_dir.join(MANIFEST_FILE), manifest).map_err(|e| e.to_string())?;
+        Ok(pyramid)
+    });
+    let mut pyramid = match result {
+        Ok(pyramid) => pyramid,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&temp_dir);
+            return Err(e);
+        }
+    };
+
+    let _ = fs::remove_dir_all(&tile_dir);
+    if let Err(e) = fs::rename(&temp_dir, &tile_dir) {
+        let _ = fs::remove_dir_all(&temp_dir);
+        // 別ウィンドウが先に生成した場合はそれを使う
+        if read_manifest(&tile_dir).is_none() {
+            return Err(format!("タイル保存エラー: {}", e));
+        }
+    }
+
+    pyramid.tile_dir = tile_dir_str;
+    Ok(pyramid)
+}
+
+/// 巨大なページを拡大・スクロールで確認するためのタイルを生成（キャッシュ済みなら再デコードしない）
+/// 原寸の level 0 から1枚に収まるまで半分ずつ縮小した段階ごとに tile_size 四方のタイルを作る
+#[tauri::command]
+pub async fn generate_deep_zoom_tiles(
+    path: String,
+    tile_size: Option<u32>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<TilePyramid, String> {
+    let tile_size = tile_size.unwrap_or(DEFAULT_TILE_SIZE).clamp(128, 2048);
+    let tiles_root = cache.sibling_dir("tiles")?;
+
+    tokio::task::spawn_blocking(move || ensure_tiles(&path, &tiles_root, tile_size))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// 縮尺以上の解像度を持つ段階のうち最も小さいもの
+fn level_for_scale(pyramid: &TilePyramid, scale: f64) -> &TileLevel {
+    pyramid
+        .levels
+        .iter()
+        .rev()
+        .find(|level| level.scale + 1e-9 >= scale)
+        .unwrap_or(&pyramid.levels[0])
+}
+
+// 段階内の範囲に重なるタイルを読み込んで1枚に合成
+fn assemble_region(
+    tile_dir: &Path,
+    pyramid: &TilePyramid,
+    level: &TileLevel,
+    (left, top, width, height): (u32, u32, u32, u32),
+) -> Result<DynamicImage, String> {
+    let tile_size = pyramid.tile_size;
+    let mut canvas = image::RgbImage::new(width, height);
+    let level_dir = tile_dir.join(level.level.to_string());
+
+    for row in top / tile_size..(top + height).div_ceil(tile_size).min(level.rows) {
+        for column in left / tile_size..(left + width).div_ceil(tile_size).min(level.columns) {
+            let tile_path = level_dir.join(format!("{}_{}.{}", column, row, pyramid.format));
+            let tile = image::open(&tile_path).map_err(|e| format!("タイル読み込みエラー: {}", e))?;
+            let x = (column * tile_size) as i64 - left as i64;
+            let y = (row * tile_size) as i64 - top as i64;
+            image::imageops::overlay(&mut canvas, &tile.to_rgb8(), x, y);
+        }
+    }
+    Ok(DynamicImage::ImageRgb8(canvas))
+}
+
+/// 原稿の指定範囲を指定の縮尺で切り出したプレビューを取得（写植の確認用）
+/// 範囲 (x, y, width, height) は原寸のピクセルで指定する。原稿のデコードはタイル生成時の1回だけで、
+/// 以降は縮尺に合った段階のタイルから必要な分だけ読み込んで合成し、結果もキャッシュする
+#[tauri::command]
+pub async fn get_preview_region(
+    path: String,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    scale: Option<f64>,
+    tile_size: Option<u32>,
+    cache: State<'_, ThumbnailCache>,
+) -> Result<PreviewRegion, String> {
+    let tile_size = tile_size.unwrap_or(DEFAULT_TILE_SIZE).clamp(128, 2048);
+    let scale = scale.unwrap_or(1.0).clamp(0.01, 1.0);
+    let tiles_root = cache.sibling_dir("tiles")?;
+
+    tokio::task::spawn_blocking(move || {
+        let pyramid = ensure_tiles(&path, &tiles_root, tile_size)?;
+        let tile_dir = PathBuf::from(&pyramid.tile_dir);
+
+        // 範囲を画像内に収める
+        let x = x.min(pyramid.width.saturating_sub(1));
+        let y = y.min(pyramid.height.saturating_sub(1));
+        let region_width = width.clamp(1, pyramid.width - x);
+        let region_height = height.clamp(1, pyramid.height - y);
+
+        let output_width = ((region_width as f64 * scale).round() as u32).max(1);
+        let output_height = ((region_height as f64 * scale).round() as u32).max(1);
+        let level = level_for_scale(&pyramid, scale);
+
+        let region_dir = tile_dir.join("regions");
+        let key = format!(
+            "{:x}",
+            md5::compute(format!("{}:{}:{}:{}:{}", x, y, region_width, region_height, scale))
+        );
+        let cached_path = region_dir.join(format!("{}.png", key));
+        let result = |status: &str| PreviewRegion {
+            cache_path: cached_path.to_string_lossy().to_string(),
+            x,
+            y,
+            region_width,
+            region_height,
+            width: output_width,
+            height: output_height,
+            scale,
+            level: level.level,
+            source_width: pyramid.width,
+            source_height: pyramid.height,
+            status: status.to_string(),
+        };
+        if cached_path.exists() {
+            return Ok(result("cached"));
+        }
+
+        // 段階の座標に変換（端数は外側に広げる）
+        let to_level = |v: u32| v as f64 * level.scale;
+        let left = (to_level(x).floor() as u32).min(level.width.saturating_sub(1));
+        let top = (to_level(y).floor() as u32).min(level.height.saturating_sub(1));
+        let right = (to_level(x + region_width).ceil() as u32).clamp(left + 1, level.width);
+        let bottom = (to_level(y + region_height).ceil() as u32).clamp(top + 1, level.height);
+
+        let region = assemble_region(&tile_dir, &pyramid, level, (left, top, right - left, bottom - top))?;
+        let region = if region.width() == output_width && region.height() == output_height {
+            region
+        } else {
+            region.resize_exact(output_width, output_height, FilterType::Triangle)
+        };
+
+        fs::create_dir_all(&region_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+        region
+            .save_with_format(&cached_path, image::ImageFormat::Png)
+            .map_err(|e| format!("プレビュー保存エラー: {}", e))?;
+        Ok(result("generated"))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::Path;
+use rayon::prelude::*;
+use tauri::AppHandle;
+use crate::jobs::run_batch_job;
+use crate::types::{BatchFileResult, ChapterToneAnalysis, PageToneAnalysis, ToneStats};
+use crate::image_utils::{open_image_budgeted, processed_file_name, save_image, ImageOperation};
+use crate::processing::{analyze_tone, apply_tone_map, ToneMap};
+
+fn median<T: Copy + PartialOrd>(mut values: Vec<T>) -> Option<T> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    Some(values[values.len() / 2])
+}
+
+// 各ページの階調を解析し、チャプターの目標値（中央値）を算出
+fn analyze_pages(paths: &[String]) -> ChapterToneAnalysis {
+    let pages: Vec<PageToneAnalysis> = paths
+        .par_iter()
+        .map(|path| match open_image_budgeted(Path::new(path), ImageOperation::General) {
+            Ok((img, _permit)) => PageToneAnalysis {
+                path: path.clone(),
+                stats: Some(analyze_tone(&img)),
+                error: None,
+            },
+            Err(e) => PageToneAnalysis {
+                path: path.clone(),
+                stats: None,
+                error: Some(e),
+            },
+        })
+        .collect();
+
+    let stats: Vec<&ToneStats> = pages.iter().filter_map(|p| p.stats.as_ref()).collect();
+    let target = ToneStats {
+        black_point: median(stats.iter().map(|s| s.black_point).collect()).unwrap_or(0),
+        white_point: median(stats.iter().map(|s| s.white_point).collect()).unwrap_or(255),
+        mean: median(stats.iter().map(|s| s.mean).collect()).unwrap_or(128.0),
+    };
+
+    ChapterToneAnalysis { pages, target }
+}
+
+/// チャプター内の各ページの明るさ・コントラストを解析
+#[tauri::command]
+pub async fn analyze_chapter_tone(paths: Vec<String>) -> Result<ChapterToneAnalysis, String> {
+    tokio::task::spawn_blocking(move || analyze_pages(&paths))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// 1ページを目標値に合わせて補正し出力
+fn normalize_single_file(
+    page: &PageToneAnalysis,
+    target: &ToneStats,
+    strength: f64,
+    output_dir: &Path,
+    quality: u8,
+) -> Result<String, String> {
+    let stats = page.stats.as_ref().ok_or_else(|| {
+        page.error.clone().unwrap_or_else(|| "解析に失敗しました".to_string())
+    })?;
+    let source = Path::new(&page.path);
+    let (img, _permit) = open_image_budgeted(source, ImageOperation::General)?;
+    let normalized = apply_tone_map(&img, &ToneMap::new(stats, target, strength));
+    let output_file = output_dir.join(processed_file_name(source));
+
+    save_image(&normalized, &output_file, quality)?;
+    Ok(output_file.to_string_lossy().to_string())
+}
+
+/// チャプター内のページの黒点・白点を揃えて出力
+/// strength: 0.0（補正なし）〜 1.0（目標値に完全一致）
+#[tauri::command]
+pub async fn normalize_chapter_tone(
+    app_handle: AppHandle,
+    paths: Vec<String>,
+    output_dir: String,
+    strength: Option<f64>,
+    jpg_quality: Option<u8>,
+) -> Result<Vec<BatchFileResult>, String> {
+    let strength = strength.unwrap_or(1.0);
+    let quality = jpg_quality.unwrap_or(95);
+
+    tokio::task::spawn_blocking(move || {
+        let out_dir = Path::new(&output_dir);
+        fs::create_dir_all(out_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+        let analysis = analyze_pages(&paths);
+        let target = analysis.target;
+
+        Ok(run_batch_job(
+            &app_handle,
+            "tone",
+            &format!("トーンの統一: {}", output_dir),
+            out_dir,
+            &analysis.pages,
+            |page| page.path.as_str(),
+            |page| normalize_single_file(page, &target, strength, out_dir, quality),
+        ))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,40 @@
+use std::path::Path;
+use rayon::prelude::*;
+use crate::image_utils::{open_image_budgeted, ImageOperation};
+use crate::processing::detect_text_near_trim;
+use crate::types::{TrimSafetyAnalysis, TrimSafetySpec};
+
+// 単発のノイズを除くため、警告に必要な検出タイル数
+const MIN_HITS: usize = 2;
+
+/// 断裁危険域にセリフ等の細かい文字がかかっていないか一括チェック
+#[tauri::command]
+pub async fn check_text_near_trim(
+    paths: Vec<String>,
+    spec: TrimSafetySpec,
+) -> Result<Vec<TrimSafetyAnalysis>, String> {
+    tokio::task::spawn_blocking(move || {
+        paths
+            .par_iter()
+            .map(|path| match open_image_budgeted(Path::new(path), ImageOperation::General) {
+                Ok((img, _permit)) => {
+                    let hits = detect_text_near_trim(&img, &spec);
+                    TrimSafetyAnalysis {
+                        path: path.clone(),
+                        flagged: hits.len() >= MIN_HITS,
+                        hits,
+                        error: None,
+                    }
+                }
+                Err(e) => TrimSafetyAnalysis {
+                    path: path.clone(),
+                    flagged: false,
+                    hits: Vec::new(),
+                    error: Some(e),
+                },
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,18 @@
+use crate::types::UsageReport;
+use crate::usage::{reset_usage, usage_report};
+
+/// ローカルの利用統計を取得（エクスポート回数・処理ページ数・平均生成時間・キャッシュヒット率）
+#[tauri::command]
+pub async fn get_usage_stats() -> Result<UsageReport, String> {
+    tokio::task::spawn_blocking(usage_report)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 利用統計をリセット
+#[tauri::command]
+pub async fn reset_usage_stats() -> Result<(), String> {
+    tokio::task::spawn_blocking(reset_usage)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::path::Path;
+use image::imageops::FilterType;
+use rayon::prelude::*;
+use serde::Serialize;
+use crate::file_utils::zip_directory;
+use crate::image_utils::{open_image, save_image};
+use crate::types::{ViewerExportOptions, ViewerExportResult, ViewerPage};
+
+const VIEWER_TEMPLATE: &str = include_str!("../../templates/viewer.html");
+
+// ビューアに埋め込むページ情報
+#[derive(Serialize)]
+pub(crate) struct ViewerEntry {
+    pub(crate) src: Option<String>,
+    pub(crate) label: Option<String>,
+}
+
+// HTML内に埋め込む文字列をエスケープ
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// ページ一覧を埋め込んだビューアのHTMLを生成（画像の参照先は呼び出し側で決める）
+pub(crate) fn render_viewer_html(title: &str, entries: &[ViewerEntry], options: &ViewerExportOptions) -> Result<String, String> {
+    // </script> による埋め込み崩れを防止
+    let pages_json = serde_json::to_string(entries)
+        .map_err(|e| format!("JSON変換に失敗: {}", e))?
+        .replace("</", "<\\/");
+
+    Ok(VIEWER_TEMPLATE
+        .replace("__TITLE__", &escape_html(title))
+        .replace("__PAGES__", &pages_json)
+        .replace("__RTL__", if options.right_to_left { "true" } else { "false" })
+        .replace("__FIRST_SINGLE__", if options.first_page_single { "true" } else { "false" }))
+}
+
+// Web用サイズに縮小してJPGで保存
+pub(crate) fn write_web_image(source: &Path, output: &Path, options: &ViewerExportOptions) -> Result<(), String> {
+    let img = open_image(source)?;
+    let img = if img.width().max(img.height()) > options.max_size {
+        img.resize(options.max_size, options.max_size, FilterType::Lanczos3)
+    } else {
+        img
+    };
+    save_image(&img, output, options.jpg_quality)
+}
+
+/// アプリなしで読み順を確認できる静的HTMLビューアを書き出し
+#[tauri::command]
+pub async fn export_html_viewer(
+    pages: Vec<ViewerPage>,
+    output_dir: String,
+    title: Option<String>,
+    options: Option<ViewerExportOptions>,
+    as_zip: Option<bool>,
+) -> Result<ViewerExportResult, String> {
+    let options = options.unwrap_or_default();
+    let title = title.unwrap_or_else(|| "台割プレビュー".to_string());
+
+    tokio::task::spawn_blocking(move || {
+        let out_dir = Path::new(&output_dir);
+        let pages_dir = out_dir.join("pages");
+        fs::create_dir_all(&pages_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+        // ページ画像を並列に書き出し（失敗したページは白紙扱い）
+        let written: Vec<(Option<String>, Option<String>)> = pages
+            .par_iter()
+            .enumerate()
+            .map(|(i, page)| match page.source_path {
+                Some(ref source) => {
+                    let file_name = format!("{:04}.jpg", i + 1);
+                    match write_web_image(Path::new(source), &pages_dir.join(&file_name), &options) {
+                        Ok(()) => (Some(format!("pages/{}", file_name)), None),
+                        Err(e) => {
+                            eprintln!("ビューア用画像の書き出し失敗: {} - {}", source, e);
+                            (None, Some(source.clone()))
+                        }
+                    }
+                }
+                None => (None, None),
+            })
+            .collect();
+
+        let failed_pages: Vec<String> = written.iter().filter_map(|(_, failed)| failed.clone()).collect();
+        let entries: Vec<ViewerEntry> = written
+            .into_iter()
+            .zip(pages.iter())
+            .map(|((src, _), page)| ViewerEntry {
+                src,
+                label: page.label.clone(),
+            })
+            .collect();
+
+        let html = render_viewer_html(&title, &entries, &options)?;
+        let index_path = out_dir.join("index.html");
+        fs::write(&index_path, html).map_err(|e| format!("ファイル書き込みエラー: {}", e))?;
+
+        let output_path = if as_zip.unwrap_or(false) {
+            let zip_path = out_dir.with_extension("zip");
+            zip_directory(out_dir, &zip_path)?;
+            zip_path
+        } else {
+            index_path
+        };
+
+        Ok(ViewerExportResult {
+            output_path: output_path.to_string_lossy().to_string(),
+            page_count: pages.len(),
+            failed_pages,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
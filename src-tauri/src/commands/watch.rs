@@ -0,0 +1,39 @@
+use tauri::{AppHandle, State};
+use crate::app_mode::is_safe_mode;
+use crate::state::AppState;
+use crate::watcher::start_folder_watch;
+
+/// フォルダの監視を開始（同じウィンドウで同じフォルダを監視中なら置き換え）
+/// 変更は "file-added" / "file-removed" / "file-modified" イベントで通知
+/// セーフモードでは監視を開始しない
+#[tauri::command]
+pub fn watch_folder(
+    app_handle: AppHandle,
+    window: tauri::WebviewWindow,
+    state: State<'_, AppState>,
+    folder_path: String,
+    recursive: Option<bool>,
+) -> Result<(), String> {
+    if is_safe_mode() {
+        eprintln!("セーフモードのためフォルダを監視しません: {}", folder_path);
+        return Ok(());
+    }
+    let watch = start_folder_watch(
+        app_handle,
+        window.label().to_string(),
+        folder_path.clone(),
+        recursive.unwrap_or(false),
+    )?;
+    state.with_window(window.label(), |w| {
+        w.folder_watches.insert(folder_path, watch);
+    });
+    Ok(())
+}
+
+/// フォルダの監視を停止
+#[tauri::command]
+pub fn unwatch_folder(window: tauri::WebviewWindow, state: State<'_, AppState>, folder_path: String) {
+    state.with_window(window.label(), |w| {
+        w.folder_watches.remove(&folder_path);
+    });
+}
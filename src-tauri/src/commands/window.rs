@@ -0,0 +1,90 @@
+use std::path::Path;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use crate::state::{AppState, WindowState};
+
+// ウィンドウアイコンを設定
+pub fn apply_window_icon(window: &tauri::WebviewWindow) {
+    let icon_bytes = include_bytes!("../../icons/icon.png");
+    if let Ok(img) = image::load_from_memory(icon_bytes) {
+        let rgba = img.to_rgba8();
+        let (width, height) = rgba.dimensions();
+        let icon = tauri::image::Image::new_owned(rgba.into_raw(), width, height);
+        let _ = window.set_icon(icon);
+    }
+}
+
+// メインウィンドウと同じ設定でウィンドウを作成
+fn build_window(app: &AppHandle, label: &str, title: String) -> Result<tauri::WebviewWindow, String> {
+    let window = WebviewWindowBuilder::new(app, label, WebviewUrl::App("index.html".into()))
+        .title(title)
+        .inner_size(1400.0, 900.0)
+        .min_inner_size(1000.0, 700.0)
+        .center()
+        .decorations(false)
+        .build()
+        .map_err(|e| format!("ウィンドウの作成に失敗: {}", e))?;
+    apply_window_icon(&window);
+    Ok(window)
+}
+
+// トレイ常駐中にメインウィンドウを作り直す
+pub fn create_main_window(app: &AppHandle) -> Result<tauri::WebviewWindow, String> {
+    build_window(app, "main", "台割マネージャー".to_string())
+}
+
+/// プロジェクトを新しいウィンドウで開く（表紙と本文を並べて作業する場合など）
+/// 戻り値: 作成したウィンドウのラベル
+#[tauri::command]
+pub async fn open_project_in_new_window(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    project_path: Option<String>,
+) -> Result<String, String> {
+    // 同じプロジェクトが既に開かれていればそのウィンドウを前面に
+    if let Some(ref path) = project_path {
+        let existing = state
+            .windows
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|(_, w)| w.project_path.as_deref() == Some(path.as_str()))
+            .map(|(label, _)| label.clone());
+        if let Some(window) = existing.and_then(|label| app_handle.get_webview_window(&label)) {
+            let _ = window.set_focus();
+            return Ok(window.label().to_string());
+        }
+    }
+
+    let label = state.next_window_label();
+    let title = match project_path.as_deref().and_then(|p| Path::new(p).file_stem()) {
+        Some(stem) => format!("台割マネージャー - {}", stem.to_string_lossy()),
+        None => "台割マネージャー".to_string(),
+    };
+
+    state
+        .windows
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(label.clone(), WindowState::new(project_path));
+
+    if let Err(e) = build_window(&app_handle, &label, title) {
+        state.remove_window(&label);
+        return Err(e);
+    }
+
+    Ok(label)
+}
+
+/// 呼び出し元ウィンドウで開くべきプロジェクトを取得（新規ウィンドウの起動時に使用）
+#[tauri::command]
+pub fn get_window_project(window: tauri::WebviewWindow, state: State<'_, AppState>) -> Option<String> {
+    state.with_window(window.label(), |w| w.project_path.clone())
+}
+
+/// 呼び出し元ウィンドウで開いているプロジェクトを記録
+#[tauri::command]
+pub fn set_window_project(window: tauri::WebviewWindow, state: State<'_, AppState>, project_path: Option<String>) {
+    // 前のプロジェクトのサムネイル生成は不要になるため中断
+    state.cancel_thumbnails(window.label());
+    state.with_window(window.label(), |w| w.project_path = project_path);
+}
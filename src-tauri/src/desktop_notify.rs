@@ -0,0 +1,153 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_opener::OpenerExt;
+use crate::tray::show_any_window;
+
+// 通知のボタン
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyAction {
+    /// 出力先フォルダを開く
+    OpenFolder,
+    /// アプリを前面に出して結果（レポート）を表示
+    ViewReport,
+}
+
+impl NotifyAction {
+    fn id(self) -> &'static str {
+        match self {
+            NotifyAction::OpenFolder => "open-folder",
+            NotifyAction::ViewReport => "view-report",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            NotifyAction::OpenFolder => "フォルダを開く",
+            NotifyAction::ViewReport => "レポートを表示",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "open-folder" => Some(NotifyAction::OpenFolder),
+            "view-report" => Some(NotifyAction::ViewReport),
+            _ => None,
+        }
+    }
+}
+
+// ボタン押下をフロントエンドに通知（"notification-action" イベント）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NotificationActionEvent {
+    action: String,
+    /// 操作対象（出力先パスなど）
+    target: String,
+}
+
+// アプリが前面にないときだけOSの通知センターに通知（前面ならUI側の表示で十分）
+pub fn notify_if_background(app: &AppHandle, title: &str, body: &str, target: &str, actions: &[NotifyAction]) {
+    let focused = app
+        .webview_windows()
+        .values()
+        .any(|w| w.is_focused().unwrap_or(false));
+    if focused {
+        return;
+    }
+    if let Err(e) = show_notification(app, title, body, target, actions) {
+        eprintln!("通知の表示に失敗: {}", e);
+    }
+}
+
+// ボタンが押されたときの処理
+fn handle_action(app: &AppHandle, action_id: Option<&str>, target: &str) {
+    match action_id.and_then(NotifyAction::from_id) {
+        Some(NotifyAction::OpenFolder) => {
+            if let Err(e) = app.opener().open_path(target, None::<&str>) {
+                eprintln!("フォルダを開けません: {} - {}", target, e);
+            }
+        }
+        Some(NotifyAction::ViewReport) => {
+            show_any_window(app);
+            let _ = app.emit(
+                "notification-action",
+                NotificationActionEvent {
+                    action: NotifyAction::ViewReport.id().to_string(),
+                    target: target.to_string(),
+                },
+            );
+        }
+        // 通知本体のクリックはアプリを前面に
+        None => show_any_window(app),
+    }
+}
+
+#[cfg(windows)]
+fn show_notification(
+    app: &AppHandle,
+    title: &str,
+    body: &str,
+    target: &str,
+    actions: &[NotifyAction],
+) -> Result<(), String> {
+    use tauri_winrt_notification::Toast;
+
+    // インストーラーが登録するアプリのAUMID（識別子）で通知する。未登録の開発ビルドではPowerShellとして表示
+    let app_id = if tauri::is_dev() { Toast::POWERSHELL_APP_ID.to_string() } else { app.config().identifier.clone() };
+    let mut toast = Toast::new(&app_id).title(title).text1(body);
+    for action in actions {
+        toast = toast.add_button(action.label(), action.id());
+    }
+
+    let app = app.clone();
+    let target = target.to_string();
+    toast
+        .on_activated(move |action| {
+            // コールバックは通知スレッドで呼ばれるため、UI操作はメインスレッドへ
+            let app_handle = app.clone();
+            let target = target.clone();
+            let _ = app.run_on_main_thread(move || handle_action(&app_handle, action.as_deref(), &target));
+            Ok(())
+        })
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(windows))]
+fn show_notification(
+    app: &AppHandle,
+    title: &str,
+    body: &str,
+    target: &str,
+    actions: &[NotifyAction],
+) -> Result<(), String> {
+    let mut notification = notify_rust::Notification::new();
+    notification.summary(title).body(body).appname("台割マネージャー");
+    // ボタンはLinuxの通知デーモンのみ対応（macOSでは通常の通知として表示）
+    #[cfg(all(unix, not(target_os = "macos")))]
+    for action in actions {
+        notification.action(action.id(), action.label());
+    }
+    #[cfg(target_os = "macos")]
+    let _ = actions;
+
+    let handle = notification.show().map_err(|e| e.to_string())?;
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        let app = app.clone();
+        let target = target.to_string();
+        // 押下を待つとブロックするため別スレッドで待機
+        std::thread::spawn(move || {
+            handle.wait_for_action(|action| match action {
+                "__closed" => {}
+                "default" => handle_action(&app, None, &target),
+                id => handle_action(&app, Some(id), &target),
+            });
+        });
+    }
+    #[cfg(target_os = "macos")]
+    let _ = (handle, app, target);
+
+    Ok(())
+}
@@ -0,0 +1,354 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::audit::machine_name;
+use crate::commands::export::run_export;
+use crate::commands::tiff::convert_tiff;
+use crate::history::new_history_id;
+use crate::types::{
+    ExportRequest, FarmClaimedJob, FarmJob, FarmJobResult, FarmStatus, FarmTiffPayload, FarmWorkerEvent,
+    FarmWorkerInfo, OutputConflictPolicy,
+};
+
+// 共有キューフォルダの構成
+// pending/<ID>.json          未実行のジョブ
+// claimed/<ID>@<ワーカー>.json 実行中のジョブ（pending からの名前変更で取得するため二重実行されない）
+// done/<ID>.json, failed/<ID>.json  実行結果
+// workers/<ワーカー>.json     ワーカーの稼働状況（実行中のジョブのリースを兼ねる）
+const PENDING_DIR: &str = "pending";
+const CLAIMED_DIR: &str = "claimed";
+const DONE_DIR: &str = "done";
+const FAILED_DIR: &str = "failed";
+const WORKERS_DIR: &str = "workers";
+
+// ワーカーの稼働状況を書き込む間隔
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+// 稼働状況がこの時間更新されていないワーカーの実行中ジョブは、異常終了したとみなして未実行に戻す
+const LEASE_SECS: i64 = 120;
+
+// ワーカーとして処理したTIFF変換の進行状況の通知先（存在しないウィンドウなので画面には出ない）
+const WORKER_WINDOW_LABEL: &str = "farm-worker";
+
+// このマシンで動いているワーカー（アプリの管理状態）
+pub struct FarmWorker {
+    current: Mutex<Option<RunningWorker>>,
+}
+
+struct RunningWorker {
+    stop: Arc<AtomicBool>,
+    // ジョブの取得から結果の書き込みまでの間 true
+    busy: Arc<AtomicBool>,
+}
+
+impl FarmWorker {
+    pub fn new() -> Self {
+        Self {
+            current: Mutex::new(None),
+        }
+    }
+}
+
+fn worker_name() -> String {
+    machine_name().unwrap_or_else(|| "unknown".to_string())
+}
+
+fn ensure_queue_dirs(queue: &Path) -> Result<(), String> {
+    for dir in [PENDING_DIR, CLAIMED_DIR, DONE_DIR, FAILED_DIR, WORKERS_DIR] {
+        fs::create_dir_all(queue.join(dir)).map_err(|e| format!("キューフォルダの作成に失敗: {}", e))?;
+    }
+    Ok(())
+}
+
+// 書き込み途中のJSONを他のマシンに読まれないよう一時ファイル経由で保存
+fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(".{}.partial", file_name));
+    fs::write(&temp, json).map_err(|e| format!("キューへの書き込みに失敗: {}", e))?;
+    fs::rename(&temp, path).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        format!("キューへの書き込みに失敗: {}", e)
+    })
+}
+
+// フォルダ内のJSONファイルを名前順に列挙（書き込み途中の一時ファイルは除く）
+fn list_json_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    let name = p.file_name().unwrap_or_default().to_string_lossy();
+                    !name.starts_with('.') && name.ends_with(".json")
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn read_json<T: for<'de> serde::Deserialize<'de>>(path: &Path) -> Option<T> {
+    fs::read_to_string(path).ok().and_then(|s| serde_json::from_str(&s).ok())
+}
+
+// ジョブをキューに追加（ID順 = 投入順に実行される）
+pub fn submit_job(queue: &Path, kind: &str, label: String, payload: serde_json::Value, seq: usize) -> Result<FarmJob, String> {
+    ensure_queue_dirs(queue)?;
+    let submitted_by = machine_name();
+    let job = FarmJob {
+        id: format!("{}-{:04}-{}", new_history_id(), seq, worker_name()),
+        kind: kind.to_string(),
+        label,
+        submitted_at: chrono::Local::now().to_rfc3339(),
+        submitted_by,
+        payload,
+    };
+    write_json_atomic(&queue.join(PENDING_DIR).join(format!("{}.json", job.id)), &job)?;
+    Ok(job)
+}
+
+// 未実行のジョブを1件取得（名前変更に成功したマシンだけが実行する）
+fn claim_next_job(queue: &Path, worker: &str) -> Option<(FarmJob, PathBuf)> {
+    for pending in list_json_files(&queue.join(PENDING_DIR)) {
+        let id = pending.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let claimed = queue.join(CLAIMED_DIR).join(format!("{}@{}.json", id, worker));
+        if fs::rename(&pending, &claimed).is_err() {
+            // 他のワーカーが先に取得した
+            continue;
+        }
+        match read_json::<FarmJob>(&claimed) {
+            Some(job) => return Some((job, claimed)),
+            None => {
+                // 読めないジョブは失敗扱いにして次へ
+                let _ = fs::rename(&claimed, queue.join(FAILED_DIR).join(format!("{}.json", id)));
+            }
+        }
+    }
+    None
+}
+
+// 前回異常終了したときに残った自分の実行中ジョブを未実行に戻す
+// （このプロセスでまだジョブを実行中のワーカーがいる間は呼ばない）
+fn requeue_own_claims(queue: &Path, worker: &str) {
+    let suffix = format!("@{}.json", worker);
+    for claimed in list_json_files(&queue.join(CLAIMED_DIR)) {
+        let name = claimed.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if let Some(id) = name.strip_suffix(&suffix) {
+            let _ = fs::rename(&claimed, queue.join(PENDING_DIR).join(format!("{}.json", id)));
+        }
+    }
+}
+
+// 稼働状況が途絶えたワーカー（クラッシュ・電源断など）の実行中ジョブを未実行に戻す
+// 名前変更で戻すため、複数のワーカーが同時に見つけても1回しか戻らない
+fn reclaim_expired_claims(queue: &Path) {
+    let now = chrono::Local::now();
+    for claimed in list_json_files(&queue.join(CLAIMED_DIR)) {
+        let stem = claimed.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let Some((id, worker)) = stem.rsplit_once('@') else {
+            continue;
+        };
+        let last_seen = read_json::<FarmWorkerInfo>(&queue.join(WORKERS_DIR).join(format!("{}.json", worker)))
+            .and_then(|info| chrono::DateTime::parse_from_rfc3339(&info.last_seen).ok());
+        let expired = match last_seen {
+            Some(last_seen) => now.signed_duration_since(last_seen).num_seconds() > LEASE_SECS,
+            None => true,
+        };
+        if expired {
+            eprintln!("応答のないワーカー {} のジョブを未実行に戻します: {}", worker, id);
+            let _ = fs::rename(&claimed, queue.join(PENDING_DIR).join(format!("{}.json", id)));
+        }
+    }
+}
+
+fn write_heartbeat(queue: &Path, worker: &str, current_job: Option<String>) {
+    let info = FarmWorkerInfo {
+        name: worker.to_string(),
+        last_seen: chrono::Local::now().to_rfc3339(),
+        current_job,
+    };
+    if let Err(e) = write_json_atomic(&queue.join(WORKERS_DIR).join(format!("{}.json", worker)), &info) {
+        eprintln!("ワーカー状況の書き込みに失敗: {}", e);
+    }
+}
+
+fn run_job(app: &AppHandle, job: &FarmJob) -> Result<serde_json::Value, String> {
+    match job.kind.as_str() {
+        "export" => {
+            let request: ExportRequest =
+                serde_json::from_value(job.payload.clone()).map_err(|e| format!("ジョブの解析に失敗: {}", e))?;
+            let exported = run_export(app, request)?;
+            Ok(serde_json::json!({ "exported": exported }))
+        }
+        "tiff-convert" => {
+            let payload: FarmTiffPayload =
+                serde_json::from_value(job.payload.clone()).map_err(|e| format!("ジョブの解析に失敗: {}", e))?;
+            // 分割した他のジョブと同じフォルダに出力する
+            let response = convert_tiff(
+                app,
+                WORKER_WINDOW_LABEL,
+                payload.config,
+                payload.output_dir,
+                payload.pinned_photoshop,
+                Some(OutputConflictPolicy::Reuse),
+            )?;
+            serde_json::to_value(response).map_err(|e| e.to_string())
+        }
+        other => Err(format!("不明なジョブの種類: {}", other)),
+    }
+}
+
+fn emit_worker_event(app: &AppHandle, queue: &Path, status: &str, job: &FarmJob, error: Option<String>) {
+    let _ = app.emit(
+        "farm-worker-status",
+        FarmWorkerEvent {
+            queue_dir: queue.to_string_lossy().to_string(),
+            status: status.to_string(),
+            job_id: job.id.clone(),
+            label: job.label.clone(),
+            error,
+        },
+    );
+}
+
+// ワーカーを開始（既に動いている場合は置き換え）
+pub fn start_worker(app: AppHandle, queue_dir: String, poll_secs: u64) -> Result<(), String> {
+    let queue = PathBuf::from(&queue_dir);
+    ensure_queue_dirs(&queue)?;
+
+    // 前のワーカーがジョブを実行中なら、そのジョブは最後まで処理させて未実行に戻さない
+    let previous_busy = stop_worker(&app);
+    let worker = worker_name();
+    if !previous_busy {
+        requeue_own_claims(&queue, &worker);
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let busy = Arc::new(AtomicBool::new(false));
+    *app.state::<FarmWorker>().current.lock().map_err(|e| e.to_string())? = Some(RunningWorker {
+        stop: stop.clone(),
+        busy: busy.clone(),
+    });
+
+    let interval = Duration::from_secs(poll_secs.max(1));
+    std::thread::spawn(move || {
+        // 長時間のジョブの実行中もリースが切れないよう、稼働状況は別スレッドで書き続ける
+        let current_job: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+        let alive = Arc::new(AtomicBool::new(true));
+        let heartbeat = {
+            let (queue, worker, current_job, alive) = (queue.clone(), worker.clone(), current_job.clone(), alive.clone());
+            std::thread::spawn(move || {
+                while alive.load(Ordering::Relaxed) {
+                    let job = current_job.lock().ok().and_then(|job| job.clone());
+                    write_heartbeat(&queue, &worker, job);
+                    std::thread::park_timeout(HEARTBEAT_INTERVAL);
+                }
+            })
+        };
+        let set_current_job = |job: Option<String>| {
+            if let Ok(mut current) = current_job.lock() {
+                *current = job;
+            }
+            heartbeat.thread().unpark();
+        };
+
+        while !stop.load(Ordering::Relaxed) {
+            reclaim_expired_claims(&queue);
+
+            busy.store(true, Ordering::SeqCst);
+            let Some((job, claimed)) = claim_next_job(&queue, &worker) else {
+                busy.store(false, Ordering::SeqCst);
+                std::thread::sleep(interval);
+                continue;
+            };
+
+            set_current_job(Some(job.id.clone()));
+            emit_worker_event(&app, &queue, "started", &job, None);
+            let started_at = chrono::Local::now().to_rfc3339();
+            let result = run_job(&app, &job);
+
+            let success = result.is_ok();
+            let error = result.as_ref().err().cloned();
+            let record = FarmJobResult {
+                job: job.clone(),
+                worker: worker.clone(),
+                started_at,
+                finished_at: chrono::Local::now().to_rfc3339(),
+                success,
+                error: error.clone(),
+                result: result.ok(),
+            };
+            let dir = if success { DONE_DIR } else { FAILED_DIR };
+            match write_json_atomic(&queue.join(dir).join(format!("{}.json", job.id)), &record) {
+                Ok(()) => {
+                    let _ = fs::remove_file(&claimed);
+                }
+                Err(e) => eprintln!("ジョブ結果の書き込みに失敗: {}", e),
+            }
+            set_current_job(None);
+            busy.store(false, Ordering::SeqCst);
+            emit_worker_event(&app, &queue, "finished", &job, error);
+        }
+
+        alive.store(false, Ordering::Relaxed);
+        heartbeat.thread().unpark();
+        let _ = heartbeat.join();
+        // 置き換えた新しいワーカーが同じ稼働状況ファイルを使っている間は消さない
+        let replaced = app
+            .state::<FarmWorker>()
+            .current
+            .lock()
+            .map(|current| current.is_some())
+            .unwrap_or(true);
+        if !replaced {
+            let _ = fs::remove_file(queue.join(WORKERS_DIR).join(format!("{}.json", worker)));
+        }
+    });
+    Ok(())
+}
+
+// ワーカーを停止（実行中のジョブは最後まで処理する）。ジョブを実行中だったかを返す
+pub fn stop_worker(app: &AppHandle) -> bool {
+    let state = app.state::<FarmWorker>();
+    let mut current = state.current.lock().unwrap_or_else(|e| e.into_inner());
+    match current.take() {
+        Some(running) => {
+            running.stop.store(true, Ordering::Relaxed);
+            running.busy.load(Ordering::SeqCst)
+        }
+        None => false,
+    }
+}
+
+// キューの状況を読み取り
+pub fn read_status(queue: &Path) -> Result<FarmStatus, String> {
+    if !queue.is_dir() {
+        return Err(format!("キューフォルダが見つかりません: {}", queue.display()));
+    }
+
+    let running = list_json_files(&queue.join(CLAIMED_DIR))
+        .iter()
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_string_lossy().to_string();
+            let (_, worker) = stem.rsplit_once('@')?;
+            Some(FarmClaimedJob {
+                job: read_json(path)?,
+                worker: worker.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(FarmStatus {
+        pending: list_json_files(&queue.join(PENDING_DIR)).iter().filter_map(|p| read_json(p)).collect(),
+        running,
+        completed: list_json_files(&queue.join(DONE_DIR)).iter().filter_map(|p| read_json(p)).collect(),
+        failed: list_json_files(&queue.join(FAILED_DIR)).iter().filter_map(|p| read_json(p)).collect(),
+        workers: list_json_files(&queue.join(WORKERS_DIR)).iter().filter_map(|p| read_json(p)).collect(),
+    })
+}
@@ -0,0 +1,419 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::app_mode::portable_data_dir;
+
+// 設定ディレクトリを取得（ポータブルモードでは実行ファイル横のデータフォルダ）
+pub fn get_config_path() -> Result<PathBuf, String> {
+    if let Some(data_dir) = portable_data_dir() {
+        return Ok(data_dir.join("config"));
+    }
+    dirs::config_dir()
+        .map(|p| p.join("daidori-manager"))
+        .ok_or_else(|| "設定ディレクトリを特定できません".to_string())
+}
+
+// パスのあるドライブの空き容量（未作成のパスは存在する親ディレクトリで判定）
+pub fn available_space(path: &Path) -> Option<u64> {
+    path.ancestors()
+        .find(|p| p.exists())
+        .and_then(|p| fs2::available_space(p).ok())
+}
+
+// 更新日時をUNIXエポックからのミリ秒で取得
+pub fn modified_millis(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// パスの更新日時を取得（取得できない場合は0）
+pub fn path_modified_millis(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| modified_millis(&m)).unwrap_or(0)
+}
+
+// 出力先のフォルダ直下を指す1階層分の名前か（"..", 区切り文字、絶対パス、ドライブ指定を含まない）
+// スクリプトやプロジェクトファイルなど外部から来た名前を出力先に連結する前に確認する
+pub fn is_single_path_component(name: &str) -> bool {
+    use std::path::Component;
+
+    if name.is_empty() || name.contains(['/', '\\', ':']) {
+        return false;
+    }
+    let mut components = Path::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    )
+}
+
+// 内容ハッシュの計算に使う先頭部分のサイズ
+const CONTENT_HASH_BYTES: u64 = 1024 * 1024;
+
+// ファイルの内容ハッシュ（先頭1MBのxxh3 + ファイルサイズ）
+// リネーム・移動されたファイルの照合用で、全体を読まずに済むよう先頭のみ使う
+pub fn content_hash(path: &Path) -> Result<String, String> {
+    use std::io::Read;
+
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let size = file.metadata().map_err(|e| e.to_string())?.len();
+    let mut head = Vec::with_capacity(CONTENT_HASH_BYTES.min(size) as usize);
+    file.take(CONTENT_HASH_BYTES)
+        .read_to_end(&mut head)
+        .map_err(|e| e.to_string())?;
+    Ok(format!("{:x}-{:016x}", size, xxhash_rust::xxh3::xxh3_64(&head)))
+}
+
+// ファイル全体の内容ハッシュ（xxh3 128bit）。同一性の判定・内容アドレスでの保管用
+pub fn full_content_hash(path: &Path) -> Result<String, String> {
+    use std::io::Read;
+
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(format!("{:032x}", hasher.digest128()))
+}
+
+// ディレクトリの内容をZIPに圧縮（画像は再圧縮しても縮まないため無圧縮で格納）
+pub fn zip_directory(dir: &Path, zip_path: &Path) -> Result<(), String> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let file = fs::File::create(zip_path).map_err(|e| format!("ZIP作成エラー: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let stored = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for entry in walkdir::WalkDir::new(dir).sort_by_file_name() {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let relative = path.strip_prefix(dir).map_err(|e| e.to_string())?;
+        // ZIP内のパス区切りは常に "/"
+        let name = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let is_image = matches!(
+            path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref(),
+            Some("jpg" | "jpeg" | "png" | "tif" | "tiff" | "psd" | "webp")
+        );
+        zip.start_file(name, if is_image { stored } else { deflated })
+            .map_err(|e| format!("ZIP書き込みエラー: {}", e))?;
+        let data = fs::read(path).map_err(|e| e.to_string())?;
+        zip.write_all(&data).map_err(|e| format!("ZIP書き込みエラー: {}", e))?;
+    }
+
+    zip.finish().map_err(|e| format!("ZIP書き込みエラー: {}", e))?;
+    Ok(())
+}
+
+// クラウド同期フォルダの判定に使うパス要素
+const CLOUD_SYNC_MARKERS: &[(&str, &str)] = &[
+    ("dropbox", "Dropbox"),
+    ("google drive", "Google Drive"),
+    ("googledrive", "Google Drive"),
+    ("マイドライブ", "Google Drive"),
+    ("onedrive", "OneDrive"),
+    ("icloud", "iCloud Drive"),
+    ("mobile documents", "iCloud Drive"),
+    ("box sync", "Box"),
+];
+
+// パスがクラウド同期フォルダ配下ならサービス名を返す
+pub fn cloud_sync_provider(path: &Path) -> Option<&'static str> {
+    path.components().find_map(|c| {
+        let name = c.as_os_str().to_string_lossy().to_lowercase();
+        CLOUD_SYNC_MARKERS
+            .iter()
+            .find(|(marker, _)| name.starts_with(marker))
+            .map(|(_, provider)| *provider)
+    })
+}
+
+// 同期サービスが競合時に付ける目印（括弧内・末尾）
+const SYNC_CONFLICT_MARKERS: [&str; 4] = ["conflicted copy", "conflict", "競合", "コンフリクト"];
+
+// OneDrive が競合時に付けるPC名らしいか
+// 「-表紙」「-A4」のような普通の名前を誤判定しないよう、このPCの名前か、
+// Windowsが自動で付ける「DESKTOP-」「LAPTOP-」＋7文字以上の英大文字・数字（コンピューター名は15文字以内）に限る
+fn looks_like_machine_name(suffix: &str) -> bool {
+    if crate::audit::machine_name().is_some_and(|name| name.eq_ignore_ascii_case(suffix)) {
+        return true;
+    }
+    ["DESKTOP-", "LAPTOP-"].iter().any(|prefix| {
+        suffix.strip_prefix(prefix).is_some_and(|rest| {
+            rest.len() >= 7 && suffix.len() <= 15 && rest.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        })
+    })
+}
+
+// 同期の競合で作られたファイル名なら、元のファイル名を返す（exists で同じフォルダに元のファイルがあるか確認）
+// Dropbox・Nextcloud: "page01 (田中's conflicted copy 2024-05-01).psd"、Synology Drive など: "page01_Conflict.psd"
+// OneDrive: "page01-DESKTOP-AB12CD3.psd"（PC名が付くだけなので、元のファイルがある場合のみ競合とみなす）
+pub fn sync_conflict_original(file_name: &str, exists: impl Fn(&str) -> bool) -> Option<String> {
+    let path = Path::new(file_name);
+    let stem = path.file_stem()?.to_str()?;
+    let with_ext = |head: &str| match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}.{}", head.trim_end(), ext),
+        None => head.trim_end().to_string(),
+    };
+    let has_marker = |text: &str| {
+        let text = text.to_lowercase();
+        SYNC_CONFLICT_MARKERS.iter().any(|marker| text.contains(marker))
+    };
+
+    // 括弧内の目印（全角括弧も）
+    for (open, close) in [('(', ')'), ('（', '）')] {
+        if let Some(inner) = stem.strip_suffix(close) {
+            if let Some(start) = inner.rfind(open) {
+                if start > 0 && has_marker(&inner[start + open.len_utf8()..]) {
+                    return Some(with_ext(&inner[..start]));
+                }
+            }
+        }
+    }
+    // 末尾の目印
+    for separator in ['_', ' ', '-'] {
+        if let Some((head, tail)) = stem.rsplit_once(separator) {
+            if !head.is_empty() && has_marker(tail) {
+                return Some(with_ext(head));
+            }
+        }
+    }
+    // OneDrive のPC名
+    stem.match_indices('-')
+        .map(|(i, _)| (&stem[..i], &stem[i + 1..]))
+        .filter(|(head, suffix)| !head.is_empty() && looks_like_machine_name(suffix))
+        .map(|(head, _)| with_ext(head))
+        .find(|original| exists(original))
+}
+
+// ファイルと同じフォルダにある同期の競合ファイル
+pub fn find_sync_conflict_copies(path: &Path) -> Vec<String> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let name = name.to_lowercase();
+    let mut copies: Vec<String> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p != path)
+        .filter(|p| {
+            let candidate = p.file_name().unwrap_or_default().to_string_lossy();
+            sync_conflict_original(&candidate, |original| original.to_lowercase() == name)
+                .is_some_and(|original| original.to_lowercase() == name)
+        })
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    copies.sort();
+    copies
+}
+
+// ファイルの実体がローカルにない（クラウド上のプレースホルダー）か判定
+#[cfg(target_os = "windows")]
+pub fn is_cloud_placeholder(metadata: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x0000_1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x0004_0000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x0040_0000;
+    metadata.file_attributes()
+        & (FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+        != 0
+}
+
+#[cfg(target_os = "macos")]
+pub fn is_cloud_placeholder(metadata: &fs::Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+    // SF_DATALESS: File Provider（iCloud/Dropbox等）のオンデマンドファイル
+    const SF_DATALESS: u32 = 0x4000_0000;
+    metadata.st_flags() & SF_DATALESS != 0
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn is_cloud_placeholder(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    // サイズがあるのにディスク上のブロックがない = 実体未取得
+    metadata.len() > 0 && metadata.blocks() == 0
+}
+
+// クラウド上のみに存在するファイルなら理由を返す
+pub fn cloud_placeholder_reason(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() || !is_cloud_placeholder(&metadata) {
+        return None;
+    }
+    let provider = cloud_sync_provider(path).unwrap_or("クラウドストレージ");
+    Some(format!(
+        "ファイルがローカルにダウンロードされていません（{}）: {}",
+        provider,
+        path.display()
+    ))
+}
+
+// ウイルス対策・インデクサーのロックによる一時的な失敗のリトライ設定
+const LOCK_RETRY_ATTEMPTS: u32 = 6;
+const LOCK_RETRY_BASE_MS: u64 = 150;
+
+// 他プロセスのロックによる一時的なエラーか
+// Windows: ERROR_SHARING_VIOLATION(32), ERROR_LOCK_VIOLATION(33)
+// アクセス拒否（権限不足）は待っても解消しないため対象外
+fn is_transient_lock_error(e: &std::io::Error) -> bool {
+    cfg!(target_os = "windows") && matches!(e.raw_os_error(), Some(32) | Some(33))
+}
+
+// ロックによる一時的な失敗を指数バックオフでリトライ
+pub fn retry_on_lock<T>(mut op: impl FnMut() -> std::io::Result<T>) -> std::io::Result<T> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(e) if attempt + 1 < LOCK_RETRY_ATTEMPTS && is_transient_lock_error(&e) => {
+                let wait = LOCK_RETRY_BASE_MS << attempt;
+                eprintln!("ファイルがロックされています。{}ms後に再試行: {}", wait, e);
+                std::thread::sleep(std::time::Duration::from_millis(wait));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+// 書き込み途中のファイル名（完成後にリネーム）
+fn partial_path(target: &Path) -> PathBuf {
+    let name = target.file_name().unwrap_or_default().to_string_lossy();
+    target.with_file_name(format!(".{}.partial", name))
+}
+
+// 一時ファイルに排他作成で書き込み、完成後に目的のパスへ移す
+// スキャナーが書き込み途中のファイルを掴むのを防ぐ
+fn write_via_partial(
+    target: &Path,
+    replace: bool,
+    write: impl FnOnce(&mut fs::File) -> Result<(), String>,
+) -> Result<(), String> {
+    let partial = partial_path(target);
+    let _ = fs::remove_file(&partial);
+
+    let mut file = retry_on_lock(|| fs::OpenOptions::new().write(true).create_new(true).open(&partial))
+        .map_err(|e| format!("ファイル作成エラー: {} - {}", partial.display(), e))?;
+    let written = write(&mut file).and_then(|_| file.sync_all().map_err(|e| e.to_string()));
+    drop(file);
+
+    if let Err(e) = written {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    let placed = if replace {
+        retry_on_lock(|| fs::rename(&partial, target))
+            .map_err(|e| format!("ファイルのリネームに失敗: {} - {}", target.display(), e))
+    } else {
+        place_no_clobber(&partial, target)
+    };
+    if placed.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    placed
+}
+
+// 既存のファイルを上書きせずに一時ファイルを目的のパスへ移す
+// ハードリンクは同名のファイルがあれば失敗するため、確認と作成の間に割り込まれても上書きしない
+// ハードリンクに対応しないファイルシステム（FAT・一部のネットワークドライブ）では確認してからリネーム
+fn place_no_clobber(partial: &Path, target: &Path) -> Result<(), String> {
+    let exists_error = || format!("出力先に同名のファイルがあります: {}", target.display());
+    match retry_on_lock(|| fs::hard_link(partial, target)) {
+        Ok(()) => {
+            let _ = retry_on_lock(|| fs::remove_file(partial));
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Err(exists_error()),
+        Err(_) => {
+            if target.exists() {
+                return Err(exists_error());
+            }
+            retry_on_lock(|| fs::rename(partial, target))
+                .map_err(|e| format!("ファイルのリネームに失敗: {} - {}", target.display(), e))
+        }
+    }
+}
+
+// 一時ファイルに書き込んでから目的のパスへ移す（同名のファイルがあればエラー）
+pub fn write_exclusive(
+    target: &Path,
+    write: impl FnOnce(&mut fs::File) -> Result<(), String>,
+) -> Result<(), String> {
+    write_via_partial(target, false, write)
+}
+
+// 一時ファイルに書き込んでから目的のパスを置き換える（書き込み途中で中断しても元のファイルは壊れない）
+pub fn write_replacing(
+    target: &Path,
+    write: impl FnOnce(&mut fs::File) -> Result<(), String>,
+) -> Result<(), String> {
+    write_via_partial(target, true, write)
+}
+
+// 排他作成＋リトライでファイルをコピー
+pub fn copy_exclusive(source: &Path, target: &Path) -> Result<(), String> {
+    write_exclusive(target, |file| {
+        let mut input = retry_on_lock(|| fs::File::open(source)).map_err(|e| e.to_string())?;
+        std::io::copy(&mut input, file).map(|_| ()).map_err(|e| e.to_string())
+    })
+}
+
+// フォルダを隠し属性に設定（Windowsのみ。他OSはドット始まりの名前で隠れる）
+pub fn mark_hidden(dir: &Path) {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+        // コンソールウィンドウは表示しない
+        let _ = std::process::Command::new("attrib")
+            .arg("+H")
+            .arg(dir)
+            .creation_flags(CREATE_NO_WINDOW)
+            .status();
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = dir;
+    }
+}
+
+// 出力フォルダをインデックス対象外に設定（Windowsのみ）
+// ウイルス対策のスキャン自体は止められないが、インデクサーによるロックを減らす
+pub fn mark_folder_scan_deferred(dir: &Path) {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+        // FILE_ATTRIBUTE_NOT_CONTENT_INDEXED を付与（コンソールウィンドウは表示しない）
+        let _ = std::process::Command::new("attrib")
+            .arg("+I")
+            .arg(dir)
+            .creation_flags(CREATE_NO_WINDOW)
+            .status();
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = dir;
+    }
+}
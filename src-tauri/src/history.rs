@@ -0,0 +1,143 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use crate::app_mode::is_safe_mode;
+use crate::file_utils::{get_config_path, path_modified_millis};
+use crate::types::{ExportHistoryEntry, ExportPage, ExportPageDiff, PageSnapshot};
+
+// 保持する履歴の上限（超えたら古いものから削除）
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+// 同じミリ秒に払い出したIDを区別する連番
+static HISTORY_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// 追記と古い履歴の削除が重ならないよう直列化
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+fn history_path() -> Result<PathBuf, String> {
+    Ok(get_config_path()?.join("export_history.jsonl"))
+}
+
+// 新しい履歴IDを払い出す（開始日時ベース）
+// 連続したエクスポートや複数のプロセス（ファームのワーカー）でも重ならないよう、プロセスIDと連番を付ける
+pub fn new_history_id() -> String {
+    format!(
+        "{}-{}-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S-%3f"),
+        std::process::id(),
+        HISTORY_ID_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+// 出力時点のページの状態を記録
+pub fn snapshot_pages(pages: &[ExportPage]) -> Vec<PageSnapshot> {
+    pages
+        .iter()
+        .map(|page| PageSnapshot {
+            page_id: page.page_id.clone(),
+            output_name: page.output_name.clone(),
+            source_path: page.source_path.clone(),
+            modified_time: page
+                .source_path
+                .as_deref()
+                .map(|p| path_modified_millis(Path::new(p)))
+                .unwrap_or(0),
+        })
+        .collect()
+}
+
+// 履歴に1件追記（JSON Lines 形式、セーフモードでは記録しない）
+pub fn record_history(entry: &ExportHistoryEntry) {
+    if is_safe_mode() {
+        return;
+    }
+    let written = (|| -> Result<(), String> {
+        let path = history_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+        let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| e.to_string())?;
+        writeln!(file, "{}", line).map_err(|e| e.to_string())?;
+        drop(file);
+        trim_history(&path)
+    })();
+
+    if let Err(e) = written {
+        eprintln!("エクスポート履歴の書き込みに失敗: {}", e);
+    }
+}
+
+// 上限を超えた古い履歴を削除（一時ファイルに書いてから置き換え）
+fn trim_history(path: &Path) -> Result<(), String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let lines: Vec<String> = BufReader::new(file).lines().map_while(Result::ok).collect();
+    if lines.len() <= MAX_HISTORY_ENTRIES {
+        return Ok(());
+    }
+    let kept = &lines[lines.len() - MAX_HISTORY_ENTRIES..];
+    let temp_path = path.with_extension("jsonl.tmp");
+    fs::write(&temp_path, kept.join("\n") + "\n").map_err(|e| e.to_string())?;
+    fs::rename(&temp_path, path).map_err(|e| e.to_string())
+}
+
+// 履歴を新しい順に読み込み
+pub fn read_history() -> Result<Vec<ExportHistoryEntry>, String> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file = fs::File::open(&path).map_err(|e| format!("読み込みエラー: {}", e))?;
+    let mut entries: Vec<ExportHistoryEntry> = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect();
+    entries.reverse();
+    Ok(entries)
+}
+
+// IDで履歴を検索
+pub fn find_history(id: &str) -> Result<ExportHistoryEntry, String> {
+    read_history()?
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| format!("履歴が見つかりません: {}", id))
+}
+
+// ページの照合キー（ページIDがなければ出力名）
+fn snapshot_key(page: &PageSnapshot) -> String {
+    page.page_id.clone().unwrap_or_else(|| page.output_name.clone())
+}
+
+// 履歴の時点と現在のページを比較
+pub fn diff_pages(before: &[PageSnapshot], after: &[PageSnapshot]) -> ExportPageDiff {
+    let mut diff = ExportPageDiff::default();
+    for page in after {
+        let key = snapshot_key(page);
+        match before.iter().find(|b| snapshot_key(b) == key) {
+            None => diff.added.push(key),
+            Some(old) => {
+                if old.source_path != page.source_path || old.modified_time != page.modified_time {
+                    diff.modified.push(key.clone());
+                }
+                if old.output_name != page.output_name {
+                    diff.renamed.push(key);
+                }
+            }
+        }
+    }
+    diff.removed = before
+        .iter()
+        .map(snapshot_key)
+        .filter(|key| !after.iter().any(|p| &snapshot_key(p) == key))
+        .collect();
+    diff
+}
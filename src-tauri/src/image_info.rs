@@ -0,0 +1,265 @@
+use std::fs;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use crate::image_utils::{is_photoshop_ext, read_image_dimensions};
+
+// PSDのイメージリソースID: 解像度情報
+const PSD_RESOLUTION_INFO: u16 = 0x03ED;
+
+// 画像全体をデコードせずに読み取れる情報（プリフライト用）
+#[derive(Debug, Clone, Default)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    /// 埋め込まれた解像度 (ppi)。記録がなければ None
+    pub dpi: Option<f64>,
+    /// "bitmap" | "grayscale" | "indexed" | "rgb" | "cmyk" | "lab" | "other"。判定できなければ None
+    pub color_mode: Option<String>,
+}
+
+fn read_u16_be<R: Read>(reader: &mut R) -> Option<u16> {
+    let mut buf = [0u8; 2];
+    reader.read_exact(&mut buf).ok()?;
+    Some(u16::from_be_bytes(buf))
+}
+
+fn read_u32_be<R: Read>(reader: &mut R) -> Option<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).ok()?;
+    Some(u32::from_be_bytes(buf))
+}
+
+// PSD/PSB: ヘッダーのカラーモードと、イメージリソースの解像度情報
+fn read_psd_info(path: &Path) -> Option<(Option<f64>, Option<String>)> {
+    let mut reader = BufReader::new(fs::File::open(path).ok()?);
+    let mut header = [0u8; 26];
+    reader.read_exact(&mut header).ok()?;
+    if &header[0..4] != b"8BPS" {
+        return None;
+    }
+    let color_mode = match u16::from_be_bytes([header[24], header[25]]) {
+        0 => "bitmap",
+        1 | 8 => "grayscale",
+        2 => "indexed",
+        3 => "rgb",
+        4 => "cmyk",
+        9 => "lab",
+        _ => "other",
+    };
+
+    // カラーモードデータを読み飛ばしてイメージリソースを走査
+    let color_data_len = read_u32_be(&mut reader)?;
+    reader.seek(SeekFrom::Current(color_data_len as i64)).ok()?;
+    let resources_len = read_u32_be(&mut reader)? as u64;
+    let mut consumed = 0u64;
+    let mut dpi = None;
+    while consumed + 12 <= resources_len {
+        let mut signature = [0u8; 4];
+        reader.read_exact(&mut signature).ok()?;
+        if &signature != b"8BIM" {
+            break;
+        }
+        let id = read_u16_be(&mut reader)?;
+        // 名前はパスカル文字列（長さバイトを含めて偶数に揃える）
+        let name_len = {
+            let mut len = [0u8; 1];
+            reader.read_exact(&mut len).ok()?;
+            len[0] as u64
+        };
+        let name_padded = (name_len + 1).div_ceil(2) * 2 - 1;
+        reader.seek(SeekFrom::Current(name_padded as i64)).ok()?;
+        let size = read_u32_be(&mut reader)? as u64;
+        let size_padded = size.div_ceil(2) * 2;
+
+        if id == PSD_RESOLUTION_INFO && size >= 4 {
+            // 水平解像度は 16.16 固定小数点 (ppi)
+            let fixed = read_u32_be(&mut reader)?;
+            dpi = Some(fixed as f64 / 65536.0);
+            break;
+        }
+        reader.seek(SeekFrom::Current(size_padded as i64)).ok()?;
+        consumed += 4 + 2 + 1 + name_padded + 4 + size_padded;
+    }
+
+    Some((dpi, Some(color_mode.to_string())))
+}
+
+// JPEG: JFIFの密度とSOFの色成分数
+fn read_jpeg_info(path: &Path) -> Option<(Option<f64>, Option<String>)> {
+    let mut reader = BufReader::new(fs::File::open(path).ok()?);
+    if read_u16_be(&mut reader)? != 0xFFD8 {
+        return None;
+    }
+
+    let mut dpi = None;
+    loop {
+        let marker = read_u16_be(&mut reader)?;
+        if marker & 0xFF00 != 0xFF00 {
+            return None;
+        }
+        let length = read_u16_be(&mut reader)?.checked_sub(2)? as usize;
+        match marker {
+            // APP0 (JFIF): 単位 1 = dpi, 2 = dpcm
+            0xFFE0 if length >= 12 => {
+                let mut segment = vec![0u8; length];
+                reader.read_exact(&mut segment).ok()?;
+                if &segment[0..5] == b"JFIF\0" {
+                    let density = u16::from_be_bytes([segment[8], segment[9]]) as f64;
+                    dpi = match segment[7] {
+                        1 if density > 0.0 => Some(density),
+                        2 if density > 0.0 => Some(density * 2.54),
+                        _ => None,
+                    };
+                }
+            }
+            // SOF0〜SOF15（DHT・JPG・DACを除く）
+            0xFFC0..=0xFFCF if !matches!(marker, 0xFFC4 | 0xFFC8 | 0xFFCC) => {
+                let mut segment = vec![0u8; length];
+                reader.read_exact(&mut segment).ok()?;
+                let color_mode = match segment.get(5)? {
+                    1 => "grayscale",
+                    3 => "rgb",
+                    4 => "cmyk",
+                    _ => "other",
+                };
+                return Some((dpi, Some(color_mode.to_string())));
+            }
+            _ => {
+                reader.seek(SeekFrom::Current(length as i64)).ok()?;
+            }
+        }
+    }
+}
+
+// PNG: IHDRのカラータイプとpHYsの解像度
+fn read_png_info(path: &Path) -> Option<(Option<f64>, Option<String>)> {
+    let mut reader = BufReader::new(fs::File::open(path).ok()?);
+    let mut signature = [0u8; 8];
+    reader.read_exact(&mut signature).ok()?;
+    if &signature != b"\x89PNG\r\n\x1a\n" {
+        return None;
+    }
+
+    let mut dpi = None;
+    let mut color_mode = None;
+    loop {
+        let length = read_u32_be(&mut reader)? as usize;
+        let mut chunk_type = [0u8; 4];
+        reader.read_exact(&mut chunk_type).ok()?;
+        match &chunk_type {
+            b"IHDR" | b"pHYs" => {
+                let mut data = vec![0u8; length];
+                reader.read_exact(&mut data).ok()?;
+                reader.seek(SeekFrom::Current(4)).ok()?;
+                if &chunk_type == b"IHDR" && data.len() >= 10 {
+                    color_mode = Some(match (data[8], data[9]) {
+                        (1, 0) => "bitmap",
+                        (_, 0) | (_, 4) => "grayscale",
+                        (_, 3) => "indexed",
+                        _ => "rgb",
+                    });
+                } else if data.len() >= 9 && data[8] == 1 {
+                    // 単位はピクセル/メートル
+                    let ppm = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as f64;
+                    dpi = Some(ppm * 0.0254);
+                }
+            }
+            b"IDAT" | b"IEND" => break,
+            _ => {
+                reader.seek(SeekFrom::Current(length as i64 + 4)).ok()?;
+            }
+        }
+    }
+    Some((dpi, color_mode.map(str::to_string)))
+}
+
+// EXIF（TIFFのIFD0を含む）の解像度と色の表現
+fn read_exif_info(path: &Path) -> Option<(Option<f64>, Option<String>)> {
+    let file = fs::File::open(path).ok()?;
+    let exif = exif::Reader::new().read_from_container(&mut BufReader::new(file)).ok()?;
+    let field = |tag| exif.get_field(tag, exif::In::PRIMARY);
+
+    let resolution = field(exif::Tag::XResolution).and_then(|f| match f.value {
+        exif::Value::Rational(ref v) if !v.is_empty() => Some(v[0].to_f64()),
+        _ => None,
+    });
+    let unit = field(exif::Tag::ResolutionUnit).and_then(|f| f.value.get_uint(0)).unwrap_or(2);
+    let dpi = resolution.filter(|r| *r > 0.0).map(|r| if unit == 3 { r * 2.54 } else { r });
+
+    let bits = field(exif::Tag::BitsPerSample).and_then(|f| f.value.get_uint(0)).unwrap_or(8);
+    let color_mode = field(exif::Tag::PhotometricInterpretation)
+        .and_then(|f| f.value.get_uint(0))
+        .map(|photometric| match photometric {
+            0 | 1 if bits == 1 => "bitmap",
+            0 | 1 => "grayscale",
+            2 | 6 => "rgb",
+            3 => "indexed",
+            5 => "cmyk",
+            8 | 9 | 10 => "lab",
+            _ => "other",
+        })
+        .map(str::to_string);
+
+    Some((dpi, color_mode))
+}
+
+// 1チャンネルあたりのビット深度をヘッダーから読み取る（読めない・混在する形式は None）
+pub fn read_bit_depth(path: &Path) -> Option<u8> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    match ext.as_str() {
+        // PSD/PSB: ヘッダーのオフセット22にビット深度
+        e if is_photoshop_ext(e) => {
+            let mut header = [0u8; 24];
+            fs::File::open(path).ok()?.read_exact(&mut header).ok()?;
+            (&header[0..4] == b"8BPS").then(|| u16::from_be_bytes([header[22], header[23]]) as u8)
+        }
+        // PNG: IHDRのビット深度（シグネチャ8 + 長さ4 + 種類4 + 幅4 + 高さ4 の次）
+        "png" => {
+            let mut header = [0u8; 25];
+            fs::File::open(path).ok()?.read_exact(&mut header).ok()?;
+            (&header[12..16] == b"IHDR").then_some(header[24])
+        }
+        "tif" | "tiff" => {
+            let file = fs::File::open(path).ok()?;
+            let mut decoder = tiff::decoder::Decoder::new(BufReader::new(file)).ok()?;
+            Some(decoder.colortype().ok()?.bit_depth())
+        }
+        "jpg" | "jpeg" | "webp" => Some(8),
+        _ => None,
+    }
+}
+
+// 寸法・解像度・カラーモードを読み取る（全体のデコードは行わない）
+pub fn read_image_info(path: &Path) -> Result<ImageInfo, String> {
+    let (width, height) = read_image_dimensions(path)?;
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let (dpi, color_mode) = match ext.as_str() {
+        e if is_photoshop_ext(e) => read_psd_info(path).unwrap_or_default(),
+        "jpg" | "jpeg" => {
+            let (jfif_dpi, color_mode) = read_jpeg_info(path).unwrap_or_default();
+            // JFIFに単位がなければEXIFの解像度を使う
+            let dpi = jfif_dpi.or_else(|| read_exif_info(path).and_then(|(dpi, _)| dpi));
+            (dpi, color_mode)
+        }
+        "png" => read_png_info(path).unwrap_or_default(),
+        "tif" | "tiff" => read_exif_info(path).unwrap_or_default(),
+        _ => (None, None),
+    };
+
+    Ok(ImageInfo {
+        width,
+        height,
+        dpi,
+        color_mode,
+    })
+}
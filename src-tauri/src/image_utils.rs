@@ -1,49 +1,133 @@
-use std::io::Cursor;
-use image::{DynamicImage, ImageFormat};
-use crate::constants::{MAX_IMAGE_DIMENSION, MAX_PIXEL_COUNT, THUMBNAIL_SIZE};
+use std::fs;
+use std::io::{BufReader, BufWriter, Cursor, Seek, Write};
+use std::path::Path;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat};
+use std::sync::RwLock;
+use crate::color::{cmyk_to_rgb, convert_to_srgb};
+use crate::constants::THUMBNAIL_SIZE;
+use crate::types::{ImageLimitOverrides, ImageLimits};
+use crate::memory_budget::{reserve_decode_memory, MemoryPermit};
+use crate::pdf::{pdf_page_dimensions, render_pdf_page, PDF_RENDER_DPI};
+
+// 画像サイズの上限を適用する処理の種類（設定で処理ごとに上限を変えられる）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageOperation {
+    General,
+    Thumbnail,
+    Export,
+    Cover,
+}
+
+// 設定の上限（起動時・設定保存時に反映、未反映なら既定値）
+static IMAGE_LIMITS: RwLock<Option<(ImageLimits, ImageLimitOverrides)>> = RwLock::new(None);
+
+pub fn set_image_limits(limits: ImageLimits, overrides: ImageLimitOverrides) {
+    *IMAGE_LIMITS.write().unwrap_or_else(|e| e.into_inner()) = Some((limits, overrides));
+}
+
+// 処理に適用する上限
+pub fn image_limits(operation: ImageOperation) -> ImageLimits {
+    let guard = IMAGE_LIMITS.read().unwrap_or_else(|e| e.into_inner());
+    let Some((base, ref overrides)) = *guard else {
+        return ImageLimits::default();
+    };
+    let specific = match operation {
+        ImageOperation::General => None,
+        ImageOperation::Thumbnail => overrides.thumbnail,
+        ImageOperation::Export => overrides.export,
+        ImageOperation::Cover => overrides.cover,
+    };
+    specific.unwrap_or(base)
+}
 
 // 画像サイズ検証（DoS防止）
-pub fn validate_dimensions(width: u32, height: u32) -> Result<(), String> {
+pub fn validate_dimensions_within(width: u32, height: u32, limits: &ImageLimits) -> Result<(), String> {
     if width == 0 || height == 0 {
         return Err("無効な画像サイズ: 幅または高さが0".to_string());
     }
-    if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+    if width > limits.max_dimension || height > limits.max_dimension {
         return Err(format!(
             "画像サイズが大きすぎます: {}x{} (最大: {})",
-            width, height, MAX_IMAGE_DIMENSION
+            width, height, limits.max_dimension
         ));
     }
     let pixel_count = (width as u64) * (height as u64);
-    if pixel_count > MAX_PIXEL_COUNT {
+    if pixel_count > limits.max_pixels {
         return Err(format!(
             "ピクセル数が多すぎます: {} (最大: {})",
-            pixel_count, MAX_PIXEL_COUNT
+            pixel_count, limits.max_pixels
         ));
     }
     Ok(())
 }
 
+// 処理ごとの上限で画像サイズを検証
+pub fn validate_dimensions_for(operation: ImageOperation, width: u32, height: u32) -> Result<(), String> {
+    validate_dimensions_within(width, height, &image_limits(operation))
+}
+
 // ファイルタイプを取得
 pub fn get_file_type(ext: &str) -> Option<&'static str> {
     match ext.to_lowercase().as_str() {
         "jpg" | "jpeg" => Some("jpg"),
         "png" => Some("png"),
-        "psd" => Some("psd"),
+        // PSB（大きなドキュメント形式）もPSDとして扱う
+        "psd" | "psb" => Some("psd"),
         "tif" | "tiff" => Some("tif"),
+        "jxl" => Some("jxl"),
+        "webp" => Some("webp"),
+        "pdf" => Some("pdf"),
         _ => None,
     }
 }
 
+// Photoshopドキュメント（PSD/PSB）の拡張子か（小文字で渡す）
+pub fn is_photoshop_ext(ext: &str) -> bool {
+    matches!(ext, "psd" | "psb")
+}
+
+// EXIFの向き（Orientation タグ）を読み取り（記録がない・読めない場合は回転なし）
+// スマートフォンで撮影した資料のJPEGなど。ヘッダーだけ読むため画像全体はデコードしない
+pub fn read_orientation(path: &Path) -> Orientation {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    if !matches!(ext.as_str(), "jpg" | "jpeg" | "tif" | "tiff" | "png" | "webp") {
+        return Orientation::NoTransforms;
+    }
+    image::ImageReader::open(path)
+        .ok()
+        .and_then(|reader| reader.with_guessed_format().ok())
+        .and_then(|reader| reader.into_decoder().ok())
+        .and_then(|mut decoder| decoder.orientation().ok())
+        .unwrap_or(Orientation::NoTransforms)
+}
+
+// 縦横が入れ替わる向きか
+pub fn orientation_swaps_axes(orientation: Orientation) -> bool {
+    matches!(
+        orientation,
+        Orientation::Rotate90 | Orientation::Rotate270 | Orientation::Rotate90FlipH | Orientation::Rotate270FlipH
+    )
+}
+
 // 画像をサムネイルに変換（高画質PNG版）
-pub fn create_thumbnail(img: DynamicImage) -> Result<Vec<u8>, String> {
+// 縮小してから EXIF の向きに合わせて回転・反転する
+pub fn create_thumbnail(img: DynamicImage, orientation: Orientation) -> Result<Vec<u8>, String> {
     use image::imageops::FilterType;
 
+    // 回転後に縦長の枠へ収まるよう、縦横が入れ替わる場合は枠も入れ替えて縮小
+    let (max_width, max_height) = if orientation_swaps_axes(orientation) {
+        (THUMBNAIL_SIZE * 14 / 10, THUMBNAIL_SIZE)
+    } else {
+        (THUMBNAIL_SIZE, THUMBNAIL_SIZE * 14 / 10)
+    };
     // Triangle: 高速なリサンプリングフィルタ（サムネイル用途では十分な品質）
-    let thumbnail = img.resize(
-        THUMBNAIL_SIZE,
-        THUMBNAIL_SIZE * 14 / 10,
-        FilterType::Triangle,
-    );
+    let mut thumbnail = img.resize(max_width, max_height, FilterType::Triangle);
+    thumbnail.apply_orientation(orientation);
 
     // PNG形式で出力（可逆圧縮で画質劣化なし）
     let mut buffer = Cursor::new(Vec::new());
@@ -53,3 +137,403 @@ pub fn create_thumbnail(img: DynamicImage) -> Result<Vec<u8>, String> {
 
     Ok(buffer.into_inner())
 }
+
+// PSDのイメージリソースID: ICCプロファイル
+const PSD_ICC_PROFILE: u16 = 1039;
+
+// PSDのイメージリソースから指定IDのデータを取り出す
+fn psd_resource(data: &[u8], resource_id: u16) -> Option<&[u8]> {
+    let read_u32 = |pos: usize| data.get(pos..pos + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize);
+    // ヘッダー(26) + カラーモードデータ
+    let color_data_len = read_u32(26)?;
+    let resources_start = 30 + color_data_len + 4;
+    let resources_end = resources_start.checked_add(read_u32(30 + color_data_len)?)?.min(data.len());
+
+    let mut pos = resources_start;
+    while pos + 12 <= resources_end {
+        if data.get(pos..pos + 4)? != b"8BIM" {
+            return None;
+        }
+        let id = u16::from_be_bytes([data[pos + 4], data[pos + 5]]);
+        // 名前はパスカル文字列（長さバイトを含めて偶数に揃える）
+        let name_len = *data.get(pos + 6)? as usize;
+        let size_pos = pos + 6 + (name_len + 2) / 2 * 2;
+        let size = read_u32(size_pos)?;
+        let start = size_pos + 4;
+        if id == resource_id {
+            return data.get(start..start + size);
+        }
+        pos = start + size.div_ceil(2) * 2;
+    }
+    None
+}
+
+// PSDデータからコンポジット画像をデコード（埋め込みプロファイルがあればsRGBに変換）
+pub fn decode_psd(data: &[u8], operation: ImageOperation) -> Result<DynamicImage, String> {
+    let psd_file = psd::Psd::from_bytes(data)
+        .map_err(|e| format!("PSD読み込みエラー: {:?}", e))?;
+
+    let width = psd_file.width();
+    let height = psd_file.height();
+
+    // 画像サイズ検証（DoS防止）
+    validate_dimensions_for(operation, width, height)?;
+
+    let rgba = psd_file.rgba();
+
+    let img = DynamicImage::ImageRgba8(
+        image::RgbaImage::from_raw(width, height, rgba)
+            .ok_or("画像データの変換に失敗")?
+    );
+    Ok(match psd_resource(data, PSD_ICC_PROFILE) {
+        Some(icc_profile) => convert_to_srgb(img, icc_profile),
+        None => img,
+    })
+}
+
+// 透明部分を白で合成（PSDの統合画像をJPG等に変換する際、透明部分が黒くならないように）
+pub fn flatten_on_white(img: &DynamicImage) -> DynamicImage {
+    if !img.color().has_alpha() {
+        return img.clone();
+    }
+    let mut rgba = img.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        let alpha = pixel[3] as u32;
+        for channel in pixel.0.iter_mut().take(3) {
+            *channel = ((*channel as u32 * alpha + 255 * (255 - alpha) + 127) / 255) as u8;
+        }
+    }
+    DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(rgba).to_rgb8())
+}
+
+// JPEG XLをデコード
+fn decode_jxl(path: &Path, limits: &ImageLimits) -> Result<DynamicImage, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let decoder = jxl_oxide::integration::JxlDecoder::new(file)
+        .map_err(|e| format!("JXL読み込みエラー: {}", e))?;
+
+    let (width, height) = image::ImageDecoder::dimensions(&decoder);
+    validate_dimensions_within(width, height, limits)?;
+
+    DynamicImage::from_decoder(decoder).map_err(|e| format!("JXL読み込みエラー: {}", e))
+}
+
+// ICCプロファイルのタグ (InterColorProfile)
+const TIFF_TAG_ICC_PROFILE: u16 = 34675;
+
+// CMYKのTIFF（Photoshopから書き出した入稿データなど）を読み込むデコーダー。CMYKでなければ None
+fn open_cmyk_tiff(path: &Path) -> Option<tiff::decoder::Decoder<BufReader<fs::File>>> {
+    let file = fs::File::open(path).ok()?;
+    let mut decoder = tiff::decoder::Decoder::new(BufReader::new(file)).ok()?;
+    match decoder.colortype() {
+        Ok(tiff::ColorType::CMYK(8 | 16)) => Some(decoder),
+        _ => None,
+    }
+}
+
+// CMYKのTIFFをデコードしてRGBに変換（埋め込みプロファイルがあればそれで変換）
+// image クレートはCMYKのTIFFを読めないため tiff クレートで直接読む
+fn decode_cmyk_tiff(
+    mut decoder: tiff::decoder::Decoder<BufReader<fs::File>>,
+    limits: &ImageLimits,
+) -> Result<DynamicImage, String> {
+    use tiff::decoder::DecodingResult;
+
+    let (width, height) = decoder.dimensions().map_err(|e| format!("TIFF読み込みエラー: {}", e))?;
+    validate_dimensions_within(width, height, limits)?;
+
+    // 既定の上限（256MB）では入稿サイズのCMYKを読めないため、設定の上限に合わせる（16bit CMYKまで）
+    let mut decode_limits = tiff::decoder::Limits::default();
+    decode_limits.decoding_buffer_size = limits.max_pixels.saturating_mul(8) as usize;
+    decode_limits.intermediate_buffer_size = decode_limits.decoding_buffer_size;
+    let mut decoder = decoder.with_limits(decode_limits);
+
+    let icc_profile = decoder
+        .get_tag_u8_vec(tiff::tags::Tag::from_u16_exhaustive(TIFF_TAG_ICC_PROFILE))
+        .ok();
+    let cmyk = match decoder.read_image().map_err(|e| format!("TIFF読み込みエラー: {}", e))? {
+        DecodingResult::U8(data) => data,
+        // 16bitは上位8bitを使う
+        DecodingResult::U16(data) => data.into_iter().map(|v| (v >> 8) as u8).collect(),
+        _ => return Err("対応していないCMYKのビット深度です".to_string()),
+    };
+    if cmyk.len() < width as usize * height as usize * 4 {
+        return Err("CMYKのTIFFを読み込めません（チャンネル分割の形式には対応していません）".to_string());
+    }
+
+    let rgb = cmyk_to_rgb(&cmyk[..width as usize * height as usize * 4], icc_profile.as_deref());
+    image::RgbImage::from_raw(width, height, rgb)
+        .map(DynamicImage::ImageRgb8)
+        .ok_or_else(|| "画像データの変換に失敗".to_string())
+}
+
+// 16bitの画素をエンコーダーに渡すバイト列にする（1サンプル2バイト、ネイティブエンディアン）
+fn samples_to_ne_bytes(samples: &[u16]) -> Vec<u8> {
+    samples.iter().flat_map(|s| s.to_ne_bytes()).collect()
+}
+
+// JPEG XLのロスレスエンコード（アーカイブ用マスター）
+// 16bit原稿は16bitのまま保存する（浮動小数点の画像は16bitにする）
+fn encode_jxl<W: Write>(img: &DynamicImage, writer: &mut W) -> Result<(), String> {
+    use zune_core::bit_depth::BitDepth;
+    use zune_core::colorspace::ColorSpace;
+    use zune_core::options::EncoderOptions;
+
+    // グレースケール原稿はグレーのまま保存（容量削減）
+    let (pixels, colorspace, depth) = match img {
+        DynamicImage::ImageLuma8(b) => (b.as_raw().clone(), ColorSpace::Luma, BitDepth::Eight),
+        DynamicImage::ImageLumaA8(b) => (b.as_raw().clone(), ColorSpace::LumaA, BitDepth::Eight),
+        DynamicImage::ImageRgba8(b) => (b.as_raw().clone(), ColorSpace::RGBA, BitDepth::Eight),
+        DynamicImage::ImageLuma16(b) => (samples_to_ne_bytes(b.as_raw()), ColorSpace::Luma, BitDepth::Sixteen),
+        DynamicImage::ImageLumaA16(b) => (samples_to_ne_bytes(b.as_raw()), ColorSpace::LumaA, BitDepth::Sixteen),
+        DynamicImage::ImageRgb16(b) => (samples_to_ne_bytes(b.as_raw()), ColorSpace::RGB, BitDepth::Sixteen),
+        DynamicImage::ImageRgba16(b) => (samples_to_ne_bytes(b.as_raw()), ColorSpace::RGBA, BitDepth::Sixteen),
+        DynamicImage::ImageRgba32F(_) => (samples_to_ne_bytes(img.to_rgba16().as_raw()), ColorSpace::RGBA, BitDepth::Sixteen),
+        DynamicImage::ImageRgb32F(_) => (samples_to_ne_bytes(img.to_rgb16().as_raw()), ColorSpace::RGB, BitDepth::Sixteen),
+        other => (other.to_rgb8().into_raw(), ColorSpace::RGB, BitDepth::Eight),
+    };
+
+    let options = EncoderOptions::new(
+        img.width() as usize,
+        img.height() as usize,
+        colorspace,
+        depth,
+    );
+    let data = zune_jpegxl::JxlSimpleEncoder::new(&pixels, options)
+        .encode()
+        .map_err(|e| format!("JXL書き出しエラー: {:?}", e))?;
+
+    writer.write_all(&data).map_err(|e| e.to_string())
+}
+
+// メモリ枠を確保してから読み込み（並列処理用。枠は返り値を破棄するまで保持）
+// 1スレッドで複数の枠を同時に持つと他スレッドと待ち合って止まるため、同時に1枚だけ読む処理で使う
+pub fn open_image_budgeted(path: &Path, operation: ImageOperation) -> Result<(DynamicImage, MemoryPermit), String> {
+    open_image_page_budgeted(path, 0, operation)
+}
+
+// ページを指定してメモリ枠を確保してから読み込み（PDFは pdf_page のページ、他の形式では無視）
+pub fn open_image_page_budgeted(
+    path: &Path,
+    pdf_page: usize,
+    operation: ImageOperation,
+) -> Result<(DynamicImage, MemoryPermit), String> {
+    let permit = reserve_decode_memory(path);
+    let img = open_image_page_for(path, pdf_page, operation)?;
+    Ok((img, permit))
+}
+
+// 画像ファイルを読み込み（PSDはコンポジット画像を使用）
+pub fn open_image(path: &Path) -> Result<DynamicImage, String> {
+    open_image_for(path, ImageOperation::General)
+}
+
+// 処理ごとのサイズ上限で画像ファイルを読み込み（PDFは1ページ目）
+pub fn open_image_for(path: &Path, operation: ImageOperation) -> Result<DynamicImage, String> {
+    open_image_page_for(path, 0, operation)
+}
+
+// 処理ごとのサイズ上限で画像ファイルを読み込み（PDFは pdf_page のページ（0始まり）をレンダリング）
+pub fn open_image_page_for(path: &Path, pdf_page: usize, operation: ImageOperation) -> Result<DynamicImage, String> {
+    let limits = image_limits(operation);
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    if ext == "psd" {
+        let data = fs::read(path).map_err(|e| e.to_string())?;
+        return decode_psd(&data, operation);
+    }
+    if ext == "psb" {
+        return Err("PSBファイルは直接読み込めません（Photoshopで変換してください）".to_string());
+    }
+    if ext == "jxl" {
+        return decode_jxl(path, &limits);
+    }
+    if ext == "pdf" {
+        // PDFは選択したページを入稿解像度でレンダリング
+        return render_pdf_page(path, pdf_page, PDF_RENDER_DPI, None, Some(&limits));
+    }
+    if matches!(ext.as_str(), "tif" | "tiff") {
+        if let Some(decoder) = open_cmyk_tiff(path) {
+            return decode_cmyk_tiff(decoder, &limits);
+        }
+    }
+
+    // image クレート既定の上限（確保512MBまで）では大きな表紙を読めないため、設定の上限に合わせる
+    let mut reader = image::ImageReader::open(path).map_err(|e| format!("画像読み込みエラー: {}", e))?;
+    let mut decode_limits = image::Limits::default();
+    decode_limits.max_image_width = Some(limits.max_dimension);
+    decode_limits.max_image_height = Some(limits.max_dimension);
+    // 16bit RGBA (8 bytes/px) まで
+    decode_limits.max_alloc = Some(limits.max_pixels.saturating_mul(8));
+    reader.limits(decode_limits);
+    let mut decoder = reader.into_decoder().map_err(|e| format!("画像読み込みエラー: {}", e))?;
+    let icc_profile = decoder.icc_profile().ok().flatten();
+    let img = DynamicImage::from_decoder(decoder).map_err(|e| format!("画像読み込みエラー: {}", e))?;
+    validate_dimensions_within(img.width(), img.height(), &limits)?;
+    // 埋め込みプロファイル（AdobeRGBなど）からsRGBに変換
+    Ok(match icc_profile {
+        Some(icc_profile) => convert_to_srgb(img, &icc_profile),
+        None => img,
+    })
+}
+
+// 画像書き出し時のエンコード設定
+#[derive(Debug, Clone)]
+pub struct EncodeOptions {
+    pub jpg_quality: u8,
+    // JPEGのクロマサブサンプリング ("444" | "422" | "420")
+    pub chroma_subsampling: String,
+}
+
+impl EncodeOptions {
+    pub fn with_quality(jpg_quality: u8) -> Self {
+        Self {
+            jpg_quality,
+            chroma_subsampling: "444".to_string(),
+        }
+    }
+}
+
+// クロマサブサンプリング指定を検証
+pub fn parse_chroma_subsampling(value: &str) -> Result<jpeg_encoder::SamplingFactor, String> {
+    use jpeg_encoder::SamplingFactor;
+    match value {
+        "444" | "4:4:4" => Ok(SamplingFactor::R_4_4_4),
+        "422" | "4:2:2" => Ok(SamplingFactor::R_4_2_2),
+        "420" | "4:2:0" => Ok(SamplingFactor::R_4_2_0),
+        _ => Err(format!("不明なクロマサブサンプリング: {}", value)),
+    }
+}
+
+// JPEGをエンコード（グレースケールはグレーのまま、アルファは破棄）
+// スクリーントーンや細線は4:2:0で劣化するため、サブサンプリングを明示的に指定する
+fn encode_jpeg<W: Write>(img: &DynamicImage, writer: &mut W, options: &EncodeOptions) -> Result<(), String> {
+    use jpeg_encoder::{ColorType, Encoder};
+
+    let (width, height) = (img.width(), img.height());
+    if width > u16::MAX as u32 || height > u16::MAX as u32 {
+        return Err(format!("JPEGの最大サイズを超えています: {}x{}", width, height));
+    }
+
+    let mut encoder = Encoder::new(writer, options.jpg_quality);
+    encoder.set_sampling_factor(parse_chroma_subsampling(&options.chroma_subsampling)?);
+
+    let result = match img {
+        DynamicImage::ImageLuma8(b) => encoder.encode(b.as_raw(), width as u16, height as u16, ColorType::Luma),
+        DynamicImage::ImageLumaA8(_) | DynamicImage::ImageLuma16(_) | DynamicImage::ImageLumaA16(_) => {
+            let gray = img.to_luma8();
+            encoder.encode(gray.as_raw(), width as u16, height as u16, ColorType::Luma)
+        }
+        _ => {
+            let rgb = img.to_rgb8();
+            encoder.encode(rgb.as_raw(), width as u16, height as u16, ColorType::Rgb)
+        }
+    };
+
+    result.map_err(|e| format!("JPEG書き出しエラー: {}", e))
+}
+
+// 拡張子に応じた形式で画像をエンコード
+pub fn encode_image_with<W: Write + Seek>(
+    img: &DynamicImage,
+    writer: &mut W,
+    ext: &str,
+    options: &EncodeOptions,
+) -> Result<(), String> {
+    match ext.to_lowercase().as_str() {
+        "jpg" | "jpeg" => encode_jpeg(img, writer, options),
+        "tif" | "tiff" => img.write_to(writer, ImageFormat::Tiff).map_err(|e| e.to_string()),
+        "jxl" => encode_jxl(img, writer),
+        // image クレートのWebPエンコーダーはロスレスのみ（RGB/RGBAのみ対応）
+        "webp" => {
+            let rgba = DynamicImage::ImageRgba8(img.to_rgba8());
+            rgba.write_to(writer, ImageFormat::WebP).map_err(|e| e.to_string())
+        }
+        _ => img.write_to(writer, ImageFormat::Png).map_err(|e| e.to_string()),
+    }
+}
+
+// 拡張子に応じた形式で画像をエンコード（JPGは品質のみ指定）
+pub fn encode_image<W: Write + Seek>(
+    img: &DynamicImage,
+    writer: &mut W,
+    ext: &str,
+    jpg_quality: u8,
+) -> Result<(), String> {
+    encode_image_with(img, writer, ext, &EncodeOptions::with_quality(jpg_quality))
+}
+
+// 拡張子に応じて画像を保存
+pub fn save_image(img: &DynamicImage, path: &Path, jpg_quality: u8) -> Result<(), String> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("png")
+        .to_lowercase();
+
+    let mut file = BufWriter::new(fs::File::create(path).map_err(|e| e.to_string())?);
+    encode_image(img, &mut file, &ext, jpg_quality)?;
+    file.flush().map_err(|e| e.to_string())
+}
+
+// ミリメートルをピクセルに変換
+pub fn mm_to_px(mm: f64, dpi: u32) -> u32 {
+    (mm / 25.4 * dpi as f64).round().max(0.0) as u32
+}
+
+// 画像全体をデコードせずにサイズを取得（PSDはヘッダーから読み取り）
+pub fn read_image_dimensions(path: &Path) -> Result<(u32, u32), String> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    if is_photoshop_ext(&ext) {
+        use std::io::Read;
+        // PSBもヘッダーの配置はPSDと同じ
+        // シグネチャ(4) + バージョン(2) + 予約(6) + チャンネル数(2) + 高さ(4) + 幅(4)
+        let mut header = [0u8; 22];
+        fs::File::open(path)
+            .and_then(|mut f| f.read_exact(&mut header))
+            .map_err(|e| e.to_string())?;
+        if &header[0..4] != b"8BPS" {
+            return Err("PSDシグネチャが不正です".to_string());
+        }
+        let height = u32::from_be_bytes([header[14], header[15], header[16], header[17]]);
+        let width = u32::from_be_bytes([header[18], header[19], header[20], header[21]]);
+        return Ok((width, height));
+    }
+
+    if ext == "pdf" {
+        return pdf_page_dimensions(path, 0, PDF_RENDER_DPI);
+    }
+
+    if ext == "jxl" {
+        let file = fs::File::open(path).map_err(|e| e.to_string())?;
+        let decoder = jxl_oxide::integration::JxlDecoder::new(file)
+            .map_err(|e| format!("JXL読み込みエラー: {}", e))?;
+        return Ok(image::ImageDecoder::dimensions(&decoder));
+    }
+
+    image::image_dimensions(path).or_else(|e| {
+        // CMYKのTIFFは image クレートで開けないため tiff クレートで読む
+        open_cmyk_tiff(path)
+            .and_then(|mut decoder| decoder.dimensions().ok())
+            .ok_or_else(|| format!("画像読み込みエラー: {}", e))
+    })
+}
+
+// 加工済み画像の出力ファイル名（PSDは同名のPNGとして出力）
+pub fn processed_file_name(source: &Path) -> String {
+    match source.extension().and_then(|e| e.to_str()) {
+        Some(ext) if !is_photoshop_ext(&ext.to_lowercase()) => {
+            source.file_name().unwrap_or_default().to_string_lossy().to_string()
+        }
+        _ => format!("{}.png", source.file_stem().unwrap_or_default().to_string_lossy()),
+    }
+}
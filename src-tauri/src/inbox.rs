@@ -0,0 +1,229 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::audit::record_audit;
+use crate::constants::SUPPORTED_EXTENSIONS;
+use crate::file_utils::retry_on_lock;
+use crate::state::AppState;
+use crate::types::{InboxConfig, InboxLinkedEvent, InboxPlaceholder, InboxUnmatchedEvent};
+
+// 受け取りフォルダの監視（破棄されると監視スレッドを停止）
+pub struct InboxWatch {
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for InboxWatch {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// 監視スレッドを開始し、取り込み結果を指定ウィンドウに通知
+pub fn start_inbox_watch(app: AppHandle, window_label: String, config: InboxConfig) -> Result<InboxWatch, String> {
+    let inbox = PathBuf::from(&config.inbox_path);
+    if !inbox.is_dir() {
+        return Err(format!("受け取りフォルダが見つかりません: {}", config.inbox_path));
+    }
+    fs::create_dir_all(&config.assets_path).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    std::thread::spawn(move || {
+        let mut pending = config.placeholders.clone();
+        // 前回確認時のサイズ（書き込み途中のファイルを取り込まないよう、2回続けて同じなら完了とみなす）
+        let mut last_sizes: HashMap<PathBuf, u64> = HashMap::new();
+        // 通知済みの照合できなかったファイル（サイズが変わるまでは再通知しない）
+        let mut reported: HashSet<(PathBuf, u64)> = HashSet::new();
+        let interval = Duration::from_secs(config.poll_secs.max(1));
+
+        while !thread_stop.load(Ordering::Relaxed) {
+            let files = list_inbox_files(&inbox);
+            reported.retain(|entry| files.contains(entry));
+
+            for (path, size) in files {
+                if reported.contains(&(path.clone(), size)) {
+                    continue;
+                }
+                if last_sizes.get(&path) != Some(&size) {
+                    last_sizes.insert(path, size);
+                    continue;
+                }
+                last_sizes.remove(&path);
+
+                let result = ingest_file(&path, &config, &mut pending);
+                if !matches!(result, Ok(Some(_))) {
+                    reported.insert((path.clone(), size));
+                }
+                match result {
+                    Ok(Some(event)) => {
+                        record_audit(
+                            app.state::<AppState>().operator(),
+                            "inbox-move",
+                            &event.path,
+                            format!("{} から取り込み", path.display()),
+                            Ok(()),
+                        );
+                        let _ = app.emit_to(window_label.as_str(), "inbox-file-linked", event);
+                    }
+                    Ok(None) => {
+                        let _ = app.emit_to(
+                            window_label.as_str(),
+                            "inbox-file-unmatched",
+                            InboxUnmatchedEvent {
+                                path: path.to_string_lossy().to_string(),
+                                reason: "対応するページが見つかりません".to_string(),
+                            },
+                        );
+                    }
+                    Err(e) => {
+                        let _ = app.emit_to(
+                            window_label.as_str(),
+                            "inbox-file-unmatched",
+                            InboxUnmatchedEvent {
+                                path: path.to_string_lossy().to_string(),
+                                reason: e,
+                            },
+                        );
+                    }
+                }
+            }
+            std::thread::sleep(interval);
+        }
+    });
+
+    Ok(InboxWatch { stop })
+}
+
+// 受け取りフォルダ直下の対応画像とサイズを列挙
+fn list_inbox_files(inbox: &Path) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(inbox) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            let ext = path.extension()?.to_str()?.to_lowercase();
+            if !SUPPORTED_EXTENSIONS.contains(&ext.as_str()) {
+                return None;
+            }
+            let metadata = e.metadata().ok()?;
+            metadata.is_file().then(|| (path, metadata.len()))
+        })
+        .collect()
+}
+
+// 比較用にファイル名を正規化（大文字小文字・区切り文字の違いを無視）
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+// ファイル名末尾の数字（"p012" → 12）
+fn trailing_number(name: &str) -> Option<u32> {
+    let digits: String = name
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    digits.parse().ok()
+}
+
+// ファイル名にチャプター名（サブフォルダ名）が区切り単位で含まれるか（"ch01_012" は "ch01" を含む）
+fn names_chapter(stem: &str, chapter: &str) -> bool {
+    let chapter = normalize_name(chapter);
+    !chapter.is_empty()
+        && stem
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|token| normalize_name(token) == chapter)
+}
+
+// 命名規則でページを照合（完全一致を優先し、なければ末尾の番号で照合）
+// 番号での照合は1つのチャプターに絞れる場合のみ（ファイル名のチャプター名、または番号が1チャプターにしかない）
+fn match_placeholder(stem: &str, placeholders: &[InboxPlaceholder]) -> Option<usize> {
+    let normalized = normalize_name(stem);
+    placeholders
+        .iter()
+        .position(|p| normalize_name(&p.expected_name) == normalized)
+        .or_else(|| {
+            let number = trailing_number(stem)?;
+            let candidates: Vec<usize> = (0..placeholders.len())
+                .filter(|&i| placeholders[i].page_number == Some(number))
+                .collect();
+            let in_chapter: Vec<usize> = candidates
+                .iter()
+                .copied()
+                .filter(|&i| {
+                    placeholders[i]
+                        .subfolder
+                        .as_deref()
+                        .is_some_and(|chapter| names_chapter(stem, chapter))
+                })
+                .collect();
+            let candidates = if in_chapter.is_empty() { candidates } else { in_chapter };
+
+            let first = *candidates.first()?;
+            candidates
+                .iter()
+                .all(|&i| placeholders[i].subfolder == placeholders[first].subfolder)
+                .then_some(first)
+        })
+}
+
+// ファイル内容のMD5（全体をメモリに読み込まず少しずつ計算）
+fn md5_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("ファイル読み込みエラー: {}", e))?;
+    let mut context = md5::Context::new();
+    std::io::copy(&mut file, &mut context).map_err(|e| format!("ファイル読み込みエラー: {}", e))?;
+    Ok(format!("{:x}", context.compute()))
+}
+
+// ファイルを照合し、素材フォルダへ移動（照合できなければ None でそのまま残す）
+fn ingest_file(
+    path: &Path,
+    config: &InboxConfig,
+    pending: &mut Vec<InboxPlaceholder>,
+) -> Result<Option<InboxLinkedEvent>, String> {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let Some(index) = match_placeholder(stem, pending) else {
+        return Ok(None);
+    };
+
+    let hash = md5_file(path)?;
+
+    let placeholder = &pending[index];
+    let mut target_dir = PathBuf::from(&config.assets_path);
+    if let Some(ref subfolder) = placeholder.subfolder {
+        target_dir.push(subfolder);
+    }
+    fs::create_dir_all(&target_dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+    let file_name = path.file_name().ok_or("ファイル名を取得できません")?;
+    let target = target_dir.join(file_name);
+    if target.exists() {
+        return Err(format!("取り込み先に同名のファイルがあります: {}", target.display()));
+    }
+
+    // 別ドライブへの移動はコピー＋削除で行う
+    if retry_on_lock(|| fs::rename(path, &target)).is_err() {
+        fs::copy(path, &target).map_err(|e| format!("ファイルコピーエラー: {}", e))?;
+        retry_on_lock(|| fs::remove_file(path)).map_err(|e| format!("ファイル削除エラー: {}", e))?;
+    }
+
+    let placeholder = pending.remove(index);
+    Ok(Some(InboxLinkedEvent {
+        page_id: placeholder.page_id,
+        original_name: file_name.to_string_lossy().to_string(),
+        path: target.to_string_lossy().to_string(),
+        hash,
+    }))
+}
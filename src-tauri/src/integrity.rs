@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::commands::project::validate_file_reference;
+use crate::desktop_notify::{notify_if_background, NotifyAction};
+use crate::file_utils::{find_sync_conflict_copies, full_content_hash, get_config_path, modified_millis};
+use crate::jobs::JobRegistry;
+use crate::types::{IntegrityIssue, IntegrityRecord, IntegrityScanReport, ProjectFile};
+
+// 1ファイルごとの待ち時間（他の処理のディスクI/Oを妨げないよう間隔を空ける）
+const FILE_INTERVAL: Duration = Duration::from_millis(200);
+// エクスポートなどのジョブ実行中は待機する間隔
+const BUSY_WAIT: Duration = Duration::from_secs(5);
+
+// 定期チェックの実行状態（アプリの管理状態）
+pub struct IntegrityScanner {
+    // 実行中のスレッドと停止フラグ
+    current: Mutex<Option<(Arc<AtomicBool>, JoinHandle<()>)>>,
+}
+
+impl IntegrityScanner {
+    pub fn new() -> Self {
+        Self {
+            current: Mutex::new(None),
+        }
+    }
+}
+
+// 記録ファイルの読み込みから保存までを1つずつ行う（定期チェックと手動のチェックが同時に書き込まないように）
+static RECORDS_LOCK: Mutex<()> = Mutex::new(());
+
+// プロジェクトごとの記録の保存先
+fn records_path(project_path: &str) -> Result<PathBuf, String> {
+    let dir = get_config_path()?.join("integrity");
+    fs::create_dir_all(&dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+    Ok(dir.join(format!("{:x}.json", md5::compute(project_path))))
+}
+
+fn load_records(path: &Path) -> HashMap<String, IntegrityRecord> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_records(path: &Path, records: &HashMap<String, IntegrityRecord>) {
+    let result = serde_json::to_string(records)
+        .map_err(|e| e.to_string())
+        .and_then(|json| fs::write(path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        eprintln!("整合性チェックの記録の保存に失敗: {}", e);
+    }
+}
+
+fn issue(page_id: &str, path: &str, kind: &str, message: impl Into<String>) -> IntegrityIssue {
+    IntegrityIssue {
+        page_id: page_id.to_string(),
+        path: path.to_string(),
+        kind: kind.to_string(),
+        message: message.into(),
+        conflict_paths: Vec::new(),
+    }
+}
+
+// ジョブの実行中は待つ（中断されたら false）
+fn wait_while_busy(app: &AppHandle, stop: &AtomicBool) -> bool {
+    while app.state::<JobRegistry>().active_counts().0 > 0 {
+        if stop.load(Ordering::Relaxed) {
+            return false;
+        }
+        std::thread::sleep(BUSY_WAIT);
+    }
+    !stop.load(Ordering::Relaxed)
+}
+
+// 参照している原稿を1つずつ再ハッシュし、前回の記録と比較
+// 更新日時・サイズが変わっていれば通常の編集として記録を更新し、同じまま内容が変わっていれば問題として報告する
+pub fn scan_project(
+    app: &AppHandle,
+    project: &ProjectFile,
+    project_path: &str,
+    stop: &AtomicBool,
+) -> Result<IntegrityScanReport, String> {
+    let started_at = chrono::Local::now().to_rfc3339();
+    let records_file = records_path(project_path)?;
+    let _records_guard = RECORDS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut records = load_records(&records_file);
+    let base = Path::new(&project.base_path);
+
+    let mut report = IntegrityScanReport {
+        project_path: project_path.to_string(),
+        started_at,
+        finished_at: String::new(),
+        scanned: 0,
+        recorded: 0,
+        updated: 0,
+        issues: Vec::new(),
+        cancelled: false,
+    };
+
+    let pages = project.chapters.iter().flat_map(|chapter| chapter.pages.iter());
+    for page in pages {
+        let Some(ref file_ref) = page.file else {
+            continue;
+        };
+        if !wait_while_busy(app, stop) {
+            report.cancelled = true;
+            break;
+        }
+
+        let validation = validate_file_reference(&page.id, file_ref, base);
+        let Some(resolved) = validation.resolved_path else {
+            report.issues.push(issue(&page.id, &validation.original_path, "missing", "ファイルが見つかりません"));
+            continue;
+        };
+        let path = Path::new(&resolved);
+
+        let conflicts = find_sync_conflict_copies(path);
+        if !conflicts.is_empty() {
+            let mut conflict = issue(
+                &page.id,
+                &resolved,
+                "conflictCopy",
+                format!("同期の競合ファイルがあります（{}件）。どちらが正しいか確認してください", conflicts.len()),
+            );
+            conflict.conflict_paths = conflicts;
+            report.issues.push(conflict);
+        }
+
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                report.issues.push(issue(&page.id, &resolved, "unreadable", format!("ファイルを読み込めません: {}", e)));
+                continue;
+            }
+        };
+        let hash = match full_content_hash(path) {
+            Ok(hash) => hash,
+            Err(e) => {
+                report.issues.push(issue(&page.id, &resolved, "unreadable", format!("ファイルを読み込めません: {}", e)));
+                continue;
+            }
+        };
+        report.scanned += 1;
+
+        let current = IntegrityRecord {
+            hash,
+            size: metadata.len(),
+            modified_time: modified_millis(&metadata),
+            checked_at: chrono::Local::now().to_rfc3339(),
+        };
+        match records.get(&resolved) {
+            None => report.recorded += 1,
+            Some(previous) if previous.hash == current.hash => {}
+            Some(previous) if previous.size == current.size && previous.modified_time == current.modified_time => {
+                // 記録は更新せず、次回も報告する
+                report.issues.push(issue(
+                    &page.id,
+                    &resolved,
+                    "silentChange",
+                    format!("更新日時が変わらないまま内容が変化しています（前回の確認: {}）", previous.checked_at),
+                ));
+                std::thread::sleep(FILE_INTERVAL);
+                continue;
+            }
+            Some(_) => report.updated += 1,
+        }
+        records.insert(resolved, current);
+        std::thread::sleep(FILE_INTERVAL);
+    }
+
+    save_records(&records_file, &records);
+    report.finished_at = chrono::Local::now().to_rfc3339();
+    Ok(report)
+}
+
+// 結果を画面に通知（問題があれば前面にないときOSにも通知）
+fn publish_report(app: &AppHandle, report: &IntegrityScanReport) {
+    let _ = app.emit("integrity-scan-finished", report);
+    if !report.issues.is_empty() {
+        notify_if_background(
+            app,
+            "原稿の整合性チェック",
+            &format!("{}件の問題が見つかりました", report.issues.len()),
+            &report.project_path,
+            &[NotifyAction::ViewReport],
+        );
+    }
+}
+
+// 定期チェックを開始（既に動いている場合は止めてから置き換え）。開始直後に1回目を実行する
+// 同時に開始されても古いスレッドが残らないよう、停止から置き換えまで状態をロックしたまま行う
+pub fn start_scanner(app: AppHandle, project: ProjectFile, project_path: String, interval_hours: u64) -> Result<(), String> {
+    let state = app.state::<IntegrityScanner>();
+    let mut current = state.current.lock().map_err(|e| e.to_string())?;
+    stop_thread(current.take());
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let interval = Duration::from_secs(interval_hours.max(1) * 3600);
+    let scanner_app = app.clone();
+    let thread_stop = stop.clone();
+    let handle = std::thread::spawn(move || {
+        let (app, stop) = (scanner_app, thread_stop);
+        while !stop.load(Ordering::Relaxed) {
+            match scan_project(&app, &project, &project_path, &stop) {
+                Ok(report) if !report.cancelled => publish_report(&app, &report),
+                Ok(_) => break,
+                Err(e) => eprintln!("整合性チェックに失敗: {}", e),
+            }
+            // 停止を素早く反映できるよう1秒ずつ待つ
+            let mut waited = Duration::ZERO;
+            while waited < interval && !stop.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_secs(1));
+                waited += Duration::from_secs(1);
+            }
+        }
+    });
+    *current = Some((stop, handle));
+    Ok(())
+}
+
+fn stop_thread(current: Option<(Arc<AtomicBool>, JoinHandle<()>)>) {
+    if let Some((stop, handle)) = current {
+        stop.store(true, Ordering::Relaxed);
+        let _ = handle.join();
+    }
+}
+
+// 定期チェックを停止（確認中のファイルが終わり、スレッドが終了するまで待つ）
+pub fn stop_scanner(app: &AppHandle) {
+    let current = app
+        .state::<IntegrityScanner>()
+        .current
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
+    stop_thread(current);
+}
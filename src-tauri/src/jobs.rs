@@ -0,0 +1,315 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::cache::ThumbnailCache;
+use crate::file_utils::available_space;
+use crate::power::{inhibit_sleep, SleepGuard};
+use crate::settings::load_settings;
+use crate::tray::refresh_tray;
+use crate::notifications::notify_operation_finished;
+use crate::types::{BackgroundJobInfo, BatchFileResult, DiskSpaceEvent, OperationSummary};
+
+// 完了済みジョブを一覧に残す件数
+const FINISHED_JOBS_KEPT: usize = 20;
+// 一時停止中の再開確認間隔
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+// 空き容量の確認間隔
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+// ジョブの一時停止・キャンセル要求
+#[derive(Default)]
+pub struct JobControl {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+}
+
+struct JobEntry {
+    info: BackgroundJobInfo,
+    control: Arc<JobControl>,
+}
+
+// ウィンドウを閉じても継続するバックグラウンドジョブの一覧
+pub struct JobRegistry {
+    jobs: Mutex<Vec<JobEntry>>,
+    next_id: AtomicU64,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    // ジョブを登録して処理側のハンドルを返す
+    pub fn start(&self, app: &AppHandle, kind: &str, label: &str, total: usize) -> JobHandle {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let control = Arc::new(JobControl::default());
+        {
+            let mut jobs = self.lock();
+            jobs.push(JobEntry {
+                info: BackgroundJobInfo {
+                    id,
+                    kind: kind.to_string(),
+                    label: label.to_string(),
+                    status: "running".to_string(),
+                    current: 0,
+                    total,
+                    error: None,
+                },
+                control: control.clone(),
+            });
+        }
+        notify_jobs_changed(app);
+
+        // キャッシュのドライブは常に監視
+        let settings = load_settings();
+        let disk_paths = app
+            .try_state::<ThumbnailCache>()
+            .map(|cache| vec![cache.cache_dir.clone()])
+            .unwrap_or_default();
+
+        JobHandle {
+            id,
+            control,
+            app: app.clone(),
+            last_percent: AtomicUsize::new(0),
+            disk_paths,
+            disk_warning_bytes: settings.disk_warning_mb.saturating_mul(1024 * 1024),
+            disk_pause_bytes: settings.disk_pause_mb.saturating_mul(1024 * 1024),
+            last_disk_check: Mutex::new(None),
+            disk_warned: AtomicBool::new(false),
+            _sleep_guard: inhibit_sleep(label),
+        }
+    }
+
+    pub fn list(&self) -> Vec<BackgroundJobInfo> {
+        self.lock().iter().map(|j| j.info.clone()).collect()
+    }
+
+    // 実行中・一時停止中のジョブ数
+    pub fn active_counts(&self) -> (usize, usize) {
+        let jobs = self.lock();
+        let running = jobs.iter().filter(|j| j.info.status == "running").count();
+        let paused = jobs.iter().filter(|j| j.info.status == "paused").count();
+        (running, paused)
+    }
+
+    // 一時停止・再開（id が None なら全ジョブ）
+    pub fn set_paused(&self, app: &AppHandle, id: Option<u64>, paused: bool) {
+        self.control_jobs(id, |entry| {
+            let (from, to) = if paused { ("running", "paused") } else { ("paused", "running") };
+            if entry.info.status == from {
+                entry.control.paused.store(paused, Ordering::Relaxed);
+                entry.info.status = to.to_string();
+            }
+        });
+        notify_jobs_changed(app);
+    }
+
+    // キャンセル（id が None なら全ジョブ）
+    pub fn cancel(&self, app: &AppHandle, id: Option<u64>) {
+        self.control_jobs(id, |entry| {
+            if entry.info.status == "running" || entry.info.status == "paused" {
+                entry.control.cancelled.store(true, Ordering::Relaxed);
+                entry.control.paused.store(false, Ordering::Relaxed);
+                entry.info.status = "cancelled".to_string();
+            }
+        });
+        notify_jobs_changed(app);
+    }
+
+    fn control_jobs(&self, id: Option<u64>, mut f: impl FnMut(&mut JobEntry)) {
+        let mut jobs = self.lock();
+        for entry in jobs.iter_mut().filter(|j| id.map_or(true, |id| j.info.id == id)) {
+            f(entry);
+        }
+    }
+
+    fn update(&self, id: u64, f: impl FnOnce(&mut BackgroundJobInfo)) {
+        let mut jobs = self.lock();
+        if let Some(entry) = jobs.iter_mut().find(|j| j.info.id == id) {
+            f(&mut entry.info);
+        }
+
+        // 古い完了済みジョブを削除
+        let finished: Vec<u64> = jobs
+            .iter()
+            .filter(|j| !matches!(j.info.status.as_str(), "running" | "paused"))
+            .map(|j| j.info.id)
+            .collect();
+        if finished.len() > FINISHED_JOBS_KEPT {
+            let remove = &finished[..finished.len() - FINISHED_JOBS_KEPT];
+            jobs.retain(|j| !remove.contains(&j.info.id));
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<JobEntry>> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// 処理側で保持するジョブのハンドル
+pub struct JobHandle {
+    id: u64,
+    control: Arc<JobControl>,
+    app: AppHandle,
+    // 通知の間引き用（前回通知した進捗率）
+    last_percent: AtomicUsize,
+    // 空き容量を監視するパス（キャッシュ・出力先）
+    disk_paths: Vec<PathBuf>,
+    disk_warning_bytes: u64,
+    disk_pause_bytes: u64,
+    last_disk_check: Mutex<Option<Instant>>,
+    disk_warned: AtomicBool,
+    // ジョブ実行中はスリープを抑止
+    _sleep_guard: SleepGuard,
+}
+
+impl JobHandle {
+    pub fn is_cancelled(&self) -> bool {
+        self.control.cancelled.load(Ordering::Relaxed)
+    }
+
+    // 出力先などのドライブを空き容量の監視対象に追加
+    pub fn with_disk_watch(mut self, paths: &[&Path]) -> Self {
+        self.disk_paths.extend(paths.iter().map(|p| p.to_path_buf()));
+        self
+    }
+
+    // 空き容量を確認し、警告・一時停止する（ディスクが実際に埋まる前に止める）
+    fn check_disk_space(&self) {
+        {
+            let mut last = self.last_disk_check.lock().unwrap_or_else(|e| e.into_inner());
+            if last.is_some_and(|t| t.elapsed() < DISK_CHECK_INTERVAL) {
+                return;
+            }
+            *last = Some(Instant::now());
+        }
+
+        for path in &self.disk_paths {
+            let Some(available) = available_space(path) else {
+                continue;
+            };
+            let (level, threshold) = if available < self.disk_pause_bytes {
+                ("critical", self.disk_pause_bytes)
+            } else if available < self.disk_warning_bytes && !self.disk_warned.swap(true, Ordering::Relaxed) {
+                ("warning", self.disk_warning_bytes)
+            } else {
+                continue;
+            };
+
+            let _ = self.app.emit(
+                "disk-space-low",
+                DiskSpaceEvent {
+                    job_id: self.id,
+                    path: path.to_string_lossy().to_string(),
+                    available_bytes: available,
+                    threshold_bytes: threshold,
+                    level: level.to_string(),
+                },
+            );
+            if level == "critical" {
+                self.app.state::<JobRegistry>().set_paused(&self.app, Some(self.id), true);
+                return;
+            }
+        }
+    }
+
+    // 一時停止中は再開まで待機（キャンセルされたら false）
+    pub fn wait_if_paused(&self) -> bool {
+        self.check_disk_space();
+        while self.control.paused.load(Ordering::Relaxed) && !self.is_cancelled() {
+            std::thread::sleep(PAUSE_POLL_INTERVAL);
+        }
+        !self.is_cancelled()
+    }
+
+    // 進捗を更新（1%単位で通知）
+    pub fn set_progress(&self, current: usize) {
+        let registry = self.app.state::<JobRegistry>();
+        let mut total = 0;
+        registry.update(self.id, |info| {
+            info.current = current;
+            total = info.total;
+        });
+        let percent = if total > 0 { current * 100 / total } else { 0 };
+        if self.last_percent.swap(percent, Ordering::Relaxed) != percent {
+            notify_jobs_changed(&self.app);
+        }
+    }
+
+    // 完了を記録（キャンセル済みの場合は状態を維持）
+    pub fn finish(self, result: Result<(), String>) {
+        let cancelled = self.is_cancelled();
+        self.app.state::<JobRegistry>().update(self.id, |info| {
+            if cancelled {
+                return;
+            }
+            match result.clone() {
+                Ok(()) => {
+                    info.status = "completed".to_string();
+                    info.current = info.total;
+                }
+                Err(e) => {
+                    info.status = "failed".to_string();
+                    info.error = Some(e);
+                }
+            }
+        });
+        notify_jobs_changed(&self.app);
+
+        // ユーザーがキャンセルした場合は通知しない
+        if !cancelled {
+            if let Some(info) = self.app.state::<JobRegistry>().list().into_iter().find(|j| j.id == self.id) {
+                let details = format!("{}/{}件", info.current, info.total);
+                notify_operation_finished(OperationSummary::new(&info.kind, &info.label, result.map(|_| Some(details))));
+            }
+        }
+    }
+}
+
+// ファイルごとの一括処理をジョブとして並列実行（トレイから一時停止・キャンセルでき、出力先の空き容量も監視）
+// キャンセル後に未着手だったファイルは処理せず、失敗として返す
+pub fn run_batch_job<T: Sync>(
+    app: &AppHandle,
+    kind: &str,
+    label: &str,
+    output_dir: &Path,
+    items: &[T],
+    source_path: impl Fn(&T) -> &str + Sync,
+    process: impl Fn(&T) -> Result<String, String> + Sync,
+) -> Vec<BatchFileResult> {
+    let job = app
+        .state::<JobRegistry>()
+        .start(app, kind, label, items.len())
+        .with_disk_watch(&[output_dir]);
+    let done = AtomicUsize::new(0);
+
+    let results: Vec<BatchFileResult> = items
+        .par_iter()
+        .map(|item| {
+            let result = if job.wait_if_paused() {
+                process(item)
+            } else {
+                Err("キャンセルされました".to_string())
+            };
+            job.set_progress(done.fetch_add(1, Ordering::Relaxed) + 1);
+            BatchFileResult::from_result(source_path(item), result)
+        })
+        .collect();
+
+    job.finish(Ok(()));
+    results
+}
+
+// ジョブ一覧の変更をフロントエンドとトレイに通知
+pub fn notify_jobs_changed(app: &AppHandle) {
+    let registry = app.state::<JobRegistry>();
+    let _ = app.emit("background-jobs-changed", registry.list());
+    refresh_tray(app, registry.active_counts());
+}
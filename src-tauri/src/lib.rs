@@ -1,65 +1,327 @@
 mod constants;
+mod app_mode;
 mod types;
 mod cache;
 mod state;
 mod image_utils;
+mod color;
+mod memory_budget;
+mod pdf;
+mod file_utils;
+mod metadata;
+mod image_info;
 mod thumbnail;
+mod processing;
+mod settings;
+mod jobs;
+mod audit;
+mod usage;
+mod plugins;
+mod scripting;
+mod inbox;
+mod watcher;
+mod farm;
+mod integrity;
+mod notifications;
+mod desktop_notify;
+mod report;
+mod sequence;
+mod page_rules;
+mod naming;
+mod cleanup;
+mod history;
+mod autosave;
+mod adobe;
+mod power;
+mod tray;
+mod token;
+mod api_server;
+mod preview_server;
 mod commands;
 
-use std::sync::Mutex;
-use cache::{ThumbnailCache, ThumbnailMemoryCache};
+use cache::ThumbnailCache;
 use state::AppState;
-use constants::MEMORY_CACHE_MAX_SIZE;
+use jobs::JobRegistry;
 use tauri::Manager;
 
 // Tauri コマンドを再エクスポート
-use commands::folder::get_folder_contents;
-use commands::export::export_pages;
-use commands::project::{save_project, load_project, validate_project_files};
+use commands::folder::{get_folder_contents, check_cloud_files, detect_sync_conflicts};
+use commands::export::{export_pages, export_page_list, preview_export, reexport_flagged_pages, validate_export_script};
+use commands::project::{
+    save_project, load_project, validate_project_files, set_project_cache, list_project_backups, restore_project_backup,
+    search_missing_files, relink_project_base,
+};
 use commands::recent::{get_recent_files, add_recent_file};
 use commands::open_file::open_file_with_default_app;
-use commands::tiff::{check_photoshop_installed, run_photoshop_tiff_convert};
-use thumbnail::generate_thumbnail;
+use commands::tiff::{
+    check_photoshop_installed, list_detected_bridge_versions, list_detected_photoshop_versions, run_photoshop_tiff_convert,
+    validate_pinned_photoshop, resolve_tiff_stall,
+};
+use commands::cover::{detect_cover_layout, compose_wraparound_cover, split_wraparound_cover};
+use commands::bleed::apply_bleed_batch;
+use commands::autocrop::{analyze_autocrop, apply_autocrop};
+use commands::tone::{analyze_chapter_tone, normalize_chapter_tone};
+use commands::strip::export_thumbnail_strip;
+use commands::viewer::export_html_viewer;
+use commands::metadata::read_image_metadata;
+use commands::proof::{render_print_preview, render_night_preview, render_color_vision_preview, compose_spread_preview};
+use commands::screen::analyze_screen_tones;
+use commands::purity::check_black_purity;
+use commands::trim::check_text_near_trim;
+use commands::session::{get_last_session, save_session};
+use commands::similar::{build_similarity_index, detect_duplicate_pages, find_similar_files};
+use commands::app_mode::get_app_mode;
+use commands::audit::{get_audit_log, get_operator_name, set_operator_name};
+use commands::health::run_health_check;
+use commands::inbox::{start_inbox, stop_inbox};
+use commands::watch::{watch_folder, unwatch_folder};
+use commands::benchmark::run_benchmark;
+use commands::tiles::{generate_deep_zoom_tiles, get_preview_region};
+use commands::pdf::{extract_pdf_page, get_pdf_page_count};
+use commands::scan::scan_to_folder;
+use commands::book_check::verify_book_output;
+use commands::preflight::preflight_project;
+use commands::storefront::generate_storefront_samples;
+use commands::spread::analyze_spreads;
+use commands::annotation::{summarize_annotations, render_annotated_pages, export_annotations, import_annotations};
+use commands::edition::{freeze_edition, compare_editions, build_reprint_package};
+use commands::integrity::{run_integrity_scan, start_integrity_scan, stop_integrity_scan};
+use commands::export_size::estimate_export_size;
+use commands::preview_server::{get_tablet_preview_info, start_tablet_preview, stop_tablet_preview, update_tablet_preview};
+use commands::autosave::{
+    start_autosave, update_autosave_state, stop_autosave, get_recovery_candidates, load_recovery, discard_recovery,
+};
+use commands::farm::{
+    submit_farm_tiff_jobs, submit_farm_export_job, start_farm_worker, stop_farm_worker, get_farm_status,
+};
+use commands::notification::test_webhook;
+use commands::report::export_preflight_report;
+use commands::sequence::{get_page_sequence, move_in_sequence};
+use commands::rules::classify_page_files;
+use commands::chapter_split::suggest_chapter_splits;
+use commands::cleanup::{get_last_cleanup_report, run_orphan_cleanup, spawn_startup_cleanup};
+use commands::history::{get_export_job, list_export_history, rerun_export_job};
+use commands::plugin::{list_analyzer_plugins, run_analyzer_plugins};
+use commands::usage::{get_usage_stats, reset_usage_stats};
+use commands::jobs::{cancel_background_job, get_disk_space, list_background_jobs, pause_background_job, resume_background_job};
+use commands::settings::{get_app_settings, save_app_settings};
+use commands::quick_export::{register_quick_export_shortcut, trigger_quick_export};
+use commands::window::{apply_window_icon, get_window_project, open_project_in_new_window, set_window_project};
+use thumbnail::{cancel_thumbnail_generation, clear_thumbnail_cache, generate_thumbnail, generate_thumbnails_batch};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    if let Err(e) = tauri::Builder::default()
+    let app = match tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, _shortcut, event| {
+                    // 登録するのはクイックエクスポートのショートカットのみ
+                    if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                        trigger_quick_export(app);
+                    }
+                })
+                .build(),
+        )
         .manage(ThumbnailCache::new())
-        .manage(AppState {
-            memory_cache: Mutex::new(ThumbnailMemoryCache::new(MEMORY_CACHE_MAX_SIZE)),
-        })
+        .manage(AppState::new())
+        .manage(JobRegistry::new())
+        .manage(api_server::ApiServer::new())
+        .manage(farm::FarmWorker::new())
+        .manage(integrity::IntegrityScanner::new())
+        .manage(preview_server::PreviewServer::new())
         .setup(|app| {
             // ウィンドウアイコンを設定
             if let Some(window) = app.get_webview_window("main") {
-                let icon_bytes = include_bytes!("../icons/icon.png");
-                if let Ok(img) = image::load_from_memory(icon_bytes) {
-                    let rgba = img.to_rgba8();
-                    let (width, height) = rgba.dimensions();
-                    let icon = tauri::image::Image::new_owned(rgba.into_raw(), width, height);
-                    let _ = window.set_icon(icon);
+                apply_window_icon(&window);
+            }
+
+            // バックグラウンドジョブの状況を表示するトレイアイコン
+            // 作成できなければウィンドウを表示する（非表示のままだと操作する手段がない）
+            if let Err(e) = tray::create_tray(app.handle()) {
+                eprintln!("トレイアイコンの作成に失敗: {}", e);
+                tray::show_any_window(app.handle());
+            }
+
+            // クイックエクスポートのショートカットを登録（他アプリと競合しても起動は続行）
+            if app_mode::is_safe_mode() {
+                eprintln!("セーフモードで起動しました（キャッシュ・設定・最近使ったファイルを読み込みません）");
+            } else {
+                let app_settings = settings::load_settings();
+                memory_budget::set_memory_budget_mb(app_settings.decode_memory_budget_mb);
+                image_utils::set_image_limits(app_settings.image_limits, app_settings.image_limit_overrides.clone());
+                color::set_color_management(app_settings.color_management);
+                if let Err(e) = register_quick_export_shortcut(app.handle(), app_settings.quick_export_shortcut.as_deref()) {
+                    eprintln!("{}", e);
+                }
+                // 外部ツール向けのローカルAPI（ポート使用中でも起動は続行）
+                if let Err(e) = api_server::apply_api_server_settings(app.handle(), &app_settings) {
+                    eprintln!("{}", e);
                 }
+                // クラッシュ等で残った一時ファイルを掃除
+                spawn_startup_cleanup(app.handle());
             }
             Ok(())
         })
+        .on_window_event(|window, event| match event {
+            // クイックエクスポートの対象として最後に操作したウィンドウを記録
+            tauri::WindowEvent::Focused(true) => {
+                window.state::<AppState>().set_active_window(window.label());
+            }
+            // 閉じたウィンドウの状態を破棄
+            tauri::WindowEvent::Destroyed => {
+                window.state::<AppState>().remove_window(window.label());
+            }
+            _ => {}
+        })
         .invoke_handler(tauri::generate_handler![
             get_folder_contents,
+            check_cloud_files,
+            detect_sync_conflicts,
             generate_thumbnail,
+            generate_thumbnails_batch,
+            cancel_thumbnail_generation,
+            clear_thumbnail_cache,
             export_pages,
+            preview_export,
+            validate_export_script,
+            reexport_flagged_pages,
+            export_page_list,
             save_project,
+            list_project_backups,
+            restore_project_backup,
             load_project,
             validate_project_files,
+            search_missing_files,
+            relink_project_base,
+            set_project_cache,
             get_recent_files,
             add_recent_file,
             open_file_with_default_app,
             check_photoshop_installed,
             run_photoshop_tiff_convert,
+            list_detected_photoshop_versions,
+            list_detected_bridge_versions,
+            validate_pinned_photoshop,
+            resolve_tiff_stall,
+            detect_cover_layout,
+            compose_wraparound_cover,
+            split_wraparound_cover,
+            apply_bleed_batch,
+            analyze_autocrop,
+            apply_autocrop,
+            analyze_chapter_tone,
+            normalize_chapter_tone,
+            export_thumbnail_strip,
+            export_html_viewer,
+            read_image_metadata,
+            render_print_preview,
+            render_night_preview,
+            render_color_vision_preview,
+            analyze_screen_tones,
+            check_black_purity,
+            check_text_near_trim,
+            detect_duplicate_pages,
+            build_similarity_index,
+            find_similar_files,
+            get_last_session,
+            save_session,
+            open_project_in_new_window,
+            get_window_project,
+            set_window_project,
+            get_app_settings,
+            save_app_settings,
+            list_background_jobs,
+            pause_background_job,
+            resume_background_job,
+            cancel_background_job,
+            get_disk_space,
+            run_health_check,
+            get_app_mode,
+            set_operator_name,
+            get_operator_name,
+            get_audit_log,
+            get_usage_stats,
+            reset_usage_stats,
+            list_analyzer_plugins,
+            run_analyzer_plugins,
+            start_inbox,
+            stop_inbox,
+            watch_folder,
+            unwatch_folder,
+            submit_farm_tiff_jobs,
+            submit_farm_export_job,
+            start_farm_worker,
+            stop_farm_worker,
+            get_farm_status,
+            run_benchmark,
+            generate_deep_zoom_tiles,
+            get_pdf_page_count,
+            extract_pdf_page,
+            scan_to_folder,
+            start_autosave,
+            update_autosave_state,
+            stop_autosave,
+            get_recovery_candidates,
+            load_recovery,
+            discard_recovery,
+            verify_book_output,
+            preflight_project,
+            generate_storefront_samples,
+            analyze_spreads,
+            summarize_annotations,
+            render_annotated_pages,
+            export_annotations,
+            import_annotations,
+            compose_spread_preview,
+            get_preview_region,
+            freeze_edition,
+            compare_editions,
+            build_reprint_package,
+            run_integrity_scan,
+            start_integrity_scan,
+            stop_integrity_scan,
+            start_tablet_preview,
+            update_tablet_preview,
+            stop_tablet_preview,
+            get_tablet_preview_info,
+            estimate_export_size,
+            test_webhook,
+            export_preflight_report,
+            get_page_sequence,
+            move_in_sequence,
+            classify_page_files,
+            suggest_chapter_splits,
+            run_orphan_cleanup,
+            get_last_cleanup_report,
+            list_export_history,
+            get_export_job,
+            rerun_export_job,
         ])
-        .run(tauri::generate_context!())
+        .build(tauri::generate_context!())
     {
-        eprintln!("Tauriアプリケーション起動エラー: {}", e);
-        std::process::exit(1);
-    }
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("Tauriアプリケーション起動エラー: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    app.run(|app_handle, event| match event {
+        // 全ウィンドウを閉じてもトレイに常駐し、実行中のエクスポート・監視・ジョブを継続
+        // （トレイの「終了」は終了コード付きのため対象外。トレイがなければ常駐しない）
+        tauri::RunEvent::ExitRequested { code: None, api, .. } => {
+            if settings::load_settings().close_to_tray && tray::has_tray(app_handle) {
+                api.prevent_exit();
+            }
+        }
+        // 正常終了を記録（未保存のプロジェクトのスナップショットを次回の復旧候補にしない）、スリープ抑止を解除
+        tauri::RunEvent::Exit => {
+            autosave::mark_clean_exit();
+            power::release_sleep_inhibitor();
+        }
+        _ => {}
+    });
 }
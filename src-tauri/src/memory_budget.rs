@@ -0,0 +1,67 @@
+use std::path::Path;
+use std::sync::{Condvar, Mutex};
+use crate::image_utils::read_image_dimensions;
+
+// デコード済み画像の概算メモリ使用量を並列処理全体で管理し、上限を超える読み込みを待たせる
+// （8GBのマシンで100MPの画像を同時に複数デコードするとスワップ・メモリ不足になるため）
+
+struct BudgetState {
+    used: u64,
+    /// 上限 (bytes)、0 は無制限
+    limit: u64,
+}
+
+static BUDGET: Mutex<BudgetState> = Mutex::new(BudgetState {
+    used: 0,
+    limit: 4096 * 1024 * 1024,
+});
+static RELEASED: Condvar = Condvar::new();
+
+// 1画素あたりの概算メモリ（デコード結果 + 変換時の複製）
+const BYTES_PER_PIXEL: u64 = 8;
+// PSDは元データ・チャンネルごとのバッファ・合成結果を同時に保持する
+const PSD_BYTES_PER_PIXEL: u64 = 12;
+
+// 確保したメモリ枠（破棄すると解放し、待っている読み込みを再開）
+pub struct MemoryPermit {
+    bytes: u64,
+}
+
+impl Drop for MemoryPermit {
+    fn drop(&mut self) {
+        let mut state = BUDGET.lock().unwrap_or_else(|e| e.into_inner());
+        state.used = state.used.saturating_sub(self.bytes);
+        RELEASED.notify_all();
+    }
+}
+
+// 設定の上限を反映（起動時・設定保存時に呼ぶ）
+pub fn set_memory_budget_mb(mb: u64) {
+    let mut state = BUDGET.lock().unwrap_or_else(|e| e.into_inner());
+    state.limit = mb.saturating_mul(1024 * 1024);
+    RELEASED.notify_all();
+}
+
+// メモリ枠を確保（空くまで待つ）
+// 上限を超える1枚は、他の読み込みがすべて終わってから単独で処理する
+pub fn acquire_memory(bytes: u64) -> MemoryPermit {
+    let mut state = BUDGET.lock().unwrap_or_else(|e| e.into_inner());
+    while state.limit > 0 && state.used > 0 && state.used + bytes > state.limit {
+        state = RELEASED.wait(state).unwrap_or_else(|e| e.into_inner());
+    }
+    state.used += bytes;
+    MemoryPermit { bytes }
+}
+
+// ファイルのデコードに必要なメモリを見積もって確保（サイズが読めない場合は見積もりなし）
+pub fn reserve_decode_memory(path: &Path) -> MemoryPermit {
+    let is_psd = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("psd"));
+    let per_pixel = if is_psd { PSD_BYTES_PER_PIXEL } else { BYTES_PER_PIXEL };
+    let bytes = read_image_dimensions(path)
+        .map(|(w, h)| w as u64 * h as u64 * per_pixel)
+        .unwrap_or(0);
+    acquire_memory(bytes)
+}
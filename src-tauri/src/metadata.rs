@@ -0,0 +1,513 @@
+use std::fs;
+use std::io::BufReader;
+use std::path::Path;
+use crate::types::{ImageMetadata, MetadataField};
+
+// XMPパケットの開始・終了タグ
+const XMP_START: &[u8] = b"<x:xmpmeta";
+const XMP_END: &[u8] = b"</x:xmpmeta>";
+
+fn find_subslice(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if from >= haystack.len() {
+        return None;
+    }
+    haystack[from..]
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|pos| pos + from)
+}
+
+// ファイル内のXMPパケットを抽出（JPEG/PNG/TIFF/PSDいずれも平文で埋め込まれる）
+fn extract_xmp(data: &[u8]) -> Option<String> {
+    let start = find_subslice(data, XMP_START, 0)?;
+    let end = find_subslice(data, XMP_END, start)? + XMP_END.len();
+    Some(String::from_utf8_lossy(&data[start..end]).to_string())
+}
+
+// 画像ファイルのEXIF・XMPを読み取り
+pub fn read_metadata(path: &Path) -> Result<ImageMetadata, String> {
+    let mut fields = Vec::new();
+    let mut has_gps = false;
+
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    // EXIFがない・未対応形式の場合は空として扱う
+    if let Ok(exif) = exif::Reader::new().read_from_container(&mut BufReader::new(&file)) {
+        for field in exif.fields() {
+            if field.tag.0 == exif::Context::Gps {
+                has_gps = true;
+            }
+            fields.push(MetadataField {
+                tag: field.tag.to_string(),
+                ifd: format!("{:?}", field.ifd_num),
+                value: field.display_value().with_unit(&exif).to_string(),
+            });
+        }
+    }
+
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let xmp = extract_xmp(&data);
+
+    Ok(ImageMetadata {
+        path: path.to_string_lossy().to_string(),
+        exif: fields,
+        xmp,
+        has_gps,
+    })
+}
+
+// JPEGからEXIF/XMP(APP1)・IPTC(APP13)・コメントを除去（再圧縮なし）
+// JFIF(APP0)・ICCプロファイル(APP2)・Adobe(APP14)は色の再現に必要なため残す
+pub fn strip_jpeg_metadata(data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
+        return Err("JPEGシグネチャが不正です".to_string());
+    }
+
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(&data[..2]);
+    let mut pos = 2;
+
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return Err("JPEGセグメントの解析に失敗しました".to_string());
+        }
+        let marker = data[pos + 1];
+
+        // 単独マーカー（長さなし）
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) || marker == 0xFF {
+            out.push(data[pos]);
+            pos += 1;
+            continue;
+        }
+
+        // SOS以降は画像データなのでそのままコピー
+        if marker == 0xDA {
+            out.extend_from_slice(&data[pos..]);
+            return Ok(out);
+        }
+
+        let len = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let end = pos + 2 + len;
+        if len < 2 || end > data.len() {
+            return Err("JPEGセグメントの長さが不正です".to_string());
+        }
+
+        let strip = matches!(marker, 0xE1 | 0xED | 0xFE);
+        if !strip {
+            out.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+
+    out.extend_from_slice(&data[pos.min(data.len())..]);
+    Ok(out)
+}
+
+// PNGからテキスト・EXIF・時刻チャンクを除去（再圧縮なし）
+pub fn strip_png_metadata(data: &[u8]) -> Result<Vec<u8>, String> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if !data.starts_with(SIGNATURE) {
+        return Err("PNGシグネチャが不正です".to_string());
+    }
+
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(SIGNATURE);
+    let mut pos = SIGNATURE.len();
+
+    while pos + 12 <= data.len() {
+        let len = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        let chunk_type = &data[pos + 4..pos + 8];
+        // 長さ(4) + 種別(4) + データ + CRC(4)
+        let end = pos + 12 + len;
+        if end > data.len() {
+            return Err("PNGチャンクの長さが不正です".to_string());
+        }
+
+        let strip = matches!(chunk_type, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME");
+        if !strip {
+            out.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+
+    Ok(out)
+}
+
+// 再圧縮せずにメタデータを除去できる形式なら除去後のデータを返す
+pub fn strip_metadata_lossless(ext: &str, data: &[u8]) -> Option<Result<Vec<u8>, String>> {
+    match ext {
+        "jpg" | "jpeg" => Some(strip_jpeg_metadata(data)),
+        "png" => Some(strip_png_metadata(data)),
+        _ => None,
+    }
+}
+
+// TIFF・EXIFの解像度タグ
+const X_RESOLUTION: u16 = 282;
+const Y_RESOLUTION: u16 = 283;
+const RESOLUTION_UNIT: u16 = 296;
+
+// JPEGのJFIF(APP0)に解像度を記録（JFIFがなければSOIの直後に追加）
+// EXIF(APP1)・Photoshop(APP13)の解像度も食い違わないよう同じ値に書き換える
+pub fn stamp_jpeg_dpi(data: &[u8], dpi: u32) -> Result<Vec<u8>, String> {
+    if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
+        return Err("JPEGシグネチャが不正です".to_string());
+    }
+    let density = dpi.clamp(1, u16::MAX as u32) as u16;
+
+    // APP0: "JFIF\0" + バージョン(2) + 単位(1) + 密度X(2) + 密度Y(2) + サムネイル(2)
+    let has_jfif = data.len() >= 20 && data[2..4] == [0xFF, 0xE0] && &data[6..11] == b"JFIF\0";
+    let mut out = if has_jfif {
+        let mut out = data.to_vec();
+        out[13] = 1;
+        out[14..16].copy_from_slice(&density.to_be_bytes());
+        out[16..18].copy_from_slice(&density.to_be_bytes());
+        out
+    } else {
+        let mut out = Vec::with_capacity(data.len() + 18);
+        out.extend_from_slice(&data[..2]);
+        out.extend_from_slice(&[0xFF, 0xE0, 0x00, 0x10]);
+        out.extend_from_slice(b"JFIF\0");
+        out.extend_from_slice(&[0x01, 0x02, 0x01]);
+        out.extend_from_slice(&density.to_be_bytes());
+        out.extend_from_slice(&density.to_be_bytes());
+        out.extend_from_slice(&[0x00, 0x00]);
+        out.extend_from_slice(&data[2..]);
+        out
+    };
+    stamp_jpeg_segments_dpi(&mut out, density);
+    Ok(out)
+}
+
+// SOSまでのセグメントのうち、EXIF・Photoshopの解像度をその場で書き換える（値の大きさは変わらない）
+fn stamp_jpeg_segments_dpi(data: &mut [u8], density: u16) {
+    let mut pos = 2;
+    while pos + 4 <= data.len() && data[pos] == 0xFF {
+        let marker = data[pos + 1];
+        if marker == 0xDA || marker == 0xD9 {
+            break;
+        }
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) || marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        let len = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let end = pos + 2 + len;
+        if len < 2 || end > data.len() {
+            break;
+        }
+        let body = &mut data[pos + 4..end];
+        match marker {
+            0xE1 if body.starts_with(b"Exif\0\0") => stamp_exif_dpi(&mut body[6..], density as u32),
+            0xED if body.starts_with(b"Photoshop 3.0\0") => stamp_photoshop_dpi(&mut body[14..], density),
+            _ => {}
+        }
+        pos = end;
+    }
+}
+
+// EXIF(TIFF形式)の最初のIFDにある解像度タグを書き換える（解析できない場合はそのまま）
+fn stamp_exif_dpi(tiff: &mut [u8], dpi: u32) {
+    let little_endian = match tiff.get(0..4) {
+        Some(b"II*\0") => true,
+        Some(b"MM\0*") => false,
+        _ => return,
+    };
+    let read_u16 = |data: &[u8], pos: usize| {
+        let bytes = data.get(pos..pos + 2)?;
+        let bytes = [bytes[0], bytes[1]];
+        Some(if little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    };
+    let read_u32 = |data: &[u8], pos: usize| {
+        let bytes = data.get(pos..pos + 4)?;
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        Some(if little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    };
+    let u16_bytes = |value: u16| if little_endian { value.to_le_bytes() } else { value.to_be_bytes() };
+    let u32_bytes = |value: u32| if little_endian { value.to_le_bytes() } else { value.to_be_bytes() };
+
+    let Some(ifd) = read_u32(tiff, 4).map(|ifd| ifd as usize) else {
+        return;
+    };
+    let Some(count) = read_u16(tiff, ifd) else {
+        return;
+    };
+    for i in 0..count as usize {
+        let pos = ifd + 2 + i * 12;
+        let (Some(tag), Some(field_type), Some(value)) = (read_u16(tiff, pos), read_u16(tiff, pos + 2), read_u32(tiff, pos + 8))
+        else {
+            return;
+        };
+        match (tag, field_type) {
+            // RATIONAL(5) は値の位置を指す
+            (X_RESOLUTION | Y_RESOLUTION, 5) => {
+                let offset = value as usize;
+                if let Some(rational) = tiff.get_mut(offset..offset + 8) {
+                    rational[..4].copy_from_slice(&u32_bytes(dpi));
+                    rational[4..].copy_from_slice(&u32_bytes(1));
+                }
+            }
+            // SHORT(3) は値そのもの（2 = インチ）
+            (RESOLUTION_UNIT, 3) => tiff[pos + 8..pos + 10].copy_from_slice(&u16_bytes(2)),
+            _ => {}
+        }
+    }
+}
+
+// Photoshopの画像リソース(APP13)の解像度情報 (ResolutionInfo, 0x03ED) を書き換える
+fn stamp_photoshop_dpi(resources: &mut [u8], density: u16) {
+    const RESOLUTION_INFO: u16 = 0x03ED;
+    let mut pos = 0;
+    // "8BIM" + ID(2) + 名前(Pascal文字列、偶数バイト) + サイズ(4) + データ（偶数バイト）
+    while pos + 12 <= resources.len() && &resources[pos..pos + 4] == b"8BIM" {
+        let id = u16::from_be_bytes([resources[pos + 4], resources[pos + 5]]);
+        let name_len = resources[pos + 6] as usize;
+        let size_pos = pos + 6 + ((name_len + 2) & !1);
+        let Some(size) = resources.get(size_pos..size_pos + 4) else {
+            return;
+        };
+        let size = u32::from_be_bytes([size[0], size[1], size[2], size[3]]) as usize;
+        let start = size_pos + 4;
+        let end = start + size;
+        if end > resources.len() {
+            return;
+        }
+        // 横・縦の順に 解像度（固定小数点16.16）(4) + 単位（1 = ppi）(2) + 表示単位(2)
+        if id == RESOLUTION_INFO && size >= 16 {
+            let fixed = ((density as u32) << 16).to_be_bytes();
+            for offset in [start, start + 8] {
+                resources[offset..offset + 4].copy_from_slice(&fixed);
+                resources[offset + 4..offset + 6].copy_from_slice(&1u16.to_be_bytes());
+            }
+        }
+        pos = end + (size & 1);
+    }
+}
+
+// PNGのpHYsチャンクに解像度を記録（既存のpHYsは置き換え、IHDRの直後に配置）
+pub fn stamp_png_dpi(data: &[u8], dpi: u32) -> Result<Vec<u8>, String> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if !data.starts_with(SIGNATURE) {
+        return Err("PNGシグネチャが不正です".to_string());
+    }
+
+    // 単位はピクセル/メートル
+    let ppm = (dpi as f64 / 0.0254).round() as u32;
+    let mut chunk = Vec::with_capacity(21);
+    chunk.extend_from_slice(&9u32.to_be_bytes());
+    chunk.extend_from_slice(b"pHYs");
+    chunk.extend_from_slice(&ppm.to_be_bytes());
+    chunk.extend_from_slice(&ppm.to_be_bytes());
+    chunk.push(1);
+    let crc = crc32fast::hash(&chunk[4..]);
+    chunk.extend_from_slice(&crc.to_be_bytes());
+
+    let mut out = Vec::with_capacity(data.len() + chunk.len());
+    out.extend_from_slice(SIGNATURE);
+    let mut pos = SIGNATURE.len();
+
+    while pos + 12 <= data.len() {
+        let len = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        let chunk_type = &data[pos + 4..pos + 8];
+        let end = pos + 12 + len;
+        if end > data.len() {
+            return Err("PNGチャンクの長さが不正です".to_string());
+        }
+
+        if chunk_type != b"pHYs" {
+            out.extend_from_slice(&data[pos..end]);
+        }
+        if chunk_type == b"IHDR" {
+            out.extend_from_slice(&chunk);
+        }
+        pos = end;
+    }
+
+    Ok(out)
+}
+
+// TIFFの解像度タグ（XResolution・YResolution・ResolutionUnit）を記録
+// 最初のIFDを書き換えたものをファイル末尾に追加し、ヘッダーの参照先を付け替える（画像データは移動しない）
+pub fn stamp_tiff_dpi(data: &[u8], dpi: u32) -> Result<Vec<u8>, String> {
+    // ヘッダー: バイト順(2) + 識別子(2) + 最初のIFDの位置(4)
+    if data.len() < 8 {
+        return Err("TIFFヘッダーが不正です".to_string());
+    }
+    let little_endian = match data.get(0..4) {
+        Some(b"II*\0") => true,
+        Some(b"MM\0*") => false,
+        _ => return Err("TIFFシグネチャが不正です（BigTIFFは未対応）".to_string()),
+    };
+    let read_u16 = |pos: usize| {
+        let bytes = [data[pos], data[pos + 1]];
+        if little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) }
+    };
+    let read_u32 = |pos: usize| {
+        let bytes = [data[pos], data[pos + 1], data[pos + 2], data[pos + 3]];
+        if little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) }
+    };
+    let u16_bytes = |value: u16| if little_endian { value.to_le_bytes() } else { value.to_be_bytes() };
+    let u32_bytes = |value: u32| if little_endian { value.to_le_bytes() } else { value.to_be_bytes() };
+
+    let ifd = read_u32(4) as usize;
+    if ifd + 2 > data.len() {
+        return Err("TIFFのIFDが不正です".to_string());
+    }
+    let count = read_u16(ifd) as usize;
+    let next_ifd_pos = ifd + 2 + count * 12;
+    if next_ifd_pos + 4 > data.len() {
+        return Err("TIFFのIFDが不正です".to_string());
+    }
+
+    let mut entries: Vec<(u16, [u8; 12])> = (0..count)
+        .map(|i| {
+            let pos = ifd + 2 + i * 12;
+            let mut entry = [0u8; 12];
+            entry.copy_from_slice(&data[pos..pos + 12]);
+            (read_u16(pos), entry)
+        })
+        .filter(|(tag, _)| !matches!(*tag, X_RESOLUTION | Y_RESOLUTION | RESOLUTION_UNIT))
+        .collect();
+
+    // IFDはワード境界から始める
+    let mut out = data.to_vec();
+    if out.len() % 2 == 1 {
+        out.push(0);
+    }
+    let new_ifd = out.len();
+    let entry_count = entries.len() + 3;
+    let rational_pos = new_ifd + 2 + entry_count * 12 + 4;
+    if rational_pos + 16 > u32::MAX as usize {
+        return Err("TIFFファイルが大きすぎます".to_string());
+    }
+
+    let entry = |tag: u16, field_type: u16, value: [u8; 4]| {
+        let mut bytes = [0u8; 12];
+        bytes[0..2].copy_from_slice(&u16_bytes(tag));
+        bytes[2..4].copy_from_slice(&u16_bytes(field_type));
+        bytes[4..8].copy_from_slice(&u32_bytes(1));
+        bytes[8..12].copy_from_slice(&value);
+        (tag, bytes)
+    };
+    // RATIONAL(5) は値の位置、SHORT(3) は値そのもの（2 = インチ）
+    entries.push(entry(X_RESOLUTION, 5, u32_bytes(rational_pos as u32)));
+    entries.push(entry(Y_RESOLUTION, 5, u32_bytes(rational_pos as u32 + 8)));
+    let unit = u16_bytes(2);
+    entries.push(entry(RESOLUTION_UNIT, 3, [unit[0], unit[1], 0, 0]));
+    entries.sort_by_key(|(tag, _)| *tag);
+
+    out.extend_from_slice(&u16_bytes(entry_count as u16));
+    for (_, bytes) in &entries {
+        out.extend_from_slice(bytes);
+    }
+    out.extend_from_slice(&data[next_ifd_pos..next_ifd_pos + 4]);
+    for _ in 0..2 {
+        out.extend_from_slice(&u32_bytes(dpi));
+        out.extend_from_slice(&u32_bytes(1));
+    }
+    out[4..8].copy_from_slice(&u32_bytes(new_ifd as u32));
+    Ok(out)
+}
+
+// 解像度を記録できる形式なら記録後のデータを返す
+pub fn stamp_dpi(ext: &str, data: &[u8], dpi: u32) -> Option<Result<Vec<u8>, String>> {
+    match ext {
+        "jpg" | "jpeg" => Some(stamp_jpeg_dpi(data, dpi)),
+        "png" => Some(stamp_png_dpi(data, dpi)),
+        "tif" | "tiff" => Some(stamp_tiff_dpi(data, dpi)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use image::{DynamicImage, ImageFormat, RgbImage};
+
+    fn encode(format: ImageFormat) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(RgbImage::new(8, 8)).write_to(&mut buffer, format).unwrap();
+        buffer.into_inner()
+    }
+
+    // 画像データのない、IFDだけのTIFF（EXIFの中身と同じ形式）
+    fn bare_tiff(little_endian: bool) -> Vec<u8> {
+        let mut data = if little_endian { b"II*\0".to_vec() } else { b"MM\0*".to_vec() };
+        data.extend_from_slice(&if little_endian { 8u32.to_le_bytes() } else { 8u32.to_be_bytes() });
+        data.extend_from_slice(&[0; 6]);
+        data
+    }
+
+    fn resolution(exif: &exif::Exif, tag: exif::Tag) -> Option<(u32, u32)> {
+        match exif.get_field(tag, exif::In::PRIMARY)?.value {
+            exif::Value::Rational(ref values) => values.first().map(|r| (r.num, r.denom)),
+            _ => None,
+        }
+    }
+
+    fn assert_exif_dpi(exif: &exif::Exif, dpi: u32) {
+        assert_eq!(resolution(exif, exif::Tag::XResolution), Some((dpi, 1)));
+        assert_eq!(resolution(exif, exif::Tag::YResolution), Some((dpi, 1)));
+        let unit = exif.get_field(exif::Tag::ResolutionUnit, exif::In::PRIMARY).unwrap();
+        assert_eq!(unit.value.get_uint(0), Some(2));
+    }
+
+    #[test]
+    fn jpeg_dpi_updates_jfif_exif_and_photoshop() {
+        let jpeg = encode(ImageFormat::Jpeg);
+        let tiff = stamp_tiff_dpi(&bare_tiff(false), 72).unwrap();
+        let mut app1 = vec![0xFF, 0xE1];
+        app1.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+        app1.extend_from_slice(b"Exif\0\0");
+        app1.extend_from_slice(&tiff);
+        let mut app13 = vec![0xFF, 0xED, 0x00, 0x2C];
+        app13.extend_from_slice(b"Photoshop 3.0\08BIM\x03\xED\0\0\0\0\0\x10");
+        app13.extend_from_slice(&[0, 72, 0, 0, 0, 1, 0, 1, 0, 72, 0, 0, 0, 1, 0, 1]);
+        let mut data = jpeg[..2].to_vec();
+        data.extend_from_slice(&app1);
+        data.extend_from_slice(&app13);
+        data.extend_from_slice(&jpeg[2..]);
+
+        let out = stamp_jpeg_dpi(&data, 350).unwrap();
+        let jfif = find_subslice(&out, b"JFIF\0", 0).unwrap();
+        assert_eq!(out[jfif + 7], 1);
+        assert_eq!(&out[jfif + 8..jfif + 12], &[1, 94, 1, 94]);
+        let exif = exif::Reader::new().read_from_container(&mut Cursor::new(&out)).unwrap();
+        assert_exif_dpi(&exif, 350);
+        let info = find_subslice(&out, b"8BIM\x03\xED", 0).unwrap() + 12;
+        assert_eq!(&out[info..info + 6], &[1, 94, 0, 0, 0, 1]);
+        assert_eq!(&out[info + 8..info + 14], &[1, 94, 0, 0, 0, 1]);
+        assert!(image::load_from_memory(&out).is_ok());
+    }
+
+    #[test]
+    fn png_dpi_replaces_phys() {
+        let once = stamp_png_dpi(&encode(ImageFormat::Png), 72).unwrap();
+        let out = stamp_png_dpi(&once, 350).unwrap();
+        let phys = find_subslice(&out, b"pHYs", 0).unwrap();
+        assert!(find_subslice(&out, b"pHYs", phys + 1).is_none());
+        let ppm = (350.0f64 / 0.0254).round() as u32;
+        assert_eq!(&out[phys + 4..phys + 8], &ppm.to_be_bytes());
+        assert_eq!(&out[phys + 8..phys + 12], &ppm.to_be_bytes());
+        assert_eq!(out[phys + 12], 1);
+        assert_eq!(&out[phys + 13..phys + 17], &crc32fast::hash(&out[phys..phys + 13]).to_be_bytes());
+        assert!(image::load_from_memory(&out).is_ok());
+    }
+
+    #[test]
+    fn tiff_dpi_round_trips_in_both_byte_orders() {
+        for little_endian in [true, false] {
+            let out = stamp_tiff_dpi(&stamp_tiff_dpi(&bare_tiff(little_endian), 72).unwrap(), 350).unwrap();
+            assert_exif_dpi(&exif::Reader::new().read_raw(out).unwrap(), 350);
+        }
+        let out = stamp_tiff_dpi(&encode(ImageFormat::Tiff), 350).unwrap();
+        assert!(image::load_from_memory(&out).is_ok());
+        assert_exif_dpi(&exif::Reader::new().read_raw(out).unwrap(), 350);
+    }
+
+    #[test]
+    fn truncated_tiff_is_rejected() {
+        assert!(stamp_tiff_dpi(b"II*\0", 350).is_err());
+        assert!(stamp_tiff_dpi(b"II*\0\xFF\xFF\xFF\xFF", 350).is_err());
+    }
+}
@@ -0,0 +1,395 @@
+use std::path::Path;
+
+// ファイル名テンプレートに埋め込む値（エクスポートとTIFF変換で共通）
+#[derive(Debug, Default)]
+pub struct NameTokens<'a> {
+    /// {name}: ページの出力名
+    pub name: &'a str,
+    /// {original}: 元ファイル名（拡張子なし）
+    pub original: &'a str,
+    /// {chapter}: チャプター名（サブフォルダ名）
+    pub chapter: &'a str,
+    /// {page}: ページ番号（ノンブル）
+    pub page: Option<u32>,
+    /// {index}: 処理対象内の連番（1始まり）
+    pub index: usize,
+    /// 桁数を指定しない番号のゼロ埋め桁数
+    pub digits: usize,
+}
+
+impl<'a> NameTokens<'a> {
+    // 元ファイルのパスから {original} を設定
+    pub fn with_source(mut self, source: &'a Path) -> Self {
+        self.original = source.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        self
+    }
+}
+
+// 番号のゼロ埋め桁数の上限（{page:999999} のような指定で巨大な名前を作らない）
+const MAX_NUMBER_WIDTH: usize = 10;
+
+fn pad_number(value: usize, width: usize) -> String {
+    format!("{:0width$}", value, width = width.clamp(1, MAX_NUMBER_WIDTH))
+}
+
+// Windowsの予約デバイス名か（大文字小文字を区別せず、「CON.txt」のように拡張子が付いていても使えない）
+fn is_reserved_name(name: &str) -> bool {
+    let base = name.split('.').next().unwrap_or_default().trim_end().to_ascii_uppercase();
+    match base.as_str() {
+        "CON" | "PRN" | "AUX" | "NUL" => true,
+        _ => {
+            base.len() == 4
+                && (base.starts_with("COM") || base.starts_with("LPT"))
+                && matches!(base.as_bytes()[3], b'1'..=b'9')
+        }
+    }
+}
+
+// 予約デバイス名は最初の「.」の前に「_」を付けて避ける（CON → CON_、nul.txt → nul_.txt）
+fn avoid_reserved_name(name: String) -> String {
+    if !is_reserved_name(&name) {
+        return name;
+    }
+    let mut name = name;
+    name.insert(name.find('.').unwrap_or(name.len()), '_');
+    name
+}
+
+// 展開後の名前をファイル名として使える形にする
+// 区切り文字や禁止文字は「_」に置き換え、「.」「..」だけの名前や末尾の「.」・空白（Windowsで消える）、予約デバイス名も残さない
+fn sanitize_file_name(name: &str) -> String {
+    let replaced: String = name.chars().map(|c| if is_forbidden_in_folder_name(c) { '_' } else { c }).collect();
+    let trimmed = replaced.trim_end_matches(['.', ' ']);
+    if trimmed.trim().is_empty() {
+        "_".to_string()
+    } else {
+        avoid_reserved_name(trimmed.to_string())
+    }
+}
+
+// テンプレートを展開
+// {name} {original} {chapter} {page} {index} に対応し、番号は {page:3} のように桁数を指定できる
+// 不明なトークンはそのまま残す。桁数は1〜10に丸め、結果はファイル名として使える形に整える
+pub fn render_name_template(template: &str, tokens: &NameTokens) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}').map(|e| start + e) else {
+            break;
+        };
+        let token = &rest[start + 1..end];
+        let (key, width) = match token.split_once(':') {
+            Some((key, width)) => (key, width.parse::<usize>().ok()),
+            None => (token, None),
+        };
+        let width = width.unwrap_or(tokens.digits);
+
+        let value = match key {
+            "name" => Some(tokens.name.to_string()),
+            "original" => Some(tokens.original.to_string()),
+            "chapter" => Some(tokens.chapter.to_string()),
+            "page" => Some(tokens.page.map(|p| pad_number(p as usize, width)).unwrap_or_default()),
+            "index" => Some(pad_number(tokens.index, width)),
+            _ => None,
+        };
+        match value {
+            Some(value) => result.push_str(&value),
+            None => result.push_str(&rest[start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    sanitize_file_name(&result)
+}
+
+// 印刷所の入稿システムで使えない文字（Windowsのファイル名の禁止文字と制御文字）
+fn is_forbidden_in_folder_name(c: char) -> bool {
+    matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || c.is_control()
+}
+
+// 全角英数記号・全角スペースを半角に
+fn to_half_width(c: char) -> char {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        '\u{3000}' => ' ',
+        _ => c,
+    }
+}
+
+// 章名によく使う漢字の読み（それ以外の漢字はローマ字にできないため除く）
+fn kanji_reading(c: char) -> Option<&'static str> {
+    Some(match c {
+        '第' => "dai",
+        '話' => "wa",
+        '章' => "shou",
+        '巻' => "kan",
+        '部' => "bu",
+        '回' => "kai",
+        '編' => "hen",
+        '幕' => "maku",
+        '序' => "jo",
+        '終' => "shuu",
+        '最' => "sai",
+        '番' => "ban",
+        '外' => "gai",
+        '前' => "zen",
+        '後' => "kou",
+        '上' => "jou",
+        '中' => "chuu",
+        '下' => "ge",
+        '表' => "hyou",
+        '紙' => "shi",
+        '扉' => "tobira",
+        '奥' => "oku",
+        '付' => "zuke",
+        _ => return None,
+    })
+}
+
+// ひらがな1文字のヘボン式ローマ字（カタカナはひらがなに直してから渡す）
+fn kana_romaji(c: char) -> Option<&'static str> {
+    Some(match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' => "e",
+        'お' | 'ぉ' => "o",
+        'か' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' => "ke",
+        'こ' => "ko",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' | 'ゃ' => "ya",
+        'ゆ' | 'ゅ' => "yu",
+        'よ' | 'ょ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'を' => "wo",
+        'ん' => "n",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'ざ' => "za",
+        'じ' => "ji",
+        'ず' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'だ' => "da",
+        'ぢ' => "ji",
+        'づ' => "zu",
+        'で' => "de",
+        'ど' => "do",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ゔ' => "vu",
+        _ => return None,
+    })
+}
+
+// カタカナをひらがなに
+fn katakana_to_hiragana(c: char) -> char {
+    match c {
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+// 直後の小書きの「ゃゅょ」と合わせて拗音になるかな（き・し・ち・に など）
+fn forms_youon(c: char) -> bool {
+    kana_romaji(c).is_some_and(|r| r.len() > 1 && r.ends_with('i'))
+}
+
+// かな・章名の漢字をローマ字に変換（拗音・促音・長音記号に対応、変換できない文字は除く）
+fn romanize(text: &str) -> String {
+    let chars: Vec<char> = text.chars().map(katakana_to_hiragana).collect();
+    let mut result = String::with_capacity(text.len());
+    let mut double_next = false;
+
+    for (i, &c) in chars.iter().enumerate() {
+        let syllable = match c {
+            // 促音: 次の子音を重ねる（ch は tch）
+            'っ' => {
+                double_next = true;
+                continue;
+            }
+            // 長音記号: 直前の母音を重ねる
+            'ー' => {
+                if let Some(vowel) = result.chars().last().filter(|v| "aiueo".contains(*v)) {
+                    result.push(vowel);
+                }
+                continue;
+            }
+            // 拗音の小書き文字は直前の文字と合わせて処理済み
+            'ゃ' | 'ゅ' | 'ょ' if i > 0 && forms_youon(chars[i - 1]) => {
+                continue;
+            }
+            _ => match kana_romaji(c).or_else(|| kanji_reading(c)) {
+                Some(romaji) => {
+                    let next_small = chars.get(i + 1).copied().filter(|n| matches!(n, 'ゃ' | 'ゅ' | 'ょ'));
+                    match next_small {
+                        // きゃ → kya、しゃ → sha、ちゃ → cha、じゃ → ja
+                        Some(small) if forms_youon(c) => {
+                            let base = &romaji[..romaji.len() - 1];
+                            let vowel = &kana_romaji(small).unwrap_or("ya")[1..];
+                            if matches!(base, "sh" | "ch" | "j") {
+                                format!("{}{}", base, vowel)
+                            } else {
+                                format!("{}y{}", base, vowel)
+                            }
+                        }
+                        _ => romaji.to_string(),
+                    }
+                }
+                None => {
+                    double_next = false;
+                    if c.is_ascii() {
+                        result.push(c);
+                    } else {
+                        result.push(' ');
+                    }
+                    continue;
+                }
+            },
+        };
+
+        if std::mem::take(&mut double_next) {
+            match syllable.chars().next() {
+                Some('c') => result.push('t'),
+                Some(first) if !"aiueon".contains(first) => result.push(first),
+                _ => {}
+            }
+        }
+        result.push_str(&syllable);
+    }
+    result
+}
+
+// チャプターのサブフォルダ名を入稿システム向けに整える
+// "normalize": 全角英数記号を半角に、空白を "_" に、使えない記号を除去
+// "romanize": さらにかな・章名の漢字をローマ字にし、英数字と "-" "_" "." 以外を除く
+// 整えた結果が空になる場合は空文字を返す（呼び出し側で連番の名前を付ける）
+// どの方法でもWindowsの予約デバイス名（CON・NUL・COM1 など）は「_」を付けて避ける
+pub fn sanitize_folder_name(name: &str, mode: &str) -> Result<String, String> {
+    if mode == "keep" {
+        return Ok(avoid_reserved_name(name.to_string()));
+    }
+    let normalized: String = name.chars().map(to_half_width).collect();
+    let text = match mode {
+        "normalize" => normalized,
+        "romanize" => romanize(&normalized)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { ' ' })
+            .collect(),
+        other => return Err(format!("不明なサブフォルダ名の変換方法: {}", other)),
+    };
+
+    // 連続する空白は1つの "_" に
+    let mut result = String::with_capacity(text.len());
+    for word in text.split_whitespace() {
+        let word: String = word.chars().filter(|c| !is_forbidden_in_folder_name(*c)).collect();
+        if word.is_empty() {
+            continue;
+        }
+        if !result.is_empty() {
+            result.push('_');
+        }
+        result.push_str(&word);
+    }
+    // 末尾のピリオドはWindowsで扱えない
+    Ok(avoid_reserved_name(result.trim_matches(|c| c == '.' || c == '_').to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_tokens_with_width() {
+        let tokens = NameTokens { name: "p", chapter: "第1話", page: Some(7), index: 2, digits: 3, ..Default::default() };
+        assert_eq!(render_name_template("{chapter}_{page}_{index:2}_{name}", &tokens), "第1話_007_02_p");
+        assert_eq!(render_name_template("{unknown}_{index}", &tokens), "{unknown}_002");
+    }
+
+    #[test]
+    fn clamps_number_width() {
+        let tokens = NameTokens { index: 5, digits: 3, ..Default::default() };
+        assert_eq!(render_name_template("{index:0}", &tokens), "5");
+        assert_eq!(render_name_template("{index:999999}", &tokens), "0000000005");
+    }
+
+    #[test]
+    fn sanitizes_separators_and_dot_names() {
+        let tokens = NameTokens { chapter: "../a\\b", name: "..", index: 1, digits: 3, ..Default::default() };
+        assert_eq!(render_name_template("{chapter}", &tokens), ".._a_b");
+        assert_eq!(render_name_template("{name}", &tokens), "_");
+        assert_eq!(render_name_template("a:b?.", &tokens), "a_b_");
+    }
+
+    #[test]
+    fn avoids_reserved_device_names() {
+        let tokens = NameTokens { name: "nul", index: 1, digits: 3, ..Default::default() };
+        assert_eq!(render_name_template("{name}", &tokens), "nul_");
+        assert_eq!(sanitize_folder_name("CON", "keep").unwrap(), "CON_");
+        assert_eq!(sanitize_folder_name("ｃｏｍ１", "normalize").unwrap(), "com1_");
+        assert_eq!(sanitize_folder_name("aux.txt", "normalize").unwrap(), "aux_.txt");
+        assert_eq!(sanitize_folder_name("LPT0", "normalize").unwrap(), "LPT0");
+        assert_eq!(sanitize_folder_name("Console", "normalize").unwrap(), "Console");
+    }
+
+    #[test]
+    fn romanizes_kana() {
+        assert_eq!(romanize("さくら"), "sakura");
+        assert_eq!(romanize("きゃっと"), "kyatto");
+        assert_eq!(romanize("しゃしん"), "shashin");
+        assert_eq!(romanize("マッチ"), "matchi");
+        assert_eq!(romanize("ラーメン"), "raamen");
+    }
+
+    #[test]
+    fn romanizes_chapter_folder_names() {
+        assert_eq!(sanitize_folder_name("第１話　さくら", "romanize").unwrap(), "dai1wa_sakura");
+        assert_eq!(sanitize_folder_name("第1話 猫", "romanize").unwrap(), "dai1wa");
+        assert_eq!(sanitize_folder_name("猫", "romanize").unwrap(), "");
+    }
+}
@@ -0,0 +1,71 @@
+use std::time::Duration;
+use crate::app_mode::is_safe_mode;
+use crate::settings::load_settings;
+use crate::types::{OperationSummary, WebhookConfig};
+
+// Webhook送信のタイムアウト
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl OperationSummary {
+    pub fn new(kind: &str, label: &str, result: Result<Option<String>, String>) -> Self {
+        let (status, details, error) = match result {
+            Ok(details) => ("completed", details, None),
+            Err(e) => ("failed", None, Some(e)),
+        };
+        Self {
+            kind: kind.to_string(),
+            label: label.to_string(),
+            status: status.to_string(),
+            details,
+            error,
+            finished_at: chrono::Local::now().to_rfc3339(),
+        }
+    }
+
+    // Slack等に表示する1行の文面
+    fn message(&self) -> String {
+        match (&self.error, &self.details) {
+            (Some(e), _) => format!("[失敗] {}: {}", self.label, e),
+            (None, Some(details)) => format!("[完了] {}（{}）", self.label, details),
+            (None, None) => format!("[完了] {}", self.label),
+        }
+    }
+}
+
+// 登録済みのWebhookへ完了を通知（送信はバックグラウンドで行い、失敗しても処理には影響させない）
+pub fn notify_operation_finished(summary: OperationSummary) {
+    if is_safe_mode() {
+        return;
+    }
+    let succeeded = summary.error.is_none();
+    let webhooks: Vec<WebhookConfig> = load_settings()
+        .webhooks
+        .into_iter()
+        .filter(|w| w.enabled && if succeeded { w.on_success } else { w.on_failure })
+        .collect();
+    if webhooks.is_empty() {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        for webhook in &webhooks {
+            if let Err(e) = post_webhook(webhook, &summary) {
+                eprintln!("Webhook通知に失敗: {} - {}", webhook.url, e);
+            }
+        }
+    });
+}
+
+// Webhookに1件送信
+pub fn post_webhook(webhook: &WebhookConfig, summary: &OperationSummary) -> Result<(), String> {
+    let body = match webhook.format.as_str() {
+        "slack" => serde_json::json!({ "text": summary.message() }),
+        _ => serde_json::to_value(summary).map_err(|e| format!("JSON変換に失敗: {}", e))?,
+    };
+
+    ureq::post(&webhook.url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .send_json(body)
+        .map(|_| ())
+        .map_err(|e| format!("送信エラー: {}", e))
+}
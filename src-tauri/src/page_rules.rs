@@ -0,0 +1,52 @@
+use crate::types::{PageClassification, PageTypeRule};
+
+// ワイルドカード（* = 任意の文字列, ? = 任意の1文字）で一致判定
+fn wildcard_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // 直前の * の位置と、そこから照合を再開するテキスト位置
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// ファイル名がパターンに一致するか（大文字小文字を区別しない）
+pub fn matches_pattern(pattern: &str, file_name: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = file_name.to_lowercase().chars().collect();
+    wildcard_match(&pattern, &text)
+}
+
+// 最初に一致したルールでファイルを分類
+pub fn classify_file(path: &str, rules: &[PageTypeRule]) -> PageClassification {
+    let file_name = std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let matched = rules
+        .iter()
+        .enumerate()
+        .find(|(_, rule)| rule.enabled && matches_pattern(&rule.pattern, &file_name));
+
+    PageClassification {
+        path: path.to_string(),
+        page_type: matched.and_then(|(_, rule)| rule.page_type.clone()),
+        label: matched.and_then(|(_, rule)| rule.label.clone()),
+        rule_index: matched.map(|(index, _)| index),
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use image::DynamicImage;
+use pdfium_render::prelude::*;
+use crate::image_utils::validate_dimensions_within;
+use crate::types::ImageLimits;
+
+// PDFページを画像として扱うときの既定解像度（入稿データの標準に合わせる）
+pub const PDF_RENDER_DPI: u32 = 350;
+
+// PDFium ライブラリを読み込み（実行ファイルと同じフォルダ、なければシステムのライブラリ）
+fn bind_pdfium() -> Result<Pdfium, String> {
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()));
+    let bindings = exe_dir
+        .map(|dir| Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path(&dir)))
+        .filter(|result| result.is_ok())
+        .unwrap_or_else(Pdfium::bind_to_system_library)
+        .map_err(|e| format!("PDFiumライブラリを読み込めません（PDFの読み込みに必要です）: {}", e))?;
+    Ok(Pdfium::new(bindings))
+}
+
+// PDFを開いて処理を実行
+// PDFiumの初期化・終了（FPDF_InitLibrary / FPDF_DestroyLibrary）は並行に呼べないため、
+// ライブラリはプロセスで1回だけ初期化し、rayon のワーカーなど複数スレッドからの利用は順番に行う
+fn with_document<T>(path: &Path, f: impl FnOnce(&PdfDocument) -> Result<T, String>) -> Result<T, String> {
+    static PDFIUM: OnceLock<Result<Mutex<Pdfium>, String>> = OnceLock::new();
+    let pdfium = PDFIUM
+        .get_or_init(|| bind_pdfium().map(Mutex::new))
+        .as_ref()
+        .map_err(|e| e.clone())?;
+    let pdfium = pdfium.lock().unwrap_or_else(|e| e.into_inner());
+    let document = pdfium
+        .load_pdf_from_file(path, None)
+        .map_err(|e| format!("PDF読み込みエラー: {}", e))?;
+    f(&document)
+}
+
+fn get_page<'a>(document: &PdfDocument<'a>, index: usize) -> Result<PdfPage<'a>, String> {
+    let page_index = PdfPageIndex::try_from(index).map_err(|_| format!("ページ番号が大きすぎます: {}", index + 1))?;
+    document
+        .pages()
+        .get(page_index)
+        .map_err(|_| format!("PDFにページ{}がありません", index + 1))
+}
+
+// ポイント（1/72インチ）をピクセルに変換
+fn points_to_px(points: f32, dpi: u32) -> u32 {
+    ((points as f64) / 72.0 * dpi as f64).round().max(1.0) as u32
+}
+
+fn page_dimensions(page: &PdfPage, dpi: u32) -> (u32, u32) {
+    (points_to_px(page.width().value, dpi), points_to_px(page.height().value, dpi))
+}
+
+// PDFのページ数
+pub fn pdf_page_count(path: &Path) -> Result<usize, String> {
+    with_document(path, |document| Ok(document.pages().len() as usize))
+}
+
+// 指定解像度でレンダリングした場合のページのピクセルサイズ
+pub fn pdf_page_dimensions(path: &Path, index: usize, dpi: u32) -> Result<(u32, u32), String> {
+    with_document(path, |document| Ok(page_dimensions(&get_page(document, index)?, dpi)))
+}
+
+// PDFのページを画像にレンダリング（max_side 指定時は長辺をその長さに収める）
+// limits 指定時はレンダリング前に原寸が上限内か確認する（寸法の確認のために読み込み直さない）
+pub fn render_pdf_page(
+    path: &Path,
+    index: usize,
+    dpi: u32,
+    max_side: Option<u32>,
+    limits: Option<&ImageLimits>,
+) -> Result<DynamicImage, String> {
+    with_document(path, |document| {
+        let page = get_page(document, index)?;
+
+        let (mut width, mut height) = page_dimensions(&page, dpi);
+        if let Some(limits) = limits {
+            validate_dimensions_within(width, height, limits)?;
+        }
+        if let Some(max_side) = max_side {
+            let scale = (max_side as f64 / width.max(height) as f64).min(1.0);
+            width = ((width as f64 * scale).round() as u32).max(1);
+            height = ((height as f64 * scale).round() as u32).max(1);
+        }
+
+        let config = PdfRenderConfig::new()
+            .set_target_width(width as i32)
+            .set_maximum_height(height as i32);
+        let bitmap = page
+            .render_with_config(&config)
+            .map_err(|e| format!("PDFレンダリングエラー: {}", e))?;
+
+        // 透明部分のない白背景のRGBとして扱う（紙面と同じ見え方）
+        Ok(DynamicImage::ImageRgb8(bitmap.as_image().to_rgb8()))
+    })
+}
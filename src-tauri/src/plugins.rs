@@ -0,0 +1,147 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use crate::app_mode::is_safe_mode;
+use crate::file_utils::get_config_path;
+use crate::types::{AnalyzerPluginInfo, AnalyzerPluginManifest, AnalyzerPluginResult, AnalyzerRequest, AnalyzerResponse};
+
+// プラグインとの入出力形式のバージョン
+pub const ANALYZER_PROTOCOL_VERSION: u32 = 1;
+
+// プラグインの配置フォルダ（設定ディレクトリ/plugins/<プラグイン>/plugin.json）
+pub fn plugins_dir() -> Result<PathBuf, String> {
+    Ok(get_config_path()?.join("plugins"))
+}
+
+// インストール済みのプラグインを列挙（セーフモードでは読み込まない）
+pub fn discover_plugins() -> Vec<AnalyzerPluginInfo> {
+    if is_safe_mode() {
+        return Vec::new();
+    }
+    let Ok(dir) = plugins_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut plugins: Vec<AnalyzerPluginInfo> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter_map(|plugin_dir| {
+            let manifest_path = plugin_dir.join("plugin.json");
+            let content = fs::read_to_string(&manifest_path).ok()?;
+            match serde_json::from_str::<AnalyzerPluginManifest>(&content) {
+                Ok(manifest) => Some(AnalyzerPluginInfo {
+                    manifest,
+                    directory: plugin_dir.to_string_lossy().to_string(),
+                }),
+                Err(e) => {
+                    eprintln!("プラグインのマニフェストが不正です: {} - {}", manifest_path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.manifest.id.cmp(&b.manifest.id));
+    plugins
+}
+
+// プラグインのプロセスを起動し、要求JSONを標準入力へ、応答JSONを標準出力から受け取る
+pub fn run_plugin(plugin: &AnalyzerPluginInfo, request: &AnalyzerRequest) -> Result<AnalyzerResponse, String> {
+    let dir = Path::new(&plugin.directory);
+    let manifest = &plugin.manifest;
+
+    // プラグインフォルダ内の実行ファイルを優先
+    let local_command = dir.join(&manifest.command);
+    let program = if local_command.exists() {
+        local_command.into_os_string()
+    } else {
+        manifest.command.clone().into()
+    };
+
+    let mut command = Command::new(program);
+    command
+        .args(&manifest.args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // コンソール用のプラグインでもウィンドウを表示しない
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("プラグインの起動に失敗: {} - {}", manifest.id, e))?;
+
+    let input = serde_json::to_vec(request).map_err(|e| format!("JSON変換に失敗: {}", e))?;
+
+    // パイプ詰まりを避けるため入出力は別スレッドで処理
+    let mut stdin = child.stdin.take().ok_or("標準入力を開けません")?;
+    let writer = std::thread::spawn(move || {
+        let _ = stdin.write_all(&input);
+    });
+    let mut stdout = child.stdout.take().ok_or("標準出力を開けません")?;
+    let reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf);
+        buf
+    });
+    let mut stderr = child.stderr.take().ok_or("標準エラーを開けません")?;
+    let err_reader = std::thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stderr.read_to_string(&mut buf);
+        buf
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(manifest.timeout_secs.max(1));
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("プラグインがタイムアウトしました（{}秒）", manifest.timeout_secs));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(format!("プラグインの終了待ちに失敗: {}", e)),
+        }
+    };
+
+    let _ = writer.join();
+    let output = reader.join().unwrap_or_default();
+    let errors = err_reader.join().unwrap_or_default();
+
+    if !status.success() {
+        return Err(format!("プラグインがエラー終了しました ({}): {}", status, errors.trim()));
+    }
+
+    serde_json::from_slice(&output).map_err(|e| format!("プラグインの応答を解析できません: {}", e))
+}
+
+// 指定したプラグイン（未指定なら全プラグイン）を順に実行し、個別の結果を返す
+pub fn run_plugins(request: &AnalyzerRequest, plugin_ids: Option<&[String]>) -> Vec<AnalyzerPluginResult> {
+    discover_plugins()
+        .into_iter()
+        .filter(|p| plugin_ids.map_or(true, |ids| ids.contains(&p.manifest.id)))
+        .map(|plugin| match run_plugin(&plugin, request) {
+            Ok(response) => AnalyzerPluginResult {
+                plugin_id: plugin.manifest.id,
+                findings: response.findings,
+                error: None,
+            },
+            Err(e) => AnalyzerPluginResult {
+                plugin_id: plugin.manifest.id,
+                findings: Vec::new(),
+                error: Some(e),
+            },
+        })
+        .collect()
+}
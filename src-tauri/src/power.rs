@@ -0,0 +1,114 @@
+use std::sync::Mutex;
+
+// スリープ抑止の参照数と、抑止中のOSハンドル
+static INHIBITOR: Mutex<(usize, Option<Inhibitor>)> = Mutex::new((0, None));
+
+// 長時間処理の間スリープを抑止するガード（破棄時に解除）
+pub struct SleepGuard {
+    _private: (),
+}
+
+impl Drop for SleepGuard {
+    fn drop(&mut self) {
+        let mut state = INHIBITOR.lock().unwrap_or_else(|e| e.into_inner());
+        state.0 = state.0.saturating_sub(1);
+        if state.0 == 0 {
+            // 最後の処理が終わったら抑止を解除（Inhibitor の破棄で解除される）
+            state.1 = None;
+        }
+    }
+}
+
+// アプリ終了時に抑止を解除（static は破棄されないため、抑止コマンドの子プロセスが残らないよう明示的に止める）
+pub fn release_sleep_inhibitor() {
+    INHIBITOR.lock().unwrap_or_else(|e| e.into_inner()).1 = None;
+}
+
+// エクスポート・TIFF変換・アップロード中のスリープを抑止
+// ノートPCが途中でスリープすると納品フォルダが中途半端な状態で残るため
+pub fn inhibit_sleep(reason: &str) -> SleepGuard {
+    let mut state = INHIBITOR.lock().unwrap_or_else(|e| e.into_inner());
+    state.0 += 1;
+    if state.1.is_none() {
+        state.1 = Inhibitor::acquire(reason);
+    }
+    SleepGuard { _private: () }
+}
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn SetThreadExecutionState(flags: u32) -> u32;
+}
+
+// 実行状態はスレッド単位のため、専用スレッドで保持する（破棄すると送信側が閉じ、スレッドが実行状態を戻す）
+#[cfg(windows)]
+struct Inhibitor {
+    _stop: std::sync::mpsc::Sender<()>,
+}
+
+#[cfg(windows)]
+impl Inhibitor {
+    fn acquire(_reason: &str) -> Option<Self> {
+        const ES_CONTINUOUS: u32 = 0x8000_0000;
+        const ES_SYSTEM_REQUIRED: u32 = 0x0000_0001;
+
+        let (stop, rx) = std::sync::mpsc::channel::<()>();
+        std::thread::spawn(move || {
+            unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) };
+            // 送信側が破棄されるまで待機
+            let _ = rx.recv();
+            unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+        });
+        Some(Self { _stop: stop })
+    }
+}
+
+// macOS / Linux は抑止コマンドを子プロセスとして保持する
+#[cfg(not(windows))]
+struct Inhibitor {
+    child: std::process::Child,
+}
+
+#[cfg(not(windows))]
+impl Inhibitor {
+    fn acquire(reason: &str) -> Option<Self> {
+        let mut command = if cfg!(target_os = "macos") {
+            let mut cmd = std::process::Command::new("caffeinate");
+            cmd.arg("-i").arg("-w").arg(std::process::id().to_string());
+            cmd
+        } else {
+            let mut cmd = std::process::Command::new("systemd-inhibit");
+            cmd.arg("--what=idle:sleep")
+                .arg("--who=台割マネージャー")
+                .arg(format!("--why={}", reason))
+                // アプリが異常終了しても残らないよう、待機コマンドはこのプロセスの終了で止める
+                .arg("tail")
+                .arg(format!("--pid={}", std::process::id()))
+                .arg("-f")
+                .arg("/dev/null");
+            cmd
+        };
+
+        match command
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+        {
+            Ok(child) => Some(Self { child }),
+            Err(e) => {
+                eprintln!("スリープ抑止に失敗: {}", e);
+                None
+            }
+        }
+    }
+}
+
+// 途中で終わったジョブでも抑止コマンドが残らないよう、破棄時に必ず終了させる
+#[cfg(not(windows))]
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
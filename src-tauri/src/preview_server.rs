@@ -0,0 +1,254 @@
+use std::fs;
+use std::net::{IpAddr, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use tiny_http::{Header, Method, Request, Response, Server};
+use crate::commands::viewer::{render_viewer_html, write_web_image, ViewerEntry};
+use crate::file_utils::{cloud_placeholder_reason, path_modified_millis};
+use crate::token::{generate_token, tokens_match};
+use crate::types::{PreviewServerInfo, ViewerExportOptions, ViewerPage};
+
+// ページ画像の一時ファイル名の連番
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// 閲覧中の台割（アプリ側で並べ替えたら差し替える）
+pub struct PreviewBook {
+    pub title: String,
+    pub pages: Vec<ViewerPage>,
+    pub options: ViewerExportOptions,
+}
+
+struct RunningPreview {
+    server: Arc<Server>,
+    book: Arc<RwLock<PreviewBook>>,
+    // 起動し直すたびに作り直す（ポートが同じなら待ち受けはそのまま）
+    token: Arc<RwLock<String>>,
+    info: PreviewServerInfo,
+}
+
+// 起動中のタブレット向けプレビューサーバー（アプリの管理状態）
+pub struct PreviewServer {
+    running: Mutex<Option<RunningPreview>>,
+}
+
+impl PreviewServer {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+        }
+    }
+}
+
+// LAN側のアドレス（UDPの connect は経路を決めるだけでパケットは送らない）
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(("8.8.8.8", 80)).ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip()).filter(|ip| !ip.is_loopback())
+}
+
+// サーバーを起動（起動中なら新しいトークンで起動し直す）
+pub fn start_preview_server(
+    state: &PreviewServer,
+    book: PreviewBook,
+    port: u16,
+    cache_dir: PathBuf,
+) -> Result<PreviewServerInfo, String> {
+    let mut running = state.running.lock().map_err(|e| e.to_string())?;
+    let token = generate_token()?;
+    let page_count = book.pages.len();
+
+    // 同じポートなら待ち受けを続けたまま、台割とトークンだけ差し替える
+    if let Some(current) = running.as_mut().filter(|r| r.info.port == port) {
+        *current.book.write().map_err(|e| e.to_string())? = book;
+        *current.token.write().map_err(|e| e.to_string())? = token.clone();
+        current.info = preview_info(port, &token, page_count);
+        return Ok(current.info.clone());
+    }
+
+    // 新しいポートで待ち受けてから古いサーバーを止める（間に他のプロセスにポートを取られない）
+    // タブレットから接続できるようLAN側でも待ち受ける（閲覧のみ・トークン必須）
+    let server = Server::http(("0.0.0.0", port))
+        .map_err(|e| format!("プレビューサーバーの起動に失敗（ポート {}）: {}", port, e))?;
+    let port = server.server_addr().to_ip().map(|addr| addr.port()).unwrap_or(port);
+    let server = Arc::new(server);
+    let info = preview_info(port, &token, page_count);
+
+    let book = Arc::new(RwLock::new(book));
+    let token = Arc::new(RwLock::new(token));
+    let thread_server = server.clone();
+    let thread_book = book.clone();
+    let thread_token = token.clone();
+    std::thread::spawn(move || {
+        for request in thread_server.incoming_requests() {
+            // ページ画像の生成に時間がかかるため1リクエスト1スレッドで処理
+            let book = thread_book.clone();
+            let token = thread_token.clone();
+            let cache_dir = cache_dir.clone();
+            std::thread::spawn(move || handle_request(request, &book, &token, &cache_dir));
+        }
+    });
+
+    if let Some(previous) = running.replace(RunningPreview {
+        server,
+        book,
+        token,
+        info: info.clone(),
+    }) {
+        previous.server.unblock();
+    }
+    Ok(info)
+}
+
+fn preview_info(port: u16, token: &str, page_count: usize) -> PreviewServerInfo {
+    let host = lan_address().map(|ip| ip.to_string()).unwrap_or_else(|| "127.0.0.1".to_string());
+    PreviewServerInfo {
+        url: format!("http://{}:{}/?token={}", host, port, token),
+        local_url: format!("http://127.0.0.1:{}/?token={}", port, token),
+        port,
+        token: token.to_string(),
+        page_count,
+    }
+}
+
+// 閲覧中の台割を差し替え（タブレット側は再読み込みで新しい順番になる）
+pub fn update_preview_book(state: &PreviewServer, book: PreviewBook) -> Result<PreviewServerInfo, String> {
+    let mut running = state.running.lock().map_err(|e| e.to_string())?;
+    let running = running.as_mut().ok_or("プレビューサーバーは起動していません")?;
+    running.info.page_count = book.pages.len();
+    *running.book.write().map_err(|e| e.to_string())? = book;
+    Ok(running.info.clone())
+}
+
+pub fn stop_preview_server(state: &PreviewServer) -> Result<(), String> {
+    let mut running = state.running.lock().map_err(|e| e.to_string())?;
+    if let Some(previous) = running.take() {
+        previous.server.unblock();
+    }
+    Ok(())
+}
+
+pub fn preview_server_info(state: &PreviewServer) -> Option<PreviewServerInfo> {
+    let running = state.running.lock().ok()?;
+    running.as_ref().map(|r| r.info.clone())
+}
+
+// クエリ文字列の値を取得（トークンは16進数のみのためデコードしない）
+fn query_param<'a>(url: &'a str, name: &str) -> Option<&'a str> {
+    let query = url.split_once('?')?.1;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("静的なヘッダーは常に有効")
+}
+
+fn text_response(status: u16, message: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    Response::from_string(message)
+        .with_status_code(status)
+        .with_header(header("Content-Type", "text/plain; charset=utf-8"))
+}
+
+fn handle_request(request: Request, book: &RwLock<PreviewBook>, token: &RwLock<String>, cache_dir: &Path) {
+    let url = request.url().to_string();
+    let path = url.split('?').next().unwrap_or(&url);
+    let token = token.read().map(|t| t.clone()).unwrap_or_default();
+
+    let response = if request.method() != &Method::Get {
+        text_response(405, "閲覧専用です")
+    } else if token.is_empty() || !query_param(&url, "token").is_some_and(|given| tokens_match(given, &token)) {
+        text_response(401, "認証トークンが正しくありません")
+    } else {
+        match route(path, book, &token, cache_dir) {
+            Ok(response) => response,
+            Err((status, message)) => text_response(status, &message),
+        }
+    };
+
+    if let Err(e) = request.respond(response) {
+        eprintln!("プレビューサーバーの応答に失敗: {}", e);
+    }
+}
+
+fn route(
+    path: &str,
+    book: &RwLock<PreviewBook>,
+    token: &str,
+    cache_dir: &Path,
+) -> Result<Response<std::io::Cursor<Vec<u8>>>, (u16, String)> {
+    let book = book.read().map_err(|e| (500, e.to_string()))?;
+
+    if path == "/" || path == "/index.html" {
+        // パスと更新日時をURLに含め、並べ替えや原稿の更新後にタブレット側の古いキャッシュを使わない
+        let entries: Vec<ViewerEntry> = book
+            .pages
+            .iter()
+            .enumerate()
+            .map(|(i, page)| ViewerEntry {
+                src: page.source_path.as_ref().map(|source| {
+                    let version = md5::compute(format!("{}:{}", source, path_modified_millis(Path::new(source))));
+                    format!("page/{}?token={}&v={:x}", i, token, version)
+                }),
+                label: page.label.clone(),
+            })
+            .collect();
+        let html = render_viewer_html(&book.title, &entries, &book.options).map_err(|e| (500, e))?;
+        return Ok(Response::from_string(html)
+            .with_header(header("Content-Type", "text/html; charset=utf-8"))
+            .with_header(header("Cache-Control", "no-store")));
+    }
+
+    let index: usize = path
+        .strip_prefix("/page/")
+        .and_then(|i| i.parse().ok())
+        .ok_or((404, "ページが見つかりません".to_string()))?;
+    let source = book
+        .pages
+        .get(index)
+        .and_then(|page| page.source_path.clone())
+        .ok_or((404, "ページが見つかりません".to_string()))?;
+    let options = book.options.clone();
+    // 画像の生成中に台割の差し替えを待たせない
+    drop(book);
+
+    let bytes = page_image(Path::new(&source), &options, cache_dir).map_err(|e| (500, e))?;
+    Ok(Response::from_data(bytes)
+        .with_header(header("Content-Type", "image/jpeg"))
+        .with_header(header("Cache-Control", "private, max-age=86400")))
+}
+
+// Web用に縮小したページ画像（原稿の更新日時ごとにキャッシュ）
+fn page_image(source: &Path, options: &ViewerExportOptions, cache_dir: &Path) -> Result<Vec<u8>, String> {
+    if !source.exists() {
+        return Err("ファイルが存在しません".to_string());
+    }
+    // クラウド上のみのファイルはダウンロードが始まるため読まない
+    if let Some(reason) = cloud_placeholder_reason(source) {
+        return Err(reason);
+    }
+
+    let input = format!(
+        "{}:{}:{}:{}",
+        source.display(),
+        path_modified_millis(source),
+        options.max_size,
+        options.jpg_quality
+    );
+    let cache_key = format!("{:x}", md5::compute(&input));
+    let cached_path = cache_dir.join(format!("{}.jpg", cache_key));
+    if !cached_path.exists() {
+        // 同じページへの同時アクセスで書きかけのファイルを返さないよう一時ファイルから置き換える
+        let temp_path = cache_dir.join(format!(
+            "{}.{}.tmp.jpg",
+            cache_key,
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        write_web_image(source, &temp_path, options)?;
+        fs::rename(&temp_path, &cached_path).map_err(|e| format!("キャッシュ保存エラー: {}", e))?;
+    }
+    fs::read(&cached_path).map_err(|e| format!("プレビュー読み込みエラー: {}", e))
+}
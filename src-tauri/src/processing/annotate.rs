@@ -0,0 +1,78 @@
+use image::{Rgb, RgbImage};
+
+// 校正指示の色
+const MARK_COLOR: Rgb<u8> = Rgb([230, 30, 40]);
+const TEXT_COLOR: Rgb<u8> = Rgb([255, 255, 255]);
+
+// 指示番号用の 5×7 ドットの数字（各行の下位5ビット）
+const DIGITS: [[u8; 7]; 10] = [
+    [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+    [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+    [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+    [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+    [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+    [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+    [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+    [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+    [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+];
+
+// 画像内に収まる範囲だけ塗りつぶす
+fn fill_rect(img: &mut RgbImage, x: i64, y: i64, width: u32, height: u32, color: Rgb<u8>) {
+    let x0 = x.max(0) as u32;
+    let y0 = y.max(0) as u32;
+    let x1 = ((x + width as i64).max(0) as u32).min(img.width());
+    let y1 = ((y + height as i64).max(0) as u32).min(img.height());
+    for py in y0..y1 {
+        for px in x0..x1 {
+            img.put_pixel(px, py, color);
+        }
+    }
+}
+
+// 番号のバッジ（赤地に白抜きの数字）を描画
+fn draw_number(img: &mut RgbImage, x: i64, y: i64, number: usize, scale: u32) {
+    let text = number.to_string();
+    let glyph_w = 5 * scale;
+    let padding = 2 * scale;
+    let badge_w = text.len() as u32 * (glyph_w + scale) - scale + padding * 2;
+    let badge_h = 7 * scale + padding * 2;
+    fill_rect(img, x, y, badge_w, badge_h, MARK_COLOR);
+
+    for (i, digit) in text.bytes().map(|b| (b - b'0') as usize).enumerate() {
+        let origin_x = x + (padding + i as u32 * (glyph_w + scale)) as i64;
+        let origin_y = y + padding as i64;
+        for (row, bits) in DIGITS[digit].iter().enumerate() {
+            for col in 0..5 {
+                if bits & (0b10000 >> col) != 0 {
+                    let px = origin_x + (col * scale) as i64;
+                    let py = origin_y + (row as u32 * scale) as i64;
+                    fill_rect(img, px, py, scale, scale, TEXT_COLOR);
+                }
+            }
+        }
+    }
+}
+
+// 校正指示の枠と番号を書き込む（範囲はページに対する割合）
+pub fn draw_annotation(img: &mut RgbImage, x: f64, y: f64, width: f64, height: f64, number: usize) {
+    let (img_w, img_h) = (img.width() as f64, img.height() as f64);
+    let left = (x.clamp(0.0, 1.0) * img_w).round() as i64;
+    let top = (y.clamp(0.0, 1.0) * img_h).round() as i64;
+    let rect_w = (width.clamp(0.0, 1.0) * img_w).round().max(1.0) as u32;
+    let rect_h = (height.clamp(0.0, 1.0) * img_h).round().max(1.0) as u32;
+
+    // 線の太さ・番号の大きさは画像の大きさに合わせる
+    let scale = (img.width().max(img.height()) / 500).max(2);
+    let line = scale;
+    fill_rect(img, left, top, rect_w, line, MARK_COLOR);
+    fill_rect(img, left, top + rect_h as i64 - line as i64, rect_w, line, MARK_COLOR);
+    fill_rect(img, left, top, line, rect_h, MARK_COLOR);
+    fill_rect(img, left + rect_w as i64 - line as i64, top, line, rect_h, MARK_COLOR);
+
+    // 番号は枠の左上の外側（上端にかかる場合は内側）
+    let badge_h = (7 + 4) * scale;
+    let badge_y = if top >= badge_h as i64 { top - badge_h as i64 } else { top };
+    draw_number(img, left, badge_y, number, scale);
+}
@@ -0,0 +1,155 @@
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage};
+use crate::types::{AutoCropOptions, CropRect};
+
+// 解析用に縮小する最大辺長（スキャン原稿は巨大なため）
+const ANALYSIS_MAX_SIZE: u32 = 1200;
+
+// 内容ブロック同士を同一とみなす最大の空白幅（解析画像上のpx）
+const MAX_CONTENT_GAP: usize = 8;
+
+// 四隅の中央値から背景の輝度を推定
+fn estimate_background(gray: &GrayImage) -> u8 {
+    let (w, h) = gray.dimensions();
+    let mut samples = vec![
+        gray.get_pixel(0, 0)[0],
+        gray.get_pixel(w - 1, 0)[0],
+        gray.get_pixel(0, h - 1)[0],
+        gray.get_pixel(w - 1, h - 1)[0],
+    ];
+    samples.sort_unstable();
+    // 4点の中央2点の平均
+    ((samples[1] as u16 + samples[2] as u16) / 2) as u8
+}
+
+// 内容行が連続する区間のうち最も長いものを返す
+// トンボや汚れは余白を挟んで孤立しているため、本体から切り離される
+fn longest_content_run(is_content: &[bool]) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize)> = None;
+    let mut current: Option<(usize, usize)> = None;
+    let mut gap = 0usize;
+
+    for (i, &content) in is_content.iter().enumerate() {
+        if content {
+            current = match current {
+                Some((start, _)) => Some((start, i)),
+                None => Some((i, i)),
+            };
+            gap = 0;
+        } else if let Some(run) = current {
+            gap += 1;
+            if gap > MAX_CONTENT_GAP {
+                if best.map_or(true, |b| run.1 - run.0 > b.1 - b.0) {
+                    best = Some(run);
+                }
+                current = None;
+                gap = 0;
+            }
+        }
+    }
+    if let Some(run) = current {
+        if best.map_or(true, |b| run.1 - run.0 > b.1 - b.0) {
+            best = Some(run);
+        }
+    }
+    best
+}
+
+// 指定した軸方向の内容割合プロファイルから内容区間を求める
+fn content_profile(
+    gray: &GrayImage,
+    background: u8,
+    options: &AutoCropOptions,
+    rows: bool,
+    range: (u32, u32),
+) -> Vec<bool> {
+    let (w, h) = gray.dimensions();
+    let (outer, inner_start, inner_end) = if rows { (h, range.0, range.1) } else { (w, range.0, range.1) };
+    let span = (inner_end - inner_start + 1).max(1) as f64;
+
+    (0..outer)
+        .map(|o| {
+            let count = (inner_start..=inner_end)
+                .filter(|&i| {
+                    let (x, y) = if rows { (i, o) } else { (o, i) };
+                    gray.get_pixel(x, y)[0].abs_diff(background) > options.tolerance
+                })
+                .count();
+            count as f64 / span >= options.min_content_ratio
+        })
+        .collect()
+}
+
+// 内容領域を中心に目標アスペクト比へ拡張（画像外にはみ出す場合は縮小）
+fn fit_aspect(content: CropRect, width: u32, height: u32, aspect: f64) -> CropRect {
+    if aspect <= 0.0 {
+        return content;
+    }
+    let (cw, ch) = (content.width as f64, content.height as f64);
+    let (mut tw, mut th) = if cw / ch > aspect { (cw, cw / aspect) } else { (ch * aspect, ch) };
+
+    // 画像サイズに収まるよう縮小
+    let scale = (width as f64 / tw).min(height as f64 / th).min(1.0);
+    tw *= scale;
+    th *= scale;
+
+    let (tw, th) = (tw.round().max(1.0) as u32, th.round().max(1.0) as u32);
+    let cx = content.x as f64 + cw / 2.0;
+    let cy = content.y as f64 + ch / 2.0;
+    let x = (cx - tw as f64 / 2.0).round().clamp(0.0, width.saturating_sub(tw) as f64) as u32;
+    let y = (cy - th as f64 / 2.0).round().clamp(0.0, height.saturating_sub(th) as f64) as u32;
+
+    CropRect { x, y, width: tw, height: th }
+}
+
+// 余白・トンボを除いた内容領域と、アスペクト比調整後の切り抜き領域を検出
+pub fn detect_crop(img: &DynamicImage, options: &AutoCropOptions) -> (CropRect, CropRect) {
+    let (width, height) = (img.width(), img.height());
+    let full = CropRect { x: 0, y: 0, width, height };
+
+    let analysis = if width.max(height) > ANALYSIS_MAX_SIZE {
+        img.resize(ANALYSIS_MAX_SIZE, ANALYSIS_MAX_SIZE, FilterType::Triangle)
+    } else {
+        img.clone()
+    };
+    let gray = analysis.to_luma8();
+    let (aw, ah) = gray.dimensions();
+    if aw < 2 || ah < 2 {
+        return (full, full);
+    }
+
+    let background = estimate_background(&gray);
+
+    // 行方向 → 列方向の順に内容区間を絞り込む
+    let row_flags = content_profile(&gray, background, options, true, (0, aw - 1));
+    let Some((top, bottom)) = longest_content_run(&row_flags) else {
+        return (full, full);
+    };
+    let col_flags = content_profile(&gray, background, options, false, (top as u32, bottom as u32));
+    let Some((left, right)) = longest_content_run(&col_flags) else {
+        return (full, full);
+    };
+
+    // 元画像の座標に戻して余白を付加
+    let sx = width as f64 / aw as f64;
+    let sy = height as f64 / ah as f64;
+    let pad = options.padding;
+    let x0 = ((left as f64 * sx).floor() as u32).saturating_sub(pad);
+    let y0 = ((top as f64 * sy).floor() as u32).saturating_sub(pad);
+    let x1 = (((right + 1) as f64 * sx).ceil() as u32 + pad).min(width);
+    let y1 = (((bottom + 1) as f64 * sy).ceil() as u32 + pad).min(height);
+
+    let content = CropRect {
+        x: x0,
+        y: y0,
+        width: (x1 - x0).max(1),
+        height: (y1 - y0).max(1),
+    };
+
+    let crop = match options.target_aspect {
+        Some(aspect) => fit_aspect(content, width, height, aspect),
+        None => content,
+    };
+
+    (content, crop)
+}
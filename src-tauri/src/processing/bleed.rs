@@ -0,0 +1,76 @@
+use image::{DynamicImage, ImageBuffer, Luma, LumaA, Pixel, Rgb, Rgba};
+
+// 塗り足し方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BleedMode {
+    Mirror,  // 端を鏡像反転
+    Extend,  // 端のピクセルを引き伸ばし
+    White,   // 白で埋める
+}
+
+impl BleedMode {
+    fn parse(mode: &str) -> Result<Self, String> {
+        match mode {
+            "mirror" => Ok(Self::Mirror),
+            "extend" => Ok(Self::Extend),
+            "white" => Ok(Self::White),
+            _ => Err(format!("不明な塗り足し方式: {}", mode)),
+        }
+    }
+}
+
+// 塗り足し領域の座標を元画像の座標に変換
+fn source_coord(pos: u32, bleed: u32, len: u32, mode: BleedMode) -> Option<u32> {
+    let offset = pos as i64 - bleed as i64;
+    let len = len as i64;
+    if (0..len).contains(&offset) {
+        return Some(offset as u32);
+    }
+    match mode {
+        BleedMode::White => None,
+        BleedMode::Extend => Some(offset.clamp(0, len - 1) as u32),
+        BleedMode::Mirror => {
+            let mirrored = if offset < 0 { -offset - 1 } else { 2 * len - offset - 1 };
+            Some(mirrored.clamp(0, len - 1) as u32)
+        }
+    }
+}
+
+fn pad_buffer<P: Pixel>(
+    src: &ImageBuffer<P, Vec<P::Subpixel>>,
+    bleed: u32,
+    mode: BleedMode,
+    fill: P,
+) -> ImageBuffer<P, Vec<P::Subpixel>> {
+    let (width, height) = src.dimensions();
+    ImageBuffer::from_fn(width + bleed * 2, height + bleed * 2, |x, y| {
+        match (
+            source_coord(x, bleed, width, mode),
+            source_coord(y, bleed, height, mode),
+        ) {
+            (Some(sx), Some(sy)) => *src.get_pixel(sx, sy),
+            _ => fill,
+        }
+    })
+}
+
+// 画像の四辺に塗り足しを追加（カラーモード・ビット深度は維持）
+pub fn apply_bleed(img: &DynamicImage, bleed: u32, mode: &str) -> Result<DynamicImage, String> {
+    let mode = BleedMode::parse(mode)?;
+    if bleed == 0 {
+        return Ok(img.clone());
+    }
+
+    let padded = match img {
+        DynamicImage::ImageLuma8(b) => DynamicImage::ImageLuma8(pad_buffer(b, bleed, mode, Luma([u8::MAX]))),
+        DynamicImage::ImageLumaA8(b) => DynamicImage::ImageLumaA8(pad_buffer(b, bleed, mode, LumaA([u8::MAX, u8::MAX]))),
+        DynamicImage::ImageRgb8(b) => DynamicImage::ImageRgb8(pad_buffer(b, bleed, mode, Rgb([u8::MAX; 3]))),
+        DynamicImage::ImageRgba8(b) => DynamicImage::ImageRgba8(pad_buffer(b, bleed, mode, Rgba([u8::MAX; 4]))),
+        DynamicImage::ImageLuma16(b) => DynamicImage::ImageLuma16(pad_buffer(b, bleed, mode, Luma([u16::MAX]))),
+        DynamicImage::ImageRgb16(b) => DynamicImage::ImageRgb16(pad_buffer(b, bleed, mode, Rgb([u16::MAX; 3]))),
+        DynamicImage::ImageRgba16(b) => DynamicImage::ImageRgba16(pad_buffer(b, bleed, mode, Rgba([u16::MAX; 4]))),
+        other => DynamicImage::ImageRgba8(pad_buffer(&other.to_rgba8(), bleed, mode, Rgba([u8::MAX; 4]))),
+    };
+
+    Ok(padded)
+}
@@ -0,0 +1,61 @@
+use image::{DynamicImage, ImageBuffer, Pixel};
+
+// 8x8 のベイヤー行列（順序ディザ用、0〜63）
+const BAYER_8X8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+// 1チャンネルあたり8bitを超える画像か
+pub fn is_high_bit_depth(img: &DynamicImage) -> bool {
+    !matches!(
+        img,
+        DynamicImage::ImageLuma8(_)
+            | DynamicImage::ImageLumaA8(_)
+            | DynamicImage::ImageRgb8(_)
+            | DynamicImage::ImageRgba8(_)
+    )
+}
+
+// 16bitの値を8bitに量子化（dither 時は位置ごとのしきい値をずらしてグラデーションの縞を防ぐ）
+fn quantize<P, Q>(buffer: &ImageBuffer<P, Vec<u16>>, dither: bool) -> ImageBuffer<Q, Vec<u8>>
+where
+    P: Pixel<Subpixel = u16>,
+    Q: Pixel<Subpixel = u8>,
+{
+    let (width, height) = buffer.dimensions();
+    let channels = P::CHANNEL_COUNT as usize;
+    let mut data = Vec::with_capacity(buffer.as_raw().len());
+    for (i, row) in buffer.as_raw().chunks_exact(width as usize * channels).enumerate() {
+        let bayer = &BAYER_8X8[i % 8];
+        for (x, pixel) in row.chunks_exact(channels).enumerate() {
+            // value / 257 に 0〜1 のしきい値を足して切り捨て（ディザなしは 0.5 で四捨五入）
+            // 整数で計算するため 257 * 128 倍したしきい値を使う
+            let threshold = if dither { (2 * bayer[x % 8] as u32 + 1) * 257 } else { 64 * 257 };
+            for &value in pixel {
+                data.push(((value as u32 * 128 + threshold) / (257 * 128)).min(255) as u8);
+            }
+        }
+    }
+    ImageBuffer::from_raw(width, height, data).expect("バッファサイズは入力と同じ")
+}
+
+// 16bit・浮動小数点の画像を8bitに変換（グレースケールはグレースケールのまま）
+// JPEGなど8bitの形式に書き出す前に使う。8bitの画像はそのまま返す
+pub fn reduce_to_8bit(img: DynamicImage, dither: bool) -> DynamicImage {
+    match img {
+        DynamicImage::ImageLuma16(buffer) => DynamicImage::ImageLuma8(quantize(&buffer, dither)),
+        DynamicImage::ImageLumaA16(buffer) => DynamicImage::ImageLumaA8(quantize(&buffer, dither)),
+        DynamicImage::ImageRgb16(buffer) => DynamicImage::ImageRgb8(quantize(&buffer, dither)),
+        DynamicImage::ImageRgba16(buffer) => DynamicImage::ImageRgba8(quantize(&buffer, dither)),
+        DynamicImage::ImageRgb32F(_) => DynamicImage::ImageRgb8(quantize(&img.to_rgb16(), dither)),
+        DynamicImage::ImageRgba32F(_) => DynamicImage::ImageRgba8(quantize(&img.to_rgba16(), dither)),
+        other => other,
+    }
+}
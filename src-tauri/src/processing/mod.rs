@@ -0,0 +1,29 @@
+mod bleed;
+mod autocrop;
+mod tone;
+mod proof;
+mod screen;
+mod purity;
+mod trim;
+mod phash;
+mod night;
+mod colorblind;
+mod spread;
+mod annotate;
+mod depth;
+mod resize;
+
+pub use self::bleed::apply_bleed;
+pub use self::autocrop::detect_crop;
+pub use self::tone::{analyze_tone, apply_tone_map, ToneMap};
+pub use self::proof::render_print_proof;
+pub use self::screen::detect_screen_lpi;
+pub use self::purity::analyze_black_purity;
+pub use self::trim::detect_text_near_trim;
+pub use self::phash::{hash_distance, perceptual_hash};
+pub use self::night::apply_night_filter;
+pub use self::colorblind::simulate_color_vision;
+pub use self::spread::{split_spread, SpreadHalf};
+pub use self::annotate::draw_annotation;
+pub use self::depth::{is_high_bit_depth, reduce_to_8bit};
+pub use self::resize::{resize_scale, resize_to_target, target_output_size, ResizeMode};
@@ -0,0 +1,23 @@
+use image::{DynamicImage, RgbImage};
+use crate::types::NightPreviewOptions;
+
+// 夜間校正用のフィルタを適用（反転は明度のみ反転して色相を保つ）
+pub fn apply_night_filter(img: &DynamicImage, options: &NightPreviewOptions) -> RgbImage {
+    let mut rgb = img.to_rgb8();
+    let level = options.brightness.clamp(0.05, 1.0) as f32;
+
+    for pixel in rgb.pixels_mut() {
+        let [r, g, b] = pixel.0.map(|c| c as f32);
+        let [r, g, b] = match options.mode.as_str() {
+            // 白地を黒に。輝度 Y を 255 - Y に置き換え、色差はそのまま残す
+            "invert" => {
+                let y = 0.299 * r + 0.587 * g + 0.114 * b;
+                let shift = 255.0 - 2.0 * y;
+                [r + shift, g + shift, b + shift]
+            }
+            _ => [r, g, b],
+        };
+        pixel.0 = [r, g, b].map(|c| (c * level).round().clamp(0.0, 255.0) as u8);
+    }
+    rgb
+}
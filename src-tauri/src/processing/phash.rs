@@ -0,0 +1,55 @@
+use image::imageops::FilterType;
+use image::DynamicImage;
+
+// DCTに使う縮小画像の一辺
+const HASH_SOURCE_SIZE: usize = 32;
+// ハッシュに使う低周波成分の一辺（8x8 = 64bit）
+const HASH_SIZE: usize = 8;
+
+// 画像の知覚ハッシュ（DCTの低周波成分を中央値で2値化した64bit値）
+pub fn perceptual_hash(img: &DynamicImage) -> u64 {
+    let n = HASH_SOURCE_SIZE;
+    let small = img
+        .resize_exact(n as u32, n as u32, FilterType::Triangle)
+        .to_luma8();
+    let pixels: Vec<f64> = small.pixels().map(|p| p[0] as f64).collect();
+
+    // 低周波 8x8 成分のみ2次元DCTを計算
+    let cos_table: Vec<f64> = (0..HASH_SIZE)
+        .flat_map(|u| {
+            (0..n).map(move |x| {
+                (std::f64::consts::PI * (2 * x + 1) as f64 * u as f64 / (2 * n) as f64).cos()
+            })
+        })
+        .collect();
+
+    let mut coeffs = Vec::with_capacity(HASH_SIZE * HASH_SIZE);
+    for v in 0..HASH_SIZE {
+        for u in 0..HASH_SIZE {
+            let mut sum = 0.0;
+            for y in 0..n {
+                let cy = cos_table[v * n + y];
+                for x in 0..n {
+                    sum += pixels[y * n + x] * cos_table[u * n + x] * cy;
+                }
+            }
+            coeffs.push(sum);
+        }
+    }
+
+    // 直流成分を除いた中央値を閾値にする
+    let mut sorted: Vec<f64> = coeffs[1..].to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let median = sorted[sorted.len() / 2];
+
+    coeffs
+        .iter()
+        .enumerate()
+        .filter(|(_, &c)| c > median)
+        .fold(0u64, |hash, (i, _)| hash | (1u64 << i))
+}
+
+// 2つのハッシュの差（異なるビット数）
+pub fn hash_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
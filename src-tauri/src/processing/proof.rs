@@ -0,0 +1,49 @@
+use image::{DynamicImage, GrayImage, Luma};
+use crate::types::PrintProofOptions;
+
+// インキ面積率にドットゲインを適用（50%で最大となる放物線モデル）
+fn apply_dot_gain(coverage: f64, gain: f64) -> f64 {
+    (coverage + gain * 4.0 * coverage * (1.0 - coverage)).clamp(0.0, 1.0)
+}
+
+// 網点のスポット関数（セル中心で1、セル角で0）
+fn spot_value(x: f64, y: f64, cell: f64, angle_rad: f64) -> f64 {
+    let (sin, cos) = angle_rad.sin_cos();
+    let u = (x * cos + y * sin) / cell;
+    let v = (-x * sin + y * cos) / cell;
+    let tau = std::f64::consts::TAU;
+    ((tau * u).cos() + (tau * v).cos()) / 4.0 + 0.5
+}
+
+// グレースケール化・ドットゲイン・網点化で印刷結果をシミュレーション
+pub fn render_print_proof(img: &DynamicImage, options: &PrintProofOptions) -> GrayImage {
+    let gray = img.to_luma8();
+    let gain = options.dot_gain / 100.0;
+
+    // 輝度→インキ面積率→ドットゲイン後の輝度のLUT
+    let lut: Vec<f64> = (0..=255u32)
+        .map(|v| apply_dot_gain(1.0 - v as f64 / 255.0, gain))
+        .collect();
+
+    let Some(ref halftone) = options.halftone else {
+        return GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+            let coverage = lut[gray.get_pixel(x, y)[0] as usize];
+            Luma([((1.0 - coverage) * 255.0).round() as u8])
+        });
+    };
+
+    // 原稿解像度での網点セルの大きさ (px)
+    let cell = (options.source_dpi as f64 / halftone.lpi.max(1.0)).max(1.0);
+    let angle = halftone.angle.to_radians();
+
+    GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        let coverage = lut[gray.get_pixel(x, y)[0] as usize];
+        let spot = spot_value(x as f64 + 0.5, y as f64 + 0.5, cell, angle);
+        // スポット値が閾値を超える位置にインキが乗る
+        if spot >= 1.0 - coverage && coverage > 0.0 {
+            Luma([0])
+        } else {
+            Luma([255])
+        }
+    })
+}
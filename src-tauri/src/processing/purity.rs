@@ -0,0 +1,50 @@
+use image::DynamicImage;
+use crate::types::BlackPurityStats;
+
+// RGBの各チャンネル差がこれを超える黒は色味のある黒とみなす
+const RICH_BLACK_SPREAD: u8 = 8;
+
+// 墨一色のはずの線画に含まれる純黒でない黒（RGB往復後の浮いた黒など）を集計
+pub fn analyze_black_purity(img: &DynamicImage, threshold: u8) -> BlackPurityStats {
+    let mut dark_pixels = 0u64;
+    let mut off_black_pixels = 0u64;
+    let mut rich_black_pixels = 0u64;
+
+    if img.color().has_color() {
+        for p in img.to_rgb8().pixels() {
+            let [r, g, b] = p.0;
+            let luma = (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000;
+            if luma > threshold as u32 {
+                continue;
+            }
+            dark_pixels += 1;
+            if r != 0 || g != 0 || b != 0 {
+                off_black_pixels += 1;
+            }
+            if r.max(g).max(b) - r.min(g).min(b) > RICH_BLACK_SPREAD {
+                rich_black_pixels += 1;
+            }
+        }
+    } else {
+        for p in img.to_luma8().pixels() {
+            if p[0] > threshold {
+                continue;
+            }
+            dark_pixels += 1;
+            if p[0] != 0 {
+                off_black_pixels += 1;
+            }
+        }
+    }
+
+    BlackPurityStats {
+        dark_pixels,
+        off_black_pixels,
+        rich_black_pixels,
+        off_black_ratio: if dark_pixels > 0 {
+            off_black_pixels as f64 / dark_pixels as f64
+        } else {
+            0.0
+        },
+    }
+}
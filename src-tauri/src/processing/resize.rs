@@ -0,0 +1,98 @@
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageBuffer, Luma, LumaA, Pixel, Rgb, Rgba};
+
+// 入稿サイズへの合わせ方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeMode {
+    Fit,   // 縦横比を保って収める（指定サイズより小さくなる辺がある）
+    Fill,  // 縦横比を保って全面を覆い、はみ出た部分を中央で切り取る
+    Pad,   // 縦横比を保って収め、足りない部分を白で埋める
+}
+
+impl ResizeMode {
+    pub fn parse(mode: &str) -> Result<Self, String> {
+        match mode {
+            "fit" => Ok(Self::Fit),
+            "fill" => Ok(Self::Fill),
+            "pad" => Ok(Self::Pad),
+            _ => Err(format!("不明なリサイズ方法: {}", mode)),
+        }
+    }
+}
+
+// 元のサイズに対する倍率（1より大きければ拡大）
+pub fn resize_scale(width: u32, height: u32, target_width: u32, target_height: u32, mode: ResizeMode) -> f64 {
+    let scale_x = target_width as f64 / width.max(1) as f64;
+    let scale_y = target_height as f64 / height.max(1) as f64;
+    match mode {
+        ResizeMode::Fill => scale_x.max(scale_y),
+        ResizeMode::Fit | ResizeMode::Pad => scale_x.min(scale_y),
+    }
+}
+
+fn pad_buffer<P: Pixel>(
+    src: &ImageBuffer<P, Vec<P::Subpixel>>,
+    width: u32,
+    height: u32,
+    fill: P,
+) -> ImageBuffer<P, Vec<P::Subpixel>> {
+    let mut canvas = ImageBuffer::from_pixel(width, height, fill);
+    let x = (width as i64 - src.width() as i64) / 2;
+    let y = (height as i64 - src.height() as i64) / 2;
+    image::imageops::replace(&mut canvas, src, x, y);
+    canvas
+}
+
+// 指定サイズの中央に白地で配置（カラーモード・ビット深度は維持）
+fn pad_to(img: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+    match img {
+        DynamicImage::ImageLuma8(b) => DynamicImage::ImageLuma8(pad_buffer(b, width, height, Luma([u8::MAX]))),
+        DynamicImage::ImageLumaA8(b) => DynamicImage::ImageLumaA8(pad_buffer(b, width, height, LumaA([u8::MAX, u8::MAX]))),
+        DynamicImage::ImageRgb8(b) => DynamicImage::ImageRgb8(pad_buffer(b, width, height, Rgb([u8::MAX; 3]))),
+        DynamicImage::ImageRgba8(b) => DynamicImage::ImageRgba8(pad_buffer(b, width, height, Rgba([u8::MAX; 4]))),
+        DynamicImage::ImageLuma16(b) => DynamicImage::ImageLuma16(pad_buffer(b, width, height, Luma([u16::MAX]))),
+        DynamicImage::ImageRgb16(b) => DynamicImage::ImageRgb16(pad_buffer(b, width, height, Rgb([u16::MAX; 3]))),
+        DynamicImage::ImageRgba16(b) => DynamicImage::ImageRgba16(pad_buffer(b, width, height, Rgba([u16::MAX; 4]))),
+        other => DynamicImage::ImageRgba8(pad_buffer(&other.to_rgba8(), width, height, Rgba([u8::MAX; 4]))),
+    }
+}
+
+// 縦横比を保って拡大・縮小したサイズ（切り取り・余白の追加前）
+fn scaled_size(width: u32, height: u32, target_width: u32, target_height: u32, mode: ResizeMode) -> (u32, u32) {
+    let scale = resize_scale(width, height, target_width, target_height, mode);
+    (
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    )
+}
+
+// resize_to_target で出力されるサイズ（画像を読み込まずに白紙ページや上限の確認に使う）
+pub fn target_output_size(width: u32, height: u32, target_width: u32, target_height: u32, mode: ResizeMode) -> (u32, u32) {
+    let (scaled_width, scaled_height) = scaled_size(width, height, target_width, target_height, mode);
+    match mode {
+        ResizeMode::Fit => (scaled_width, scaled_height),
+        ResizeMode::Fill => (target_width.min(scaled_width), target_height.min(scaled_height)),
+        ResizeMode::Pad => (target_width, target_height),
+    }
+}
+
+// 入稿サイズにリサンプリング（fill・pad は指定サイズちょうど、fit は収まる最大サイズ）
+pub fn resize_to_target(img: &DynamicImage, target_width: u32, target_height: u32, mode: ResizeMode) -> DynamicImage {
+    let (width, height) = scaled_size(img.width(), img.height(), target_width, target_height, mode);
+    let resized = if (width, height) == (img.width(), img.height()) {
+        img.clone()
+    } else {
+        img.resize_exact(width, height, FilterType::Lanczos3)
+    };
+
+    match mode {
+        ResizeMode::Fit => resized,
+        ResizeMode::Fill => resized.crop_imm(
+            width.saturating_sub(target_width) / 2,
+            height.saturating_sub(target_height) / 2,
+            target_width.min(width),
+            target_height.min(height),
+        ),
+        ResizeMode::Pad => pad_to(&resized, target_width, target_height),
+    }
+}
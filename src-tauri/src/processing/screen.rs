@@ -0,0 +1,120 @@
+use image::GrayImage;
+
+// 解析ブロックの一辺 (px)
+const BLOCK_SIZE: u32 = 64;
+// 1ページあたりの最大解析ブロック数
+const MAX_BLOCKS: usize = 48;
+// 自己相関の探索半径の上限 (px)
+const MAX_LAG: i32 = 32;
+// 周期パターンとみなす自己相関の下限
+const MIN_PEAK_CORRELATION: f64 = 0.35;
+
+// ブロックの平均・標準偏差
+fn block_stats(gray: &GrayImage, bx: u32, by: u32) -> (f64, f64) {
+    let mut sum = 0.0;
+    let mut sum_sq = 0.0;
+    for y in by..by + BLOCK_SIZE {
+        for x in bx..bx + BLOCK_SIZE {
+            let v = gray.get_pixel(x, y)[0] as f64;
+            sum += v;
+            sum_sq += v * v;
+        }
+    }
+    let n = (BLOCK_SIZE * BLOCK_SIZE) as f64;
+    let mean = sum / n;
+    (mean, (sum_sq / n - mean * mean).max(0.0).sqrt())
+}
+
+// ブロックの正規化自己相関から網点の周期（最も近い格子点までの距離）を求める
+fn block_period(gray: &GrayImage, bx: u32, by: u32, mean: f64, std: f64, max_lag: i32) -> Option<f64> {
+    let size = BLOCK_SIZE as i32;
+    let variance = std * std;
+    let values: Vec<f64> = (by..by + BLOCK_SIZE)
+        .flat_map(|y| (bx..bx + BLOCK_SIZE).map(move |x| (x, y)))
+        .map(|(x, y)| gray.get_pixel(x, y)[0] as f64 - mean)
+        .collect();
+
+    // 対称性を利用して半平面（dy >= 0）のみ計算
+    let width = (max_lag * 2 + 1) as usize;
+    let mut corr = vec![0.0f64; width * (max_lag as usize + 1)];
+    for dy in 0..=max_lag {
+        for dx in -max_lag..=max_lag {
+            let mut sum = 0.0;
+            let mut count = 0usize;
+            for y in 0..size - dy {
+                let x_start = (-dx).max(0);
+                let x_end = (size - dx).min(size);
+                for x in x_start..x_end {
+                    sum += values[(y * size + x) as usize] * values[((y + dy) * size + x + dx) as usize];
+                    count += 1;
+                }
+            }
+            if count > 0 {
+                corr[dy as usize * width + (dx + max_lag) as usize] = sum / count as f64 / variance;
+            }
+        }
+    }
+
+    let at = |dx: i32, dy: i32| -> f64 {
+        // dy < 0 は原点対称の値を参照
+        let (dx, dy) = if dy < 0 { (-dx, -dy) } else { (dx, dy) };
+        if dx.abs() > max_lag || dy > max_lag {
+            return f64::MIN;
+        }
+        corr[dy as usize * width + (dx + max_lag) as usize]
+    };
+
+    // 原点近傍を除いた極大点のうち、原点に最も近いもの
+    let mut best: Option<(f64, f64)> = None;
+    for dy in 0..max_lag {
+        for dx in -max_lag + 1..max_lag {
+            let distance = ((dx * dx + dy * dy) as f64).sqrt();
+            if distance < 2.0 || (dy == 0 && dx < 0) {
+                continue;
+            }
+            let value = at(dx, dy);
+            if value < MIN_PEAK_CORRELATION {
+                continue;
+            }
+            let is_peak = (-1..=1)
+                .flat_map(|oy| (-1..=1).map(move |ox| (ox, oy)))
+                .filter(|&(ox, oy)| ox != 0 || oy != 0)
+                .all(|(ox, oy)| at(dx + ox, dy + oy) <= value);
+            if is_peak && best.map_or(true, |(d, _)| distance < d) {
+                best = Some((distance, value));
+            }
+        }
+    }
+    best.map(|(distance, _)| distance)
+}
+
+// ページ内のトーン領域ごとの線数を推定（原稿解像度での解析が必要）
+pub fn detect_screen_lpi(gray: &GrayImage, dpi: u32) -> Vec<f64> {
+    let (w, h) = gray.dimensions();
+    if w < BLOCK_SIZE || h < BLOCK_SIZE {
+        return Vec::new();
+    }
+
+    // 中間調でコントラストの高いブロックをトーン候補とする（ベタ・白地・線画の一部を除外）
+    let mut candidates = Vec::new();
+    for by in (0..=h - BLOCK_SIZE).step_by(BLOCK_SIZE as usize) {
+        for bx in (0..=w - BLOCK_SIZE).step_by(BLOCK_SIZE as usize) {
+            let (mean, std) = block_stats(gray, bx, by);
+            if (40.0..=215.0).contains(&mean) && std > 40.0 {
+                candidates.push((bx, by, mean, std));
+            }
+        }
+    }
+
+    // ページ全体から均等に間引く
+    let step = candidates.len().div_ceil(MAX_BLOCKS).max(1);
+    // 10線相当の周期まで探索
+    let max_lag = ((dpi as f64 / 10.0) as i32).clamp(4, MAX_LAG);
+
+    candidates
+        .iter()
+        .step_by(step)
+        .filter_map(|&(bx, by, mean, std)| block_period(gray, bx, by, mean, std, max_lag))
+        .map(|period| dpi as f64 / period)
+        .collect()
+}
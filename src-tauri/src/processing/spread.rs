@@ -0,0 +1,41 @@
+use image::DynamicImage;
+
+// 見開き原稿の片側
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpreadHalf {
+    Right,
+    Left,
+}
+
+impl SpreadHalf {
+    // 綴じ方向での読み順（右綴じは右側が先）
+    pub fn reading_order(right_to_left: bool) -> [SpreadHalf; 2] {
+        if right_to_left {
+            [SpreadHalf::Right, SpreadHalf::Left]
+        } else {
+            [SpreadHalf::Left, SpreadHalf::Right]
+        }
+    }
+
+    // 出力名に付ける記号
+    pub fn suffix(self) -> &'static str {
+        match self {
+            SpreadHalf::Right => "R",
+            SpreadHalf::Left => "L",
+        }
+    }
+}
+
+// 見開き原稿を中央で分割して片側を取り出す
+// overlap_px: ノド側に中央を越えて含める幅（断裁・綴じのずれで絵柄が切れないように）
+pub fn split_spread(img: &DynamicImage, half: SpreadHalf, overlap_px: u32) -> DynamicImage {
+    let (width, height) = (img.width(), img.height());
+    let middle = width / 2;
+    match half {
+        SpreadHalf::Left => img.crop_imm(0, 0, (middle + overlap_px).min(width), height),
+        SpreadHalf::Right => {
+            let start = middle.saturating_sub(overlap_px);
+            img.crop_imm(start, 0, width - start, height)
+        }
+    }
+}
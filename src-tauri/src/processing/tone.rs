@@ -0,0 +1,121 @@
+use image::imageops::FilterType;
+use image::DynamicImage;
+use crate::types::ToneStats;
+
+// 解析用に縮小する最大辺長
+const ANALYSIS_MAX_SIZE: u32 = 1200;
+
+// 黒点・白点とみなすパーセンタイル（ゴミや飛びを除外）
+const BLACK_PERCENTILE: f64 = 0.02;
+const WHITE_PERCENTILE: f64 = 0.98;
+
+fn percentile(histogram: &[u64; 256], total: u64, p: f64) -> u8 {
+    let target = (total as f64 * p).round() as u64;
+    let mut acc = 0u64;
+    for (value, count) in histogram.iter().enumerate() {
+        acc += count;
+        if acc >= target {
+            return value as u8;
+        }
+    }
+    255
+}
+
+// ページの輝度ヒストグラムから黒点・白点・平均を算出
+pub fn analyze_tone(img: &DynamicImage) -> ToneStats {
+    let analysis = if img.width().max(img.height()) > ANALYSIS_MAX_SIZE {
+        img.resize(ANALYSIS_MAX_SIZE, ANALYSIS_MAX_SIZE, FilterType::Triangle)
+    } else {
+        img.clone()
+    };
+    let gray = analysis.to_luma8();
+
+    let mut histogram = [0u64; 256];
+    let mut sum = 0u64;
+    for p in gray.pixels() {
+        histogram[p[0] as usize] += 1;
+        sum += p[0] as u64;
+    }
+    let total = (gray.width() as u64 * gray.height() as u64).max(1);
+
+    ToneStats {
+        black_point: percentile(&histogram, total, BLACK_PERCENTILE),
+        white_point: percentile(&histogram, total, WHITE_PERCENTILE),
+        mean: sum as f64 / total as f64,
+    }
+}
+
+// 黒点・白点を目標値へ写すトーンカーブ（0.0-1.0）
+pub struct ToneMap {
+    src_black: f64,
+    src_white: f64,
+    dst_black: f64,
+    dst_white: f64,
+    strength: f64,
+}
+
+impl ToneMap {
+    pub fn new(source: &ToneStats, target: &ToneStats, strength: f64) -> Self {
+        Self {
+            src_black: source.black_point as f64 / 255.0,
+            src_white: source.white_point as f64 / 255.0,
+            dst_black: target.black_point as f64 / 255.0,
+            dst_white: target.white_point as f64 / 255.0,
+            strength: strength.clamp(0.0, 1.0),
+        }
+    }
+
+    fn apply(&self, v: f64) -> f64 {
+        let range = (self.src_white - self.src_black).max(1.0 / 255.0);
+        let normalized = ((v - self.src_black) / range).clamp(0.0, 1.0);
+        let mapped = self.dst_black + normalized * (self.dst_white - self.dst_black);
+        // 補正強度で元の値とブレンド
+        (v + (mapped - v) * self.strength).clamp(0.0, 1.0)
+    }
+}
+
+// トーンカーブを適用（カラーモード・ビット深度は維持、アルファは変更しない）
+pub fn apply_tone_map(img: &DynamicImage, map: &ToneMap) -> DynamicImage {
+    let lut8: Vec<u8> = (0..=255u32)
+        .map(|v| (map.apply(v as f64 / 255.0) * 255.0).round() as u8)
+        .collect();
+    let map16 = |v: u16| (map.apply(v as f64 / 65535.0) * 65535.0).round() as u16;
+
+    match img {
+        DynamicImage::ImageLuma8(b) => {
+            let mut out = b.clone();
+            out.pixels_mut().for_each(|p| p[0] = lut8[p[0] as usize]);
+            DynamicImage::ImageLuma8(out)
+        }
+        DynamicImage::ImageLumaA8(b) => {
+            let mut out = b.clone();
+            out.pixels_mut().for_each(|p| p[0] = lut8[p[0] as usize]);
+            DynamicImage::ImageLumaA8(out)
+        }
+        DynamicImage::ImageRgb8(b) => {
+            let mut out = b.clone();
+            out.pixels_mut().for_each(|p| p.0.iter_mut().for_each(|c| *c = lut8[*c as usize]));
+            DynamicImage::ImageRgb8(out)
+        }
+        DynamicImage::ImageLuma16(b) => {
+            let mut out = b.clone();
+            out.pixels_mut().for_each(|p| p[0] = map16(p[0]));
+            DynamicImage::ImageLuma16(out)
+        }
+        DynamicImage::ImageRgb16(b) => {
+            let mut out = b.clone();
+            out.pixels_mut().for_each(|p| p.0.iter_mut().for_each(|c| *c = map16(*c)));
+            DynamicImage::ImageRgb16(out)
+        }
+        DynamicImage::ImageRgba16(b) => {
+            let mut out = b.clone();
+            out.pixels_mut().for_each(|p| p.0[..3].iter_mut().for_each(|c| *c = map16(*c)));
+            DynamicImage::ImageRgba16(out)
+        }
+        other => {
+            let mut out = other.to_rgba8();
+            out.pixels_mut().for_each(|p| p.0[..3].iter_mut().for_each(|c| *c = lut8[*c as usize]));
+            DynamicImage::ImageRgba8(out)
+        }
+    }
+}
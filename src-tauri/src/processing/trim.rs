@@ -0,0 +1,95 @@
+use image::imageops::FilterType;
+use image::{DynamicImage, GrayImage};
+use crate::types::{CropRect, TrimDangerHit, TrimSafetySpec};
+
+// 解析用に縮小する最大辺長（写植の文字がおよそ十数pxになる大きさ）
+const ANALYSIS_MAX_SIZE: u32 = 1600;
+// 解析タイルの一辺（解析画像上のpx）
+const TILE_SIZE: u32 = 16;
+// 文字とみなすエッジとなる輝度差
+const EDGE_CONTRAST: u8 = 80;
+// 文字タイルとみなすエッジ密度の下限
+const MIN_EDGE_DENSITY: f64 = 0.08;
+
+// 白地に黒の細かいエッジが密集するタイル（フキダシ内の文字）か判定
+// 網点は縮小で中間調に潰れるためエッジ数が伸びず除外される
+fn is_text_like(gray: &GrayImage, tx: u32, ty: u32) -> bool {
+    let mut sum = 0u32;
+    let mut dark = 0u32;
+    let mut edges = 0u32;
+    for y in ty..ty + TILE_SIZE {
+        for x in tx..tx + TILE_SIZE {
+            let v = gray.get_pixel(x, y)[0];
+            sum += v as u32;
+            if v < 96 {
+                dark += 1;
+            }
+            if x + 1 < tx + TILE_SIZE && v.abs_diff(gray.get_pixel(x + 1, y)[0]) > EDGE_CONTRAST {
+                edges += 1;
+            }
+            if y + 1 < ty + TILE_SIZE && v.abs_diff(gray.get_pixel(x, y + 1)[0]) > EDGE_CONTRAST {
+                edges += 1;
+            }
+        }
+    }
+    let n = (TILE_SIZE * TILE_SIZE) as f64;
+    let mean = sum as f64 / n;
+    let dark_ratio = dark as f64 / n;
+    mean > 140.0 && (0.04..=0.45).contains(&dark_ratio) && edges as f64 / n >= MIN_EDGE_DENSITY
+}
+
+// 断裁危険域（原稿端から仕上がり線＋安全マージンまで）の文字らしき領域を検出
+pub fn detect_text_near_trim(img: &DynamicImage, spec: &TrimSafetySpec) -> Vec<TrimDangerHit> {
+    let (width, height) = (img.width(), img.height());
+    let bleed_mm = if spec.includes_bleed { spec.bleed_mm } else { 0.0 };
+    let page_width_mm = spec.trim_width_mm + bleed_mm * 2.0;
+    if page_width_mm <= 0.0 {
+        return Vec::new();
+    }
+
+    let analysis = if width.max(height) > ANALYSIS_MAX_SIZE {
+        img.resize(ANALYSIS_MAX_SIZE, ANALYSIS_MAX_SIZE, FilterType::Triangle)
+    } else {
+        img.clone()
+    };
+    let gray = analysis.to_luma8();
+    let (aw, ah) = gray.dimensions();
+    if aw < TILE_SIZE * 2 || ah < TILE_SIZE * 2 {
+        return Vec::new();
+    }
+
+    // 危険域の幅（解析画像上のpx）
+    let px_per_mm = aw as f64 / page_width_mm;
+    let band = ((bleed_mm + spec.safe_margin_mm) * px_per_mm).ceil() as u32;
+    let sx = width as f64 / aw as f64;
+    let sy = height as f64 / ah as f64;
+
+    let mut hits = Vec::new();
+    for ty in (0..=ah - TILE_SIZE).step_by(TILE_SIZE as usize) {
+        for tx in (0..=aw - TILE_SIZE).step_by(TILE_SIZE as usize) {
+            // タイルが危険域にかかる辺（最も近い辺を採用）
+            let distances = [
+                ("top", ty),
+                ("bottom", ah - (ty + TILE_SIZE)),
+                ("left", tx),
+                ("right", aw - (tx + TILE_SIZE)),
+            ];
+            let Some(&(side, _)) = distances.iter().filter(|(_, d)| *d < band).min_by_key(|(_, d)| *d) else {
+                continue;
+            };
+            if !is_text_like(&gray, tx, ty) {
+                continue;
+            }
+            hits.push(TrimDangerHit {
+                side: side.to_string(),
+                rect: CropRect {
+                    x: (tx as f64 * sx) as u32,
+                    y: (ty as f64 * sy) as u32,
+                    width: (TILE_SIZE as f64 * sx).ceil() as u32,
+                    height: (TILE_SIZE as f64 * sy).ceil() as u32,
+                },
+            });
+        }
+    }
+    hits
+}
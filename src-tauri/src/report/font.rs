@@ -0,0 +1,356 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+// 埋め込みに使うシステムの和文フォント（TrueTypeアウトラインのもの。先に見つかったものを使う）
+fn font_candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(windir) = std::env::var_os("WINDIR") {
+        let fonts = PathBuf::from(windir).join("Fonts");
+        for name in ["YuGothM.ttc", "meiryo.ttc", "msgothic.ttc"] {
+            candidates.push(fonts.join(name));
+        }
+    }
+    for path in [
+        "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+        "/Library/Fonts/Arial Unicode.ttf",
+        "/usr/share/fonts/opentype/ipafont-gothic/ipag.ttf",
+        "/usr/share/fonts/truetype/fonts-japanese-gothic.ttf",
+        "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+    ] {
+        candidates.push(PathBuf::from(path));
+    }
+    candidates
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    data.get(pos..pos + 2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn read_i16(data: &[u8], pos: usize) -> Option<i16> {
+    read_u16(data, pos).map(|v| v as i16)
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    data.get(pos..pos + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+// 文字からグリフ番号を引く cmap のサブテーブル（位置, 形式）。Unicodeの全範囲を引ける形式12を優先
+fn find_cmap(data: &[u8], cmap: usize) -> Option<(usize, u16)> {
+    let count = read_u16(data, cmap + 2)? as usize;
+    let mut best: Option<(u8, usize, u16)> = None;
+    for i in 0..count {
+        let record = cmap + 4 + i * 8;
+        let platform = read_u16(data, record)?;
+        let encoding = read_u16(data, record + 2)?;
+        let offset = cmap + read_u32(data, record + 4)? as usize;
+        let format = read_u16(data, offset)?;
+        let rank = match (platform, encoding, format) {
+            (0, _, 12) | (3, 10, 12) => 2,
+            (0, _, 4) | (3, 1, 4) => 1,
+            _ => continue,
+        };
+        if best.is_none_or(|(best_rank, _, _)| rank > best_rank) {
+            best = Some((rank, offset, format));
+        }
+    }
+    best.map(|(_, offset, format)| (offset, format))
+}
+
+fn lookup_format4(data: &[u8], offset: usize, code: u32) -> Option<u16> {
+    let code = u16::try_from(code).ok()?;
+    let seg_count = read_u16(data, offset + 6)? as usize / 2;
+    let ends = offset + 14;
+    let starts = ends + seg_count * 2 + 2;
+    let deltas = starts + seg_count * 2;
+    let range_offsets = deltas + seg_count * 2;
+
+    let i = (0..seg_count).find(|&i| read_u16(data, ends + i * 2).is_some_and(|end| code <= end))?;
+    let start = read_u16(data, starts + i * 2)?;
+    if code < start {
+        return None;
+    }
+    let delta = read_u16(data, deltas + i * 2)?;
+    let range_pos = range_offsets + i * 2;
+    let range_offset = read_u16(data, range_pos)? as usize;
+    if range_offset == 0 {
+        return Some(code.wrapping_add(delta));
+    }
+    let glyph = read_u16(data, range_pos + range_offset + (code - start) as usize * 2)?;
+    (glyph != 0).then(|| glyph.wrapping_add(delta))
+}
+
+fn lookup_format12(data: &[u8], offset: usize, code: u32) -> Option<u16> {
+    let groups = read_u32(data, offset + 12)? as usize;
+    (0..groups).find_map(|i| {
+        let group = offset + 16 + i * 12;
+        let start = read_u32(data, group)?;
+        let end = read_u32(data, group + 4)?;
+        if !(start..=end).contains(&code) {
+            return None;
+        }
+        u16::try_from(read_u32(data, group + 8)?.checked_add(code - start)?).ok()
+    })
+}
+
+// name テーブルのPostScript名（PDFのフォント名に使えない文字は除く）
+fn postscript_name(data: &[u8], name: usize) -> Option<String> {
+    let count = read_u16(data, name + 2)? as usize;
+    let strings = name + read_u16(data, name + 4)? as usize;
+    (0..count).find_map(|i| {
+        let record = name + 6 + i * 12;
+        if read_u16(data, record + 6)? != 6 {
+            return None;
+        }
+        let platform = read_u16(data, record)?;
+        let length = read_u16(data, record + 8)? as usize;
+        let offset = strings + read_u16(data, record + 10)? as usize;
+        let bytes = data.get(offset..offset + length)?;
+        let raw: String = if platform == 0 || platform == 3 {
+            char::decode_utf16(bytes.chunks_exact(2).map(|b| u16::from_be_bytes([b[0], b[1]])))
+                .filter_map(Result::ok)
+                .collect()
+        } else {
+            bytes.iter().map(|&b| b as char).collect()
+        };
+        let name: String = raw.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+        (!name.is_empty()).then_some(name)
+    })
+}
+
+// 複合グリフが部品として参照するグリフ
+fn component_glyphs(glyph: &[u8]) -> Vec<u16> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+    let mut components = Vec::new();
+    if read_i16(glyph, 0).is_none_or(|contours| contours >= 0) {
+        return components;
+    }
+    let mut pos = 10;
+    while let (Some(flags), Some(component)) = (read_u16(glyph, pos), read_u16(glyph, pos + 2)) {
+        components.push(component);
+        pos += 4 + if flags & ARG_1_AND_2_ARE_WORDS != 0 { 4 } else { 2 };
+        pos += if flags & WE_HAVE_A_SCALE != 0 {
+            2
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            4
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    components
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+// テーブルを並べてTrueTypeフォントのファイルを組み立てる（tables はタグ順）
+fn build_sfnt(tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.leading_zeros() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    out.extend_from_slice(&num_tables.to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&entry_selector.to_be_bytes());
+    out.extend_from_slice(&(num_tables * 16 - search_range).to_be_bytes());
+
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, body) in tables {
+        out.extend_from_slice(*tag);
+        out.extend_from_slice(&checksum(body).to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        offset += body.len().next_multiple_of(4);
+    }
+
+    let mut head_pos = None;
+    for (tag, body) in tables {
+        if *tag == b"head" {
+            head_pos = Some(out.len());
+        }
+        out.extend_from_slice(body);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+
+    // フォント全体のチェックサムを head に記録
+    if let Some(pos) = head_pos {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&out));
+        out[pos + 8..pos + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    out
+}
+
+// PDFに部分埋め込みするTrueTypeフォント
+pub struct TrueTypeFont {
+    data: Vec<u8>,
+    tables: HashMap<[u8; 4], (usize, usize)>,
+    pub units_per_em: u16,
+    /// 外接矩形 (xMin, yMin, xMax, yMax)
+    pub bbox: [i16; 4],
+    pub ascent: i16,
+    pub descent: i16,
+    pub postscript_name: String,
+    long_loca: bool,
+    num_glyphs: u16,
+    num_h_metrics: u16,
+    cmap: (usize, u16),
+}
+
+impl TrueTypeFont {
+    // システムの和文フォントを読み込み（見つからなければ None）
+    pub fn load_system() -> Option<Self> {
+        font_candidates()
+            .into_iter()
+            .find_map(|path| Self::parse(fs::read(path).ok()?))
+    }
+
+    fn parse(data: Vec<u8>) -> Option<Self> {
+        // TTC（複数フォントの集合）は最初のフォントを使う
+        let sfnt = if data.get(0..4)? == b"ttcf" { read_u32(&data, 12)? as usize } else { 0 };
+        let num_tables = read_u16(&data, sfnt + 4)? as usize;
+        let mut tables = HashMap::new();
+        for i in 0..num_tables {
+            let record = sfnt + 12 + i * 16;
+            let tag: [u8; 4] = data.get(record..record + 4)?.try_into().ok()?;
+            let offset = read_u32(&data, record + 8)? as usize;
+            let length = read_u32(&data, record + 12)? as usize;
+            if offset.checked_add(length)? > data.len() {
+                return None;
+            }
+            tables.insert(tag, (offset, length));
+        }
+
+        // CFFアウトライン（glyf のないOpenType）は部分埋め込みに対応しない
+        let required: [(&[u8; 4], usize); 7] =
+            [(b"head", 54), (b"hhea", 36), (b"maxp", 6), (b"hmtx", 4), (b"loca", 0), (b"glyf", 0), (b"cmap", 4)];
+        if required
+            .iter()
+            .any(|(tag, min_len)| tables.get(*tag).is_none_or(|(_, len)| len < min_len))
+        {
+            return None;
+        }
+
+        let head = tables[b"head"].0;
+        let hhea = tables[b"hhea"].0;
+        let units_per_em = read_u16(&data, head + 18).filter(|&v| v > 0)?;
+        let bbox = [
+            read_i16(&data, head + 36)?,
+            read_i16(&data, head + 38)?,
+            read_i16(&data, head + 40)?,
+            read_i16(&data, head + 42)?,
+        ];
+        let cmap = find_cmap(&data, tables[b"cmap"].0)?;
+        let postscript_name = tables
+            .get(b"name")
+            .and_then(|&(name, _)| postscript_name(&data, name))
+            .unwrap_or_else(|| "CJKFont".to_string());
+
+        Some(Self {
+            units_per_em,
+            bbox,
+            ascent: read_i16(&data, hhea + 4)?,
+            descent: read_i16(&data, hhea + 6)?,
+            postscript_name,
+            long_loca: read_i16(&data, head + 50)? != 0,
+            num_glyphs: read_u16(&data, tables[b"maxp"].0 + 4)?,
+            num_h_metrics: read_u16(&data, hhea + 34).filter(|&v| v > 0)?,
+            cmap,
+            tables,
+            data,
+        })
+    }
+
+    fn table(&self, tag: &[u8; 4]) -> &[u8] {
+        let (offset, length) = self.tables[tag];
+        &self.data[offset..offset + length]
+    }
+
+    // 文字のグリフ番号（フォントにない文字は 0 = .notdef）
+    pub fn glyph_id(&self, c: char) -> u16 {
+        let (offset, format) = self.cmap;
+        let glyph = match format {
+            12 => lookup_format12(&self.data, offset, c as u32),
+            _ => lookup_format4(&self.data, offset, c as u32),
+        };
+        glyph.filter(|&g| g < self.num_glyphs).unwrap_or(0)
+    }
+
+    // グリフの送り幅 (em)
+    pub fn advance(&self, glyph: u16) -> f64 {
+        let index = glyph.min(self.num_h_metrics - 1) as usize;
+        let width = read_u16(self.table(b"hmtx"), index * 4).unwrap_or(0);
+        width as f64 / self.units_per_em as f64
+    }
+
+    fn glyph_data(&self, glyph: u16) -> &[u8] {
+        let loca = self.table(b"loca");
+        let glyf = self.table(b"glyf");
+        let g = glyph as usize;
+        let range = if self.long_loca {
+            read_u32(loca, g * 4).zip(read_u32(loca, g * 4 + 4)).map(|(s, e)| (s as usize, e as usize))
+        } else {
+            read_u16(loca, g * 2)
+                .zip(read_u16(loca, g * 2 + 2))
+                .map(|(s, e)| (s as usize * 2, e as usize * 2))
+        };
+        match range {
+            Some((start, end)) if glyph < self.num_glyphs && start <= end && end <= glyf.len() => &glyf[start..end],
+            _ => &[],
+        }
+    }
+
+    // 使うグリフだけを残したフォントを作成
+    // グリフ番号は元のまま残して使わないグリフを空にする（PDF側はグリフ番号をそのままCIDとして使う）
+    pub fn subset(&self, used: &BTreeSet<u16>) -> Vec<u8> {
+        // 複合グリフが参照する部品と .notdef も含める
+        let mut glyphs: BTreeSet<u16> = BTreeSet::new();
+        let mut pending: Vec<u16> = std::iter::once(0).chain(used.iter().copied()).collect();
+        while let Some(glyph) = pending.pop() {
+            if glyph < self.num_glyphs && glyphs.insert(glyph) {
+                pending.extend(component_glyphs(self.glyph_data(glyph)));
+            }
+        }
+
+        let mut glyf = Vec::new();
+        let mut loca = Vec::with_capacity((self.num_glyphs as usize + 1) * 4);
+        for glyph in 0..self.num_glyphs {
+            loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+            if glyphs.contains(&glyph) {
+                glyf.extend_from_slice(self.glyph_data(glyph));
+                glyf.resize(glyf.len().next_multiple_of(4), 0);
+            }
+        }
+        loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+
+        // loca は長形式で書き出す（checkSumAdjustment は組み立て後に計算）
+        let mut head = self.table(b"head").to_vec();
+        head[8..12].fill(0);
+        head[50..52].copy_from_slice(&1i16.to_be_bytes());
+
+        let mut tables: Vec<(&[u8; 4], Vec<u8>)> = vec![(b"glyf", glyf), (b"head", head), (b"loca", loca)];
+        for tag in [b"cvt ", b"fpgm", b"hhea", b"hmtx", b"maxp", b"prep"] {
+            if self.tables.contains_key(tag) {
+                tables.push((tag, self.table(tag).to_vec()));
+            }
+        }
+        tables.sort_by_key(|(tag, _)| **tag);
+        build_sfnt(&tables)
+    }
+}
@@ -0,0 +1,88 @@
+use base64::Engine;
+use crate::types::{PreflightIssue, PreflightReport};
+use super::{severity_label, summary_line};
+
+// HTMLの特殊文字をエスケープ
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_issues(issues: &[PreflightIssue]) -> String {
+    if issues.is_empty() {
+        return "<p class=\"ok\">問題なし</p>".to_string();
+    }
+    let items: String = issues
+        .iter()
+        .map(|issue| {
+            format!(
+                "<li class=\"{}\"><span class=\"badge\">{}</span> [{}] {}</li>",
+                escape(&issue.severity),
+                severity_label(&issue.severity),
+                escape(&issue.category),
+                escape(&issue.message)
+            )
+        })
+        .collect();
+    format!("<ul>{}</ul>", items)
+}
+
+// レポートを単体で開けるHTMLに変換（サムネイルはPNGをデータURLで埋め込み）
+// thumbnails はページと同じ順序
+pub fn render_html_report(report: &PreflightReport, thumbnails: &[Option<Vec<u8>>], generated_at: &str) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape(&report.title)));
+    html.push_str(
+        "<style>\n\
+         body { font-family: sans-serif; margin: 24px; color: #222; }\n\
+         h1 { font-size: 20px; margin-bottom: 4px; }\n\
+         .meta { color: #666; font-size: 12px; }\n\
+         table { border-collapse: collapse; width: 100%; margin-top: 16px; }\n\
+         td, th { border: 1px solid #ccc; padding: 6px; vertical-align: top; font-size: 13px; text-align: left; }\n\
+         td.thumb { width: 110px; }\n\
+         td.thumb img { max-width: 100px; max-height: 140px; }\n\
+         ul { margin: 0; padding-left: 16px; }\n\
+         li.error { color: #c00; }\n\
+         li.warning { color: #b36b00; }\n\
+         .badge { font-weight: bold; }\n\
+         .ok { color: #2a7a2a; margin: 0; }\n\
+         @media print { tr { page-break-inside: avoid; } }\n\
+         </style>\n</head>\n<body>\n",
+    );
+
+    html.push_str(&format!("<h1>{}</h1>\n", escape(&report.title)));
+    if let Some(ref name) = report.project_name {
+        html.push_str(&format!("<p class=\"meta\">作品: {}</p>\n", escape(name)));
+    }
+    html.push_str(&format!("<p class=\"meta\">作成日時: {}</p>\n", escape(generated_at)));
+    html.push_str(&format!("<p>{}</p>\n", escape(&summary_line(report))));
+
+    if !report.issues.is_empty() {
+        html.push_str("<h2>作品全体</h2>\n");
+        html.push_str(&render_issues(&report.issues));
+    }
+
+    html.push_str("<table>\n<tr><th>サムネイル</th><th>ページ</th><th>指摘事項</th></tr>\n");
+    for (page, thumbnail) in report.pages.iter().zip(thumbnails) {
+        let image = thumbnail
+            .as_ref()
+            .map(|data| {
+                format!(
+                    "<img src=\"data:image/png;base64,{}\" alt=\"\">",
+                    base64::engine::general_purpose::STANDARD.encode(data)
+                )
+            })
+            .unwrap_or_default();
+        html.push_str(&format!(
+            "<tr><td class=\"thumb\">{}</td><td>{}</td><td>{}</td></tr>\n",
+            image,
+            escape(&page.label),
+            render_issues(&page.issues)
+        ));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
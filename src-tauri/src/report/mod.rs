@@ -0,0 +1,44 @@
+mod font;
+mod html;
+mod pdf;
+
+pub use self::html::render_html_report;
+pub use self::pdf::render_pdf_report;
+
+use crate::types::{PreflightIssue, PreflightReport};
+
+// 重要度の表示名
+fn severity_label(severity: &str) -> &'static str {
+    match severity {
+        "error" => "エラー",
+        "warning" => "警告",
+        _ => "情報",
+    }
+}
+
+// 重要度ごとの件数（エラー, 警告, 情報）
+fn count_issues(report: &PreflightReport) -> (usize, usize, usize) {
+    let all = report
+        .issues
+        .iter()
+        .chain(report.pages.iter().flat_map(|p| p.issues.iter()));
+    all.fold((0, 0, 0), |(e, w, i), issue: &PreflightIssue| match issue.severity.as_str() {
+        "error" => (e + 1, w, i),
+        "warning" => (e, w + 1, i),
+        _ => (e, w, i + 1),
+    })
+}
+
+// 集計行の文言
+fn summary_line(report: &PreflightReport) -> String {
+    let (errors, warnings, infos) = count_issues(report);
+    let flagged = report.pages.iter().filter(|p| !p.issues.is_empty()).count();
+    format!(
+        "全{}ページ / 指摘のあるページ {} / エラー {}件・警告 {}件・情報 {}件",
+        report.pages.len(),
+        flagged,
+        errors,
+        warnings,
+        infos
+    )
+}
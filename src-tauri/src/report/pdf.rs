@@ -0,0 +1,434 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Cursor;
+use image::DynamicImage;
+use crate::image_utils::encode_image;
+use crate::types::{PreflightIssue, PreflightReport};
+use super::font::TrueTypeFont;
+use super::{severity_label, summary_line};
+
+// A4縦 (pt)
+const PAGE_WIDTH: f64 = 595.0;
+const PAGE_HEIGHT: f64 = 842.0;
+const MARGIN: f64 = 40.0;
+// サムネイル枠 (pt)
+const THUMB_WIDTH: f64 = 64.0;
+const THUMB_HEIGHT: f64 = 90.0;
+const BODY_SIZE: f64 = 9.0;
+const LINE_HEIGHT: f64 = 13.0;
+
+// 埋め込める和文フォントがない場合は、Acrobat標準の小塚明朝（埋め込みなし）を参照し、UTF-16で文字を指定する
+const FONT_NAME: &str = "KozMinPr6N-Regular";
+// 部分埋め込みしたフォント名に付けるタグ（英大文字6文字）
+const SUBSET_TAG: &str = "DAIDRI";
+
+// PDFのオブジェクトを順に積み上げる簡易ライター（オブジェクト番号 = 添字 + 1）
+struct PdfObjects {
+    objects: Vec<Vec<u8>>,
+}
+
+impl PdfObjects {
+    fn reserve(&mut self) -> usize {
+        self.objects.push(Vec::new());
+        self.objects.len()
+    }
+
+    fn set(&mut self, id: usize, body: Vec<u8>) {
+        self.objects[id - 1] = body;
+    }
+
+    fn add(&mut self, body: Vec<u8>) -> usize {
+        let id = self.reserve();
+        self.set(id, body);
+        id
+    }
+
+    fn finish(self, root: usize) -> Vec<u8> {
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(self.objects.len());
+        for (i, body) in self.objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(body);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", self.objects.len() + 1).as_bytes());
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+                self.objects.len() + 1,
+                root,
+                xref
+            )
+            .as_bytes(),
+        );
+        out
+    }
+}
+
+fn stream_object(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut body = format!("<< {} /Length {} >>\nstream\n", dict, data.len()).into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\nendstream");
+    body
+}
+
+// 埋め込んだグリフから元の文字を引く対応表（テキストのコピー・検索用）
+fn to_unicode_cmap(used: &BTreeMap<u16, char>) -> String {
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    let entries: Vec<(&u16, &char)> = used.iter().filter(|(glyph, _)| **glyph != 0).collect();
+    for chunk in entries.chunks(100) {
+        cmap += &format!("{} beginbfchar\n", chunk.len());
+        for (glyph, c) in chunk {
+            let unicode: String = c.encode_utf16(&mut [0; 2]).iter().map(|u| format!("{:04X}", u)).collect();
+            cmap += &format!("<{:04X}> <{}>\n", glyph, unicode);
+        }
+        cmap += "endbfchar\n";
+    }
+    cmap += "endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n";
+    cmap
+}
+
+// 本文のフォント
+// システムの和文TrueTypeフォントが見つかれば使った文字だけを埋め込む（フォントのない環境でも文字化けしない）
+enum ReportFont {
+    Embedded {
+        font: TrueTypeFont,
+        /// 使ったグリフと対応する文字
+        used: BTreeMap<u16, char>,
+    },
+    Reference,
+}
+
+impl ReportFont {
+    fn load() -> Self {
+        match TrueTypeFont::load_system() {
+            Some(font) => ReportFont::Embedded { font, used: BTreeMap::new() },
+            None => ReportFont::Reference,
+        }
+    }
+
+    // 文字列の幅 (pt)。参照フォントは半角 0.5em、全角 1em で概算
+    fn text_width(&self, text: &str, size: f64) -> f64 {
+        let em: f64 = match self {
+            ReportFont::Embedded { font, .. } => text.chars().map(|c| font.advance(font.glyph_id(c))).sum(),
+            ReportFont::Reference => text.chars().map(|c| if c.is_ascii() { 0.5 } else { 1.0 }).sum(),
+        };
+        em * size
+    }
+
+    // テキストを16進文字列に変換（埋め込み時はグリフ番号、参照時はUTF-16BE）
+    fn encode(&mut self, text: &str) -> String {
+        let hex: String = match self {
+            ReportFont::Embedded { font, used } => text
+                .chars()
+                .map(|c| {
+                    let glyph = font.glyph_id(c);
+                    used.entry(glyph).or_insert(c);
+                    format!("{:04X}", glyph)
+                })
+                .collect(),
+            ReportFont::Reference => text.encode_utf16().map(|u| format!("{:04X}", u)).collect(),
+        };
+        format!("<{}>", hex)
+    }
+
+    // フォントのオブジェクトを書き出す（font_id は予約済みの Type0 フォント）
+    fn write(self, pdf: &mut PdfObjects, font_id: usize) {
+        match self {
+            ReportFont::Embedded { font, used } => {
+                let glyphs: BTreeSet<u16> = used.keys().copied().collect();
+                let program = font.subset(&glyphs);
+                let file_id = pdf.add(stream_object(&format!("/Length1 {}", program.len()), &program));
+
+                let name = format!("{}+{}", SUBSET_TAG, font.postscript_name);
+                let scale = 1000.0 / font.units_per_em as f64;
+                let [x_min, y_min, x_max, y_max] = font.bbox.map(|v| (v as f64 * scale).round() as i64);
+                let ascent = (font.ascent as f64 * scale).round() as i64;
+                let descent = (font.descent as f64 * scale).round() as i64;
+                let descriptor_id = pdf.add(
+                    format!(
+                        "<< /Type /FontDescriptor /FontName /{} /Flags 4 /FontBBox [{} {} {} {}] \
+                         /ItalicAngle 0 /Ascent {} /Descent {} /CapHeight {} /StemV 80 /FontFile2 {} 0 R >>",
+                        name, x_min, y_min, x_max, y_max, ascent, descent, ascent, file_id
+                    )
+                    .into_bytes(),
+                );
+                let widths: String = glyphs
+                    .iter()
+                    .map(|&glyph| format!("{} [{}] ", glyph, (font.advance(glyph) * 1000.0).round() as i64))
+                    .collect();
+                let cid_font_id = pdf.add(
+                    format!(
+                        "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /{} \
+                         /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> \
+                         /FontDescriptor {} 0 R /DW 1000 /W [{}] /CIDToGIDMap /Identity >>",
+                        name, descriptor_id, widths
+                    )
+                    .into_bytes(),
+                );
+                let to_unicode_id = pdf.add(stream_object("", to_unicode_cmap(&used).as_bytes()));
+                pdf.set(
+                    font_id,
+                    format!(
+                        "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /Identity-H \
+                         /DescendantFonts [{} 0 R] /ToUnicode {} 0 R >>",
+                        name, cid_font_id, to_unicode_id
+                    )
+                    .into_bytes(),
+                );
+            }
+            ReportFont::Reference => {
+                let descriptor_id = pdf.add(
+                    format!(
+                        "<< /Type /FontDescriptor /FontName /{} /Flags 6 /FontBBox [-437 -340 1147 1317] \
+                         /ItalicAngle 0 /Ascent 1317 /Descent -349 /CapHeight 742 /StemV 80 >>",
+                        FONT_NAME
+                    )
+                    .into_bytes(),
+                );
+                let cid_font_id = pdf.add(
+                    format!(
+                        "<< /Type /Font /Subtype /CIDFontType0 /BaseFont /{} \
+                         /CIDSystemInfo << /Registry (Adobe) /Ordering (Japan1) /Supplement 6 >> \
+                         /FontDescriptor {} 0 R /DW 1000 /W [1 95 500 231 632 500] >>",
+                        FONT_NAME, descriptor_id
+                    )
+                    .into_bytes(),
+                );
+                pdf.set(
+                    font_id,
+                    format!(
+                        "<< /Type /Font /Subtype /Type0 /BaseFont /{}-UniJIS-UTF16-H /Encoding /UniJIS-UTF16-H \
+                         /DescendantFonts [{} 0 R] >>",
+                        FONT_NAME, cid_font_id
+                    )
+                    .into_bytes(),
+                );
+            }
+        }
+    }
+}
+
+// 指定幅で折り返し
+fn wrap_text(font: &ReportFont, text: &str, size: f64, max_width: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        let candidate = format!("{}{}", current, c);
+        if !current.is_empty() && font.text_width(&candidate, size) > max_width {
+            lines.push(std::mem::take(&mut current));
+        }
+        current.push(c);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+// 重要度ごとの文字色
+fn severity_color(severity: &str) -> &'static str {
+    match severity {
+        "error" => "0.8 0 0 rg",
+        "warning" => "0.7 0.42 0 rg",
+        _ => "0.13 0.13 0.13 rg",
+    }
+}
+
+fn issue_lines(font: &ReportFont, issues: &[PreflightIssue], max_width: f64) -> Vec<(String, &'static str)> {
+    if issues.is_empty() {
+        return vec![("問題なし".to_string(), "0.16 0.48 0.16 rg")];
+    }
+    issues
+        .iter()
+        .flat_map(|issue| {
+            let text = format!("{} [{}] {}", severity_label(&issue.severity), issue.category, issue.message);
+            let color = severity_color(&issue.severity);
+            wrap_text(font, &text, BODY_SIZE, max_width)
+                .into_iter()
+                .map(move |line| (line, color))
+        })
+        .collect()
+}
+
+// 描画中のページ
+struct PageCanvas {
+    ops: String,
+    images: Vec<usize>,
+    y: f64,
+}
+
+impl PageCanvas {
+    fn new() -> Self {
+        Self {
+            ops: String::new(),
+            images: Vec::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn text(&mut self, font: &mut ReportFont, x: f64, y: f64, size: f64, color: &str, text: &str) {
+        self.ops.push_str(&format!(
+            "{} BT /F1 {} Tf {:.2} {:.2} Td {} Tj ET\n",
+            color,
+            size,
+            x,
+            y,
+            font.encode(text)
+        ));
+    }
+}
+
+// サムネイルPNGをPDFに埋め込めるJPEGに変換（幅, 高さ, データ）
+fn thumbnail_jpeg(png: &[u8]) -> Option<(u32, u32, Vec<u8>)> {
+    let img = image::load_from_memory(png).ok()?;
+    let rgb = DynamicImage::ImageRgb8(img.to_rgb8());
+    let mut buffer = Cursor::new(Vec::new());
+    encode_image(&rgb, &mut buffer, "jpg", 85).ok()?;
+    Some((rgb.width(), rgb.height(), buffer.into_inner()))
+}
+
+// レポートをA4のPDFに変換（thumbnails はページと同じ順序のPNG）
+pub fn render_pdf_report(report: &PreflightReport, thumbnails: &[Option<Vec<u8>>], generated_at: &str) -> Vec<u8> {
+    let mut pdf = PdfObjects { objects: Vec::new() };
+    let catalog_id = pdf.reserve();
+    let pages_id = pdf.reserve();
+
+    // フォントは使った文字が出そろってから書き出す
+    let mut font = ReportFont::load();
+    let font_id = pdf.reserve();
+
+    let text_x = MARGIN + THUMB_WIDTH + 12.0;
+    let issue_x = text_x + 90.0;
+    let issue_width = PAGE_WIDTH - MARGIN - issue_x;
+
+    let mut canvases = vec![PageCanvas::new()];
+
+    // 見出し
+    {
+        let canvas = canvases.last_mut().expect("1ページ目は常にある");
+        canvas.y -= 18.0;
+        let y = canvas.y;
+        canvas.text(&mut font, MARGIN, y, 16.0, "0 g", &report.title);
+        let mut meta = format!("作成日時: {}", generated_at);
+        if let Some(ref name) = report.project_name {
+            meta = format!("作品: {}　{}", name, meta);
+        }
+        canvas.y -= 18.0;
+        let y = canvas.y;
+        canvas.text(&mut font, MARGIN, y, BODY_SIZE, "0.4 0.4 0.4 rg", &meta);
+        canvas.y -= LINE_HEIGHT + 2.0;
+        let y = canvas.y;
+        canvas.text(&mut font, MARGIN, y, 10.0, "0 g", &summary_line(report));
+        canvas.y -= 8.0;
+
+        if !report.issues.is_empty() {
+            for (line, color) in issue_lines(&font, &report.issues, PAGE_WIDTH - MARGIN * 2.0) {
+                canvas.y -= LINE_HEIGHT;
+                let y = canvas.y;
+                canvas.text(&mut font, MARGIN, y, BODY_SIZE, color, &line);
+            }
+            canvas.y -= 8.0;
+        }
+    }
+
+    for (page, thumbnail) in report.pages.iter().zip(thumbnails) {
+        let lines = issue_lines(&font, &page.issues, issue_width);
+        let row_height = (lines.len() as f64 * LINE_HEIGHT).max(THUMB_HEIGHT) + 10.0;
+
+        if canvases.last().map_or(true, |c| c.y - row_height < MARGIN) {
+            canvases.push(PageCanvas::new());
+        }
+        let canvas = canvases.last_mut().expect("描画中のページは常にある");
+        let top = canvas.y;
+
+        // 区切り線
+        canvas.ops.push_str(&format!(
+            "0.8 G 0.5 w {:.2} {:.2} m {:.2} {:.2} l S\n",
+            MARGIN,
+            top,
+            PAGE_WIDTH - MARGIN,
+            top
+        ));
+
+        if let Some((width, height, data)) = thumbnail.as_deref().and_then(thumbnail_jpeg) {
+            let scale = (THUMB_WIDTH / width as f64).min(THUMB_HEIGHT / height as f64);
+            let (w, h) = (width as f64 * scale, height as f64 * scale);
+            let mut body = format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB \
+                 /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+                width,
+                height,
+                data.len()
+            )
+            .into_bytes();
+            body.extend_from_slice(&data);
+            body.extend_from_slice(b"\nendstream");
+            let image_id = pdf.add(body);
+            canvas.images.push(image_id);
+            canvas.ops.push_str(&format!(
+                "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im{} Do Q\n",
+                w,
+                h,
+                MARGIN,
+                top - 5.0 - h,
+                canvas.images.len()
+            ));
+        }
+
+        canvas.text(&mut font, text_x, top - 5.0 - BODY_SIZE, 10.0, "0 g", &page.label);
+        for (i, (line, color)) in lines.iter().enumerate() {
+            let y = top - 5.0 - BODY_SIZE - i as f64 * LINE_HEIGHT;
+            canvas.text(&mut font, issue_x, y, BODY_SIZE, color, line);
+        }
+        canvas.y = top - row_height;
+    }
+
+    // ページオブジェクトを作成
+    let total = canvases.len();
+    let mut page_ids = Vec::with_capacity(total);
+    for (index, mut canvas) in canvases.into_iter().enumerate() {
+        let footer = format!("{} / {}", index + 1, total);
+        canvas.text(&mut font, PAGE_WIDTH / 2.0 - 10.0, MARGIN / 2.0, 8.0, "0.5 0.5 0.5 rg", &footer);
+
+        let content = canvas.ops.into_bytes();
+        let mut body = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        body.extend_from_slice(&content);
+        body.extend_from_slice(b"\nendstream");
+        let content_id = pdf.add(body);
+
+        let images: String = canvas
+            .images
+            .iter()
+            .enumerate()
+            .map(|(i, id)| format!("/Im{} {} 0 R ", i + 1, id))
+            .collect();
+        page_ids.push(pdf.add(
+            format!(
+                "<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 {} 0 R >> /XObject << {}>> >> /Contents {} 0 R >>",
+                pages_id, PAGE_WIDTH, PAGE_HEIGHT, font_id, images, content_id
+            )
+            .into_bytes(),
+        ));
+    }
+
+    font.write(&mut pdf, font_id);
+
+    let kids: String = page_ids.iter().map(|id| format!("{} 0 R ", id)).collect();
+    pdf.set(
+        pages_id,
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids, page_ids.len()).into_bytes(),
+    );
+    pdf.set(catalog_id, format!("<< /Type /Catalog /Pages {} 0 R >>", pages_id).into_bytes());
+    pdf.finish(catalog_id)
+}
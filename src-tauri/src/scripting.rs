@@ -0,0 +1,142 @@
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use crate::file_utils::is_single_path_component;
+use crate::processing::SpreadHalf;
+use crate::types::ExportPage;
+
+// スクリプト1回の実行で許可する命令数（無限ループ対策）
+const MAX_OPERATIONS: u64 = 1_000_000;
+// 文字列・配列・マップの大きさの上限（巨大なデータを作ってメモリを使い切らないよう）
+const MAX_STRING_SIZE: usize = 64 * 1024;
+const MAX_ARRAY_SIZE: usize = 10_000;
+const MAX_MAP_SIZE: usize = 1_000;
+
+// スクリプトがページごとに決めた出力内容
+pub struct PageDecision {
+    pub page: ExportPage,
+    /// 変換形式の上書き (Some(None) = 元形式のまま出力)
+    pub format: Option<Option<&'static str>>,
+    /// 品質の上書き
+    pub quality: Option<u8>,
+    /// 見開き原稿を分割して出力する場合の片側
+    pub spread_half: Option<SpreadHalf>,
+}
+
+/// プロジェクトに保存されたエクスポートスクリプト（Rhai）
+///
+/// スクリプトは `fn page(p)` を定義し、各ページごとに呼ばれる。
+/// p: #{ index, total, outputName, pageType, subfolder, sourcePath, ext }
+/// 戻り値: () = 変更なし / false = 出力しない /
+///         #{ skip, outputName, subfolder, format: "jpg"|"jxl"|"original", quality }
+pub struct ExportScript {
+    engine: Engine,
+    ast: AST,
+}
+
+impl ExportScript {
+    pub fn compile(source: &str) -> Result<Self, String> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_string_size(MAX_STRING_SIZE);
+        engine.set_max_array_size(MAX_ARRAY_SIZE);
+        engine.set_max_map_size(MAX_MAP_SIZE);
+        let ast = engine
+            .compile(source)
+            .map_err(|e| format!("スクリプトの構文エラー: {}", e))?;
+
+        let has_page_fn = ast.iter_functions().any(|f| f.name == "page" && f.params.len() == 1);
+        if !has_page_fn {
+            return Err("スクリプトに fn page(p) が定義されていません".to_string());
+        }
+        Ok(Self { engine, ast })
+    }
+
+    // 1ページ分の判定（None = 出力しない）
+    pub fn decide(&self, page: &ExportPage, index: usize, total: usize) -> Result<Option<PageDecision>, String> {
+        let ext = page
+            .source_path
+            .as_deref()
+            .and_then(|s| std::path::Path::new(s).extension())
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
+
+        let mut input = Map::new();
+        input.insert("index".into(), Dynamic::from(index as i64));
+        input.insert("total".into(), Dynamic::from(total as i64));
+        input.insert("outputName".into(), Dynamic::from(page.output_name.clone()));
+        input.insert("pageType".into(), Dynamic::from(page.page_type.clone()));
+        input.insert("subfolder".into(), optional_string(page.subfolder.clone()));
+        input.insert("sourcePath".into(), optional_string(page.source_path.clone()));
+        input.insert("ext".into(), optional_string(ext));
+
+        let mut scope = Scope::new();
+        let result: Dynamic = self
+            .engine
+            .call_fn(&mut scope, &self.ast, "page", (input,))
+            .map_err(|e| format!("スクリプトの実行エラー（{}ページ目）: {}", index + 1, e))?;
+
+        let mut decision = PageDecision {
+            page: page.clone(),
+            format: None,
+            quality: None,
+            spread_half: None,
+        };
+
+        if result.is_unit() {
+            return Ok(Some(decision));
+        }
+        if let Ok(keep) = result.as_bool() {
+            return Ok(if keep { Some(decision) } else { None });
+        }
+        let Some(map) = result.try_cast::<Map>() else {
+            return Err(format!(
+                "スクリプトの戻り値が不正です（{}ページ目）: (), bool, マップのいずれかを返してください",
+                index + 1
+            ));
+        };
+
+        if map.get("skip").and_then(|v| v.as_bool().ok()).unwrap_or(false) {
+            return Ok(None);
+        }
+        if let Some(name) = map.get("outputName").and_then(|v| v.clone().into_string().ok()) {
+            check_output_component(&name, "outputName", index)?;
+            decision.page.output_name = name;
+        }
+        if let Some(subfolder) = map.get("subfolder") {
+            decision.page.subfolder = subfolder.clone().into_string().ok().filter(|s| !s.is_empty());
+            if let Some(ref subfolder) = decision.page.subfolder {
+                check_output_component(subfolder, "subfolder", index)?;
+            }
+        }
+        if let Some(format) = map.get("format").and_then(|v| v.clone().into_string().ok()) {
+            decision.format = Some(match format.to_lowercase().as_str() {
+                "jpg" | "jpeg" => Some("jpg"),
+                "jxl" => Some("jxl"),
+                "original" => None,
+                other => return Err(format!("スクリプトが指定した形式はサポートされていません: {}", other)),
+            });
+        }
+        if let Some(quality) = map.get("quality").and_then(|v| v.as_int().ok()) {
+            decision.quality = Some(quality.clamp(1, 100) as u8);
+        }
+
+        Ok(Some(decision))
+    }
+}
+
+// 出力フォルダの外に書き込まないよう、1階層分の名前だけ受け付ける
+pub fn check_output_component(value: &str, key: &str, index: usize) -> Result<(), String> {
+    if is_single_path_component(value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "スクリプトが返した {} が不正です（{}ページ目）: \"{}\"（フォルダの区切りや \"..\" は使えません）",
+            key,
+            index + 1,
+            value
+        ))
+    }
+}
+
+fn optional_string(value: Option<String>) -> Dynamic {
+    value.map(Dynamic::from).unwrap_or(Dynamic::UNIT)
+}
@@ -0,0 +1,243 @@
+use crate::types::{ChapterNaming, SequenceChapter, SequenceInput, SequenceMove, SequencePage, SequenceResult, SequencedPage};
+
+// 連番の桁数の上限（u32 の最大値が収まる桁数）
+const MAX_DIGITS: usize = 10;
+
+// 連番の出力名（接頭辞 + ゼロ埋め番号）
+fn format_output_name(naming: &ChapterNaming, index: usize) -> Result<String, String> {
+    let number = (naming.start_number as usize)
+        .checked_add(index)
+        .ok_or_else(|| format!("連番が大きすぎます（開始番号 {}）", naming.start_number))?;
+    Ok(format!(
+        "{}{:0width$}",
+        naming.prefix,
+        number,
+        width = naming.digits.clamp(1, MAX_DIGITS)
+    ))
+}
+
+// ノンブルから見開きの左右を判定
+// 右綴じは奇数ページが左、左綴じは奇数ページが右
+fn page_side(folio: u32, binding: &str) -> &'static str {
+    let odd = folio % 2 == 1;
+    match (binding, odd) {
+        ("left", true) | ("right", false) => "right",
+        _ => "left",
+    }
+}
+
+// 全ページの位置・ノンブル・左右・出力名を計算
+pub fn compute_sequence(input: &SequenceInput) -> Result<Vec<SequencedPage>, String> {
+    let per_chapter = input.naming.mode == "perChapter";
+    let default_naming = ChapterNaming::default();
+    let mut result = Vec::new();
+    let mut global_index = 0;
+
+    for chapter in &input.chapters {
+        let naming = if per_chapter {
+            input.naming.per_chapter.get(&chapter.id).unwrap_or(&default_naming)
+        } else {
+            &input.naming.unified
+        };
+
+        for (chapter_index, page) in chapter.pages.iter().enumerate() {
+            let folio = u32::try_from(global_index)
+                .ok()
+                .and_then(|index| input.folio_start.checked_add(index))
+                .ok_or_else(|| format!("ノンブルが大きすぎます（開始ノンブル {}）", input.folio_start))?;
+            let output_name = if !naming.enabled {
+                None
+            } else if per_chapter {
+                Some(format_output_name(naming, chapter_index)?)
+            } else {
+                Some(format_output_name(naming, global_index)?)
+            };
+
+            result.push(SequencedPage {
+                page_id: page.id.clone(),
+                chapter_id: chapter.id.clone(),
+                global_index,
+                chapter_index,
+                folio,
+                side: page_side(folio, &input.binding).to_string(),
+                output_name,
+                subfolder: per_chapter.then(|| chapter.name.clone()),
+            });
+            global_index += 1;
+        }
+    }
+    Ok(result)
+}
+
+// 移動を適用した並びを返す
+fn apply_move(chapters: &[SequenceChapter], mv: &SequenceMove) -> Result<Vec<SequenceChapter>, String> {
+    let mut chapters = chapters.to_vec();
+    match mv {
+        SequenceMove::Pages {
+            page_ids,
+            target_chapter_id,
+            target_index,
+        } => {
+            if !chapters.iter().any(|c| &c.id == target_chapter_id) {
+                return Err(format!("移動先のチャプターが見つかりません: {}", target_chapter_id));
+            }
+
+            // 指定順でページを取り出す
+            let mut moving: Vec<SequencePage> = Vec::with_capacity(page_ids.len());
+            for page_id in page_ids {
+                let found = chapters.iter_mut().find_map(|c| {
+                    let index = c.pages.iter().position(|p| &p.id == page_id)?;
+                    Some(c.pages.remove(index))
+                });
+                match found {
+                    Some(page) => moving.push(page),
+                    None => return Err(format!("ページが見つかりません: {}", page_id)),
+                }
+            }
+
+            let target = chapters
+                .iter_mut()
+                .find(|c| &c.id == target_chapter_id)
+                .expect("存在確認済み");
+            let index = (*target_index).min(target.pages.len());
+            target.pages.splice(index..index, moving);
+        }
+        SequenceMove::Chapter { chapter_id, target_index } => {
+            let index = chapters
+                .iter()
+                .position(|c| &c.id == chapter_id)
+                .ok_or_else(|| format!("チャプターが見つかりません: {}", chapter_id))?;
+            let chapter = chapters.remove(index);
+            let target = (*target_index).min(chapters.len());
+            chapters.insert(target, chapter);
+        }
+    }
+    Ok(chapters)
+}
+
+// 移動を適用して再計算し、番号・左右・出力名が変わったページを返す
+pub fn apply_sequence_move(input: &SequenceInput, mv: &SequenceMove) -> Result<SequenceResult, String> {
+    let before = compute_sequence(input)?;
+    let moved = SequenceInput {
+        chapters: apply_move(&input.chapters, mv)?,
+        ..input.clone()
+    };
+    let pages = compute_sequence(&moved)?;
+
+    let affected_page_ids = pages
+        .iter()
+        .filter(|page| {
+            before.iter().find(|b| b.page_id == page.page_id).map_or(true, |b| {
+                b.folio != page.folio || b.side != page.side || b.output_name != page.output_name || b.subfolder != page.subfolder
+            })
+        })
+        .map(|page| page.page_id.clone())
+        .collect();
+
+    Ok(SequenceResult {
+        chapters: moved.chapters,
+        pages,
+        affected_page_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SequenceNaming;
+
+    fn page(id: &str) -> SequencePage {
+        SequencePage { id: id.to_string(), page_type: "file".to_string() }
+    }
+
+    fn chapter(id: &str, page_ids: &[&str]) -> SequenceChapter {
+        SequenceChapter {
+            id: id.to_string(),
+            name: format!("name-{}", id),
+            pages: page_ids.iter().map(|p| page(p)).collect(),
+        }
+    }
+
+    fn input(chapters: Vec<SequenceChapter>) -> SequenceInput {
+        SequenceInput {
+            chapters,
+            naming: SequenceNaming::default(),
+            binding: "right".to_string(),
+            folio_start: 1,
+        }
+    }
+
+    #[test]
+    fn unified_numbering_runs_across_chapters() {
+        let pages = compute_sequence(&input(vec![chapter("a", &["p1", "p2"]), chapter("b", &["p3"])])).unwrap();
+        let names: Vec<_> = pages.iter().map(|p| p.output_name.clone().unwrap()).collect();
+        assert_eq!(names, ["0001", "0002", "0003"]);
+        assert_eq!(pages[2].folio, 3);
+        assert_eq!(pages[2].chapter_index, 0);
+        assert!(pages.iter().all(|p| p.subfolder.is_none()));
+    }
+
+    #[test]
+    fn per_chapter_numbering_restarts_and_uses_subfolders() {
+        let mut input = input(vec![chapter("a", &["p1", "p2"]), chapter("b", &["p3"])]);
+        input.naming.mode = "perChapter".to_string();
+        let pages = compute_sequence(&input).unwrap();
+        assert_eq!(pages[2].output_name.as_deref(), Some("0001"));
+        assert_eq!(pages[2].subfolder.as_deref(), Some("name-b"));
+    }
+
+    #[test]
+    fn sides_follow_binding() {
+        let mut right = input(vec![chapter("a", &["p1", "p2"])]);
+        let pages = compute_sequence(&right).unwrap();
+        assert_eq!((pages[0].side.as_str(), pages[1].side.as_str()), ("left", "right"));
+
+        right.binding = "left".to_string();
+        let pages = compute_sequence(&right).unwrap();
+        assert_eq!((pages[0].side.as_str(), pages[1].side.as_str()), ("right", "left"));
+    }
+
+    #[test]
+    fn digits_are_clamped() {
+        let mut input = input(vec![chapter("a", &["p1"])]);
+        input.naming.unified.digits = 0;
+        assert_eq!(compute_sequence(&input).unwrap()[0].output_name.as_deref(), Some("1"));
+        input.naming.unified.digits = 1000;
+        assert_eq!(compute_sequence(&input).unwrap()[0].output_name.as_deref(), Some("0000000001"));
+    }
+
+    #[test]
+    fn folio_overflow_is_an_error() {
+        let mut input = input(vec![chapter("a", &["p1", "p2"])]);
+        input.folio_start = u32::MAX;
+        assert!(compute_sequence(&input).is_err());
+    }
+
+    #[test]
+    fn moving_pages_reports_affected_pages() {
+        let input = input(vec![chapter("a", &["p1", "p2", "p3"])]);
+        let result = apply_sequence_move(
+            &input,
+            &SequenceMove::Pages {
+                page_ids: vec!["p3".to_string()],
+                target_chapter_id: "a".to_string(),
+                target_index: 0,
+            },
+        )
+        .unwrap();
+        let order: Vec<_> = result.chapters[0].pages.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(order, ["p3", "p1", "p2"]);
+        assert_eq!(result.affected_page_ids.len(), 3);
+    }
+
+    #[test]
+    fn moving_unknown_page_fails() {
+        let input = input(vec![chapter("a", &["p1"])]);
+        let movement = SequenceMove::Pages {
+            page_ids: vec!["missing".to_string()],
+            target_chapter_id: "a".to_string(),
+            target_index: 0,
+        };
+        assert!(apply_sequence_move(&input, &movement).is_err());
+    }
+}
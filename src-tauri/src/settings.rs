@@ -0,0 +1,30 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::app_mode::is_safe_mode;
+use crate::file_utils::get_config_path;
+use crate::types::AppSettings;
+
+fn settings_path() -> Result<PathBuf, String> {
+    Ok(get_config_path()?.join("settings.json"))
+}
+
+// 設定を読み込み（未保存・破損時・セーフモードでは既定値）
+pub fn load_settings() -> AppSettings {
+    if is_safe_mode() {
+        return AppSettings::default();
+    }
+    settings_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// 設定を保存
+pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
+    let config_path = get_config_path()?;
+    fs::create_dir_all(&config_path).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+
+    let json = serde_json::to_string_pretty(settings).map_err(|e| format!("JSONシリアライズエラー: {}", e))?;
+    fs::write(settings_path()?, json).map_err(|e| format!("ファイル書き込みエラー: {}", e))
+}
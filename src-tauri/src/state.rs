@@ -1,7 +1,155 @@
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
 use crate::cache::ThumbnailMemoryCache;
+use crate::constants::MEMORY_CACHE_MAX_SIZE;
+use crate::autosave::Autosave;
+use crate::inbox::InboxWatch;
+use crate::watcher::FolderWatch;
+use crate::types::{ExportRequest, TiffStallAction};
 
-// アプリケーション状態（メモリキャッシュを保持）
+// プロジェクト横のサムネイルキャッシュ
+#[derive(Clone)]
+pub struct ProjectCache {
+    /// プロジェクトファイルのあるフォルダ（キャッシュキーの基準）
+    pub project_dir: PathBuf,
+    pub cache_dir: PathBuf,
+}
+
+// 実行中の処理を中断するためのトークン（複製したものは同じ状態を共有）
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// ウィンドウごとの状態（プロジェクトごとに分離）
+pub struct WindowState {
+    pub memory_cache: ThumbnailMemoryCache,
+    /// ウィンドウで開くプロジェクト（新規ウィンドウの起動時に読み込む）
+    pub project_path: Option<String>,
+    /// 前回のエクスポート設定（クイックエクスポートで再実行）
+    pub last_export: Option<ExportRequest>,
+    /// プロジェクト横のキャッシュを使う場合の保存先
+    pub project_cache: Option<ProjectCache>,
+    /// 受け取りフォルダの監視（ウィンドウを閉じると停止）
+    pub inbox: Option<InboxWatch>,
+    /// 素材フォルダの変更監視（フォルダパスごと、ウィンドウを閉じると停止）
+    pub folder_watches: HashMap<String, FolderWatch>,
+    /// 実行中のサムネイル生成のトークン（フォルダ切り替え時に中断）
+    pub thumbnail_token: CancelToken,
+    /// 停止中のTIFF変換への指示（変換ループが受け取ると消える）
+    pub tiff_stall_action: Option<TiffStallAction>,
+    /// プロジェクトの自動保存（ウィンドウを閉じると停止）
+    pub autosave: Option<Autosave>,
+}
+
+impl WindowState {
+    pub fn new(project_path: Option<String>) -> Self {
+        Self {
+            memory_cache: ThumbnailMemoryCache::new(MEMORY_CACHE_MAX_SIZE),
+            project_path,
+            last_export: None,
+            project_cache: None,
+            inbox: None,
+            folder_watches: HashMap::new(),
+            thumbnail_token: CancelToken::default(),
+            tiff_stall_action: None,
+            autosave: None,
+        }
+    }
+}
+
+// アプリケーション状態（ウィンドウラベルごとの状態を保持）
 pub struct AppState {
-    pub memory_cache: Mutex<ThumbnailMemoryCache>,
+    pub windows: Mutex<HashMap<String, WindowState>>,
+    /// 最後にフォーカスされたウィンドウ
+    pub active_window: Mutex<Option<String>>,
+    /// 操作者名（共有端末の監査ログ用、起動中のみ保持）
+    operator: Mutex<Option<String>>,
+    next_window_id: AtomicU32,
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        Self {
+            windows: Mutex::new(HashMap::new()),
+            active_window: Mutex::new(None),
+            operator: Mutex::new(None),
+            next_window_id: AtomicU32::new(1),
+        }
+    }
+
+    // 新規プロジェクトウィンドウのラベルを払い出す
+    pub fn next_window_label(&self) -> String {
+        format!("project-{}", self.next_window_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    // ウィンドウの状態を操作（未登録なら作成）
+    pub fn with_window<T>(&self, label: &str, f: impl FnOnce(&mut WindowState) -> T) -> T {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        f(windows.entry(label.to_string()).or_insert_with(|| WindowState::new(None)))
+    }
+
+    // ウィンドウで実行中のサムネイル生成を中断し、以降の生成用に新しいトークンを用意
+    pub fn cancel_thumbnails(&self, label: &str) {
+        self.with_window(label, |w| {
+            w.thumbnail_token.cancel();
+            w.thumbnail_token = CancelToken::default();
+        });
+    }
+
+    // 全ウィンドウのメモリキャッシュを破棄
+    pub fn clear_memory_caches(&self) {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        for window in windows.values_mut() {
+            window.memory_cache.clear();
+        }
+    }
+
+    // 各ウィンドウで開いているプロジェクトのキャッシュフォルダ（重複は除く）
+    pub fn project_cache_dirs(&self) -> Vec<PathBuf> {
+        let windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        for cache in windows.values().filter_map(|w| w.project_cache.as_ref()) {
+            if !dirs.contains(&cache.cache_dir) {
+                dirs.push(cache.cache_dir.clone());
+            }
+        }
+        dirs
+    }
+
+    pub fn set_active_window(&self, label: &str) {
+        *self.active_window.lock().unwrap_or_else(|e| e.into_inner()) = Some(label.to_string());
+    }
+
+    pub fn active_window(&self) -> Option<String> {
+        self.active_window.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn set_operator(&self, name: Option<String>) {
+        *self.operator.lock().unwrap_or_else(|e| e.into_inner()) = name;
+    }
+
+    pub fn operator(&self) -> Option<String> {
+        self.operator.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    // 閉じたウィンドウの状態を破棄
+    pub fn remove_window(&self, label: &str) {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        windows.remove(label);
+        let mut active = self.active_window.lock().unwrap_or_else(|e| e.into_inner());
+        if active.as_deref() == Some(label) {
+            *active = None;
+        }
+    }
 }
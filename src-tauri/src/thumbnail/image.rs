@@ -1,10 +1,19 @@
 use std::path::Path;
-use crate::image_utils::create_thumbnail;
+use crate::constants::THUMBNAIL_SIZE;
+use image::metadata::Orientation;
+use crate::image_utils::{create_thumbnail, open_image_budgeted, read_orientation, ImageOperation};
+use crate::pdf::{render_pdf_page, PDF_RENDER_DPI};
 
 // 一般画像ファイルからサムネイルを生成
 pub fn generate_image_thumbnail(path: &Path) -> Result<Vec<u8>, String> {
-    let img = image::open(path)
-        .map_err(|e| format!("画像読み込みエラー: {}", e))?;
+    let (img, _permit) = open_image_budgeted(path, ImageOperation::Thumbnail)?;
 
-    create_thumbnail(img)
+    create_thumbnail(img, read_orientation(path))
+}
+
+// PDFの指定ページ（0始まり）からサムネイルを生成（原寸ではなくサムネイルの大きさで直接レンダリング）
+pub fn generate_pdf_thumbnail(path: &Path, page: usize) -> Result<Vec<u8>, String> {
+    let img = render_pdf_page(path, page, PDF_RENDER_DPI, Some(THUMBNAIL_SIZE * 2), None)?;
+
+    create_thumbnail(img, Orientation::NoTransforms)
 }
@@ -1,19 +1,27 @@
 mod image;
 mod psd;
 
-pub use self::image::generate_image_thumbnail;
+pub use self::image::{generate_image_thumbnail, generate_pdf_thumbnail};
 pub use self::psd::generate_psd_thumbnail;
 
 use std::fs;
 use std::path::Path;
-use serde::Serialize;
-use tauri::State;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, State};
+use crate::audit::record_audit;
 use crate::cache::ThumbnailCache;
+use crate::color::color_management_enabled;
 use crate::state::AppState;
 use crate::constants::THUMBNAIL_SIZE;
+use crate::file_utils::{cloud_placeholder_reason, path_modified_millis};
+use crate::settings::load_settings;
+use crate::types::ThumbnailLetterbox;
+use crate::usage::record_thumbnail;
 
 /// サムネイル生成結果
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct ThumbnailResult {
     /// キャッシュキー（MD5ハッシュ）
     pub cache_key: String,
@@ -23,61 +31,328 @@ pub struct ThumbnailResult {
     pub status: String,
 }
 
+/// 一括生成の対象ファイル
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailRequest {
+    pub file_path: String,
+    /// 更新日時（未指定ならファイルから取得）
+    pub modified_time: Option<u64>,
+    /// PDFのページ（0始まり、未指定なら1ページ目）
+    #[serde(default)]
+    pub pdf_page: Option<usize>,
+}
+
+/// 一括生成の個別結果
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailBatchItem {
+    pub file_path: String,
+    pub result: Option<ThumbnailResult>,
+    pub error: Option<String>,
+}
+
+/// 一括生成の進捗（"thumbnail-batch-progress" イベント、1ファイルごと）
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailBatchProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub item: ThumbnailBatchItem,
+}
+
+// 中断時のエラーメッセージ
+const THUMBNAIL_CANCELLED: &str = "サムネイル生成はキャンセルされました";
+
+// キャッシュキーを生成
+// key_base 指定時はそこからの相対パスを使う（NASのマウント先がマシンごとに違っても共有できるように）
+fn thumbnail_cache_key(file_path: &str, modified_time: u64, key_base: Option<&Path>, pdf_page: usize) -> String {
+    let relative = key_base
+        .and_then(|base| Path::new(file_path).strip_prefix(base).ok())
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"));
+    let key_path = relative.as_deref().unwrap_or(file_path);
+    // "exif": EXIFの向きを反映したサムネイル（反映前のキャッシュを使わないよう区別）
+    // 色変換の有無でも見た目が変わるため、設定ごとにキャッシュを分ける
+    let color = if color_management_enabled() { "icc" } else { "raw" };
+    let mut input = format!("{}:{}:{}:png:exif:{}", key_path, modified_time, THUMBNAIL_SIZE, color);
+    // PDFの2ページ目以降はページごとに分ける（1ページ目は従来のキーのまま）
+    if pdf_page > 0 {
+        input.push_str(&format!(":page{}", pdf_page));
+    }
+    format!("{:x}", md5::compute(&input))
+}
+
+// ディスクキャッシュを確認し、なければサムネイルを生成（ブロッキング処理）
+pub fn ensure_thumbnail(cache_dir: &Path, file_path: &str, modified_time: u64) -> Result<ThumbnailResult, String> {
+    ensure_thumbnail_keyed(cache_dir, None, file_path, modified_time, 0)
+}
+
+// キャッシュキーの基準フォルダを指定してサムネイルを取得・生成（pdf_page はPDFのページ、他の形式では無視）
+pub fn ensure_thumbnail_keyed(
+    cache_dir: &Path,
+    key_base: Option<&Path>,
+    file_path: &str,
+    modified_time: u64,
+    pdf_page: usize,
+) -> Result<ThumbnailResult, String> {
+    let cache_key = thumbnail_cache_key(file_path, modified_time, key_base, pdf_page);
+    let path = Path::new(file_path);
+
+    if !path.exists() {
+        return Err("ファイルが存在しません".to_string());
+    }
+
+    let cached_path = cache_dir.join(format!("{}.png", cache_key));
+    let cache_path_str = cached_path.to_string_lossy().to_string();
+
+    // ディスクキャッシュチェック
+    if cached_path.exists() {
+        record_thumbnail(true, 0);
+        return Ok(ThumbnailResult {
+            cache_key,
+            cache_path: cache_path_str,
+            status: "cached".to_string(),
+        });
+    }
+
+    // クラウド上のみのファイルはダウンロードを誘発せずエラーにする
+    if let Some(reason) = cloud_placeholder_reason(path) {
+        return Err(reason);
+    }
+
+    // サムネイル生成
+    let started = std::time::Instant::now();
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let thumbnail_data = match ext.as_str() {
+        "psd" | "psb" => generate_psd_thumbnail(path)?,
+        "tif" | "tiff" | "jpg" | "jpeg" | "png" | "jxl" | "webp" => generate_image_thumbnail(path)?,
+        "pdf" => generate_pdf_thumbnail(path, pdf_page)?,
+        _ => return Err(format!("サポートされていないファイル形式: {}", ext)),
+    };
+
+    // ディスクキャッシュに保存（共有キャッシュで書き込み途中を読まれないよう一時ファイル経由）
+    let temp_path = cache_dir.join(format!("{}.{}.tmp", cache_key, std::process::id()));
+    fs::write(&temp_path, &thumbnail_data).map_err(|e| e.to_string())?;
+    if let Err(e) = fs::rename(&temp_path, &cached_path) {
+        let _ = fs::remove_file(&temp_path);
+        // 他のマシンが先に書き込んだ場合はそれを使う
+        if !cached_path.exists() {
+            return Err(e.to_string());
+        }
+    }
+    record_thumbnail(false, started.elapsed().as_millis() as u64);
+
+    Ok(ThumbnailResult {
+        cache_key,
+        cache_path: cache_path_str,
+        status: "generated".to_string(),
+    })
+}
+
+// 生成済みのサムネイルを一定の縦横比のキャンバスに中央配置した版を取得・生成
+// 元のサムネイルのキャッシュはそのまま残し、余白付きの版は設定ごとに別キーで保存する
+fn letterbox_thumbnail(
+    cache_dir: &Path,
+    base: ThumbnailResult,
+    letterbox: &ThumbnailLetterbox,
+) -> Result<ThumbnailResult, String> {
+    let aspect = letterbox.aspect_ratio.clamp(0.2, 5.0);
+    let [r, g, b] = letterbox.background;
+    let cache_key = format!("{}-lb{:x}", base.cache_key, md5::compute(format!("{:.4}:{}:{}:{}", aspect, r, g, b)));
+    let cached_path = cache_dir.join(format!("{}.png", cache_key));
+    let cache_path_str = cached_path.to_string_lossy().to_string();
+    if cached_path.exists() {
+        return Ok(ThumbnailResult {
+            cache_key,
+            cache_path: cache_path_str,
+            status: base.status,
+        });
+    }
+
+    let thumbnail = image::open(&base.cache_path).map_err(|e| format!("サムネイル読み込みエラー: {}", e))?;
+    let canvas_width = THUMBNAIL_SIZE;
+    let canvas_height = ((THUMBNAIL_SIZE as f64 / aspect).round() as u32).max(1);
+    let fitted = thumbnail.resize(canvas_width, canvas_height, image::imageops::FilterType::Triangle);
+
+    let mut canvas = image::RgbaImage::from_pixel(canvas_width, canvas_height, image::Rgba([r, g, b, 255]));
+    let x = (canvas_width - fitted.width()) / 2;
+    let y = (canvas_height - fitted.height()) / 2;
+    image::imageops::overlay(&mut canvas, &fitted.to_rgba8(), x as i64, y as i64);
+
+    let temp_path = cache_dir.join(format!("{}.{}.tmp", cache_key, std::process::id()));
+    canvas
+        .save_with_format(&temp_path, image::ImageFormat::Png)
+        .map_err(|e| format!("サムネイル書き出しエラー: {}", e))?;
+    if let Err(e) = fs::rename(&temp_path, &cached_path) {
+        let _ = fs::remove_file(&temp_path);
+        if !cached_path.exists() {
+            return Err(e.to_string());
+        }
+    }
+
+    Ok(ThumbnailResult {
+        cache_key,
+        cache_path: cache_path_str,
+        status: base.status,
+    })
+}
+
+// グリッド表示用のサムネイル（設定に応じて余白付きの版を返す）
+fn ensure_grid_thumbnail(
+    cache_dir: &Path,
+    key_base: Option<&Path>,
+    file_path: &str,
+    modified_time: u64,
+    pdf_page: usize,
+    letterbox: Option<&ThumbnailLetterbox>,
+) -> Result<ThumbnailResult, String> {
+    let base = ensure_thumbnail_keyed(cache_dir, key_base, file_path, modified_time, pdf_page)?;
+    match letterbox {
+        Some(letterbox) => letterbox_thumbnail(cache_dir, base, letterbox),
+        None => Ok(base),
+    }
+}
+
 #[tauri::command]
 pub async fn generate_thumbnail(
     file_path: String,
     modified_time: u64,
+    pdf_page: Option<usize>,
+    window: tauri::WebviewWindow,
     cache: State<'_, ThumbnailCache>,
-    _app_state: State<'_, AppState>,
+    app_state: State<'_, AppState>,
 ) -> Result<ThumbnailResult, String> {
-    let cache_dir = cache.cache_dir.clone();
-
-    // キャッシュキーを生成
-    let input = format!("{}:{}:{}:png", file_path, modified_time, THUMBNAIL_SIZE);
-    let cache_key = format!("{:x}", md5::compute(&input));
+    // プロジェクト横のキャッシュが有効ならそちらを使用
+    let (project_cache, token) =
+        app_state.with_window(window.label(), |w| (w.project_cache.clone(), w.thumbnail_token.clone()));
+    let (cache_dir, key_base) = match project_cache {
+        Some(pc) => (pc.cache_dir, Some(pc.project_dir)),
+        None => (cache.cache_dir.clone(), None),
+    };
 
     // ディスクキャッシュをチェック & サムネイル生成
+    // 実行待ちの間にフォルダが切り替わっていたら生成しない
     tokio::task::spawn_blocking(move || {
-        let path = Path::new(&file_path);
-
-        if !path.exists() {
-            return Err("ファイルが存在しません".to_string());
+        if token.is_cancelled() {
+            return Err(THUMBNAIL_CANCELLED.to_string());
         }
+        let letterbox = load_settings().thumbnail_letterbox;
+        ensure_grid_thumbnail(
+            &cache_dir,
+            key_base.as_deref(),
+            &file_path,
+            modified_time,
+            pdf_page.unwrap_or(0),
+            letterbox.as_ref(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
 
-        let cached_path = cache_dir.join(format!("{}.png", cache_key));
-        let cache_path_str = cached_path.to_string_lossy().to_string();
+/// 複数ファイルのサムネイルを並列に生成し、1件ごとに呼び出し元ウィンドウへ進捗を通知
+/// 結果は files と同じ順序で返す
+#[tauri::command]
+pub async fn generate_thumbnails_batch(
+    files: Vec<ThumbnailRequest>,
+    window: tauri::WebviewWindow,
+    cache: State<'_, ThumbnailCache>,
+    app_state: State<'_, AppState>,
+) -> Result<Vec<ThumbnailBatchItem>, String> {
+    let (project_cache, token) =
+        app_state.with_window(window.label(), |w| (w.project_cache.clone(), w.thumbnail_token.clone()));
+    let (cache_dir, key_base) = match project_cache {
+        Some(pc) => (pc.cache_dir, Some(pc.project_dir)),
+        None => (cache.cache_dir.clone(), None),
+    };
 
-        // ディスクキャッシュチェック
-        if cached_path.exists() {
-            return Ok(ThumbnailResult {
-                cache_key,
-                cache_path: cache_path_str,
-                status: "cached".to_string(),
-            });
-        }
+    tokio::task::spawn_blocking(move || {
+        let total = files.len();
+        let completed = AtomicUsize::new(0);
+        let letterbox = load_settings().thumbnail_letterbox;
 
-        // サムネイル生成
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_lowercase();
+        files
+            .par_iter()
+            .map(|file| {
+                let modified_time = file
+                    .modified_time
+                    .unwrap_or_else(|| path_modified_millis(Path::new(&file.file_path)));
+                let generated = if token.is_cancelled() {
+                    Err(THUMBNAIL_CANCELLED.to_string())
+                } else {
+                    ensure_grid_thumbnail(
+                        &cache_dir,
+                        key_base.as_deref(),
+                        &file.file_path,
+                        modified_time,
+                        file.pdf_page.unwrap_or(0),
+                        letterbox.as_ref(),
+                    )
+                };
+                let item = match generated {
+                    Ok(result) => ThumbnailBatchItem {
+                        file_path: file.file_path.clone(),
+                        result: Some(result),
+                        error: None,
+                    },
+                    Err(e) => ThumbnailBatchItem {
+                        file_path: file.file_path.clone(),
+                        result: None,
+                        error: Some(e),
+                    },
+                };
 
-        let thumbnail_data = match ext.as_str() {
-            "psd" => generate_psd_thumbnail(path)?,
-            "tif" | "tiff" | "jpg" | "jpeg" | "png" => generate_image_thumbnail(path)?,
-            _ => return Err(format!("サポートされていないファイル形式: {}", ext)),
-        };
+                let progress = ThumbnailBatchProgress {
+                    completed: completed.fetch_add(1, Ordering::Relaxed) + 1,
+                    total,
+                    item: item.clone(),
+                };
+                let _ = window.emit_to(window.label(), "thumbnail-batch-progress", progress);
+                item
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
 
-        // ディスクキャッシュに保存
-        fs::write(&cached_path, &thumbnail_data).map_err(|e| e.to_string())?;
+/// 呼び出し元ウィンドウで実行中・実行待ちのサムネイル生成を中断（フォルダ・プロジェクト切り替え時）
+/// 生成中のファイルは完了まで続くが、未着手のファイルは生成しない
+#[tauri::command]
+pub fn cancel_thumbnail_generation(window: tauri::WebviewWindow, app_state: State<'_, AppState>) {
+    app_state.cancel_thumbnails(window.label());
+}
 
-        Ok(ThumbnailResult {
-            cache_key,
-            cache_path: cache_path_str,
-            status: "generated".to_string(),
-        })
+/// サムネイルのディスクキャッシュとメモリキャッシュを削除し、解放したバイト数を返す
+/// 開いているプロジェクトの横のキャッシュ（.cache）も対象にする
+#[tauri::command]
+pub async fn clear_thumbnail_cache(
+    cache: State<'_, ThumbnailCache>,
+    app_state: State<'_, AppState>,
+) -> Result<u64, String> {
+    app_state.clear_memory_caches();
+    let mut cache_dirs = vec![cache.cache_dir.clone()];
+    cache_dirs.extend(app_state.project_cache_dirs());
+    let target = cache_dirs.iter().map(|d| d.to_string_lossy()).collect::<Vec<_>>().join(", ");
+    let result = tokio::task::spawn_blocking(move || {
+        cache_dirs
+            .into_iter()
+            .map(|cache_dir| ThumbnailCache { cache_dir }.clear())
+            .sum::<Result<u64, String>>()
     })
     .await
-    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+    record_audit(
+        app_state.operator(),
+        "clear-thumbnail-cache",
+        &target,
+        format!("{} bytes を解放", result.as_ref().map_or(0, |freed| *freed)),
+        result.as_ref().map(|_| ()).map_err(|e| e.as_str()),
+    );
+    result
 }
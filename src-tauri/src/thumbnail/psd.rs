@@ -1,14 +1,15 @@
 use std::fs;
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
-use image::DynamicImage;
-use crate::image_utils::{create_thumbnail, validate_dimensions};
+use image::metadata::Orientation;
+use crate::image_utils::{create_thumbnail, decode_psd, ImageOperation};
 use crate::constants::THUMBNAIL_SIZE;
+use crate::memory_budget::reserve_decode_memory;
 
-// PSDファイルから埋め込みサムネイルを高速抽出
-fn extract_psd_embedded_thumbnail(data: &[u8]) -> Option<Vec<u8>> {
-    let mut cursor = Cursor::new(data);
-
+// PSD/PSBファイルから埋め込みサムネイルを高速抽出
+// PSB（バージョン2）で長さが8バイトになるのはレイヤー以降のセクションのみで、
+// イメージリソースまでの配置はPSDと同じ
+fn extract_psd_embedded_thumbnail<R: Read + Seek>(cursor: &mut R) -> Option<Vec<u8>> {
     // PSDシグネチャ確認 "8BPS"
     let mut sig = [0u8; 4];
     cursor.read_exact(&mut sig).ok()?;
@@ -16,8 +17,15 @@ fn extract_psd_embedded_thumbnail(data: &[u8]) -> Option<Vec<u8>> {
         return None;
     }
 
-    // バージョン (2bytes) + 予約 (6bytes) + チャンネル数 (2bytes) + 高さ (4bytes) + 幅 (4bytes) + 深度 (2bytes) + カラーモード (2bytes)
-    cursor.seek(SeekFrom::Current(22)).ok()?;
+    // バージョン (2bytes): 1 = PSD, 2 = PSB
+    let mut version = [0u8; 2];
+    cursor.read_exact(&mut version).ok()?;
+    if !matches!(u16::from_be_bytes(version), 1 | 2) {
+        return None;
+    }
+
+    // 予約 (6bytes) + チャンネル数 (2bytes) + 高さ (4bytes) + 幅 (4bytes) + 深度 (2bytes) + カラーモード (2bytes)
+    cursor.seek(SeekFrom::Current(20)).ok()?;
 
     // カラーモードデータセクションをスキップ
     let mut len_buf = [0u8; 4];
@@ -28,12 +36,12 @@ fn extract_psd_embedded_thumbnail(data: &[u8]) -> Option<Vec<u8>> {
     // イメージリソースセクション
     cursor.read_exact(&mut len_buf).ok()?;
     let resources_len = u32::from_be_bytes(len_buf);
-    let resources_end = cursor.position() + resources_len as u64;
+    let resources_end = cursor.stream_position().ok()? + resources_len as u64;
 
     // リソースを検索
-    while cursor.position() < resources_end {
+    while cursor.stream_position().ok()? < resources_end {
         // 無限ループ防止: ループ開始位置を記録
-        let loop_start_pos = cursor.position();
+        let loop_start_pos = cursor.stream_position().ok()?;
 
         // リソースシグネチャ "8BIM"
         let mut resource_sig = [0u8; 4];
@@ -93,7 +101,7 @@ fn extract_psd_embedded_thumbnail(data: &[u8]) -> Option<Vec<u8>> {
         }
 
         // 無限ループ防止: カーソルが進んでいることを確認
-        if cursor.position() <= loop_start_pos {
+        if cursor.stream_position().ok()? <= loop_start_pos {
             break;
         }
     }
@@ -104,37 +112,47 @@ fn extract_psd_embedded_thumbnail(data: &[u8]) -> Option<Vec<u8>> {
 // PSDファイルからサムネイルを生成
 // 埋め込みサムネイルがTHUMBNAIL_SIZE以上の場合のみ使用、それ以外はフルコンポジット
 pub fn generate_psd_thumbnail(path: &Path) -> Result<Vec<u8>, String> {
+    let is_psb = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("psb"));
+    if is_psb {
+        return generate_psb_thumbnail(path);
+    }
+
     let data = fs::read(path).map_err(|e| e.to_string())?;
 
     // 1. 埋め込みサムネイル（JPEG）を試行
-    if let Some(jpeg_data) = extract_psd_embedded_thumbnail(&data) {
+    if let Some(jpeg_data) = extract_psd_embedded_thumbnail(&mut Cursor::new(&data)) {
         if let Ok(img) = image::load_from_memory_with_format(&jpeg_data, image::ImageFormat::Jpeg) {
             // 埋め込みサムネイルのサイズをチェック
             // THUMBNAIL_SIZE以上の場合のみ使用（低解像度だと画質が劣化するため）
             let (width, height) = (img.width(), img.height());
             if width >= THUMBNAIL_SIZE || height >= THUMBNAIL_SIZE {
-                return create_thumbnail(img);
+                return create_thumbnail(img, Orientation::NoTransforms);
             }
             // サイズが小さい場合はフルコンポジットにフォールバック
         }
     }
 
-    // 2. フルコンポジットで高品質なサムネイルを生成
-    let psd_file = psd::Psd::from_bytes(&data)
-        .map_err(|e| format!("PSD読み込みエラー: {:?}", e))?;
-
-    let width = psd_file.width();
-    let height = psd_file.height();
-
-    // 画像サイズ検証（DoS防止）
-    validate_dimensions(width, height)?;
+    // 2. フルコンポジットで高品質なサムネイルを生成（並列生成時のメモリ上限を守る）
+    let _permit = reserve_decode_memory(path);
+    let img = decode_psd(&data, ImageOperation::Thumbnail)?;
 
-    let rgba = psd_file.rgba();
-
-    let img = DynamicImage::ImageRgba8(
-        image::RgbaImage::from_raw(width, height, rgba)
-            .ok_or("画像データの変換に失敗")?
-    );
+    create_thumbnail(img, Orientation::NoTransforms)
+}
 
-    create_thumbnail(img)
+// PSBファイルからサムネイルを生成
+// 数GBになることがあり合成もできないため、先頭のリソースだけを読んで埋め込みサムネイルを使う
+// （小さくてもフルコンポジットにはフォールバックしない）
+fn generate_psb_thumbnail(path: &Path) -> Result<Vec<u8>, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let jpeg_data = extract_psd_embedded_thumbnail(&mut BufReader::new(file)).ok_or_else(|| {
+        "PSBに埋め込みサムネイルがありません（Photoshopで「プレビュー画像を保存」を有効にして保存してください）"
+            .to_string()
+    })?;
+    let img = image::load_from_memory_with_format(&jpeg_data, image::ImageFormat::Jpeg)
+        .map_err(|e| format!("PSBの埋め込みサムネイル読み込みエラー: {}", e))?;
+
+    create_thumbnail(img, Orientation::NoTransforms)
 }
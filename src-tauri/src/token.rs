@@ -0,0 +1,17 @@
+// 認証トークンの生成と照合（ローカルAPI・タブレット向けプレビューサーバーで共用）
+
+// OSの乱数源から推測されにくいトークンを生成（16進数32文字 = 128bit）
+pub fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("乱数の取得に失敗: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// 比較にかかる時間から一致した文字数を推測されないよう、長さが同じなら常に全バイトを比較
+pub fn tokens_match(given: &str, expected: &str) -> bool {
+    let (given, expected) = (given.as_bytes(), expected.as_bytes());
+    if given.len() != expected.len() {
+        return false;
+    }
+    given.iter().zip(expected).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
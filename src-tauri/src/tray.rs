@@ -0,0 +1,106 @@
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Wry};
+use crate::commands::window::create_main_window;
+use crate::jobs::JobRegistry;
+
+const TRAY_ID: &str = "main";
+
+// 状態表示のメニュー項目（ジョブ変更時に文言を更新）
+pub struct TrayStatus {
+    status_item: MenuItem<Wry>,
+}
+
+fn status_text(running: usize, paused: usize) -> String {
+    match (running, paused) {
+        (0, 0) => "実行中のジョブはありません".to_string(),
+        (r, 0) => format!("実行中のジョブ: {}件", r),
+        (r, p) => format!("実行中のジョブ: {}件（一時停止 {}件）", r, p),
+    }
+}
+
+// いずれかのウィンドウを表示して前面に（トレイ常駐中で全て閉じていれば作り直す）
+pub fn show_any_window(app: &AppHandle) {
+    let window = app
+        .get_webview_window("main")
+        .or_else(|| app.webview_windows().into_values().next());
+    match window {
+        Some(window) => {
+            let _ = window.show();
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+        }
+        None => {
+            if let Err(e) = create_main_window(app) {
+                eprintln!("{}", e);
+            }
+        }
+    }
+}
+
+// トレイアイコンを作成できたか（作成できなければ常駐するとウィンドウを開く手段がなくなる）
+pub fn has_tray(app: &AppHandle) -> bool {
+    app.try_state::<TrayStatus>().is_some()
+}
+
+// トレイアイコンとメニューを作成
+pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
+    let status_item = MenuItem::with_id(app, "status", status_text(0, 0), false, None::<&str>)?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &status_item,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "pause_jobs", "ジョブを一時停止", true, None::<&str>)?,
+            &MenuItem::with_id(app, "resume_jobs", "ジョブを再開", true, None::<&str>)?,
+            &MenuItem::with_id(app, "cancel_jobs", "ジョブをキャンセル", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "show", "ウィンドウを表示", true, None::<&str>)?,
+            &MenuItem::with_id(app, "quit", "終了", true, None::<&str>)?,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("台割マネージャー")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| {
+            let registry = app.state::<JobRegistry>();
+            match event.id.as_ref() {
+                "pause_jobs" => registry.set_paused(app, None, true),
+                "resume_jobs" => registry.set_paused(app, None, false),
+                "cancel_jobs" => registry.cancel(app, None),
+                "show" => show_any_window(app),
+                "quit" => app.exit(0),
+                _ => {}
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_any_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    app.manage(TrayStatus { status_item });
+    Ok(())
+}
+
+// ジョブ状況をトレイのツールチップとメニューに反映
+pub fn refresh_tray(app: &AppHandle, (running, paused): (usize, usize)) {
+    let text = status_text(running, paused);
+    if let Some(status) = app.try_state::<TrayStatus>() {
+        let _ = status.status_item.set_text(&text);
+    }
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(format!("台割マネージャー - {}", text)));
+    }
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use super::PageAnnotation;
+
+/// 校正指示入りの確認用画像の書き出し設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationRenderOptions {
+    /// 対応済みの指示も書き込む
+    #[serde(default)]
+    pub include_resolved: bool,
+    /// 指示のないページも書き出す
+    #[serde(default)]
+    pub include_unannotated: bool,
+    /// 画像の長辺 (px)
+    #[serde(default = "default_max_size")]
+    pub max_size: u32,
+    /// JPG出力時の品質
+    #[serde(default = "default_quality")]
+    pub jpg_quality: u8,
+}
+
+fn default_max_size() -> u32 {
+    2000
+}
+
+fn default_quality() -> u8 {
+    85
+}
+
+impl Default for AnnotationRenderOptions {
+    fn default() -> Self {
+        Self {
+            include_resolved: false,
+            include_unannotated: false,
+            max_size: default_max_size(),
+            jpg_quality: default_quality(),
+        }
+    }
+}
+
+/// 校正指示入り画像の書き出し結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationRenderResult {
+    pub output_dir: String,
+    /// 書き出した画像（読み順）
+    pub files: Vec<String>,
+    /// 指示番号とコメントの一覧（comments.txt）
+    pub comments_path: String,
+    /// 元ファイルが見つからず書き出せなかったページID
+    pub missing_page_ids: Vec<String>,
+}
+
+/// 未対応の校正指示の一覧の1件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationSummary {
+    pub annotation_id: String,
+    pub page_id: String,
+    /// ページの表示名（ラベル、なければチャプター名と通し番号）
+    pub page_label: String,
+    pub chapter_name: String,
+    /// 書き出した画像上の指示番号（ページ内の順、1始まり）
+    pub number: usize,
+    pub comment: String,
+    pub author: Option<String>,
+    pub created_at: String,
+    pub resolved: bool,
+}
+
+/// 校正指示の交換用の1件（他のツールとやり取りするJSON/CSVの行）
+/// 取り込み時はページID → ファイル名 → 通しページ番号の順にページを照合する
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationRecord {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub page_id: String,
+    /// 通しページ番号（1始まり）
+    #[serde(default)]
+    pub page_number: Option<usize>,
+    #[serde(default)]
+    pub file_name: String,
+    #[serde(default)]
+    pub page_label: String,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub comment: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub resolved: bool,
+}
+
+/// 校正指示の交換用ファイル（JSON）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationExchange {
+    /// 形式の識別子（"daidori-annotations"）
+    pub format: String,
+    pub version: u32,
+    #[serde(default)]
+    pub project_name: Option<String>,
+    #[serde(default)]
+    pub exported_at: String,
+    pub annotations: Vec<AnnotationRecord>,
+}
+
+/// 校正指示の取り込み結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationImportResult {
+    /// 取り込み後のプロジェクトの校正指示（既存 + 取り込み分）
+    pub annotations: Vec<PageAnnotation>,
+    pub added: usize,
+    /// 同じIDの指示を更新した数
+    pub updated: usize,
+    /// 対応するページが見つからなかった指示（コメント）
+    pub unmatched: Vec<String>,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// 起動モード
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppModeInfo {
+    /// セーフモードで起動しているか
+    pub safe_mode: bool,
+    /// ポータブルモードのデータフォルダ（通常モードなら None）
+    pub portable_data_dir: Option<String>,
+}
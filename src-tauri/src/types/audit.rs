@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// 監査ログの1件（破壊的な操作ごとに記録）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub timestamp: String,
+    /// 操作者名（未入力なら None）
+    pub operator: Option<String>,
+    /// 端末名
+    pub machine: Option<String>,
+    /// 操作 ("export-move" など)
+    pub action: String,
+    /// 対象（出力先など）
+    pub target: String,
+    /// 補足（ページ数など）
+    pub details: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use super::ProjectFile;
+
+/// 自動保存されたプロジェクトのスナップショット（復旧用フォルダに保存）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverySnapshot {
+    /// 元のプロジェクトファイル（未保存のプロジェクトは None）
+    pub project_path: Option<String>,
+    pub saved_at: String,
+    pub project: ProjectFile,
+}
+
+/// 起動時に提示する復旧候補
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCandidate {
+    /// load_recovery / discard_recovery に渡すパス
+    pub recovery_path: String,
+    pub project_path: Option<String>,
+    pub project_name: String,
+    /// 自動保存した日時
+    pub saved_at: String,
+    /// 元のプロジェクトファイルが残っているか
+    pub project_exists: bool,
+}
@@ -0,0 +1,55 @@
+use serde::Serialize;
+
+/// 入力形式ごとの処理時間（1ファイルあたりの平均）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkFormatResult {
+    /// 拡張子（"psd", "jpg" など）
+    pub format: String,
+    /// 計測したファイル数（読み込めなかったものを除く）
+    pub files: usize,
+    /// 読み込めなかったファイル数
+    pub failed: usize,
+    /// 平均画素数（メガピクセル）
+    pub megapixels: f64,
+    pub decode_ms: f64,
+    /// サムネイルサイズへの縮小
+    pub resize_ms: f64,
+    /// 出力形式（JPG品質90）へのエンコード
+    pub encode_ms: f64,
+}
+
+/// サムネイルの保存形式ごとのエンコード時間とサイズ
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkThumbnailResult {
+    /// "png" | "jpg"
+    pub format: String,
+    pub encode_ms: f64,
+    pub bytes: u64,
+}
+
+/// 並列数ごとのサムネイル生成速度
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkParallelResult {
+    pub workers: usize,
+    pub files_per_sec: f64,
+}
+
+/// ベンチマーク結果と推奨設定
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    /// 論理CPU数
+    pub cpu_threads: usize,
+    pub formats: Vec<BenchmarkFormatResult>,
+    pub thumbnails: Vec<BenchmarkThumbnailResult>,
+    pub parallel: Vec<BenchmarkParallelResult>,
+    /// 推奨する並列数（最速の9割以上の速度が出る最小の並列数）
+    pub recommended_workers: usize,
+    /// 推奨するサムネイル形式（"png" | "jpg"）
+    pub recommended_thumbnail_format: String,
+    /// 推奨の理由など
+    pub notes: Vec<String>,
+}
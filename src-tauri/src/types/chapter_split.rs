@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// チャプター分割の判定設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterSplitOptions {
+    /// 番号の飛びとみなす差（これを超えたら区切り候補）
+    #[serde(default = "default_max_gap")]
+    pub max_gap: u32,
+    /// 画像サイズの変化とみなす割合
+    #[serde(default = "default_size_tolerance")]
+    pub size_tolerance: f64,
+    /// 区切りとみなすスコア（接頭辞の変化・番号のリセット = 2、番号の飛び・サイズの変化 = 1）
+    #[serde(default = "default_min_score")]
+    pub min_score: u32,
+}
+
+fn default_max_gap() -> u32 {
+    1
+}
+
+fn default_size_tolerance() -> f64 {
+    0.02
+}
+
+fn default_min_score() -> u32 {
+    2
+}
+
+impl Default for ChapterSplitOptions {
+    fn default() -> Self {
+        Self {
+            max_gap: default_max_gap(),
+            size_tolerance: default_size_tolerance(),
+            min_score: default_min_score(),
+        }
+    }
+}
+
+/// 提案するチャプター
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedChapter {
+    pub name: String,
+    pub paths: Vec<String>,
+    /// このチャプターの先頭で区切った理由（最初のチャプターは空）
+    pub reasons: Vec<String>,
+    pub score: u32,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// 残骸ファイルの掃除結果（"orphan-cleanup-finished" イベント）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    pub files_removed: usize,
+    /// 解放した容量 (bytes)
+    pub bytes_reclaimed: u64,
+    /// 削除できなかったファイル（使用中など）
+    pub errors: Vec<String>,
+}
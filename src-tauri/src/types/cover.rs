@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+/// 表紙の仕上がり仕様（くるみ表紙・カバー共通）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverSpec {
+    /// 仕上がり幅 (mm)
+    pub trim_width_mm: f64,
+    /// 仕上がり高さ (mm)
+    pub trim_height_mm: f64,
+    /// 背幅 (mm)
+    pub spine_width_mm: f64,
+    /// 塗り足し (mm)
+    #[serde(default = "default_bleed_mm")]
+    pub bleed_mm: f64,
+    /// 解像度
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+    /// 綴じ方向 ("right" = 右綴じ | "left" = 左綴じ)
+    #[serde(default = "default_binding")]
+    pub binding: String,
+}
+
+fn default_bleed_mm() -> f64 {
+    3.0
+}
+
+fn default_dpi() -> u32 {
+    350
+}
+
+fn default_binding() -> String {
+    "right".to_string()
+}
+
+/// 表紙仕様から算出したピクセル寸法
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverDimensions {
+    /// 表1・表4の仕上がり幅 (px)
+    pub trim_width: u32,
+    /// 仕上がり高さ (px)
+    pub trim_height: u32,
+    /// 背幅 (px)
+    pub spine_width: u32,
+    /// 塗り足し (px)
+    pub bleed: u32,
+    /// 展開図全体の幅（塗り足し込み）
+    pub full_width: u32,
+    /// 展開図全体の高さ（塗り足し込み）
+    pub full_height: u32,
+}
+
+/// 表紙画像のレイアウト判定結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverLayoutInfo {
+    /// 判定結果 ("single" | "wraparound" | "unknown")
+    pub layout: String,
+    /// 塗り足しを含んでいると推定されるか
+    pub includes_bleed: bool,
+    pub width: u32,
+    pub height: u32,
+    /// 仕様から算出した寸法
+    pub expected: CoverDimensions,
+}
+
+/// 展開図の分割結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverSplitResult {
+    pub front_path: String,
+    /// 背幅が0の場合は背画像を出力しない
+    pub spine_path: Option<String>,
+    pub back_path: String,
+}
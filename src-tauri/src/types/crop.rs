@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// 画像上の矩形領域 (px)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 自動トリミングの解析オプション
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoCropOptions {
+    /// 目標アスペクト比（幅/高さ）。未指定なら検出範囲のまま
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_aspect: Option<f64>,
+    /// 背景色との差がこの値を超えるピクセルを内容とみなす (0-255)
+    #[serde(default = "default_tolerance")]
+    pub tolerance: u8,
+    /// 行・列のうち内容ピクセルがこの割合以上なら内容行とみなす
+    #[serde(default = "default_min_content_ratio")]
+    pub min_content_ratio: f64,
+    /// 内容の周囲に残す余白 (px)
+    #[serde(default)]
+    pub padding: u32,
+}
+
+fn default_tolerance() -> u8 {
+    40
+}
+
+fn default_min_content_ratio() -> f64 {
+    0.01
+}
+
+/// 自動トリミングの解析結果（適用前のプレビュー用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoCropAnalysis {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    /// 検出した内容領域
+    pub content: CropRect,
+    /// アスペクト比調整後の切り抜き領域
+    pub crop: CropRect,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 切り抜き適用対象
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CropRequest {
+    pub path: String,
+    pub crop: CropRect,
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use super::{Edition, ProjectFile};
+
+/// 版の凍結結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditionFreezeResult {
+    /// 版を追加したプロジェクト（保存はフロントエンド側）
+    pub project: ProjectFile,
+    pub edition: Edition,
+    /// アーカイブに新しく保存したファイル数
+    pub stored: usize,
+    /// 以前の版と内容が同じため保存を省いたファイル数
+    pub reused: usize,
+}
+
+/// 2つの版の差分
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditionComparison {
+    pub from_edition: String,
+    pub to_edition: String,
+    /// 新しい版にだけあるファイル
+    pub added: Vec<String>,
+    /// 古い版にだけあるファイル
+    pub removed: Vec<String>,
+    /// 同じパスで内容が変わったファイル（差し替えページ）
+    pub changed: Vec<String>,
+    /// 内容が同じでパスだけ変わったファイル
+    pub renamed: Vec<EditionRename>,
+    pub unchanged: usize,
+}
+
+/// パスだけ変わったファイル
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditionRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// 重版用差し替えデータの作成結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReprintPackageResult {
+    pub output_dir: String,
+    /// 書き出した差し替え・追加ファイル
+    pub files: Vec<String>,
+    /// 変更一覧のテキスト
+    pub change_list_path: String,
+    /// ZIPにまとめた場合のパス
+    pub zip_path: Option<String>,
+    pub comparison: EditionComparison,
+}
@@ -1,4 +1,6 @@
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use super::CoverSpec;
 
 // エクスポート用ページ情報
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,4 +9,349 @@ pub struct ExportPage {
     pub output_name: String,
     pub page_type: String,  // "file", "cover", "blank", "intermission", "colophon"
     pub subfolder: Option<String>,  // チャプターごとのサブフォルダ名
+    #[serde(default)]
+    pub page_id: Option<String>,  // 台割上のページID（指摘ページのみの再出力で使用）
+    #[serde(default)]
+    pub spread: bool,  // 見開き原稿（2ページ分の幅）
+    #[serde(default)]
+    pub pdf_page: Option<usize>,  // PDF素材の使用するページ（0始まり、未指定なら1ページ目）
+}
+
+/// エクスポート要求（export_pages の引数一式。クイックエクスポートの再実行に使用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRequest {
+    pub output_path: String,
+    pub pages: Vec<ExportPage>,
+    pub move_files: Option<bool>,
+    pub convert_to_jpg: Option<bool>,
+    pub jpg_quality: Option<u8>,
+    pub bleed: Option<BleedSettings>,
+    pub safe_write: Option<bool>,
+    pub output_format: Option<String>,
+    pub strip_metadata: Option<bool>,
+    pub chroma_subsampling: Option<String>,
+    pub cover_export: Option<CoverExportSettings>,
+    /// ページごとの命名・取捨・変換を決めるスクリプト（Rhai）
+    #[serde(default)]
+    pub script: Option<String>,
+    /// チャプター（サブフォルダ）ごとに "<サブフォルダ名>.zip" を作成（Web連載の入稿用）
+    #[serde(default)]
+    pub chapter_zips: Option<bool>,
+    /// 見開き原稿を左右2ページに分割して出力する場合の設定（未指定なら1ファイルのまま）
+    #[serde(default)]
+    pub spread_split: Option<SpreadSplitSettings>,
+    /// 16bit原稿をJPG等の8bit形式に変換するときディザを掛ける（未指定なら掛ける）
+    #[serde(default)]
+    pub dither: Option<bool>,
+    /// ページ種別ごとのJPG品質（"cover" | "body" | "intermission" | "colophon" | "blank"、未指定の種別は jpgQuality）
+    #[serde(default)]
+    pub page_type_quality: Option<HashMap<String, u8>>,
+    /// 入稿サイズの幅 (px、塗り足し込み)。高さと合わせて指定すると全ページをこのサイズにリサンプリング
+    #[serde(default)]
+    pub target_width: Option<u32>,
+    /// 入稿サイズの高さ (px、塗り足し込み)
+    #[serde(default)]
+    pub target_height: Option<u32>,
+    /// 入稿解像度 (ppi)。幅・高さの指定がなければ、原稿に記録された解像度からこの解像度にリサンプリング
+    #[serde(default)]
+    pub target_dpi: Option<u32>,
+    /// 入稿サイズへの合わせ方 ("fit" | "fill" | "pad"、未指定なら "pad")
+    #[serde(default)]
+    pub resize_mode: Option<String>,
+    /// 出力ファイル（JPG・PNG・TIFF）に記録する解像度 (ppi)。未指定なら targetDpi、原稿に記録された解像度の順に使う
+    #[serde(default)]
+    pub output_dpi: Option<u32>,
+    /// チャプターのサブフォルダ名の変換 ("keep" | "normalize" = 全角を半角・空白を "_" に | "romanize" = ローマ字・英数字のみ、未指定なら "keep")
+    #[serde(default)]
+    pub subfolder_naming: Option<String>,
+}
+
+/// 見開き原稿の分割設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpreadSplitSettings {
+    /// 綴じ方向 ("right" = 右綴じ | "left" = 左綴じ、分割したページの順に使用)
+    #[serde(default = "default_binding")]
+    pub binding: String,
+    /// ノド側で左右のページに重複して含める幅 (mm)
+    #[serde(default)]
+    pub gutter_overlap_mm: f64,
+    /// 解像度（mm→px換算用）
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+    /// 出力名 ("side" = "{name}_R" "{name}_L" | "number" = 以降のページを繰り下げて連番)
+    #[serde(default = "default_spread_naming")]
+    pub naming: String,
+}
+
+fn default_binding() -> String {
+    "right".to_string()
+}
+
+fn default_spread_naming() -> String {
+    "side".to_string()
+}
+
+impl Default for SpreadSplitSettings {
+    fn default() -> Self {
+        Self {
+            binding: default_binding(),
+            gutter_overlap_mm: 0.0,
+            dpi: default_dpi(),
+            naming: default_spread_naming(),
+        }
+    }
+}
+
+/// 指摘ページのみの再エクスポート結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReexportResult {
+    /// 再出力の対象になったページ数
+    pub requested: usize,
+    pub exported: usize,
+    /// 指摘があったがエクスポート対象に見つからなかったページID
+    pub missing_page_ids: Vec<String>,
+}
+
+/// エクスポートのドライラン結果（ファイルは書き込まない）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPreview {
+    /// 出力予定のファイル（出力順）
+    pub planned: Vec<PlannedExportFile>,
+    /// 同じパスに複数のページが出力される衝突
+    pub collisions: Vec<ExportCollision>,
+    /// 既に存在し、上書きされるファイル
+    pub existing_files: Vec<String>,
+    /// 元ファイルが見つからないページ
+    pub missing_sources: Vec<SkippedExportPage>,
+    /// 出力されないページ（変換モードのPSD、スクリプトで除外など）
+    pub skipped: Vec<SkippedExportPage>,
+    /// 入稿サイズに合わせるために拡大されるページ
+    #[serde(default)]
+    pub upscaled_pages: Vec<UpscaledExportPage>,
+    /// 入稿解像度を指定したが、解像度の記録がなく合わせられないページ（原寸のまま出力）
+    #[serde(default)]
+    pub unknown_dpi_pages: Vec<SkippedExportPage>,
+    /// 出力されるが塗り足しが付かないページ（そのままコピーするPSD）
+    #[serde(default)]
+    pub bleed_skipped: Vec<SkippedExportPage>,
+}
+
+/// 入稿サイズへのリサンプリングで拡大されるページ（画質が落ちるため確認用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpscaledExportPage {
+    pub page_id: Option<String>,
+    pub source_path: Option<String>,
+    pub output_name: String,
+    /// 拡大率（1.0より大きい）
+    pub scale: f64,
+}
+
+/// 出力予定のファイル
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedExportFile {
+    pub page_id: Option<String>,
+    pub source_path: Option<String>,
+    pub output_path: String,
+    /// "copy" | "move" | "convert" | "blank" | "cover" | "archive"
+    pub action: String,
+}
+
+/// 出力パスの衝突
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportCollision {
+    pub output_path: String,
+    /// 衝突しているページの元ファイル（白紙ページは出力名）
+    pub sources: Vec<String>,
+    pub page_ids: Vec<String>,
+}
+
+/// 出力されないページ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedExportPage {
+    pub page_id: Option<String>,
+    pub source_path: Option<String>,
+    pub output_name: String,
+    pub reason: String,
+}
+
+/// クイックエクスポートの完了通知（"quick-export-finished" イベント）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickExportResult {
+    pub success: bool,
+    pub output_path: Option<String>,
+    pub exported: usize,
+    pub error: Option<String>,
+}
+
+/// 塗り足し設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BleedSettings {
+    /// 塗り足し幅 (mm)
+    pub bleed_mm: f64,
+    /// 解像度（mm→px換算用）
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+    /// 塗り足し方式 ("mirror" | "extend" | "white")
+    #[serde(default = "default_bleed_mode")]
+    pub mode: String,
+}
+
+fn default_dpi() -> u32 {
+    350
+}
+
+fn default_bleed_mode() -> String {
+    "mirror".to_string()
+}
+
+/// 一括処理の個別結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFileResult {
+    pub source_path: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchFileResult {
+    pub fn from_result(source_path: &str, result: Result<String, String>) -> Self {
+        match result {
+            Ok(output_path) => Self {
+                source_path: source_path.to_string(),
+                success: true,
+                output_path: Some(output_path),
+                error: None,
+            },
+            Err(e) => Self {
+                source_path: source_path.to_string(),
+                success: false,
+                output_path: None,
+                error: Some(e),
+            },
+        }
+    }
+}
+
+/// 表紙専用の書き出し設定（本文と納品仕様が異なるため）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverExportSettings {
+    /// 表紙の出力先サブフォルダ
+    #[serde(default = "default_cover_subfolder")]
+    pub subfolder: String,
+    /// ファイル名テンプレート（{name} = ページの出力名, {index} = 表紙内の連番, {original} = 元ファイル名, {chapter} = サブフォルダ名）
+    #[serde(default = "default_cover_name_template")]
+    pub name_template: String,
+    /// RGBに変換して出力するか
+    #[serde(default = "default_true")]
+    pub force_rgb: bool,
+    /// 出力形式 ("jpg" | "png" | "tif")
+    #[serde(default = "default_cover_format")]
+    pub output_format: String,
+    /// JPG出力時の品質
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jpg_quality: Option<u8>,
+    /// 展開図の合成設定（指定時は最初の表紙を表1、最後を表4、間を背として合成）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wraparound: Option<CoverSpec>,
+}
+
+fn default_cover_subfolder() -> String {
+    "cover".to_string()
+}
+
+fn default_cover_name_template() -> String {
+    "{name}".to_string()
+}
+
+fn default_cover_format() -> String {
+    "jpg".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 出力サイズ見積もりの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSizeEstimateOptions {
+    /// 見積もる出力形式 ("requested" = 要求の設定どおり | "jpg" | "png" | "tif" | "webp" | "jxl" = 全ページをその形式に変換)
+    /// JPGの品質・クロマサブサンプリングは要求の設定を使う
+    #[serde(default = "default_estimate_formats")]
+    pub formats: Vec<String>,
+    /// 実際にエンコードして計測するページ数
+    #[serde(default = "default_sample_count")]
+    pub sample_count: usize,
+    /// 入稿先のアップロード上限 (バイト)
+    #[serde(default = "default_size_limit")]
+    pub size_limit_bytes: u64,
+}
+
+fn default_estimate_formats() -> Vec<String> {
+    vec!["requested".to_string(), "jpg".to_string(), "png".to_string()]
+}
+
+fn default_sample_count() -> usize {
+    6
+}
+
+// 印刷所の入稿システムで多い2GBの上限
+fn default_size_limit() -> u64 {
+    2 * 1024 * 1024 * 1024
+}
+
+impl Default for ExportSizeEstimateOptions {
+    fn default() -> Self {
+        Self {
+            formats: default_estimate_formats(),
+            sample_count: default_sample_count(),
+            size_limit_bytes: default_size_limit(),
+        }
+    }
+}
+
+/// 出力形式ごとの見積もり
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportFormatEstimate {
+    pub format: String,
+    /// 見積もった合計サイズ (バイト)
+    pub estimated_bytes: u64,
+    /// サンプル中で最も圧縮が効いたページの比率で計算した下限
+    pub low_bytes: u64,
+    /// サンプル中で最も圧縮が効かなかったページの比率で計算した上限
+    pub high_bytes: u64,
+    /// 1ページあたりの平均 (バイト)
+    pub average_page_bytes: u64,
+    /// 上限の見積もり (high_bytes) でも上限に収まるか
+    pub fits_limit: bool,
+    /// 見積もり (estimated_bytes) が上限に収まるか
+    pub estimate_fits_limit: bool,
+}
+
+/// 出力サイズの見積もり結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSizeEstimate {
+    pub page_count: usize,
+    /// 実際にエンコードして計測したページ
+    pub sampled_pages: Vec<String>,
+    /// ファイルが見つからない・読めないため見積もりに含めていないページ
+    pub unestimated_pages: Vec<String>,
+    pub size_limit_bytes: u64,
+    pub formats: Vec<ExportFormatEstimate>,
 }
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use super::{DetectedAdobeApp, TiffConvertConfig};
+
+/// レンダーファームのジョブ（共有キューフォルダに置くJSON）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmJob {
+    pub id: String,
+    /// "export" | "tiff-convert"
+    pub kind: String,
+    /// 表示用の名前
+    pub label: String,
+    pub submitted_at: String,
+    /// 投入したマシン名
+    #[serde(default)]
+    pub submitted_by: Option<String>,
+    /// kind ごとの内容（export は ExportRequest、tiff-convert は FarmTiffPayload）
+    pub payload: serde_json::Value,
+}
+
+/// TIFF変換ジョブの内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmTiffPayload {
+    pub config: TiffConvertConfig,
+    /// 出力先（全ワーカーから見える共有フォルダ）
+    pub output_dir: String,
+    #[serde(default)]
+    pub pinned_photoshop: Option<DetectedAdobeApp>,
+}
+
+/// ジョブの実行結果（done/ または failed/ に置く）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmJobResult {
+    pub job: FarmJob,
+    /// 実行したワーカー名
+    pub worker: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub success: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// 成功時の結果（エクスポート件数、TIFF変換結果など）
+    #[serde(default)]
+    pub result: Option<serde_json::Value>,
+}
+
+/// ワーカーの稼働状況（workers/<ワーカー名>.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmWorkerInfo {
+    pub name: String,
+    /// 最終応答日時（一定時間更新がなければ停止とみなす）
+    pub last_seen: String,
+    /// 実行中のジョブID
+    #[serde(default)]
+    pub current_job: Option<String>,
+}
+
+/// 実行中のジョブ
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmClaimedJob {
+    pub job: FarmJob,
+    pub worker: String,
+}
+
+/// キューの状況
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmStatus {
+    pub pending: Vec<FarmJob>,
+    pub running: Vec<FarmClaimedJob>,
+    pub completed: Vec<FarmJobResult>,
+    pub failed: Vec<FarmJobResult>,
+    pub workers: Vec<FarmWorkerInfo>,
+}
+
+/// このマシンのワーカーの状態（"farm-worker-status" イベント）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmWorkerEvent {
+    pub queue_dir: String,
+    /// "started" | "finished"
+    pub status: String,
+    pub job_id: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
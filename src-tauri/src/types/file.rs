@@ -8,4 +8,48 @@ pub struct FileInfo {
     pub size: u64,
     pub modified_time: u64,
     pub file_type: String,
+    /// クラウド同期のプレースホルダー（ローカル未ダウンロード）か
+    #[serde(default)]
+    pub cloud_placeholder: bool,
+    /// ページ種別ルールで決まった種別・ラベル（一致なしは None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_page_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_label: Option<String>,
+    /// 再帰読み込み時の読み込みフォルダからの相対パス（"/" 区切り、例: "ch01/pages/001.psd"）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relative_path: Option<String>,
+    /// 1チャンネルあたりのビット深度（16bitの原稿の確認用、読み取れない形式は None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bit_depth: Option<u8>,
+    /// 同期の競合で作られたファイルの場合、元のファイルのパス
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_conflict_of: Option<String>,
+    /// このファイルの同期の競合ファイル（どちらを使うか確認が必要）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sync_conflict_copies: Vec<String>,
+}
+
+/// 同期の競合ファイルの検出結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflictStatus {
+    pub path: String,
+    /// 同じフォルダにある競合ファイル
+    pub conflict_copies: Vec<String>,
+    /// このファイル自体が競合ファイルの場合、元のファイル名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflict_of: Option<String>,
+}
+
+/// クラウド同期ファイルの状態
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudFileStatus {
+    pub path: String,
+    /// 同期サービス名（Dropbox, OneDrive等）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// ローカルに実体がないか
+    pub placeholder: bool,
 }
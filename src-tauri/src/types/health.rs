@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// ヘルスチェックの個別項目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheckItem {
+    /// 項目ID ("cache" | "config" | "photoshop" | "watcher" | "longPath" | "diskSpace")
+    pub id: String,
+    /// 表示名
+    pub label: String,
+    /// "ok" | "warning" | "error"
+    pub status: String,
+    pub message: String,
+}
+
+/// ヘルスチェックの結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheckReport {
+    pub items: Vec<HealthCheckItem>,
+    /// エラー項目がないか
+    pub healthy: bool,
+    pub checked_at: String,
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use super::ExportRequest;
+
+/// 出力時点のページの状態（再実行時の差分確認用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageSnapshot {
+    pub page_id: Option<String>,
+    pub output_name: String,
+    pub source_path: Option<String>,
+    /// 元ファイルの更新日時（ミリ秒、ファイルなしは 0）
+    pub modified_time: u64,
+}
+
+/// エクスポート・変換の履歴1件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportHistoryEntry {
+    pub id: String,
+    /// 種類 ("export" | "tiff-convert")
+    pub kind: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub output_path: String,
+    /// "completed" | "failed"
+    pub status: String,
+    pub error: Option<String>,
+    /// 出力件数
+    pub exported: usize,
+    pub operator: Option<String>,
+    /// エクスポートの設定一式
+    #[serde(default)]
+    pub request: Option<ExportRequest>,
+    /// TIFF変換の設定一式
+    #[serde(default)]
+    pub tiff_config: Option<serde_json::Value>,
+    /// 処理結果の詳細（TIFF変換の個別結果など）
+    #[serde(default)]
+    pub report: Option<serde_json::Value>,
+    #[serde(default)]
+    pub pages: Vec<PageSnapshot>,
+}
+
+/// 履歴一覧用の要約
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportHistorySummary {
+    pub id: String,
+    pub kind: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub output_path: String,
+    pub status: String,
+    pub exported: usize,
+    pub page_count: usize,
+    pub operator: Option<String>,
+}
+
+impl From<&ExportHistoryEntry> for ExportHistorySummary {
+    fn from(entry: &ExportHistoryEntry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            kind: entry.kind.clone(),
+            started_at: entry.started_at.clone(),
+            duration_ms: entry.duration_ms,
+            output_path: entry.output_path.clone(),
+            status: entry.status.clone(),
+            exported: entry.exported,
+            page_count: entry.pages.len(),
+            operator: entry.operator.clone(),
+        }
+    }
+}
+
+/// 履歴の時点から現在までのページの変化
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPageDiff {
+    /// 追加されたページ（ページID、なければ出力名）
+    pub added: Vec<String>,
+    /// なくなったページ
+    pub removed: Vec<String>,
+    /// 元ファイルが差し替えられた・更新されたページ
+    pub modified: Vec<String>,
+    /// 出力名が変わったページ
+    pub renamed: Vec<String>,
+}
+
+/// 履歴からの再エクスポート結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RerunExportResult {
+    /// 出力件数（dry_run では 0）
+    pub exported: usize,
+    pub diff: ExportPageDiff,
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// 受け取りフォルダの監視設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxConfig {
+    /// 受け取りフォルダ（アシスタントが完成原稿を置く場所）
+    pub inbox_path: String,
+    /// 取り込み先の素材フォルダ（チャプター名のサブフォルダに振り分け）
+    pub assets_path: String,
+    /// ファイルを待っているページ
+    pub placeholders: Vec<InboxPlaceholder>,
+    /// 確認間隔 (秒)
+    #[serde(default = "default_poll_secs")]
+    pub poll_secs: u64,
+}
+
+fn default_poll_secs() -> u64 {
+    3
+}
+
+/// ファイル未配置のページ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxPlaceholder {
+    pub page_id: String,
+    /// 対応するファイル名（拡張子なし。例: "ch01_012"）
+    pub expected_name: String,
+    /// ファイル名末尾の番号で照合する場合のページ番号
+    #[serde(default)]
+    pub page_number: Option<u32>,
+    /// 取り込み先のサブフォルダ（チャプター名など）
+    #[serde(default)]
+    pub subfolder: Option<String>,
+}
+
+/// 取り込み完了通知（"inbox-file-linked" イベント）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxLinkedEvent {
+    pub page_id: String,
+    pub original_name: String,
+    /// 取り込み後のパス
+    pub path: String,
+    /// ファイル内容のMD5
+    pub hash: String,
+}
+
+/// 照合できなかった・取り込みに失敗したファイルの通知（"inbox-file-unmatched" イベント）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxUnmatchedEvent {
+    pub path: String,
+    pub reason: String,
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// 整合性チェックで見つかった問題
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    pub page_id: String,
+    pub path: String,
+    /// "missing" | "unreadable" | "silentChange"（更新日時・サイズが同じまま内容が変化） | "conflictCopy"（同期の競合ファイル）
+    pub kind: String,
+    pub message: String,
+    /// 見つかった競合ファイル
+    #[serde(default)]
+    pub conflict_paths: Vec<String>,
+}
+
+/// 整合性チェックの結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityScanReport {
+    pub project_path: String,
+    pub started_at: String,
+    pub finished_at: String,
+    /// ハッシュを計算したファイル数
+    pub scanned: usize,
+    /// 初めて記録したファイル数
+    pub recorded: usize,
+    /// 更新日時が変わっていた（編集された）ため記録を更新したファイル数
+    pub updated: usize,
+    pub issues: Vec<IntegrityIssue>,
+    /// 中断した場合 true（一部のファイルのみ確認済み）
+    pub cancelled: bool,
+}
+
+/// ファイルごとの前回の記録
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityRecord {
+    pub hash: String,
+    pub size: u64,
+    pub modified_time: u64,
+    pub checked_at: String,
+}
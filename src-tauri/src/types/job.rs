@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// バックグラウンドジョブの状態
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundJobInfo {
+    pub id: u64,
+    /// 種類 ("thumbnail" | "watch-export" | "upload" | "index" など)
+    pub kind: String,
+    /// 表示名
+    pub label: String,
+    /// 状態 ("running" | "paused" | "cancelled" | "completed" | "failed")
+    pub status: String,
+    pub current: usize,
+    pub total: usize,
+    pub error: Option<String>,
+}
+
+/// 空き容量不足の通知（"disk-space-low" イベント）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskSpaceEvent {
+    /// 対象ジョブ
+    pub job_id: u64,
+    /// 監視対象のパス
+    pub path: String,
+    pub available_bytes: u64,
+    pub threshold_bytes: u64,
+    /// "warning" | "critical"（critical はジョブを一時停止済み）
+    pub level: String,
+}
+
+/// ドライブの空き容量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskSpaceInfo {
+    pub path: String,
+    /// 取得できなければ None
+    pub available_bytes: Option<u64>,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// EXIFの1項目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataField {
+    pub tag: String,
+    /// IFD（本画像 / サムネイル）
+    pub ifd: String,
+    pub value: String,
+}
+
+/// 画像ファイルのメタデータ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageMetadata {
+    pub path: String,
+    pub exif: Vec<MetadataField>,
+    /// XMPパケット（XML）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xmp: Option<String>,
+    /// 位置情報を含むか
+    pub has_gps: bool,
+}
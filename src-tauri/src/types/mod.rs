@@ -2,8 +2,82 @@ mod file;
 mod export;
 mod project;
 mod tiff;
+mod watch;
+mod farm;
+mod benchmark;
+mod tiles;
+mod autosave;
+mod storefront;
+mod spread;
+mod annotation;
+mod edition;
+mod integrity;
+mod cover;
+mod crop;
+mod tone;
+mod strip;
+mod viewer;
+mod metadata;
+mod proof;
+mod screen;
+mod purity;
+mod trim;
+mod similar;
+mod session;
+mod settings;
+mod job;
+mod health;
+mod app_mode;
+mod audit;
+mod usage;
+mod plugin;
+mod inbox;
+mod notification;
+mod report;
+mod sequence;
+mod rules;
+mod chapter_split;
+mod cleanup;
+mod history;
 
-pub use file::FileInfo;
-pub use export::ExportPage;
+pub use file::*;
+pub use export::*;
 pub use project::*;
 pub use tiff::*;
+pub use watch::*;
+pub use farm::*;
+pub use benchmark::*;
+pub use tiles::*;
+pub use autosave::*;
+pub use storefront::*;
+pub use spread::*;
+pub use annotation::*;
+pub use edition::*;
+pub use integrity::*;
+pub use cover::*;
+pub use crop::*;
+pub use tone::*;
+pub use strip::*;
+pub use viewer::*;
+pub use metadata::*;
+pub use proof::*;
+pub use screen::*;
+pub use purity::*;
+pub use trim::*;
+pub use similar::*;
+pub use session::*;
+pub use settings::*;
+pub use job::*;
+pub use health::*;
+pub use app_mode::*;
+pub use audit::*;
+pub use usage::*;
+pub use plugin::*;
+pub use inbox::*;
+pub use notification::*;
+pub use report::*;
+pub use sequence::*;
+pub use rules::*;
+pub use chapter_split::*;
+pub use cleanup::*;
+pub use history::*;
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+/// 完了通知を送るWebhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    /// 表示名
+    #[serde(default)]
+    pub name: String,
+    pub url: String,
+    /// 送信形式 ("slack" = Slack互換の text、"json" = 処理結果のJSONをそのまま送信)
+    #[serde(default = "default_webhook_format")]
+    pub format: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 成功時に通知
+    #[serde(default = "default_true")]
+    pub on_success: bool,
+    /// 失敗時に通知
+    #[serde(default = "default_true")]
+    pub on_failure: bool,
+}
+
+fn default_webhook_format() -> String {
+    "json".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 長時間処理の完了サマリー（Webhookの送信内容）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationSummary {
+    /// 種類 ("export" | "tiff-convert" | "index" など)
+    pub kind: String,
+    pub label: String,
+    /// "completed" | "failed"
+    pub status: String,
+    pub details: Option<String>,
+    pub error: Option<String>,
+    pub finished_at: String,
+}
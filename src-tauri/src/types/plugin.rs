@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+/// 解析プラグインのマニフェスト（プラグインフォルダの plugin.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzerPluginManifest {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// 実行コマンド（プラグインフォルダからの相対パスも可）
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// タイムアウト (秒)
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    120
+}
+
+/// 解析プラグインの一覧情報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzerPluginInfo {
+    #[serde(flatten)]
+    pub manifest: AnalyzerPluginManifest,
+    /// プラグインフォルダ
+    pub directory: String,
+}
+
+/// プラグインに渡すページ情報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzerPage {
+    pub index: usize,
+    pub path: Option<String>,
+    #[serde(default)]
+    pub output_name: Option<String>,
+    #[serde(default)]
+    pub page_type: Option<String>,
+    #[serde(default)]
+    pub chapter: Option<String>,
+}
+
+/// プラグインへの要求（標準入力にJSONで渡す）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzerRequest {
+    pub protocol_version: u32,
+    pub pages: Vec<AnalyzerPage>,
+    pub options: serde_json::Value,
+}
+
+/// プラグインの指摘事項
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzerFinding {
+    /// 対象ページ（作品全体への指摘なら None）
+    #[serde(default)]
+    pub page_index: Option<usize>,
+    /// "info" | "warning" | "error"
+    #[serde(default = "default_severity")]
+    pub severity: String,
+    #[serde(default)]
+    pub code: Option<String>,
+    pub message: String,
+}
+
+fn default_severity() -> String {
+    "warning".to_string()
+}
+
+/// プラグインの応答（標準出力のJSON）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzerResponse {
+    #[serde(default)]
+    pub findings: Vec<AnalyzerFinding>,
+}
+
+/// プラグインの実行結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyzerPluginResult {
+    pub plugin_id: String,
+    pub findings: Vec<AnalyzerFinding>,
+    pub error: Option<String>,
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use super::{DetectedAdobeApp, PageTypeRule};
 
 // ファイル参照情報（保存用）
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,6 +10,9 @@ pub struct SavedFileReference {
     pub file_type: String,
     pub file_size: u64,
     pub modified_time: u64,
+    // 内容ハッシュ（リネーム・移動後の再リンク用、保存時に付与）
+    #[serde(default)]
+    pub content_hash: Option<String>,
 }
 
 // 保存されるページ
@@ -18,6 +22,12 @@ pub struct SavedPage {
     pub page_type: String,
     pub file: Option<SavedFileReference>,
     pub label: Option<String>,
+    // 見開き原稿（2ページ分の幅）
+    #[serde(default)]
+    pub spread: bool,
+    // PDF素材の使用するページ（0始まり、未指定なら1ページ目）
+    #[serde(default)]
+    pub pdf_page: Option<usize>,
 }
 
 // 保存されるチャプター
@@ -51,6 +61,91 @@ pub struct ProjectFile {
     pub base_path: String,
     pub chapters: Vec<SavedChapter>,
     pub ui_state: Option<SavedUiState>,
+    // サムネイルをプロジェクト横の .cache に保存（共有NASでチーム内のキャッシュを再利用）
+    #[serde(default)]
+    pub use_project_cache: bool,
+    // エクスポートスクリプト（印刷所ごとの命名・変換ルール）
+    #[serde(default)]
+    pub export_script: Option<String>,
+    // 読み込み時にページ種別を自動で割り当てるルール
+    #[serde(default)]
+    pub page_type_rules: Vec<PageTypeRule>,
+    // TIFF変換に使うPhotoshop（チャプター間で色変換の結果を揃えるため固定）
+    #[serde(default)]
+    pub pinned_photoshop: Option<DetectedAdobeApp>,
+    // 名前付きのページ選択（イベント用サンプル、重版の修正ページなど）
+    #[serde(default)]
+    pub page_lists: Vec<PageList>,
+    // 校正の指示
+    #[serde(default)]
+    pub annotations: Vec<PageAnnotation>,
+    // 凍結した版（初版・二刷など、出力したデータ一式の記録）
+    #[serde(default)]
+    pub editions: Vec<Edition>,
+}
+
+// 凍結した版（記録後は変更しない）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Edition {
+    pub id: String,
+    // 版の名前（"初版第1刷" など）
+    pub name: String,
+    pub frozen_at: String,
+    // 凍結元の出力フォルダ
+    pub source_dir: String,
+    // 保管先（内容アドレスのアーカイブ）
+    pub archive_dir: String,
+    // 元になったエクスポート履歴
+    #[serde(default)]
+    pub history_id: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    pub files: Vec<EditionFile>,
+}
+
+// 版に含まれるファイル
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditionFile {
+    // 出力フォルダからの相対パス（区切りは "/"）
+    pub path: String,
+    // ファイル全体のハッシュ（アーカイブ内の保存名）
+    pub hash: String,
+    pub size: u64,
+}
+
+// ページへの校正の指示（矩形の範囲とコメント）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageAnnotation {
+    pub id: String,
+    pub page_id: String,
+    // 範囲（ページの幅・高さに対する割合 0.0〜1.0、原稿の解像度によらない）
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub comment: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    pub created_at: String,
+    // 対応済み
+    #[serde(default)]
+    pub resolved: bool,
+}
+
+// 名前付きのページ選択（台割とは独立した並び順を持つ）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageList {
+    pub name: String,
+    // 出力順のページID
+    pub page_ids: Vec<String>,
+}
+
+// 読み込んだプロジェクトと、読み込み時に実行したスキーマの移行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadedProject {
+    #[serde(flatten)]
+    pub project: ProjectFile,
+    pub migrations: Vec<String>,
 }
 
 // ファイル検証結果
@@ -63,6 +158,33 @@ pub struct FileValidationResult {
     pub suggested_path: Option<String>,
 }
 
+// フォルダ移動に伴う一括再リンクの結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelinkBaseResult {
+    // パスを書き換えたプロジェクト
+    pub project: ProjectFile,
+    // 旧パスに一致して書き換えたページ数
+    pub matched: usize,
+    // 書き換え後にファイルが見つかったページ数
+    pub fixed: usize,
+    // 書き換え後も見つからないページ
+    pub still_missing: usize,
+    pub missing_page_ids: Vec<String>,
+}
+
+// 見つからないファイルの再リンク候補
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelinkSuggestion {
+    pub page_id: String,
+    pub original_path: String,
+    // 最も確からしい候補
+    pub suggested_path: String,
+    // "hash"（内容が一致） | "name"（ファイル名とサイズが一致）
+    pub match_kind: String,
+    // 同じ条件で一致したすべての候補
+    pub candidates: Vec<String>,
+}
+
 // 最近使ったファイル
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecentFile {
@@ -70,3 +192,11 @@ pub struct RecentFile {
     pub name: String,
     pub opened_at: String,
 }
+
+// プロジェクトの自動バックアップ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBackup {
+    pub path: String,
+    pub created_at: String,
+    pub size: u64,
+}
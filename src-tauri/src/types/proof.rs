@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+/// 網点シミュレーション設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HalftoneSettings {
+    /// スクリーン線数 (lpi)
+    pub lpi: f64,
+    /// スクリーン角度 (度)
+    #[serde(default = "default_angle")]
+    pub angle: f64,
+}
+
+fn default_angle() -> f64 {
+    45.0
+}
+
+/// 印刷シミュレーションの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintProofOptions {
+    /// 50%網点でのドットゲイン（%）
+    #[serde(default = "default_dot_gain")]
+    pub dot_gain: f64,
+    /// 原稿の解像度（網点サイズの計算用）
+    #[serde(default = "default_source_dpi")]
+    pub source_dpi: u32,
+    /// 網点化（未指定ならグレースケール＋ドットゲインのみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub halftone: Option<HalftoneSettings>,
+    /// プレビュー画像の長辺 (px)
+    #[serde(default = "default_max_size")]
+    pub max_size: u32,
+}
+
+impl Default for PrintProofOptions {
+    fn default() -> Self {
+        Self {
+            dot_gain: default_dot_gain(),
+            source_dpi: default_source_dpi(),
+            halftone: None,
+            max_size: default_max_size(),
+        }
+    }
+}
+
+fn default_dot_gain() -> f64 {
+    15.0
+}
+
+fn default_source_dpi() -> u32 {
+    600
+}
+
+fn default_max_size() -> u32 {
+    1600
+}
+
+/// 夜間校正用プレビューの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NightPreviewOptions {
+    /// "invert"（白黒を反転） | "dim"（明るさを落とすのみ）
+    #[serde(default = "default_night_mode")]
+    pub mode: String,
+    /// 明るさ（0.05〜1.0、反転後にも適用）
+    #[serde(default = "default_night_brightness")]
+    pub brightness: f64,
+    /// プレビュー画像の長辺 (px)
+    #[serde(default = "default_max_size")]
+    pub max_size: u32,
+}
+
+impl Default for NightPreviewOptions {
+    fn default() -> Self {
+        Self {
+            mode: default_night_mode(),
+            brightness: default_night_brightness(),
+            max_size: default_max_size(),
+        }
+    }
+}
+
+fn default_night_mode() -> String {
+    "invert".to_string()
+}
+
+fn default_night_brightness() -> f64 {
+    0.85
+}
+
+/// 色覚シミュレーションのプレビュー設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorVisionPreviewOptions {
+    /// "protanopia"（1型） | "deuteranopia"（2型） | "tritanopia"（3型）
+    pub kind: String,
+    /// プレビュー画像の長辺 (px)
+    #[serde(default = "default_max_size")]
+    pub max_size: u32,
+}
+
+/// 見開きプレビューの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpreadPreviewOptions {
+    /// true なら引数を読み順（前のページ, 後のページ）として扱い、綴じ方向に合わせて左右に配置する
+    #[serde(default)]
+    pub reading_order: bool,
+    /// 右綴じ（前のページが右側）か。reading_order が true のときのみ使う
+    #[serde(default = "default_true")]
+    pub right_to_left: bool,
+    /// ノド（左右のページの間）の余白 (px、ページ幅まで)
+    #[serde(default)]
+    pub gap: u32,
+    /// 背景色（ページがない側・余白）
+    #[serde(default = "default_spread_background")]
+    pub background: [u8; 3],
+    /// プレビュー画像の長辺 (px)
+    #[serde(default = "default_max_size")]
+    pub max_size: u32,
+}
+
+impl Default for SpreadPreviewOptions {
+    fn default() -> Self {
+        Self {
+            reading_order: false,
+            right_to_left: true,
+            gap: 0,
+            background: default_spread_background(),
+            max_size: default_max_size(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_spread_background() -> [u8; 3] {
+    [255, 255, 255]
+}
+
+/// プレビュー画像の生成結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewImageResult {
+    /// キャッシュファイルの絶対パス（asset プロトコル用）
+    pub cache_path: String,
+    pub width: u32,
+    pub height: u32,
+    /// ステータス: "cached" | "generated"
+    pub status: String,
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// 墨ベタ純度チェックの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlackPurityOptions {
+    /// この輝度以下を「黒」とみなす
+    #[serde(default = "default_threshold")]
+    pub threshold: u8,
+    /// 黒のうち純黒でない画素の許容割合
+    #[serde(default = "default_max_off_black_ratio")]
+    pub max_off_black_ratio: f64,
+}
+
+fn default_threshold() -> u8 {
+    24
+}
+
+fn default_max_off_black_ratio() -> f64 {
+    0.05
+}
+
+impl Default for BlackPurityOptions {
+    fn default() -> Self {
+        Self {
+            threshold: default_threshold(),
+            max_off_black_ratio: default_max_off_black_ratio(),
+        }
+    }
+}
+
+/// 黒画素の内訳
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlackPurityStats {
+    /// 黒とみなした画素数
+    pub dark_pixels: u64,
+    /// 純黒 (0) でない黒画素数
+    pub off_black_pixels: u64,
+    /// 色味のある黒（リッチブラック）画素数（RGBのみ）
+    pub rich_black_pixels: u64,
+    /// 黒画素に占める純黒でない画素の割合
+    pub off_black_ratio: f64,
+}
+
+/// ページごとの墨ベタ純度チェック結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlackPurityAnalysis {
+    pub path: String,
+    pub stats: Option<BlackPurityStats>,
+    /// 許容割合を超えているか
+    pub flagged: bool,
+    pub error: Option<String>,
+}
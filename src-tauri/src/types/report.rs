@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+/// プリフライトの指摘事項
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightIssue {
+    /// "info" | "warning" | "error"
+    pub severity: String,
+    /// 分類（"resolution" | "colorMode" | "binding" | "coverage" など）
+    pub category: String,
+    pub message: String,
+}
+
+/// プリフライト結果の1ページ分
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReportPage {
+    #[serde(default)]
+    pub page_id: Option<String>,
+    /// 表示名（ノンブル・出力名など）
+    pub label: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub issues: Vec<PreflightIssue>,
+}
+
+/// プリフライト結果のレポート
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    pub title: String,
+    #[serde(default)]
+    pub project_name: Option<String>,
+    /// 作品全体への指摘
+    #[serde(default)]
+    pub issues: Vec<PreflightIssue>,
+    pub pages: Vec<PreflightReportPage>,
+}
+
+/// プロジェクト全体のプリフライトの設定（None のチェックは行わない）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PreflightOptions {
+    /// これを下回る解像度のページをエラーにする (ppi)
+    pub min_dpi: Option<f64>,
+    /// 本文の仕上がり寸法（原稿の寸法と照合）
+    pub page_size: Option<PreflightPageSize>,
+    /// 想定するカラーモード（"grayscale" | "bitmap" | "rgb" | "cmyk"）。未指定なら最も多いモードと異なるページを警告
+    pub color_mode: Option<String>,
+    /// カラーモードの混在をチェックする
+    pub check_color_modes: bool,
+    /// 総ページ数がこの倍数でなければエラー（折の単位）
+    pub page_multiple: Option<usize>,
+    /// 解像度の記録がない原稿の解像度として扱う値
+    pub default_dpi: u32,
+}
+
+impl Default for PreflightOptions {
+    fn default() -> Self {
+        Self {
+            min_dpi: Some(350.0),
+            page_size: None,
+            color_mode: None,
+            check_color_modes: true,
+            page_multiple: Some(4),
+            default_dpi: 350,
+        }
+    }
+}
+
+/// 本文ページの仕上がり寸法
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightPageSize {
+    /// 仕上がり幅 (mm)
+    pub trim_width_mm: f64,
+    /// 仕上がり高さ (mm)
+    pub trim_height_mm: f64,
+    /// 塗り足し (mm)
+    #[serde(default = "default_bleed_mm")]
+    pub bleed_mm: f64,
+    /// 原稿が塗り足しを含んでいるか
+    #[serde(default = "default_true")]
+    pub includes_bleed: bool,
+    /// 許容する誤差 (mm)
+    #[serde(default = "default_tolerance_mm")]
+    pub tolerance_mm: f64,
+}
+
+fn default_bleed_mm() -> f64 {
+    3.0
+}
+
+fn default_tolerance_mm() -> f64 {
+    0.5
+}
+
+/// 出力したEPUB/PDFの照合に使う台割の仕様
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookOutputSpec {
+    /// 綴じ方向 ("right" = 右綴じ | "left" = 左綴じ)
+    #[serde(default = "default_binding")]
+    pub binding: String,
+    /// 読み順に並べたページ画像のファイル名（空なら並びは確認しない）
+    #[serde(default)]
+    pub page_order: Vec<String>,
+    /// 表紙画像のファイル名（未指定なら page_order の先頭）
+    #[serde(default)]
+    pub cover: Option<String>,
+}
+
+fn default_binding() -> String {
+    "right".to_string()
+}
+
+/// EPUB/PDFの照合結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookVerification {
+    /// エラーの指摘がなければ true
+    pub passed: bool,
+    pub report: PreflightReport,
+}
+
+/// レポート出力の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportExportOptions {
+    /// 出力形式 ("html" | "pdf"、未指定なら出力パスの拡張子で判定)
+    #[serde(default)]
+    pub format: Option<String>,
+    /// ページのサムネイルを載せる
+    #[serde(default = "default_true")]
+    pub include_thumbnails: bool,
+    /// 指摘のあるページのみ載せる
+    #[serde(default)]
+    pub flagged_only: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for ReportExportOptions {
+    fn default() -> Self {
+        Self {
+            format: None,
+            include_thumbnails: true,
+            flagged_only: false,
+        }
+    }
+}
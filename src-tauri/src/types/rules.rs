@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// ファイル名からページ種別・ラベルを決めるルール（プロジェクトごとに保存）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageTypeRule {
+    /// ファイル名のパターン（* と ? が使える。大文字小文字は区別しない。例: "cover*.psd", "*okuduke*"）
+    pub pattern: String,
+    /// 割り当てるページ種別 ("file" | "cover" | "blank" | "intermission" | "colophon")
+    #[serde(default)]
+    pub page_type: Option<String>,
+    /// 割り当てるラベル
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// ルールの適用結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageClassification {
+    pub path: String,
+    pub page_type: Option<String>,
+    pub label: Option<String>,
+    /// 一致したルールの番号（一致なしは None）
+    pub rule_index: Option<usize>,
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// スクリーントーン線数チェックの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenToneOptions {
+    /// 印刷所の想定線数 (lpi)
+    #[serde(default = "default_target_lpi")]
+    pub target_lpi: f64,
+    /// 原稿の解像度
+    #[serde(default = "default_dpi")]
+    pub dpi: u32,
+    /// 目標線数との許容差（割合）
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+}
+
+fn default_target_lpi() -> f64 {
+    85.0
+}
+
+fn default_dpi() -> u32 {
+    600
+}
+
+fn default_tolerance() -> f64 {
+    0.1
+}
+
+impl Default for ScreenToneOptions {
+    fn default() -> Self {
+        Self {
+            target_lpi: default_target_lpi(),
+            dpi: default_dpi(),
+            tolerance: default_tolerance(),
+        }
+    }
+}
+
+/// ページごとのスクリーントーン線数の推定結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenToneAnalysis {
+    pub path: String,
+    /// 推定線数（トーン領域の中央値、トーンがなければ None）
+    pub estimated_lpi: Option<f64>,
+    /// 検出された線数の最小値
+    pub min_lpi: Option<f64>,
+    /// 検出された線数の最大値
+    pub max_lpi: Option<f64>,
+    /// トーンと判定された解析ブロック数
+    pub toned_blocks: usize,
+    /// 目標線数と一致しない線数のトーンを含むか
+    pub mismatch: bool,
+    pub error: Option<String>,
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// 並び順の計算対象のページ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SequencePage {
+    pub id: String,
+    pub page_type: String,
+}
+
+/// 並び順の計算対象のチャプター
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SequenceChapter {
+    pub id: String,
+    pub name: String,
+    pub pages: Vec<SequencePage>,
+}
+
+/// チャプターごとの連番設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterNaming {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_start_number")]
+    pub start_number: u32,
+    #[serde(default = "default_digits")]
+    pub digits: usize,
+    #[serde(default)]
+    pub prefix: String,
+}
+
+impl Default for ChapterNaming {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            start_number: default_start_number(),
+            digits: default_digits(),
+            prefix: String::new(),
+        }
+    }
+}
+
+/// 出力名の付け方
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SequenceNaming {
+    /// "unified"（全ページ通し番号） | "perChapter"（チャプターごとの連番・サブフォルダ）
+    #[serde(default = "default_naming_mode")]
+    pub mode: String,
+    /// 通し番号の設定
+    #[serde(default)]
+    pub unified: ChapterNaming,
+    /// チャプターIDごとの設定（未指定のチャプターは既定値）
+    #[serde(default)]
+    pub per_chapter: HashMap<String, ChapterNaming>,
+}
+
+impl Default for SequenceNaming {
+    fn default() -> Self {
+        Self {
+            mode: default_naming_mode(),
+            unified: ChapterNaming::default(),
+            per_chapter: HashMap::new(),
+        }
+    }
+}
+
+/// 台割の並び
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SequenceInput {
+    pub chapters: Vec<SequenceChapter>,
+    #[serde(default)]
+    pub naming: SequenceNaming,
+    /// 綴じ方向 ("right" = 右綴じ | "left" = 左綴じ)
+    #[serde(default = "default_binding")]
+    pub binding: String,
+    /// 最初のページのノンブル
+    #[serde(default = "default_start_number")]
+    pub folio_start: u32,
+}
+
+/// ドラッグ＆ドロップによる移動
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SequenceMove {
+    /// ページを移動（複数選択時は選択順を保って挿入）
+    #[serde(rename_all = "camelCase")]
+    Pages {
+        page_ids: Vec<String>,
+        target_chapter_id: String,
+        /// 移動先チャプター内の挿入位置（移動するページを除いた並びでの位置）
+        target_index: usize,
+    },
+    /// チャプターを移動
+    #[serde(rename_all = "camelCase")]
+    Chapter { chapter_id: String, target_index: usize },
+}
+
+/// ページごとの計算結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SequencedPage {
+    pub page_id: String,
+    pub chapter_id: String,
+    /// 全体での位置（0始まり）
+    pub global_index: usize,
+    /// チャプター内での位置（0始まり）
+    pub chapter_index: usize,
+    /// ノンブル
+    pub folio: u32,
+    /// 見開きでの位置 ("left" | "right")
+    pub side: String,
+    /// 出力名（出力しないチャプターは None）
+    pub output_name: Option<String>,
+    pub subfolder: Option<String>,
+}
+
+/// 移動後の並びと再計算結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SequenceResult {
+    pub chapters: Vec<SequenceChapter>,
+    pub pages: Vec<SequencedPage>,
+    /// ノンブル・左右・出力名が変わったページ
+    pub affected_page_ids: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_start_number() -> u32 {
+    1
+}
+
+fn default_digits() -> usize {
+    4
+}
+
+fn default_naming_mode() -> String {
+    "unified".to_string()
+}
+
+fn default_binding() -> String {
+    "right".to_string()
+}
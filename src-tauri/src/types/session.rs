@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use super::{ProjectFile, SavedUiState};
+
+/// 前回終了時のセッション状態（起動時に復元）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionState {
+    /// 開いていたプロジェクトファイル（未保存なら None）
+    #[serde(default)]
+    pub project_path: Option<String>,
+    /// 未保存の変更を含むプロジェクト内容
+    #[serde(default)]
+    pub project_snapshot: Option<ProjectFile>,
+    /// 選択・表示モードなどのUI状態
+    #[serde(default)]
+    pub ui_state: Option<SavedUiState>,
+    /// プレビューのスクロール位置 (px)
+    #[serde(default)]
+    pub scroll_position: f64,
+    /// パネルIDごとのサイズ (px)
+    #[serde(default)]
+    pub panel_layout: HashMap<String, f64>,
+    /// 保存日時（バックエンドで設定）
+    #[serde(default)]
+    pub saved_at: String,
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use super::WebhookConfig;
+use crate::constants::{MAX_IMAGE_DIMENSION, MAX_PIXEL_COUNT};
+
+/// アプリ全体の設定（設定ディレクトリの settings.json に保存）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    /// クイックエクスポートのグローバルショートカット（None で無効）
+    pub quick_export_shortcut: Option<String>,
+    /// ウィンドウを閉じてもトレイに常駐し、バックグラウンド処理を継続する
+    pub close_to_tray: bool,
+    /// 空き容量がこれを下回ったら警告 (MB)
+    pub disk_warning_mb: u64,
+    /// 空き容量がこれを下回ったらジョブを一時停止 (MB)
+    pub disk_pause_mb: u64,
+    /// 破壊的な操作（元ファイルの移動など）の前に操作者名の入力を必須にする
+    pub require_operator_name: bool,
+    /// 外部ツール向けのローカルHTTP APIを有効にする（127.0.0.1のみで待ち受け）
+    pub api_server_enabled: bool,
+    /// ローカルHTTP APIのポート
+    pub api_server_port: u16,
+    /// ローカルHTTP APIの認証トークン（Authorization: Bearer で必須、未設定なら有効化時に生成）
+    pub api_token: Option<String>,
+    /// 長時間処理の完了を通知するWebhook
+    pub webhooks: Vec<WebhookConfig>,
+    /// 起動時に削除する一時ファイルの経過時間 (時間)
+    pub cleanup_max_age_hours: u64,
+    /// TIFF変換に使うPhotoshopの実行ファイル（None なら検出した最新版）
+    pub photoshop_path: Option<String>,
+    /// 同時にデコードする画像の概算メモリ上限 (MB、0 で無制限)
+    pub decode_memory_budget_mb: u64,
+    /// 読み込める画像サイズの上限
+    pub image_limits: ImageLimits,
+    /// 処理ごとの上限（展開図の表紙など大きな画像を扱う処理だけ緩める）
+    pub image_limit_overrides: ImageLimitOverrides,
+    /// プロジェクト保存時にプロジェクト横に残すバックアップ数（0 で作らない）
+    pub project_backup_count: usize,
+    /// サムネイルを一定の縦横比のキャンバスに余白付きで配置する（None なら従来どおり画像の比率のまま）
+    pub thumbnail_letterbox: Option<ThumbnailLetterbox>,
+    /// 埋め込みのICCプロファイル（AdobeRGB・CMYKなど）でsRGBに変換して表示・変換出力する
+    pub color_management: bool,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            quick_export_shortcut: Some("CmdOrCtrl+Shift+E".to_string()),
+            close_to_tray: true,
+            disk_warning_mb: 2048,
+            disk_pause_mb: 512,
+            require_operator_name: false,
+            api_server_enabled: false,
+            api_server_port: 47321,
+            api_token: None,
+            webhooks: Vec::new(),
+            cleanup_max_age_hours: 24,
+            photoshop_path: None,
+            decode_memory_budget_mb: 4096,
+            image_limits: ImageLimits::default(),
+            image_limit_overrides: ImageLimitOverrides::default(),
+            project_backup_count: 5,
+            thumbnail_letterbox: None,
+            color_management: true,
+        }
+    }
+}
+
+/// サムネイルの余白付き配置（グリッドの高さを揃えるため）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ThumbnailLetterbox {
+    /// キャンバスの縦横比（幅 / 高さ）。既定はB5・A5判の比率
+    pub aspect_ratio: f64,
+    /// 余白の色 (RGB)
+    pub background: [u8; 3],
+}
+
+impl Default for ThumbnailLetterbox {
+    fn default() -> Self {
+        Self {
+            aspect_ratio: 182.0 / 257.0,
+            background: [224, 224, 224],
+        }
+    }
+}
+
+/// 画像サイズの上限（DoS・メモリ不足防止）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImageLimits {
+    /// 最大辺長 (px)
+    pub max_dimension: u32,
+    /// 最大ピクセル数
+    pub max_pixels: u64,
+}
+
+impl Default for ImageLimits {
+    fn default() -> Self {
+        Self {
+            max_dimension: MAX_IMAGE_DIMENSION,
+            max_pixels: MAX_PIXEL_COUNT,
+        }
+    }
+}
+
+/// 処理ごとの画像サイズの上限（None なら image_limits を使用）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ImageLimitOverrides {
+    pub thumbnail: Option<ImageLimits>,
+    pub export: Option<ImageLimits>,
+    pub cover: Option<ImageLimits>,
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// 連続する重複ページの検出結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatePagePair {
+    /// 前のページのインデックス
+    pub first_index: usize,
+    /// 後のページのインデックス
+    pub second_index: usize,
+    pub first_path: String,
+    pub second_path: String,
+    /// 知覚ハッシュの差（0 = 同一画像）
+    pub distance: u32,
+}
+
+/// 類似画像インデックスの1ファイル分（キャッシュに保存）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarityIndexEntry {
+    pub path: String,
+    pub modified_time: u64,
+    /// 知覚ハッシュ
+    pub hash: u64,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 類似画像インデックス
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarityIndex {
+    /// インデックス対象のフォルダ
+    pub folders: Vec<String>,
+    pub entries: Vec<SimilarityIndexEntry>,
+}
+
+/// インデックス構築の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarityIndexResult {
+    /// インデックス内のファイル数
+    pub indexed_files: usize,
+    /// 前回の結果を再利用したファイル数
+    pub reused_files: usize,
+    /// ハッシュ計算に失敗したファイル
+    pub failed_files: Vec<String>,
+}
+
+/// 類似ファイルの検索結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarFileMatch {
+    pub path: String,
+    /// 知覚ハッシュの差（0 = 同一画像）
+    pub distance: u32,
+    pub width: u32,
+    pub height: u32,
+}
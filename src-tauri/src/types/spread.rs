@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// 見開き判定の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpreadAnalysis {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    /// 見開き（単ページの約2倍の幅）と判定したか
+    pub is_spread: bool,
+    /// 単ページの縦横比に対する幅の倍率（見開きなら約2.0）
+    pub width_ratio: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
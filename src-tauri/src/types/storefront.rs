@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+/// 販売サイト向けサンプル画像の設定（プリセットの値を個別に上書きできる）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorefrontSampleOptions {
+    /// プリセット ("booth" | "melonbooks" | "custom")
+    #[serde(default = "default_preset")]
+    pub preset: String,
+    /// サンプル画像の長辺 (px)
+    #[serde(default)]
+    pub max_size: Option<u32>,
+    /// 表紙以外に含めるページ数の上限
+    #[serde(default)]
+    pub max_pages: Option<usize>,
+    /// JPG出力時の品質
+    #[serde(default)]
+    pub jpg_quality: Option<u8>,
+    /// 一覧画像の高さ (px)
+    #[serde(default)]
+    pub preview_height: Option<u32>,
+    /// 透かしに使う画像（PNGなど透過画像、未指定なら透かしなし）
+    #[serde(default)]
+    pub watermark_path: Option<String>,
+    /// 透かしの不透明度 (0.0〜1.0)
+    #[serde(default = "default_watermark_opacity")]
+    pub watermark_opacity: f32,
+    /// 透かしの配置 ("center" | "bottomRight" | "tile")
+    #[serde(default = "default_watermark_position")]
+    pub watermark_position: String,
+    /// 一覧画像で右から左へ並べるか（右綴じ）
+    #[serde(default = "default_true")]
+    pub right_to_left: bool,
+}
+
+fn default_preset() -> String {
+    "booth".to_string()
+}
+
+fn default_watermark_opacity() -> f32 {
+    0.35
+}
+
+fn default_watermark_position() -> String {
+    "center".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for StorefrontSampleOptions {
+    fn default() -> Self {
+        Self {
+            preset: default_preset(),
+            max_size: None,
+            max_pages: None,
+            jpg_quality: None,
+            preview_height: None,
+            watermark_path: None,
+            watermark_opacity: default_watermark_opacity(),
+            watermark_position: default_watermark_position(),
+            right_to_left: true,
+        }
+    }
+}
+
+/// サンプル画像の出力結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorefrontSampleResult {
+    pub output_dir: String,
+    /// 出力したサンプル画像（表紙が先頭）
+    pub files: Vec<String>,
+    /// 表紙とサンプルページを横に並べた一覧画像
+    pub preview_path: String,
+    /// 読み込めずに除外したページ
+    pub failed_pages: Vec<String>,
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// サムネイル一覧画像の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailStripOptions {
+    /// 1ページあたりの幅 (px)
+    #[serde(default = "default_cell_width")]
+    pub cell_width: u32,
+    /// 横に並べるページ数（1なら縦一列）
+    #[serde(default = "default_columns")]
+    pub columns: u32,
+    /// ページ間の余白 (px)
+    #[serde(default = "default_gap")]
+    pub gap: u32,
+    /// 横並び時に右から左へ配置するか（右綴じ）
+    #[serde(default = "default_true")]
+    pub right_to_left: bool,
+    /// JPG出力時の品質
+    #[serde(default = "default_quality")]
+    pub jpg_quality: u8,
+}
+
+fn default_cell_width() -> u32 {
+    240
+}
+
+fn default_columns() -> u32 {
+    1
+}
+
+fn default_gap() -> u32 {
+    8
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_quality() -> u8 {
+    85
+}
+
+impl Default for ThumbnailStripOptions {
+    fn default() -> Self {
+        Self {
+            cell_width: default_cell_width(),
+            columns: default_columns(),
+            gap: default_gap(),
+            right_to_left: true,
+            jpg_quality: default_quality(),
+        }
+    }
+}
+
+/// サムネイル一覧画像の出力結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailStripResult {
+    pub output_path: String,
+    pub width: u32,
+    pub height: u32,
+    pub page_count: usize,
+}
@@ -1,22 +1,29 @@
 use serde::{Deserialize, Serialize};
 
 /// TIFF変換の個別ファイル設定
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TiffFileConfig {
     /// 入力ファイルパス
     pub path: String,
     /// 出力ディレクトリ
     pub output_path: String,
-    /// 出力ファイル名
+    /// 出力ファイル名（空の場合は name_template または元ファイル名から決める）
+    #[serde(default)]
     pub output_name: String,
+    /// チャプター名（name_template の {chapter}）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapter: Option<String>,
+    /// ページ番号（name_template の {page}）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_number: Option<u32>,
     /// カラーモード ("rgb" | "grayscale")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub color_mode: Option<String>,
 }
 
 /// TIFF変換のグローバル設定
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TiffGlobalSettings {
     /// レイヤーを統合するか
@@ -34,6 +41,9 @@ pub struct TiffGlobalSettings {
     /// 出力DPI
     #[serde(skip_serializing_if = "Option::is_none")]
     pub target_dpi: Option<u32>,
+    /// この輝度以下の黒を純黒に補正（未指定なら補正しない）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snap_black_threshold: Option<u8>,
 }
 
 fn default_true() -> bool {
@@ -48,16 +58,23 @@ impl Default for TiffGlobalSettings {
             target_width: None,
             target_height: None,
             target_dpi: None,
+            snap_black_threshold: None,
         }
     }
 }
 
 /// TIFF変換の設定全体（JSXに渡すJSON）
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TiffConvertConfig {
     pub global_settings: TiffGlobalSettings,
     pub files: Vec<TiffFileConfig>,
+    /// 出力ファイル名テンプレート（エクスポートと同じトークン: {name} {original} {chapter} {page} {index}）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_template: Option<String>,
+    /// JSXとの取り決めのバージョン（送信時にバックエンドが設定）
+    #[serde(default)]
+    pub protocol_version: u32,
 }
 
 /// TIFF変換の個別結果
@@ -85,5 +102,66 @@ pub struct TiffConvertResponse {
 /// JSXからの結果JSONのラッパー
 #[derive(Debug, Deserialize)]
 pub struct TiffResultsWrapper {
+    /// JSXが対応するプロトコルバージョン（古いスクリプトは返さない）
+    #[serde(default, rename = "protocolVersion")]
+    pub protocol_version: Option<u32>,
+    /// JSX側で変換を始められなかった理由
+    #[serde(default)]
+    pub error: Option<String>,
     pub results: Vec<TiffConvertResult>,
 }
+
+/// 検出したAdobeアプリ（Photoshop / Bridge）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedAdobeApp {
+    /// 表示名（インストールフォルダ名。例: "Adobe Photoshop 2025"）
+    pub name: String,
+    /// レジストリ上のバージョン（例: "190.0"、既定パスから見つけた場合は None）
+    pub version: Option<String>,
+    /// 実行ファイルのパス
+    pub path: String,
+}
+
+/// TIFF変換の進行状況（"tiff-convert-status" イベント）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TiffConvertStatus {
+    /// "converting" | "stalled"
+    pub status: String,
+    /// JSXが書き出した進捗（"X/N"、開始前は None）
+    pub progress: Option<String>,
+    /// 最後に進捗があってからの秒数
+    pub seconds_since_progress: u64,
+    /// 起動したPhotoshopのプロセスが生きているか
+    /// （既に起動中のPhotoshopへ処理を渡した場合は false になる）
+    pub process_alive: bool,
+    /// 利用者向けの案内（停止時のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// 停止したTIFF変換への指示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TiffStallAction {
+    /// 変換を中断
+    Abort,
+    /// そのまま待ち続ける（待ち時間をリセット）
+    ForceContinue,
+}
+
+/// 出力フォルダが既にある場合の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputConflictPolicy {
+    /// "フォルダ名 (1)" のように連番を付けた新しいフォルダに出力（従来の動作）
+    #[default]
+    Suffix,
+    /// 既存のフォルダにそのまま出力（同名ファイルは上書き）
+    Reuse,
+    /// 既存フォルダに出力し、全件の変換に成功したら今回出力しなかった既存のTIFFを削除（他のファイルは残す）
+    Clean,
+    /// 既存の場合はエラー
+    Error,
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// ディープズーム用タイルの1段階（level 0 が原寸、以降1段ごとに半分）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TileLevel {
+    pub level: u32,
+    /// 原寸に対する縮尺
+    pub scale: f64,
+    pub width: u32,
+    pub height: u32,
+    /// 横方向・縦方向のタイル数
+    pub columns: u32,
+    pub rows: u32,
+}
+
+/// ディープズーム用タイルの構成
+/// タイルは {tileDir}/{level}/{x}_{y}.{format} に保存される
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TilePyramid {
+    /// タイル保存先フォルダの絶対パス（asset プロトコル用）
+    pub tile_dir: String,
+    /// 原寸の画像サイズ
+    pub width: u32,
+    pub height: u32,
+    pub tile_size: u32,
+    pub format: String,
+    pub levels: Vec<TileLevel>,
+    /// ステータス: "cached" | "generated"
+    pub status: String,
+}
+
+/// 原稿の一部を切り出した拡大プレビュー
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewRegion {
+    /// 切り出した画像の絶対パス（asset プロトコル用）
+    pub cache_path: String,
+    /// 原寸での切り出し範囲（画像の範囲に収めた後の値）
+    pub x: u32,
+    pub y: u32,
+    pub region_width: u32,
+    pub region_height: u32,
+    /// 出力画像のサイズ
+    pub width: u32,
+    pub height: u32,
+    /// 実際の縮尺
+    pub scale: f64,
+    /// 切り出し元にしたタイルの段階
+    pub level: u32,
+    /// 原寸の画像サイズ
+    pub source_width: u32,
+    pub source_height: u32,
+    /// ステータス: "cached" | "generated"
+    pub status: String,
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// ページの階調統計
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToneStats {
+    /// 黒点（2パーセンタイルの輝度）
+    pub black_point: u8,
+    /// 白点（98パーセンタイルの輝度）
+    pub white_point: u8,
+    /// 平均輝度
+    pub mean: f64,
+}
+
+/// ページごとの階調解析結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageToneAnalysis {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<ToneStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// チャプター全体の階調解析結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterToneAnalysis {
+    pub pages: Vec<PageToneAnalysis>,
+    /// 正規化の目標値（各ページの中央値）
+    pub target: ToneStats,
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use super::CropRect;
+
+/// 本文ページの仕上がり仕様（断裁危険域の算出用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimSafetySpec {
+    /// 仕上がり幅 (mm)
+    pub trim_width_mm: f64,
+    /// 仕上がり高さ (mm)
+    pub trim_height_mm: f64,
+    /// 塗り足し (mm)
+    #[serde(default = "default_bleed_mm")]
+    pub bleed_mm: f64,
+    /// 仕上がり線から内側の安全マージン (mm)
+    #[serde(default = "default_safe_margin_mm")]
+    pub safe_margin_mm: f64,
+    /// 原稿が塗り足しを含んでいるか
+    #[serde(default = "default_true")]
+    pub includes_bleed: bool,
+}
+
+fn default_bleed_mm() -> f64 {
+    3.0
+}
+
+fn default_safe_margin_mm() -> f64 {
+    5.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 断裁危険域で検出された文字らしき領域
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimDangerHit {
+    /// 検出位置 ("top" | "bottom" | "left" | "right")
+    pub side: String,
+    /// 原稿上の領域 (px)
+    pub rect: CropRect,
+}
+
+/// ページごとの断裁危険域チェック結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrimSafetyAnalysis {
+    pub path: String,
+    /// 文字が切れる恐れがあるか
+    pub flagged: bool,
+    pub hits: Vec<TrimDangerHit>,
+    pub error: Option<String>,
+}
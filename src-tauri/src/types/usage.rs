@@ -0,0 +1,40 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// 1日分の利用統計
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DailyUsage {
+    pub exports: u64,
+    pub pages_exported: u64,
+    pub thumbnails_generated: u64,
+}
+
+/// ローカルの利用統計（外部送信なし、設定ディレクトリに保存）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UsageStats {
+    /// 集計開始日時
+    pub since: String,
+    pub exports_run: u64,
+    pub pages_exported: u64,
+    pub export_time_ms_total: u64,
+    pub thumbnails_generated: u64,
+    pub thumbnail_time_ms_total: u64,
+    pub thumbnail_cache_hits: u64,
+    /// 日付 (YYYY-MM-DD) ごとの推移
+    pub daily: BTreeMap<String, DailyUsage>,
+}
+
+/// 利用統計と算出値
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub stats: UsageStats,
+    /// エクスポート1回あたりの平均時間 (ms)
+    pub average_export_ms: f64,
+    /// サムネイル1枚あたりの平均生成時間 (ms)
+    pub average_thumbnail_ms: f64,
+    /// サムネイルのキャッシュヒット率 (0.0〜1.0)
+    pub cache_hit_rate: f64,
+}
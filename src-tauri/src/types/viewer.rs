@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+/// HTMLビューアに含めるページ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewerPage {
+    /// ソース画像（白紙ページは None）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+    /// ページラベル（ノンブルやチャプター名）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// HTMLビューア書き出し設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewerExportOptions {
+    /// Web用画像の長辺 (px)
+    #[serde(default = "default_max_size")]
+    pub max_size: u32,
+    /// Web用画像のJPG品質
+    #[serde(default = "default_quality")]
+    pub jpg_quality: u8,
+    /// 右綴じ（右から左へ読む）か
+    #[serde(default = "default_true")]
+    pub right_to_left: bool,
+    /// 1ページ目を単独で表示するか（表紙）
+    #[serde(default = "default_true")]
+    pub first_page_single: bool,
+}
+
+fn default_max_size() -> u32 {
+    1600
+}
+
+fn default_quality() -> u8 {
+    82
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for ViewerExportOptions {
+    fn default() -> Self {
+        Self {
+            max_size: default_max_size(),
+            jpg_quality: default_quality(),
+            right_to_left: true,
+            first_page_single: true,
+        }
+    }
+}
+
+/// HTMLビューア書き出し結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewerExportResult {
+    /// index.html のパス（ZIP出力時はZIPファイルのパス）
+    pub output_path: String,
+    pub page_count: usize,
+    /// 読み込みに失敗したページ（白紙として出力）
+    pub failed_pages: Vec<String>,
+}
+
+/// タブレット向けプレビューサーバーの接続情報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewServerInfo {
+    /// LAN内の端末から開くURL（認証トークン付き）
+    pub url: String,
+    /// このPCで確認するためのURL
+    pub local_url: String,
+    pub port: u16,
+    /// 閲覧用の認証トークン（起動ごとに生成）
+    pub token: String,
+    pub page_count: usize,
+}
@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+/// 監視中フォルダ内のファイル変更（"file-added" / "file-removed" / "file-modified" イベント）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderChangeEvent {
+    /// 監視しているフォルダ（watch_folder に渡したパス）
+    pub folder: String,
+    pub path: String,
+    pub name: String,
+    /// 更新日時（削除時は None）。サムネイルのキャッシュキーに使う値と同じ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_time: Option<u64>,
+}
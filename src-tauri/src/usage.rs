@@ -0,0 +1,133 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use crate::app_mode::is_safe_mode;
+use crate::file_utils::get_config_path;
+use crate::types::{DailyUsage, UsageReport, UsageStats};
+
+// 日別推移を保持する日数
+const DAILY_HISTORY_DAYS: usize = 90;
+// サムネイル統計をディスクに書き出す間隔（件数）
+const THUMBNAIL_FLUSH_INTERVAL: u64 = 50;
+
+// 読み込み済みの統計（初回アクセス時にファイルから読み込む）
+static STATS: Mutex<Option<UsageStats>> = Mutex::new(None);
+
+fn stats_path() -> Result<PathBuf, String> {
+    Ok(get_config_path()?.join("usage_stats.json"))
+}
+
+fn load_stats() -> UsageStats {
+    stats_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(|| UsageStats {
+            since: chrono::Local::now().to_rfc3339(),
+            ..Default::default()
+        })
+}
+
+fn save_stats(stats: &UsageStats) {
+    let written = (|| -> Result<(), String> {
+        let path = stats_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string(stats).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| e.to_string())
+    })();
+    if let Err(e) = written {
+        eprintln!("利用統計の保存に失敗: {}", e);
+    }
+}
+
+// 統計を更新（セーフモードでは記録しない）
+fn update(flush: impl FnOnce(&UsageStats) -> bool, f: impl FnOnce(&mut UsageStats, &mut DailyUsage)) {
+    if is_safe_mode() {
+        return;
+    }
+    let mut guard = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    let stats = guard.get_or_insert_with(load_stats);
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let mut daily = stats.daily.remove(&today).unwrap_or_default();
+    f(stats, &mut daily);
+    stats.daily.insert(today, daily);
+
+    // 古い日別データを削除
+    while stats.daily.len() > DAILY_HISTORY_DAYS {
+        if let Some(oldest) = stats.daily.keys().next().cloned() {
+            stats.daily.remove(&oldest);
+        }
+    }
+
+    if flush(stats) {
+        save_stats(stats);
+    }
+}
+
+// エクスポート1回分を記録
+pub fn record_export(pages: usize, elapsed_ms: u64) {
+    update(
+        |_| true,
+        |stats, daily| {
+            stats.exports_run += 1;
+            stats.pages_exported += pages as u64;
+            stats.export_time_ms_total += elapsed_ms;
+            daily.exports += 1;
+            daily.pages_exported += pages as u64;
+        },
+    );
+}
+
+// サムネイル1件分を記録（キャッシュヒット or 生成時間）
+pub fn record_thumbnail(cached: bool, elapsed_ms: u64) {
+    update(
+        |stats| (stats.thumbnails_generated + stats.thumbnail_cache_hits) % THUMBNAIL_FLUSH_INTERVAL == 0,
+        |stats, daily| {
+            if cached {
+                stats.thumbnail_cache_hits += 1;
+            } else {
+                stats.thumbnails_generated += 1;
+                stats.thumbnail_time_ms_total += elapsed_ms;
+                daily.thumbnails_generated += 1;
+            }
+        },
+    );
+}
+
+// 現在の統計と算出値
+pub fn usage_report() -> UsageReport {
+    let stats = {
+        let mut guard = STATS.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = guard.get_or_insert_with(load_stats);
+        // 未保存のサムネイル統計も書き出しておく
+        if !is_safe_mode() {
+            save_stats(stats);
+        }
+        stats.clone()
+    };
+
+    let ratio = |a: u64, b: u64| if b > 0 { a as f64 / b as f64 } else { 0.0 };
+    UsageReport {
+        average_export_ms: ratio(stats.export_time_ms_total, stats.exports_run),
+        average_thumbnail_ms: ratio(stats.thumbnail_time_ms_total, stats.thumbnails_generated),
+        cache_hit_rate: ratio(
+            stats.thumbnail_cache_hits,
+            stats.thumbnail_cache_hits + stats.thumbnails_generated,
+        ),
+        stats,
+    }
+}
+
+// 統計をリセット
+pub fn reset_usage() {
+    let mut guard = STATS.lock().unwrap_or_else(|e| e.into_inner());
+    let stats = UsageStats {
+        since: chrono::Local::now().to_rfc3339(),
+        ..Default::default()
+    };
+    save_stats(&stats);
+    *guard = Some(stats);
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter};
+use crate::constants::SUPPORTED_EXTENSIONS;
+use crate::types::FolderChangeEvent;
+
+// 保存時に連続して届く通知をまとめる待ち時間（Photoshopは一時ファイルへの書き込み→置き換えで保存する）
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+// フォルダの監視（破棄されると監視と通知スレッドを停止）
+pub struct FolderWatch {
+    _watcher: RecommendedWatcher,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl ChangeKind {
+    fn event_name(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "file-added",
+            ChangeKind::Removed => "file-removed",
+            ChangeKind::Modified => "file-modified",
+        }
+    }
+
+    // 待ち時間内に同じファイルで続いた変更をまとめる
+    fn merge(self, next: ChangeKind) -> ChangeKind {
+        match (self, next) {
+            // 追加直後の書き込みは追加のまま
+            (ChangeKind::Added, ChangeKind::Modified) => ChangeKind::Added,
+            // 削除→追加（置き換え保存）は更新
+            (ChangeKind::Removed, ChangeKind::Added) => ChangeKind::Modified,
+            (_, next) => next,
+        }
+    }
+}
+
+fn is_supported_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| SUPPORTED_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+// notify のイベントを追加・削除・更新に振り分け
+fn classify_event(event: Event) -> Vec<(ChangeKind, PathBuf)> {
+    let kinds: Vec<ChangeKind> = match event.kind {
+        EventKind::Create(_) => vec![ChangeKind::Added],
+        EventKind::Remove(_) => vec![ChangeKind::Removed],
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => vec![ChangeKind::Removed],
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => vec![ChangeKind::Added],
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => vec![ChangeKind::Removed, ChangeKind::Added],
+        // 名前変更の向きが分からない場合は存在するかで判断
+        EventKind::Modify(ModifyKind::Name(_)) => event
+            .paths
+            .iter()
+            .map(|p| if p.exists() { ChangeKind::Added } else { ChangeKind::Removed })
+            .collect(),
+        // 属性のみの変更（アクセス日時など）は無視
+        EventKind::Modify(ModifyKind::Metadata(_)) => return Vec::new(),
+        EventKind::Modify(_) => vec![ChangeKind::Modified],
+        _ => return Vec::new(),
+    };
+
+    event
+        .paths
+        .into_iter()
+        .zip(kinds.into_iter().chain(std::iter::repeat(ChangeKind::Modified)))
+        .filter(|(path, _)| is_supported_image(path))
+        .map(|(path, kind)| (kind, path))
+        .collect()
+}
+
+fn change_event(folder: &str, path: &Path, kind: ChangeKind) -> FolderChangeEvent {
+    let modified_time = if kind == ChangeKind::Removed {
+        None
+    } else {
+        path.metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis() as u64)
+    };
+    FolderChangeEvent {
+        folder: folder.to_string(),
+        path: path.to_string_lossy().to_string(),
+        name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        modified_time,
+    }
+}
+
+// フォルダの監視を開始し、画像ファイルの変更を指定ウィンドウに通知
+pub fn start_folder_watch(
+    app: AppHandle,
+    window_label: String,
+    folder: String,
+    recursive: bool,
+) -> Result<FolderWatch, String> {
+    let root = PathBuf::from(&folder);
+    if !root.is_dir() {
+        return Err(format!("監視するフォルダが見つかりません: {}", folder));
+    }
+
+    let (tx, rx) = mpsc::channel::<(ChangeKind, PathBuf)>();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+        Ok(event) => {
+            for change in classify_event(event) {
+                let _ = tx.send(change);
+            }
+        }
+        Err(e) => eprintln!("フォルダ監視エラー: {}", e),
+    })
+    .map_err(|e| format!("フォルダ監視の開始に失敗: {}", e))?;
+
+    let mode = if recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+    watcher
+        .watch(&root, mode)
+        .map_err(|e| format!("フォルダ監視の開始に失敗: {}", e))?;
+
+    // 通知スレッド（監視を破棄すると送信側がなくなり終了する）
+    std::thread::spawn(move || {
+        let mut pending: HashMap<PathBuf, (ChangeKind, Instant)> = HashMap::new();
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok((kind, path)) => {
+                    let merged = match pending.get(&path) {
+                        Some((previous, _)) => previous.merge(kind),
+                        None => kind,
+                    };
+                    pending.insert(path, (merged, Instant::now()));
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+
+            let ready: Vec<PathBuf> = pending
+                .iter()
+                .filter(|(_, (_, at))| at.elapsed() >= DEBOUNCE)
+                .map(|(path, _)| path.clone())
+                .collect();
+            for path in ready {
+                if let Some((kind, _)) = pending.remove(&path) {
+                    let event = change_event(&folder, &path, kind);
+                    let _ = app.emit_to(window_label.as_str(), kind.event_name(), event);
+                }
+            }
+        }
+    });
+
+    Ok(FolderWatch { _watcher: watcher })
+}
@@ -917,11 +917,13 @@ function App() {
 
     try {
       const count = await invoke<number>('export_pages', {
-        outputPath,
-        pages: exportPages,
-        moveFiles: exportMode === 'move',
-        convertToJpg,
-        jpgQuality,
+        request: {
+          outputPath,
+          pages: exportPages,
+          moveFiles: exportMode === 'move',
+          convertToJpg,
+          jpgQuality,
+        },
       });
 
       // 統計情報